@@ -0,0 +1,3307 @@
+//! app
+//! The drawing application, the side panel with its messages, the
+//! update and view around the canvas, and the save and load of the
+//! drawing.  The binary only runs it, another iced application can
+//! run it the same way or embed the canvas of draw_canvas.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced::theme::palette::Background;
+use iced::widget::text::LineHeight;
+use iced::widget::{button, canvas, checkbox, column, container, 
+    pick_list, radio, row, scrollable, slider, text, text_input};
+use iced::{alignment, time, Color, Element, Pixels,
+    Point, Subscription, Task, Theme};
+
+use iced_aw::color_picker;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::WidgetId;
+use crate::pressure::{PressureCurve, PressureCurveEditor, PressurePreset};
+use crate::settings::{save_settings, BACKUPS_RANGE, GRAB_RADIUS_RANGE, PRECISION_RANGE};
+use crate::components::{find_instance, is_component_name, list_components, load_component, place_instance, remove_instance_widgets,
+    save_component, update_instances, ComponentInstance};
+use crate::data_export::{get_widget_records, records_to_string, DataFormat};
+use crate::image_map::{export_image_map, export_layer_svgs, MapFormat};
+use crate::optimize::{optimize_widgets, round_widgets};
+use crate::drawing_file::{rotate_backups, write_drawing, SaveFormat};
+use crate::document::{read_document, ExportDocument, ExportSize};
+use crate::hatch::{get_closed_outline, is_hatch, Hatch, HatchPattern, ANGLE_RANGE, SPACING_RANGE};
+use crate::title_block::{build_title_block, get_frame_size, get_title_block_ids, parse_frame_size, update_title_field, TitleField};
+use crate::examples::{example_names, load_example};
+use crate::preview::PreviewQuality;
+use crate::helpers::round_to;
+use crate::svg_import::import_svg;
+use crate::numbering::{is_number, renumber, NumberStyle};
+use crate::legend::{build_legend, get_legend_entries, get_legend_ids, get_legend_origin, get_legend_tags, LegendSource};
+use crate::constraints::{build_constraints, scale_constraints, solve_constraints, ConstraintKind};
+use crate::groups::{expand_to_groups, find_group, group_widgets, ungroup_widgets, Group};
+use crate::layers::{find_layer, get_new_layer_name, is_excluded, merge_exportable, merge_layers, move_layer, Layer, NO_EXPORT_TAG};
+#[cfg(feature = "control")]
+use crate::control::ControlCommand;
+#[cfg(feature = "live")]
+use crate::live::{apply_value, get_live_key, LiveValue};
+#[cfg(feature = "image")]
+use crate::background::{export_annotated_image, is_image_path, parse_position, BackgroundImage, BACKGROUND_OPACITY_RANGE, BACKGROUND_SCALE_RANGE};
+#[cfg(feature = "image")]
+use crate::layers::exportable_widgets;
+use crate::widget_trait::WidgetTrait;
+use crate::canvas_update::CanvasEvent;
+use crate::text_style::{FontFamily, TextStyle};
+use crate::numeric_input::{input_style, parse_input, DEFAULT_LINE_HEIGHT, DEFAULT_POLY_POINTS, DEFAULT_TEXT_SIZE, DEFAULT_WIDTH,
+    LINE_HEIGHT_RANGE, POLY_POINTS_RANGE, TEXT_SIZE_RANGE, WIDTH_RANGE};
+use crate::themes::AppTheme;
+use crate::templates::{get_template_path, is_template_name, list_templates, load_template, save_template, TEMPLATES_DIR};
+use crate::style_presets::{add_preset, find_preset, get_preset_names, load_presets, save_presets, StylePreset};
+use crate::replace_color::{get_document_colors, get_palette_remap, remap_colors, replace_color, restore_colors, RECENT_COLORS, TOLERANCE_RANGE};
+use crate::resize::{get_drawing_bounds, get_resized_bounds, parse_factor, resize_layers, scale_widths, Resize};
+use crate::query::{find_same_width, find_widgets, parse_tags, Query};
+use crate::palettes::{add_recent_color, fix_contrast, get_contrast_ratio, get_palette, ColorVision, CONTRAST_THRESHOLD};
+use crate::descriptions::{describe_drawing, describe_widget, describe_widgets};
+use crate::draw_canvas::{self, get_widget_color, get_widget_id, get_widget_width, set_widget_hatch, Arc, ArcClosure, Bezier, CanvasMessage, CanvasWidget, Circle, DrawMode, Ellipse, FreeHand, Gauge, GaugeField, HTextAlignment, Line, PolyLine, PolyLineCenter, Polygon, PreciseField, PreciseShape, RightTriangle, RoundedRect, Text, VTextAlignment, Widget};
+
+
+#[derive(Default)]
+pub struct CanvasDraw {
+    pub canvas_state: draw_canvas::CanvasState,
+    show_draw_color_picker: bool,
+    show_canvas_color_picker: bool,
+    show_object_list: bool,
+    // Clear was pressed and waits for Yes or No
+    confirm_clear: bool,
+    #[cfg(feature = "live")]
+    live: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Canvas(CanvasMessage),
+    CanvasEvent(CanvasEvent),
+    Clear,
+    ClearDrawing,
+    CancelClear,
+    UndoClear,
+    ResetToolSettings,
+    ModeSelected(String),
+    RadioSelected(Widget),
+    Load,
+    DismissLoadError,
+    LoadFrom(Option<PathBuf>),
+    Example(String),
+    DuplicateExample,
+    ThemeSelected(String),
+    TemplateName(String),
+    SaveTemplate,
+    NewFromTemplate(String),
+    Save,
+    SaveTo(Option<PathBuf>),
+    Optimize(bool),
+    SaveFormat(String),
+    Backups(u8),
+    Precision(u8),
+    RoundCoordinates(bool),
+    ClassicModes(bool),
+    GrabRadius(f32),
+    PreviewQuality(String),
+    ResizeWidths(bool),
+    PolyInput(String),
+    PolyLineCenter(String),
+    ArcClosure(String),
+    PolyLineClosed(bool),
+    WidthInput(String),
+    HTextAlignment(String),
+    VTextAlignment(String),
+    FontFamily(String),
+    TextSize(String),
+    TextLineHeight(String),
+    TextBold(bool),
+    TextItalic(bool),
+    Tick,
+    SelectDrawColor,
+    SubmitDrawColor(Color),
+    PickDrawColor(Color),
+    DrawOpacity(f32),
+    CanvasOpacity(f32),
+    CancelDrawColor,
+    SelectCanvasColor,
+    SubmitCanvasColor(Color),
+    CancelCanvasColor,
+    ShowIntersections(bool),
+    ShowRulers(bool),
+    SnapIntersections(bool),
+    SnapObjects(bool),
+    PreciseShape(bool),
+    PreciseInput(PreciseField, String),
+    GaugeInput(GaugeField, String),
+    CircleRadius(String),
+    ShowObjectList(bool),
+    ExportDescription,
+    ExportDescriptionTo(Option<PathBuf>),
+    PaletteSelected(String),
+    SimulateVision(String),
+    FixTextContrast,
+    PressurePreset(String),
+    PressureCurve(PressureCurve),
+    Stabilizer(bool),
+    StabilizerStrength(f32),
+    Smoothing(bool),
+    SmoothingStrength(f32),
+    ApplyStyleToSelection(Option<f32>, Option<Color>),
+    ComponentName(String),
+    SaveComponent,
+    ComponentSelected(String),
+    UpdateInstances,
+    InstanceColor(Option<Color>),
+    InstanceScale(String),
+    InstanceText(String),
+    DetachInstance,
+    GroupSelection,
+    UngroupSelection,
+    TagsInput(String),
+    ExcludeFromExport(bool),
+    HatchPattern(String),
+    HatchSpacing(f32),
+    HatchAngle(f32),
+    HatchSelection,
+    RemoveHatch,
+    PresetName(String),
+    SavePreset,
+    PresetSelected(String),
+    DeletePreset,
+    ConstraintKind(String),
+    AddConstraint,
+    RemoveConstraints,
+    QueryInput(String),
+    FindWidgets,
+    SelectSameWidth,
+    ExportData(DataFormat),
+    ExportMap(MapFormat),
+    ExportLayerSvgs,
+    LayerSelected(usize),
+    LayerVisible(usize, bool),
+    LayerExport(usize, bool),
+    LayerName(String),
+    NewLayer,
+    DeleteLayer,
+    MoveLayer(bool),
+    Legend(LegendSource),
+    Metadata(TitleField, String),
+    FrameSize(String),
+    TitleBlock,
+    NumberStyle(String),
+    NumberPrefix(String),
+    NumberSuffix(String),
+    NumberNext(String),
+    Renumber,
+    ImportSvg,
+    ImportSvgFrom(Option<PathBuf>),
+    ResizeInput(String),
+    ResizeDocument,
+    WidthScaleInput(String),
+    ScaleWidths,
+    ReplaceTolerance(f32),
+    ReplaceColor,
+    UndoReplaceColor,
+    ScanColors,
+    RemapPalette(String),
+    ApplyRemap,
+    CancelRemap,
+    #[cfg(feature = "control")]
+    Control(ControlCommand),
+    #[cfg(feature = "live")]
+    Live(bool),
+    #[cfg(feature = "live")]
+    LiveUrl(String),
+    #[cfg(feature = "live")]
+    LiveValue(LiveValue),
+    #[cfg(feature = "image")]
+    LoadBackground,
+    #[cfg(feature = "image")]
+    BackgroundFrom(Option<PathBuf>),
+    #[cfg(feature = "image")]
+    BackgroundPosition(String),
+    #[cfg(feature = "image")]
+    BackgroundScale(String),
+    #[cfg(feature = "image")]
+    BackgroundOpacity(f32),
+    #[cfg(feature = "image")]
+    RemoveBackground,
+    #[cfg(feature = "image")]
+    ExportAnnotated,
+    #[cfg(feature = "image")]
+    ExportAnnotatedTo(Option<PathBuf>),
+}
+
+impl CanvasDraw {
+    // The settings with the command line options over them,
+    // --pretty, --compact or --gzip is the save format.
+    pub fn theme(&self) -> Theme {
+        self.canvas_state.app_theme.theme()
+    }
+
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut app = CanvasDraw::default();
+        if let Some(format) = SaveFormat::from_args(args) {
+            app.canvas_state.save_format = format;
+        }
+        app
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Canvas(message) => {
+                // only the changes to the drawing or selection come back
+                if let Some(event) = self.canvas_state.update(message) {
+                    return self.update(Message::CanvasEvent(event))
+                }
+            },
+            Message::CanvasEvent(event) => {
+                match event {
+                    CanvasEvent::SelectionChanged | CanvasEvent::WidgetDeleted(_) => {
+                        let state = &mut self.canvas_state;
+                        state.tags_str = 
+                            state.selected_ids.first()
+                                .and_then(|id| state.tags.get(id))
+                                .map(|tags| tags.join(", "))
+                                .unwrap_or_default();
+                    },
+                    CanvasEvent::WidgetAdded(_) | CanvasEvent::WidgetChanged(_) => (),
+                }
+            },
+            Message::Clear => {
+                self.confirm_clear = true;
+            },
+            Message::ClearDrawing => {
+                self.confirm_clear = false;
+                let drawing = self.canvas_state.take_drawing();
+                self.canvas_state.cleared = Some(drawing);
+            },
+            Message::CancelClear => {
+                self.confirm_clear = false;
+            },
+            Message::UndoClear => {
+                if self.canvas_state.can_undo_clear() {
+                    if let Some(drawing) = self.canvas_state.cleared.take() {
+                        self.canvas_state.set_drawing(drawing);
+                    }
+                }
+            },
+            Message::ResetToolSettings => {
+                self.canvas_state.reset_tool_settings();
+            },
+            Message::ModeSelected(mode) => {
+                let mode = DrawMode::to_enum(mode.clone());
+                match mode {
+                    DrawMode::DrawAll => {
+                        self.canvas_state.draw_mode = DrawMode::DrawAll;
+                    },
+                    DrawMode::Edit | DrawMode::Rotate if !self.canvas_state.classic_modes => {
+                        return Task::none()
+                    },
+                    DrawMode::Edit => {
+                        if self.canvas_state.curves().is_empty() {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = DrawMode::Edit;
+                    },
+                    DrawMode::New => {
+                        self.canvas_state.draw_mode = DrawMode::New;
+                        // When both the draw_mode is new and widget is text
+                        // then we cut on the timer
+                        if self.canvas_state.selected_radio_widget == Some(Widget::Text) {
+                            self.canvas_state.timer_event_enabled = true;
+                        }
+                    },
+                    DrawMode::Rotate => {
+                        self.canvas_state.draw_mode = DrawMode::Rotate;
+                    },
+                    DrawMode::Trim | DrawMode::Extend => {
+                        // needs at least a line and another widget
+                        if self.canvas_state.curves().values().filter(|widget| !matches!(widget, CanvasWidget::Text(_))).count() < 2 {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = mode;
+                    },
+                    DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent => {
+                        if self.canvas_state.curves().values().all(|widget| matches!(widget, CanvasWidget::Text(_))) {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = mode;
+                    },
+                    DrawMode::Select => {
+                        if self.canvas_state.curves().is_empty() {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = DrawMode::Select;
+                    },
+                    DrawMode::Place => {
+                        if self.canvas_state.selected_component.is_none() {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = DrawMode::Place;
+                    },
+                    DrawMode::Number => {
+                        self.canvas_state.draw_mode = DrawMode::Number;
+                    },
+                    DrawMode::Delete => {
+                        if self.canvas_state.curves().is_empty() {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = DrawMode::Delete;
+                    },
+                    DrawMode::Resize => {
+                        if self.canvas_state.curves().is_empty() {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = DrawMode::Resize;
+                    },
+                    DrawMode::Split | DrawMode::Join => {
+                        // needs a freehand or polyline
+                        if !self.canvas_state.curves().values().any(|widget| matches!(widget, CanvasWidget::FreeHand(_) | CanvasWidget::PolyLine(_))) {
+                            return Task::none()
+                        }
+                        self.canvas_state.draw_mode = mode;
+                    },
+                }
+                self.canvas_state.request_redraw();
+            },
+            Message::RadioSelected(choice) => {
+                // Have to  make sure and only use the timer event during
+                // the text only.
+                self.canvas_state.timer_event_enabled = false;
+                match choice {
+                    Widget::Arc => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Arc);
+                    },
+                    Widget::Bezier => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Bezier);
+                    },
+                    Widget::Circle => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Circle);
+                    },
+                    Widget::Gauge => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Gauge);
+                    },
+                    Widget::Ellipse => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Ellipse);
+                    },
+                    Widget::Line => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Line);
+                    },
+                    Widget::PolyLine => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::PolyLine);
+                    },
+                    Widget::Polygon => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Polygon);
+                    },
+                    Widget::RightTriangle => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::RightTriangle);
+                    },
+                    Widget::RoundedRect => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::RoundedRect);
+                    },
+                    Widget::FreeHand => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::FreeHand);
+                    }
+                    Widget::Text => {
+                        self.canvas_state.selected_radio_widget = Some(Widget::Text);
+                        // When both the draw_mode is new and widget is text
+                        // then we cut on the timer
+                        if self.canvas_state.draw_mode == DrawMode::New {
+                            self.canvas_state.timer_event_enabled = true;
+                        }
+                        self.canvas_state.selected_h_text_alignment = HTextAlignment::Center;
+                        self.canvas_state.selected_v_text_alignment = VTextAlignment::Center;
+                    }
+                    Widget::None => (),
+                } 
+            },
+            Message::Tick => {
+                self.canvas_state.elapsed_time += self.canvas_state.timer_duration;
+                self.canvas_state.blink = !self.canvas_state.blink;
+                self.canvas_state.request_caret_redraw();
+            },
+            Message::Load => {
+                return Task::perform(pick_load_path(self.canvas_state.data_path.clone()), Message::LoadFrom)
+            },
+            Message::LoadFrom(path) => {
+                // None when the dialog was cancelled
+                let Some(path) = path else {
+                    return Task::none()
+                };
+                // pretty, compact or gzipped
+                let document = match read_document(&path) {
+                    Ok(document) => document,
+                    Err(error) => {
+                        self.canvas_state.load_error = Some(format!("Couldn't load {}: {}", path.display(), error));
+                        return Task::none()
+                    },
+                };
+                self.canvas_state.load_error = None;
+                self.canvas_state.style_presets = load_presets(&path);
+                self.canvas_state.selected_preset = None;
+                self.canvas_state.data_path = path;
+                self.open_drawing(document);
+                self.canvas_state.read_only = false;
+            },
+            Message::DismissLoadError => {
+                self.canvas_state.load_error = None;
+            },
+            Message::Example(name) => {
+                let Some(document) = load_example(&name) else {
+                    return Task::none()
+                };
+                self.open_drawing(document);
+                self.canvas_state.read_only = true;
+            },
+            Message::DuplicateExample => {
+                // the drawing stays, only now it can be changed and saved
+                self.canvas_state.read_only = false;
+            },
+            Message::ThemeSelected(theme) => {
+                // the colors still at the old theme's defaults follow the new theme
+                let theme = AppTheme::to_enum(theme);
+                let state = &mut self.canvas_state;
+                let (old_draw, old_canvas) = state.app_theme.default_colors();
+                let (draw, canvas) = theme.default_colors();
+                if state.selected_draw_color == old_draw {
+                    state.selected_draw_color = draw;
+                }
+                if state.selected_canvas_color == old_canvas {
+                    state.selected_canvas_color = canvas;
+                }
+                state.app_theme = theme;
+                let _ = save_settings(&state.get_settings());
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::TemplateName(name) => {
+                self.canvas_state.template_name = name;
+            },
+            Message::SaveTemplate => {
+                // the whole drawing with its styles
+                let state = &mut self.canvas_state;
+                if !is_template_name(&state.template_name) {
+                    return Task::none()
+                }
+                let document = convert_to_document(state);
+                let dir = Path::new(TEMPLATES_DIR);
+                if let Ok(path) = save_template(dir, &state.template_name, &document) {
+                    let _ = save_presets(&path, &state.style_presets);
+                    state.template_names = list_templates(dir);
+                    state.template_name.clear();
+                }
+            },
+            Message::NewFromTemplate(name) => {
+                // a new drawing, it isn't saved over the template
+                let dir = Path::new(TEMPLATES_DIR);
+                let Some(document) = load_template(dir, &name) else {
+                    self.canvas_state.load_error = Some(format!("Couldn't load the template {}", name));
+                    return Task::none()
+                };
+                self.open_drawing(document);
+                let state = &mut self.canvas_state;
+                state.style_presets = load_presets(&get_template_path(dir, &name));
+                state.selected_preset = None;
+                state.data_path = state.data_path.with_file_name("untitled.json");
+                state.read_only = false;
+            },
+            Message::Save => {
+                let state = &self.canvas_state;
+                return Task::perform(pick_save_path(state.data_path.clone(), state.save_format), Message::SaveTo)
+            },
+            Message::SaveTo(path) => {
+                let Some(path) = path else {
+                    return Task::none()
+                };
+                if self.canvas_state.read_only {
+                    return Task::none()
+                }
+                let mut document = convert_to_document(&self.canvas_state);
+                // rounded first so the points it makes the same are removed
+                if self.canvas_state.round_coordinates {
+                    round_widgets(&mut document.widgets, self.canvas_state.precision);
+                }
+                if self.canvas_state.optimize {
+                    optimize_widgets(&mut document.widgets);
+                }
+                // the drawing saved over is kept as a backup, the
+                // drawing is still saved when the backup fails
+                let mut errors = vec![];
+                if let Err(error) = rotate_backups(&path, self.canvas_state.backups as usize) {
+                    errors.push(format!("Couldn't keep a backup of {}: {}", path.display(), error));
+                }
+                match write_drawing(&path, &document, self.canvas_state.save_format) {
+                    Ok(()) => {
+                        if let Err(error) = save_presets(&path, &self.canvas_state.style_presets) {
+                            errors.push(format!("Couldn't save the style presets: {}", error));
+                        }
+                        self.canvas_state.data_path = path;
+                    },
+                    Err(error) => errors.push(format!("Couldn't save {}: {}", path.display(), error)),
+                }
+                if !errors.is_empty() {
+                    self.canvas_state.load_error = Some(errors.join("\n"));
+                }
+            },
+            Message::ClassicModes(enabled) => {
+                self.canvas_state.classic_modes = enabled;
+                if !enabled && matches!(self.canvas_state.draw_mode, DrawMode::Edit | DrawMode::Rotate) {
+                    self.canvas_state.draw_mode = DrawMode::DrawAll;
+                }
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::GrabRadius(radius) => {
+                self.canvas_state.grab_radius = radius;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::PreviewQuality(quality) => {
+                self.canvas_state.preview_quality = PreviewQuality::to_enum(quality);
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::ResizeWidths(enabled) => {
+                self.canvas_state.resize_widths = enabled;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::Optimize(enabled) => {
+                self.canvas_state.optimize = enabled;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::SaveFormat(format) => {
+                self.canvas_state.save_format = SaveFormat::to_enum(format);
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::Backups(backups) => {
+                self.canvas_state.backups = backups;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::Precision(places) => {
+                self.canvas_state.precision = places;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::RoundCoordinates(enabled) => {
+                self.canvas_state.round_coordinates = enabled;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::ImportSvg => {
+                return Task::perform(pick_svg_path(self.canvas_state.data_path.clone()), Message::ImportSvgFrom)
+            },
+            Message::ImportSvgFrom(path) => {
+                // The svg widgets are added to the drawing.
+                let Some(path) = path else {
+                    return Task::none()
+                };
+                let data = match fs::read_to_string(&path) {
+                    Ok(data) => data,
+                    Err(error) => {
+                        self.canvas_state.load_error = Some(format!("Couldn't read {}: {}", path.display(), error));
+                        return Task::none()
+                    },
+                };
+                let widgets = 
+                    match import_svg(
+                        &data, 
+                        self.canvas_state.selected_draw_color, 
+                        self.canvas_state.selected_width,
+                    ) {
+                        Ok(widgets) => widgets,
+                        Err(error) => {
+                            self.canvas_state.load_error = Some(format!("Couldn't import {}: {}", path.display(), error));
+                            return Task::none()
+                        },
+                    };
+                for widget in widgets {
+                    let id = get_widget_id(&widget);
+                    self.canvas_state.active_layer_mut().insert(id, widget);
+                }
+                self.canvas_state.request_redraw();
+                self.canvas_state.request_text_redraw();
+            },
+            Message::ResizeInput(input) => {
+                // The new bounds are outlined until the resize is done.
+                let state = &mut self.canvas_state;
+                state.resize_preview = 
+                    match (Resize::parse(&input), get_drawing_bounds(&state.layers)) {
+                        (Ok(resize), Some(bounds)) => Some(get_resized_bounds(bounds, resize.factor(bounds))),
+                        _ => None,
+                    };
+                state.resize_str = input;
+                state.request_redraw();
+            },
+            Message::ResizeDocument => {
+                let state = &mut self.canvas_state;
+                let (Ok(resize), Some(bounds)) = (Resize::parse(&state.resize_str), get_drawing_bounds(&state.layers)) else {
+                    return Task::none()
+                };
+                let factor = resize.factor(bounds);
+                resize_layers(&mut state.layers, factor);
+                // the instances are placed again at their new scale
+                for instance in state.instances.iter_mut() {
+                    instance.origin = Point::new(instance.origin.x * factor, instance.origin.y * factor);
+                    instance.scale *= factor;
+                    instance.scale_str = instance.scale.to_string();
+                }
+                scale_constraints(&mut state.constraints, factor);
+                state.resize_str.clear();
+                state.resize_preview = None;
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::WidthScaleInput(input) => {
+                self.canvas_state.width_scale_str = input;
+            },
+            Message::ScaleWidths => {
+                // the selected widgets, or all of them
+                let state = &mut self.canvas_state;
+                let Ok(factor) = parse_factor(&state.width_scale_str) else {
+                    return Task::none()
+                };
+                scale_widths(&mut state.layers, &state.selected_ids, factor);
+                state.width_scale_str.clear();
+                state.request_redraw();
+            },
+            Message::ReplaceTolerance(tolerance) => {
+                self.canvas_state.replace_tolerance = tolerance;
+            },
+            Message::ReplaceColor => {
+                // the color of the first selected widget becomes the draw color
+                let state = &mut self.canvas_state;
+                let Some(from) = 
+                    state.selected_ids.first()
+                        .and_then(|id| state.curves().get(id))
+                        .map(get_widget_color) else {
+                    return Task::none()
+                };
+                let to = state.selected_draw_color;
+                state.replaced_colors = replace_color(&mut state.layers, from, to, state.replace_tolerance);
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::UndoReplaceColor => {
+                let state = &mut self.canvas_state;
+                restore_colors(&mut state.layers, &std::mem::take(&mut state.replaced_colors));
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::ScanColors => {
+                self.canvas_state.document_colors = get_document_colors(&self.canvas_state.layers);
+            },
+            Message::RemapPalette(palette) => {
+                // previewed until applied or canceled
+                let state = &mut self.canvas_state;
+                let colors = 
+                    if palette == RECENT_COLORS {
+                        state.recent_colors.clone()
+                    } else {
+                        get_palette(ColorVision::to_enum(palette.clone()))
+                    };
+                state.document_colors = get_document_colors(&state.layers);
+                state.palette_remap = Some(get_palette_remap(&state.document_colors, &colors));
+                state.remap_palette = Some(palette);
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::ApplyRemap => {
+                // undone with Undo Replace
+                let state = &mut self.canvas_state;
+                if let Some(remap) = state.palette_remap.take() {
+                    state.replaced_colors = remap_colors(&mut state.layers, &remap);
+                }
+                state.remap_palette = None;
+                state.document_colors = get_document_colors(&state.layers);
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::CancelRemap => {
+                let state = &mut self.canvas_state;
+                state.palette_remap = None;
+                state.remap_palette = None;
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            #[cfg(feature = "control")]
+            Message::Control(command) => {
+                match command {
+                    ControlCommand::AddWidget(widget) => {
+                        let widget = import_widget(&widget);
+                        let id = get_widget_id(&widget);
+                        self.canvas_state.active_layer_mut().insert(id, widget);
+                        self.canvas_state.request_redraw();
+                        self.canvas_state.request_text_redraw();
+                    },
+                    ControlCommand::Delete(query) => {
+                        let Ok(query) = Query::parse(&query) else {
+                            return Task::none()
+                        };
+                        // the matching widgets on every layer
+                        let state = &self.canvas_state;
+                        let ids: Vec<WidgetId> = 
+                            state.layers.iter()
+                                .flat_map(|layer| find_widgets(&query, &layer.curves, &state.tags))
+                                .collect();
+                        self.canvas_state.delete_widgets(&ids);
+                    },
+                    ControlCommand::Export(format) => return self.update(Message::ExportData(format)),
+                    // no dialog for the endpoint, the last file is used
+                    ControlCommand::Load => {
+                        return self.update(Message::LoadFrom(Some(self.canvas_state.data_path.clone())))
+                    },
+                    ControlCommand::Save => {
+                        return self.update(Message::SaveTo(Some(self.canvas_state.data_path.clone())))
+                    },
+                    // the remote has no one to confirm it
+                    ControlCommand::Clear => return self.update(Message::ClearDrawing),
+                    ControlCommand::Failed(error) => self.canvas_state.load_error = Some(error),
+                }
+            },
+            #[cfg(feature = "live")]
+            Message::Live(enabled) => {
+                self.live = enabled;
+            },
+            #[cfg(feature = "live")]
+            Message::LiveUrl(url) => {
+                self.canvas_state.live_url = url;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            #[cfg(feature = "image")]
+            Message::LoadBackground => {
+                return Task::perform(pick_image_path(self.canvas_state.data_path.clone()), Message::BackgroundFrom)
+            },
+            #[cfg(feature = "image")]
+            Message::BackgroundFrom(path) => {
+                // None when the dialog was cancelled
+                let Some(path) = path.filter(|path| is_image_path(path)) else {
+                    return Task::none()
+                };
+                self.canvas_state.background = Some(BackgroundImage::new(path));
+                self.canvas_state.request_background_redraw();
+            },
+            #[cfg(feature = "image")]
+            Message::BackgroundPosition(input) => {
+                let state = &mut self.canvas_state;
+                if let Some(background) = &mut state.background {
+                    // the last good position stays while typing
+                    if let Some(position) = parse_position(&input) {
+                        background.position = position;
+                    }
+                    background.position_str = input;
+                }
+                state.request_background_redraw();
+            },
+            #[cfg(feature = "image")]
+            Message::BackgroundScale(input) => {
+                let state = &mut self.canvas_state;
+                if let Some(background) = &mut state.background {
+                    (background.scale, _) = parse_input(&input, background.scale, 1.0, BACKGROUND_SCALE_RANGE);
+                    background.scale_str = input;
+                }
+                state.request_background_redraw();
+            },
+            #[cfg(feature = "image")]
+            Message::BackgroundOpacity(opacity) => {
+                let state = &mut self.canvas_state;
+                if let Some(background) = &mut state.background {
+                    background.opacity = opacity;
+                }
+                state.request_background_redraw();
+            },
+            #[cfg(feature = "image")]
+            Message::RemoveBackground => {
+                self.canvas_state.background = None;
+                self.canvas_state.request_background_redraw();
+            },
+            #[cfg(feature = "image")]
+            Message::ExportAnnotated => {
+                let Some(background) = &self.canvas_state.background else {
+                    return Task::none()
+                };
+                return Task::perform(pick_annotated_path(background.path.clone()), Message::ExportAnnotatedTo)
+            },
+            #[cfg(feature = "image")]
+            Message::ExportAnnotatedTo(path) => {
+                // the layers shown, at the image's resolution
+                let state = &mut self.canvas_state;
+                let (Some(path), Some(background)) = (path, &state.background) else {
+                    return Task::none()
+                };
+                let widgets: Vec<&CanvasWidget> = 
+                    exportable_widgets(state.layers.iter().filter(|layer| layer.visible), &state.tags)
+                        .map(|(_, widget)| widget)
+                        .collect();
+                if let Err(error) = export_annotated_image(background, &widgets, &path) {
+                    state.load_error = Some(format!("Couldn't export {}: {}", path.display(), error));
+                }
+            },
+            #[cfg(feature = "live")]
+            Message::LiveValue(live_value) => {
+                let state = &mut self.canvas_state;
+                let mut changed_layers = vec![];
+                for (id, tags) in state.tags.iter() {
+                    if get_live_key(tags) != Some(live_value.key.as_str()) {
+                        continue
+                    }
+                    for (index, layer) in state.layers.iter_mut().enumerate() {
+                        // the texts have their own caches
+                        if let Some(widget) = layer.curves.get_mut(id) {
+                            let changed = apply_value(widget, &live_value.value);
+                            if changed && !matches!(widget, CanvasWidget::Text(_)) && !changed_layers.contains(&index) {
+                                changed_layers.push(index);
+                            }
+                        }
+                    }
+                }
+                for layer in changed_layers {
+                    state.request_layer_redraw(layer);
+                }
+                state.request_text_redraw();
+            },
+            Message::PolyInput(input) => {
+                let state = &mut self.canvas_state;
+                (state.selected_poly_points, state.poly_points_error) = 
+                    parse_input(&input, state.selected_poly_points, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE);
+                state.selected_poly_points_str = input;
+            },
+            Message::PolyLineCenter(center) => {
+                // new polylines and the selected ones
+                let center = PolyLineCenter::to_enum(center);
+                let state = &mut self.canvas_state;
+                state.polyline_center = center;
+                for layer in state.layers.iter_mut() {
+                    for id in state.selected_ids.iter() {
+                        if let Some(CanvasWidget::PolyLine(pl)) = layer.curves.get_mut(id) {
+                            pl.center = center;
+                            pl.recenter();
+                        }
+                    }
+                }
+                state.request_redraw();
+            },
+            Message::ArcClosure(closure) => {
+                // new arcs and the selected ones
+                let closure = ArcClosure::to_enum(closure);
+                let state = &mut self.canvas_state;
+                state.arc_closure = closure;
+                for layer in state.layers.iter_mut() {
+                    for id in state.selected_ids.iter() {
+                        if let Some(CanvasWidget::Arc(arc)) = layer.curves.get_mut(id) {
+                            arc.closure = closure;
+                        }
+                    }
+                }
+                state.request_redraw();
+            },
+            Message::PolyLineClosed(closed) => {
+                // new polylines and the selected ones
+                let state = &mut self.canvas_state;
+                state.polyline_closed = closed;
+                for layer in state.layers.iter_mut() {
+                    for id in state.selected_ids.iter() {
+                        if let Some(CanvasWidget::PolyLine(pl)) = layer.curves.get_mut(id) {
+                            pl.closed = closed;
+                        }
+                    }
+                }
+                state.request_redraw();
+            },
+            Message::WidthInput(input) => {
+                let state = &mut self.canvas_state;
+                (state.selected_width, state.width_error) = 
+                    parse_input(&input, state.selected_width, DEFAULT_WIDTH, WIDTH_RANGE);
+                // an entry being cleared to retype it isn't a width yet
+                let width = (!state.width_error && !input.trim().is_empty()).then_some(state.selected_width);
+                state.selected_width_str = input;
+                if width.is_some() {
+                    return self.update(Message::ApplyStyleToSelection(width, None))
+                }
+            },
+            Message::PresetName(name) => {
+                self.canvas_state.preset_name = name;
+            },
+            Message::SavePreset => {
+                // the draw color and width under the name typed in
+                let state = &mut self.canvas_state;
+                let preset = StylePreset::new(&state.preset_name, state.selected_draw_color, state.selected_width);
+                if preset.name.is_empty() {
+                    return Task::none()
+                }
+                state.selected_preset = Some(preset.name.clone());
+                add_preset(&mut state.style_presets, preset);
+                state.preset_name.clear();
+            },
+            Message::PresetSelected(name) => {
+                // for the new widgets and the selection
+                let state = &mut self.canvas_state;
+                let Some(preset) = find_preset(&state.style_presets, &name) else {
+                    return Task::none()
+                };
+                let (color, width) = (preset.color(), preset.width);
+                state.selected_draw_color = color;
+                state.selected_width = width;
+                state.selected_width_str = width.to_string();
+                state.width_error = false;
+                state.selected_preset = Some(name);
+                return self.update(Message::ApplyStyleToSelection(Some(width), Some(color)))
+            },
+            Message::DeletePreset => {
+                let state = &mut self.canvas_state;
+                if let Some(name) = state.selected_preset.take() {
+                    state.style_presets.retain(|preset| preset.name != name);
+                }
+            },
+            Message::HTextAlignment(alignment) => {
+                self.canvas_state.selected_h_text_alignment = HTextAlignment::to_enum(alignment.clone());
+                self.canvas_state.request_redraw();
+            },
+            Message::VTextAlignment(alignment) => {
+                self.canvas_state.selected_v_text_alignment = VTextAlignment::to_enum(alignment.clone());
+                self.canvas_state.request_redraw();
+            },
+            Message::FontFamily(family) => {
+                self.canvas_state.text_style.family = FontFamily::to_enum(family);
+                let font = self.canvas_state.text_style.font();
+                self.restyle_selected_text(|txt| txt.font = font);
+            },
+            Message::TextSize(input) => {
+                // an entry being cleared or half typed isn't a size yet
+                let state = &mut self.canvas_state;
+                (state.text_style.size, state.text_size_error) = 
+                    parse_input(&input, state.text_style.size, DEFAULT_TEXT_SIZE, TEXT_SIZE_RANGE);
+                let size = (!state.text_size_error && !input.trim().is_empty()).then_some(state.text_style.size);
+                state.text_size_str = input;
+                if let Some(size) = size {
+                    self.restyle_selected_text(|txt| txt.size = Pixels(size));
+                }
+            },
+            Message::TextLineHeight(input) => {
+                let state = &mut self.canvas_state;
+                (state.text_style.line_height, state.line_height_error) = 
+                    parse_input(&input, state.text_style.line_height, DEFAULT_LINE_HEIGHT, LINE_HEIGHT_RANGE);
+                let height = (!state.line_height_error && !input.trim().is_empty()).then_some(state.text_style.line_height);
+                state.line_height_str = input;
+                if let Some(height) = height {
+                    self.restyle_selected_text(|txt| txt.line_height = LineHeight::Relative(height));
+                }
+            },
+            Message::TextBold(bold) => {
+                self.canvas_state.text_style.bold = bold;
+                let font = self.canvas_state.text_style.font();
+                self.restyle_selected_text(|txt| txt.font.weight = font.weight);
+            },
+            Message::TextItalic(italic) => {
+                self.canvas_state.text_style.italic = italic;
+                let font = self.canvas_state.text_style.font();
+                self.restyle_selected_text(|txt| txt.font.style = font.style);
+            },
+            Message::SelectDrawColor => {
+                self.show_draw_color_picker = true;
+            },
+            Message::SubmitDrawColor(color) => {
+                self.canvas_state.selected_draw_color = color;
+                self.show_draw_color_picker = false;
+                return self.update(Message::ApplyStyleToSelection(None, Some(color)))
+            },
+            Message::PickDrawColor(color) => {
+                // a color from the picker is kept with the recent ones
+                add_recent_color(&mut self.canvas_state.recent_colors, color);
+                let _ = save_settings(&self.canvas_state.get_settings());
+                return self.update(Message::SubmitDrawColor(color))
+            },
+            Message::DrawOpacity(opacity) => {
+                let color = Color { a: opacity, ..self.canvas_state.selected_draw_color };
+                self.canvas_state.selected_draw_color = color;
+                return self.update(Message::ApplyStyleToSelection(None, Some(color)))
+            },
+            Message::ApplyStyleToSelection(width, color) => {
+                // the widget being edited or rotated, or the Select tool's selection
+                let state = &mut self.canvas_state;
+                let ids = match (state.draw_mode, state.edit_widget_id) {
+                    (DrawMode::Edit | DrawMode::Rotate, Some(id)) => vec![id],
+                    (DrawMode::Select, _) => state.selected_ids.clone(),
+                    _ => vec![],
+                };
+                let mut changed_layers = vec![];
+                for (index, layer) in state.layers.iter_mut().enumerate() {
+                    for id in ids.iter() {
+                        let Some(wt) = layer.curves.get_mut(id)
+                            .and_then(CanvasWidget::as_trait_mut) else {
+                            continue
+                        };
+                        if let Some(width) = width {
+                            wt.set_width(width);
+                        }
+                        if let Some(color) = color {
+                            wt.set_color(color);
+                        }
+                        if !changed_layers.contains(&index) {
+                            changed_layers.push(index);
+                        }
+                    }
+                }
+                for layer in changed_layers {
+                    state.request_layer_redraw(layer);
+                }
+                state.request_text_redraw_of(&ids);
+            },
+            Message::CancelDrawColor => {
+                self.show_draw_color_picker = false;
+            },
+            Message::SelectCanvasColor => {
+                self.show_canvas_color_picker = true;
+            },
+            Message::SubmitCanvasColor(color) => {
+                self.canvas_state.selected_canvas_color = color;
+                self.show_canvas_color_picker = false;
+                self.canvas_state.request_background_redraw();
+            },
+            Message::CanvasOpacity(opacity) => {
+                self.canvas_state.selected_canvas_color.a = opacity;
+                self.canvas_state.request_background_redraw();
+            },
+            Message::CancelCanvasColor => {
+                self.show_canvas_color_picker = false;
+            },
+            Message::ShowIntersections(show) => {
+                self.canvas_state.show_intersections = show;
+                self.canvas_state.request_redraw();
+            },
+            Message::ShowRulers(show) => {
+                self.canvas_state.show_rulers = show;
+            },
+            Message::SnapIntersections(snap) => {
+                self.canvas_state.snap_to_intersections = snap;
+                self.canvas_state.request_redraw();
+            },
+            Message::SnapObjects(snap) => {
+                self.canvas_state.snap_to_objects = snap;
+                self.canvas_state.request_redraw();
+            },
+            Message::PreciseShape(enabled) => {
+                self.canvas_state.precise_shape.enabled = enabled;
+            },
+            Message::PreciseInput(field, input) => {
+                self.canvas_state.precise_shape.set(field, input);
+            },
+            Message::GaugeInput(field, input) => {
+                let state = &mut self.canvas_state;
+                if let Some(id) = &state.edit_widget_id {
+                    if let Some(CanvasWidget::Gauge(gauge)) = state.layers[state.active_layer].curves.get_mut(id) {
+                        // little error checking
+                        if let Ok(value) = input.trim().parse::<f32>() {
+                            gauge.set(field, value);
+                            let id = *id;
+                            state.update_spatial_index(&[id]);
+                            state.request_layer_redraw(state.active_layer);
+                        }
+                    }
+                }
+                state.gauge_input = Some((field, input));
+            },
+            Message::CircleRadius(input) => {
+                let state = &mut self.canvas_state;
+                if let Some(id) = &state.edit_widget_id {
+                    if let Some(CanvasWidget::Circle(cir)) = state.layers[state.active_layer].curves.get_mut(id) {
+                        if let Ok(radius) = input.trim().parse::<f32>() {
+                            if radius > 0.0 {
+                                cir.set_radius(radius);
+                                let id = *id;
+                                state.update_spatial_index(&[id]);
+                                state.request_layer_redraw(state.active_layer);
+                            }
+                        }
+                    }
+                }
+                state.radius_input = Some(input);
+            },
+            Message::PaletteSelected(palette) => {
+                self.canvas_state.selected_palette = ColorVision::to_enum(palette);
+            },
+            Message::SimulateVision(vision) => {
+                self.canvas_state.simulated_vision = ColorVision::to_enum(vision);
+                self.canvas_state.request_redraw();
+                self.canvas_state.request_text_redraw();
+            },
+            Message::FixTextContrast => {
+                let background = self.canvas_state.selected_canvas_color;
+                let state = &mut self.canvas_state;
+                if let Some(id) = &state.edit_widget_id {
+                    if let Some(CanvasWidget::Text(txt)) = state.layers[state.active_layer].curves.get_mut(id) {
+                        txt.color = fix_contrast(txt.color, background);
+                    }
+                }
+                self.canvas_state.request_text_redraw();
+            },
+            Message::PressurePreset(preset) => {
+                let preset = PressurePreset::to_enum(preset);
+                // Custom keeps the current curve so it can be edited
+                if preset == PressurePreset::Custom {
+                    self.canvas_state.pressure_curve.preset = preset;
+                } else {
+                    self.canvas_state.pressure_curve = PressureCurve::from_preset(preset);
+                }
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::PressureCurve(curve) => {
+                self.canvas_state.pressure_curve = curve;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::Stabilizer(enabled) => {
+                self.canvas_state.stabilizer = enabled;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::StabilizerStrength(strength) => {
+                self.canvas_state.stabilizer_strength = strength;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::Smoothing(enabled) => {
+                self.canvas_state.smoothing = enabled;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+            Message::SmoothingStrength(strength) => {
+                self.canvas_state.smoothing_strength = strength;
+                let _ = save_settings(&self.canvas_state.get_settings());
+            },
+
+            Message::ComponentName(name) => {
+                self.canvas_state.component_name = name;
+            },
+            Message::SaveComponent => {
+                let name = self.canvas_state.component_name.trim().to_string();
+                if !is_component_name(&name) || self.canvas_state.selected_ids.is_empty() {
+                    return Task::none()
+                }
+                let widgets: Vec<CanvasWidget> = 
+                    self.canvas_state.selected_ids.iter()
+                        .filter_map(|id| self.canvas_state.curves().get(id))
+                        .cloned()
+                        .collect();
+                if save_component(&name, &widgets).is_ok() {
+                    self.canvas_state.component_names = list_components();
+                    self.canvas_state.selected_component = Some(name);
+                }
+            },
+            Message::ComponentSelected(name) => {
+                self.canvas_state.selected_component = Some(name);
+            },
+            Message::UpdateInstances => {
+                let Some(name) = &self.canvas_state.selected_component else {
+                    return Task::none()
+                };
+                if let Some(component) = load_component(name) {
+                    update_instances(
+                        &component, 
+                        &mut self.canvas_state.instances, 
+                        &mut self.canvas_state.layers, 
+                        self.canvas_state.active_layer,
+                    );
+                    self.canvas_state.selected_ids.clear();
+                    self.canvas_state.request_redraw();
+                    self.canvas_state.request_text_redraw();
+                }
+            },
+            Message::InstanceColor(color) => {
+                if let Some(index) = find_instance(&self.canvas_state.instances, &self.canvas_state.selected_ids) {
+                    self.canvas_state.instances[index].color = color;
+                    self.replace_instance(index);
+                }
+            },
+            Message::InstanceScale(input) => {
+                if let Some(index) = find_instance(&self.canvas_state.instances, &self.canvas_state.selected_ids) {
+                    let instance = &mut self.canvas_state.instances[index];
+                    instance.scale_str = input.clone();
+                    // little error checking
+                    match input.parse::<f32>() {
+                        Ok(scale) if scale > 0.0 => instance.scale = scale,
+                        _ => return Task::none(),
+                    }
+                    self.replace_instance(index);
+                }
+            },
+            Message::InstanceText(input) => {
+                if let Some(index) = find_instance(&self.canvas_state.instances, &self.canvas_state.selected_ids) {
+                    self.canvas_state.instances[index].text = 
+                        if input.is_empty() {
+                            None
+                        } else {
+                            Some(input)
+                        };
+                    self.replace_instance(index);
+                }
+            },
+            Message::DetachInstance => {
+                // The widgets stay as plain widgets.
+                if let Some(index) = find_instance(&self.canvas_state.instances, &self.canvas_state.selected_ids) {
+                    self.canvas_state.instances.remove(index);
+                }
+            },
+            Message::GroupSelection => {
+                // the selection becomes the whole new group
+                let state = &mut self.canvas_state;
+                if group_widgets(&mut state.groups, &state.selected_ids) {
+                    state.selected_ids = expand_to_groups(&state.groups, &state.selected_ids);
+                }
+            },
+            Message::UngroupSelection => {
+                // The widgets stay selected and where they are.
+                let state = &mut self.canvas_state;
+                ungroup_widgets(&mut state.groups, &state.selected_ids);
+            },
+            Message::TagsInput(input) => {
+                // The tags are set on every selected widget.
+                let tags = parse_tags(&input);
+                for id in self.canvas_state.selected_ids.iter() {
+                    if tags.is_empty() {
+                        self.canvas_state.tags.remove(id);
+                    } else {
+                        self.canvas_state.tags.insert(*id, tags.clone());
+                    }
+                }
+                self.canvas_state.tags_str = input;
+                // the widgets left out of the exports are marked
+                self.canvas_state.request_redraw();
+            },
+            Message::HatchPattern(pattern) => {
+                self.canvas_state.hatch_pattern = HatchPattern::to_enum(pattern);
+            },
+            Message::HatchSpacing(spacing) => {
+                self.canvas_state.hatch_spacing = spacing;
+            },
+            Message::HatchAngle(angle) => {
+                self.canvas_state.hatch_angle = angle;
+            },
+            Message::HatchSelection => {
+                // the hatch is part of each closed shape, an old one is replaced
+                let state = &mut self.canvas_state;
+                let hatch = Hatch { pattern: state.hatch_pattern, spacing: state.hatch_spacing, angle: state.hatch_angle };
+                for id in expand_to_groups(&state.groups, &state.selected_ids) {
+                    let widget = state.layers.iter_mut().find_map(|layer| layer.curves.get_mut(&id));
+                    if let Some(widget) = widget.filter(|widget| get_closed_outline(widget).is_some()) {
+                        set_widget_hatch(widget, Some(hatch));
+                    }
+                }
+                state.request_redraw();
+            },
+            Message::RemoveHatch => {
+                // the hatch lines of older drawings are deleted
+                let state = &mut self.canvas_state;
+                let ids = expand_to_groups(&state.groups, &state.selected_ids);
+                let (old_ids, shape_ids): (Vec<WidgetId>, Vec<WidgetId>) = 
+                    ids.into_iter().partition(|id| is_hatch(&state.tags, id));
+                state.delete_widgets(&old_ids);
+                for id in shape_ids {
+                    if let Some(widget) = state.layers.iter_mut().find_map(|layer| layer.curves.get_mut(&id)) {
+                        set_widget_hatch(widget, None);
+                    }
+                }
+                state.request_redraw();
+            },
+            Message::ExcludeFromExport(exclude) => {
+                // the noexport tag is added to or taken off the selected widgets
+                let state = &mut self.canvas_state;
+                for id in state.selected_ids.iter() {
+                    let tags = state.tags.entry(*id).or_default();
+                    tags.retain(|tag| tag != NO_EXPORT_TAG);
+                    if exclude {
+                        tags.push(NO_EXPORT_TAG.to_string());
+                    }
+                    if tags.is_empty() {
+                        state.tags.remove(id);
+                    }
+                }
+                state.tags_str = 
+                    state.selected_ids.first()
+                        .and_then(|id| state.tags.get(id))
+                        .map(|tags| tags.join(", "))
+                        .unwrap_or_default();
+                state.request_redraw();
+            },
+            Message::ConstraintKind(kind) => {
+                self.canvas_state.constraint_kind = ConstraintKind::to_enum(kind);
+            },
+            Message::AddConstraint => {
+                // The first selected widget stays put, the others move to it.
+                let state = &mut self.canvas_state;
+                let curves = merge_layers(&state.layers);
+                let constraints = build_constraints(state.constraint_kind, &state.selected_ids, &curves);
+                if constraints.is_empty() {
+                    return Task::none()
+                }
+                state.constraints.extend(constraints);
+                solve_constraints(&state.constraints, &mut state.layers, &state.selected_ids[..1]);
+                state.request_redraw();
+            },
+            Message::RemoveConstraints => {
+                let selected_ids = &self.canvas_state.selected_ids;
+                self.canvas_state.constraints.retain(|constraint| !constraint.involves(selected_ids));
+            },
+            Message::QueryInput(input) => {
+                self.canvas_state.query = input;
+                self.canvas_state.query_error = None;
+            },
+            Message::FindWidgets => {
+                match Query::parse(&self.canvas_state.query) {
+                    Ok(query) => {
+                        self.canvas_state.selected_ids = 
+                            find_widgets(
+                                &query, 
+                                self.canvas_state.curves(), 
+                                &self.canvas_state.tags,
+                            );
+                        self.canvas_state.query_error = None;
+                    },
+                    Err(error) => {
+                        self.canvas_state.query_error = Some(error);
+                    },
+                }
+                self.canvas_state.request_redraw();
+                self.canvas_state.request_text_redraw();
+            },
+            Message::SelectSameWidth => {
+                // the widths of the first selected widget
+                let state = &mut self.canvas_state;
+                let Some(width) = 
+                    state.selected_ids.first()
+                        .and_then(|id| state.curves().get(id))
+                        .filter(|widget| !matches!(widget, CanvasWidget::Text(_)))
+                        .map(get_widget_width) else {
+                    return Task::none()
+                };
+                state.selected_ids = find_same_width(state.curves(), width);
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::ShowObjectList(show) => {
+                self.show_object_list = show;
+            },
+            Message::ExportDescription => {
+                return Task::perform(pick_description_path(self.canvas_state.data_path.clone()), Message::ExportDescriptionTo)
+            },
+            Message::ExportDescriptionTo(path) => {
+                let Some(path) = path else {
+                    return Task::none()
+                };
+                let curves = merge_exportable(&self.canvas_state.layers, &self.canvas_state.tags);
+                let description = 
+                    describe_drawing(
+                        &curves, 
+                        self.canvas_state.selected_canvas_color,
+                    );
+                if let Err(error) = fs::write(&path, description) {
+                    self.canvas_state.load_error = Some(format!("Couldn't export {}: {}", path.display(), error));
+                }
+            },
+            Message::ExportData(format) => {
+                // next to the drawing, the endpoint has no one to ask
+                let path = self.canvas_state.data_path.with_file_name(format!("widgets.{}", format.extension()));
+                let curves = merge_exportable(&self.canvas_state.layers, &self.canvas_state.tags);
+                let records = 
+                    get_widget_records(
+                        &curves, 
+                        &self.canvas_state.instances,
+                        &self.canvas_state.tags,
+                    );
+                if let Err(error) = fs::write(&path, records_to_string(&records, format)) {
+                    self.canvas_state.load_error = Some(format!("Couldn't export {}: {}", path.display(), error));
+                }
+            },
+            Message::ExportMap(format) => {
+                // the html map is over the svg, written with it first
+                // so a map is never left pointing at a missing svg
+                let state = &mut self.canvas_state;
+                let curves = merge_exportable(&state.layers, &state.tags);
+                let formats = 
+                    match format {
+                        MapFormat::Svg => vec![MapFormat::Svg],
+                        MapFormat::Html => vec![MapFormat::Svg, MapFormat::Html],
+                    };
+                for format in formats {
+                    let map = 
+                        export_image_map(
+                            &curves, 
+                            &state.instances,
+                            &state.tags,
+                            state.selected_canvas_color,
+                            format,
+                            state.precision,
+                            "drawing.svg",
+                        );
+                    let path = state.data_path.with_file_name(format!("drawing.{}", format.extension()));
+                    if let Err(error) = fs::write(&path, map) {
+                        state.load_error = Some(format!("Couldn't export {}: {}", path.display(), error));
+                        break
+                    }
+                }
+            },
+            Message::ExportLayerSvgs => {
+                // one file per layer checked for export, next to the drawing
+                let state = &mut self.canvas_state;
+                let svgs = export_layer_svgs(&state.layers, &state.instances, &state.tags, state.precision);
+                let errors: Vec<String> = 
+                    svgs.into_iter()
+                        .filter_map(|(file_name, svg)| {
+                            let path = state.data_path.with_file_name(file_name);
+                            fs::write(&path, svg).err()
+                                .map(|error| format!("Couldn't export {}: {}", path.display(), error))
+                        })
+                        .collect();
+                if !errors.is_empty() {
+                    state.load_error = Some(errors.join("\n"));
+                }
+            },
+            Message::LayerSelected(index) => {
+                // only the active layer is edited
+                self.canvas_state.active_layer = index;
+                self.canvas_state.selected_ids.clear();
+                self.canvas_state.edit_widget_id = None;
+                self.canvas_state.request_redraw();
+            },
+            Message::LayerVisible(index, visible) => {
+                self.canvas_state.layers[index].visible = visible;
+                self.canvas_state.request_redraw();
+                self.canvas_state.request_text_redraw();
+            },
+            Message::LayerExport(index, export) => {
+                self.canvas_state.layers[index].export = export;
+            },
+            Message::LayerName(name) => {
+                self.canvas_state.active_layer_mut().name = name;
+            },
+            Message::NewLayer => {
+                let state = &mut self.canvas_state;
+                let name = get_new_layer_name(&state.layers);
+                // added above the active layer
+                state.active_layer += 1;
+                state.layers.insert(state.active_layer, Layer::new(name));
+                state.selected_ids.clear();
+                state.edit_widget_id = None;
+                state.request_redraw();
+            },
+            Message::DeleteLayer => {
+                let state = &mut self.canvas_state;
+                if state.layers.len() < 2 {
+                    return Task::none()
+                }
+                // its widgets go the way any deleted widget does
+                let ids: Vec<WidgetId> = state.curves().keys().copied().collect();
+                state.delete_widgets(&ids);
+                state.layers.remove(state.active_layer);
+                state.active_layer = state.active_layer.min(state.layers.len() - 1);
+                state.selected_ids.clear();
+                state.edit_widget_id = None;
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::MoveLayer(up) => {
+                let state = &mut self.canvas_state;
+                state.active_layer = move_layer(&mut state.layers, state.active_layer, up);
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::Legend(source) => {
+                // The old legend is replaced.
+                let state = &mut self.canvas_state;
+                let old_ids = get_legend_ids(&state.tags);
+                for layer in state.layers.iter_mut() {
+                    for id in old_ids.iter() {
+                        layer.remove(id);
+                    }
+                }
+                for id in old_ids.iter() {
+                    state.tags.remove(id);
+                }
+
+                let curves = merge_layers(&state.layers);
+                let entries = get_legend_entries(&curves, &state.tags, source);
+                let widgets = build_legend(&entries, get_legend_origin(&curves), state.selected_draw_color);
+                state.tags.extend(get_legend_tags(&widgets));
+                // selected together so the tags can be changed at once
+                state.selected_ids = widgets.iter().map(get_widget_id).collect();
+                for widget in widgets {
+                    let id = get_widget_id(&widget);
+                    state.active_layer_mut().insert(id, widget);
+                }
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::Metadata(field, value) => {
+                // the title block shows the field as it's typed
+                let state = &mut self.canvas_state;
+                field.set(&mut state.metadata, value);
+                let ids = update_title_field(&mut state.layers, &state.tags, field, &state.metadata);
+                state.request_text_redraw_of(&ids);
+            },
+            Message::FrameSize(input) => {
+                self.canvas_state.frame_size_str = input;
+            },
+            Message::TitleBlock => {
+                // The old title block is replaced.
+                let state = &mut self.canvas_state;
+                let Ok(size) = parse_frame_size(&state.frame_size_str) else {
+                    return Task::none()
+                };
+                let size = size.unwrap_or_else(|| get_frame_size(&state.layers, &state.tags));
+                let old_ids = get_title_block_ids(&state.tags);
+                for layer in state.layers.iter_mut() {
+                    for id in old_ids.iter() {
+                        layer.remove(id);
+                    }
+                }
+                for id in old_ids.iter() {
+                    state.tags.remove(id);
+                }
+
+                let (widgets, tags) = build_title_block(size, &state.metadata, state.selected_draw_color);
+                state.tags.extend(tags);
+                state.selected_ids = widgets.iter().map(get_widget_id).collect();
+                for widget in widgets {
+                    let id = get_widget_id(&widget);
+                    state.active_layer_mut().insert(id, widget);
+                }
+                state.request_redraw();
+                state.request_text_redraw();
+            },
+            Message::NumberStyle(style) => {
+                self.canvas_state.numbering.style = NumberStyle::to_enum(style);
+            },
+            Message::NumberPrefix(prefix) => {
+                self.canvas_state.numbering.prefix = prefix;
+            },
+            Message::NumberSuffix(suffix) => {
+                self.canvas_state.numbering.suffix = suffix;
+            },
+            Message::NumberNext(input) => {
+                // little error checking
+                if let Ok(next) = input.trim().parse::<usize>() {
+                    if next > 0 {
+                        self.canvas_state.numbering.next = next;
+                    }
+                }
+                self.canvas_state.numbering.next_str = input;
+            },
+            Message::Renumber => {
+                // The labels on every layer are one sequence.
+                let state = &mut self.canvas_state;
+                let mut labels: Vec<&mut CanvasWidget> = 
+                    state.layers.iter_mut()
+                        .flat_map(|layer| layer.curves.iter_mut())
+                        .filter(|(id, _)| is_number(&state.tags, id))
+                        .map(|(_, widget)| widget)
+                        .collect();
+                state.numbering.next = renumber(&mut labels, &state.numbering);
+                state.numbering.next_str = String::new();
+                state.request_text_redraw();
+            },
+        }
+        Task::none()
+    }
+
+    // Places the instance again with its overrides and
+    // selects the new widgets.
+    fn replace_instance(&mut self, index: usize) {
+        let state = &mut self.canvas_state;
+        let Some(component) = load_component(&state.instances[index].name) else {
+            return
+        };
+        // the instance stays on its layer
+        let layer = 
+            state.instances[index].widget_ids.first()
+                .and_then(|id| find_layer(&state.layers, id))
+                .unwrap_or(state.active_layer);
+        remove_instance_widgets(&state.instances[index], &mut state.layers[layer]);
+        place_instance(&component, &mut state.instances[index], &mut state.layers[layer]);
+        state.selected_ids = state.instances[index].widget_ids.clone();
+        state.request_redraw();
+        state.request_text_redraw();
+    }
+
+    // Only the changed part of the style is given to the selected
+    // text, so each keeps the rest of its own style.
+    fn restyle_selected_text(&mut self, restyle: impl Fn(&mut Text)) {
+        let state = &mut self.canvas_state;
+        for layer in state.layers.iter_mut() {
+            for id in state.selected_ids.iter() {
+                if let Some(CanvasWidget::Text(txt)) = layer.curves.get_mut(id) {
+                    restyle(txt);
+                }
+            }
+        }
+        let ids = state.selected_ids.clone();
+        state.request_text_redraw_of(&ids);
+    }
+
+    // Replaces the drawing with a loaded one, an example or a template.
+    fn open_drawing(&mut self, mut document: ExportDocument) {
+        let state = &mut self.canvas_state;
+        if state.optimize {
+            optimize_widgets(&mut document.widgets);
+        }
+        (state.layers, state.instances, state.tags, state.groups) = import_widgets(document.widgets, &document.layers);
+        state.metadata = document.metadata;
+        if let Some(color) = &document.canvas_color {
+            state.selected_canvas_color = convert_to_color(color);
+            state.request_background_redraw();
+        }
+        state.active_layer = 0;
+        state.selected_ids.clear();
+        state.constraints.clear();
+        state.request_redraw();
+        state.request_text_redraw();
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let mut subscriptions = vec![];
+        
+        if self.canvas_state.timer_event_enabled {
+            subscriptions.push(time::every(
+                iced::time::Duration::from_millis(
+                    self.canvas_state.timer_duration))
+                    .map(|_| Message::Tick));
+        }
+
+        #[cfg(feature = "control")]
+        subscriptions.push(Subscription::run(crate::control::listen).map(Message::Control));
+
+        // A new url starts a new subscription.
+        #[cfg(feature = "live")]
+        if self.live {
+            let url = self.canvas_state.live_url.clone();
+            subscriptions.push(
+                Subscription::run_with_id(url.clone(), crate::live::listen(url))
+                    .map(Message::LiveValue));
+        }
+    
+        Subscription::batch(subscriptions)
+        
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        // Clear asks first, Undo Clear is there until something is drawn
+        let clear_btn = 
+            if self.confirm_clear {
+                row![
+                    text("Clear the canvas?").size(12.0),
+                    button("Yes").padding(3.0).on_press(Message::ClearDrawing),
+                    button("No").padding(3.0).on_press(Message::CancelClear),
+                ]
+                .spacing(5.0)
+                .align_y(alignment::Vertical::Center)
+                .into()
+            } else {
+                let mut buttons = vec![button("Clear Canvas").on_press(Message::Clear).into()];
+                if self.canvas_state.can_undo_clear() {
+                    buttons.push(button("Undo Clear").on_press(Message::UndoClear).into());
+                }
+                buttons.push(button("Reset Tool Settings").on_press(Message::ResetToolSettings).into());
+                row(buttons).spacing(5.0).into()
+            };
+
+        let arc = 
+            radio(
+                "Arc",
+                Widget::Arc,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let bezier = 
+            radio(
+                "Bezier",
+                Widget::Bezier,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let circle = 
+            radio(
+                "Circle",
+                Widget::Circle,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+        
+        let gauge = 
+            radio(
+                "Gauge",
+                Widget::Gauge,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+        
+        let elipse = 
+            radio(
+                "Ellipse",
+                Widget::Ellipse,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let line = 
+            radio(
+                "Line",
+                Widget::Line,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let polygon = 
+            radio(
+                "Polygon",
+                Widget::Polygon,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let polyline = 
+            radio(
+                "PolyLine",
+                Widget::PolyLine,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let r_triangle = 
+            radio(
+                "Right Triangle",
+                Widget::RightTriangle,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let rounded_rect = 
+            radio(
+                "Rounded Rect",
+                Widget::RoundedRect,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let freehand = 
+            radio(
+                "FreeHand",
+                Widget::FreeHand,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+
+        let txt = 
+            radio(
+                "Text",
+                Widget::Text,
+                self.canvas_state.selected_radio_widget,
+                Message::RadioSelected,
+                ).into();
+ 
+        let width_error = self.canvas_state.width_error;
+        let widths = 
+            text_input("Width(2.0)", 
+                        &self.canvas_state.selected_width_str)
+                .on_input(Message::WidthInput)
+                .style(move |theme: &Theme, status| input_style(theme, status, width_error))
+                .into();
+
+        // The named styles of the drawing.
+        let presets = 
+            column(vec![
+                row![
+                    pick_list(
+                        get_preset_names(&self.canvas_state.style_presets),
+                        self.canvas_state.selected_preset.clone(),
+                        Message::PresetSelected,
+                    )
+                    .placeholder("Style"),
+                    button("Delete")
+                        .padding(5.0)
+                        .on_press_maybe(self.canvas_state.selected_preset.as_ref().map(|_| Message::DeletePreset)),
+                ]
+                .spacing(5.0)
+                .into(),
+                row![
+                    text_input("Style name", &self.canvas_state.preset_name)
+                        .on_input(Message::PresetName)
+                        .on_submit(Message::SavePreset),
+                    button("Save Style")
+                        .padding(5.0)
+                        .on_press_maybe(
+                            (!self.canvas_state.preset_name.trim().is_empty()).then_some(Message::SavePreset)),
+                ]
+                .spacing(5.0)
+                .into(),
+            ])
+            .spacing(5.0)
+            .into();
+
+        let poly_points_error = self.canvas_state.poly_points_error;
+        let poly_pts_input: Element<Message> = 
+            text_input("Poly Points(3)", 
+                        &self.canvas_state.selected_poly_points_str)
+                .on_input(Message::PolyInput)
+                .style(move |theme: &Theme, status| input_style(theme, status, poly_points_error))
+                .into();
+    
+        let mode = 
+            pick_list(
+                DrawMode::options(self.canvas_state.classic_modes), 
+                self.canvas_state.draw_mode.string(), 
+                Message::ModeSelected
+            ).into();
+
+        // an example is duplicated before it can be saved
+        let save = 
+            button("Save")
+                .padding(5.0)
+                .on_press_maybe((!self.canvas_state.read_only).then_some(Message::Save))
+                .into();
+
+        let load = 
+            button("Load")
+                .padding(5.0)
+                .on_press(Message::Load)
+                .into();
+
+        let save_format = 
+            pick_list(
+                SaveFormat::options(), 
+                self.canvas_state.save_format.string(), 
+                Message::SaveFormat)
+                .into();
+
+        let optimize = 
+            checkbox("Optimize", self.canvas_state.optimize)
+                .on_toggle(Message::Optimize)
+                .into();
+
+        let precision = 
+            column(vec![
+                text(format!("Precision {}", self.canvas_state.precision)).size(12.0).into(),
+                slider(PRECISION_RANGE, self.canvas_state.precision, Message::Precision).into(),
+            ])
+            .width(100.0)
+            .into();
+
+        let backups = 
+            column(vec![
+                text(format!("Backups {}", self.canvas_state.backups)).size(12.0).into(),
+                slider(BACKUPS_RANGE, self.canvas_state.backups, Message::Backups).into(),
+            ])
+            .width(100.0)
+            .into();
+
+        let round_coordinates = 
+            checkbox("Round", self.canvas_state.round_coordinates)
+                .on_toggle(Message::RoundCoordinates)
+                .into();
+
+        let theme = 
+            pick_list(
+                AppTheme::options(), 
+                self.canvas_state.app_theme.string(), 
+                Message::ThemeSelected
+            ).into();
+
+        let classic_modes = 
+            checkbox("Edit/Rotate modes", self.canvas_state.classic_modes)
+                .on_toggle(Message::ClassicModes)
+                .into();
+
+        let resize_widths = 
+            checkbox("Resize widths", self.canvas_state.resize_widths)
+                .on_toggle(Message::ResizeWidths)
+                .into();
+
+        let grab_radius = 
+            column(vec![
+                text(format!("Grab radius {:.0}", self.canvas_state.grab_radius)).size(12.0).into(),
+                slider(GRAB_RADIUS_RANGE, self.canvas_state.grab_radius, Message::GrabRadius).into(),
+            ])
+            .into();
+
+        let preview_quality = 
+            row(vec![
+                text("Drag preview").size(12.0).into(),
+                pick_list(
+                    PreviewQuality::options(), 
+                    self.canvas_state.preview_quality.string(), 
+                    Message::PreviewQuality)
+                    .into(),
+            ])
+            .spacing(5.0)
+            .align_y(alignment::Vertical::Center)
+            .into();
+
+        let select_draw_color = 
+            button("Draw Color")
+                .padding(5.0)
+                .on_press(Message::SelectDrawColor)
+                .style(move|theme: &Theme, status| {   
+                    get_button_styling(theme, status, self.canvas_state.selected_draw_color)  
+                    });
+
+        let select_canvas_color = 
+            button("Canvas Color")
+                .padding(5.0)
+                .on_press(Message::SelectCanvasColor)
+                .style(move|theme: &Theme, status| {   
+                    get_button_styling(theme, status, self.canvas_state.selected_canvas_color)  
+                    });
+        
+        let draw_color = color_picker(
+            self.show_draw_color_picker,
+            self.canvas_state.selected_draw_color,
+            select_draw_color,
+            Message::CancelDrawColor,
+            Message::PickDrawColor,
+        ).into();
+
+        let draw_opacity = 
+            column(vec![
+                text(format!("Draw opacity {:.2}", self.canvas_state.selected_draw_color.a)).size(12.0).into(),
+                slider(0.0..=1.0, self.canvas_state.selected_draw_color.a, Message::DrawOpacity)
+                    .step(0.01)
+                    .into(),
+            ])
+            .into();
+
+        let canvas_color = color_picker(
+            self.show_canvas_color_picker,
+            self.canvas_state.selected_canvas_color,
+            select_canvas_color,
+            Message::CancelCanvasColor,
+            Message::SubmitCanvasColor,
+        ).into();
+
+        let canvas_opacity = 
+            column(vec![
+                text(format!("Canvas opacity {:.2}", self.canvas_state.selected_canvas_color.a)).size(12.0).into(),
+                slider(0.0..=1.0, self.canvas_state.selected_canvas_color.a, Message::CanvasOpacity)
+                    .step(0.01)
+                    .into(),
+            ])
+            .into();
+
+        let show_intersections = 
+            checkbox("Intersections", self.canvas_state.show_intersections)
+                .on_toggle(Message::ShowIntersections)
+                .into();
+
+        let snap_objects = 
+            checkbox("Object Snap", self.canvas_state.snap_to_objects)
+                .on_toggle(Message::SnapObjects)
+                .into();
+
+        let show_rulers = 
+            checkbox("Rulers", self.canvas_state.show_rulers)
+                .on_toggle(Message::ShowRulers)
+                .into();
+
+        let snap_intersections = 
+            checkbox("Snap", self.canvas_state.snap_to_intersections)
+                .on_toggle(Message::SnapIntersections)
+                .into();
+
+        let precise_shape = 
+            checkbox("Precise", self.canvas_state.precise_shape.enabled)
+                .on_toggle(Message::PreciseShape)
+                .into();
+
+        let object_list = 
+            checkbox("Object List", self.show_object_list)
+                .on_toggle(Message::ShowObjectList)
+                .into();
+
+        let describe = 
+            button("Describe")
+                .padding(5.0)
+                .on_press(Message::ExportDescription)
+                .into();
+
+        let export_data = 
+            row![
+                button("CSV")
+                    .padding(5.0)
+                    .on_press(Message::ExportData(DataFormat::Csv)),
+                button("JSON")
+                    .padding(5.0)
+                    .on_press(Message::ExportData(DataFormat::Json)),
+            ]
+            .spacing(5.0)
+            .into();
+
+        // The layers to include, the top layer first.
+        let mut export_rows: Vec<Element<Message>> = 
+            self.canvas_state.layers.iter()
+                .enumerate()
+                .rev()
+                .map(|(index, layer)| {
+                    checkbox(layer.name.clone(), layer.export)
+                        .size(14.0)
+                        .text_size(12.0)
+                        .on_toggle(move |export| Message::LayerExport(index, export))
+                        .into()
+                })
+                .collect();
+        export_rows.push(
+            row![
+                button("SVG")
+                    .padding(5.0)
+                    .on_press(Message::ExportMap(MapFormat::Svg)),
+                button("HTML Map")
+                    .padding(5.0)
+                    .on_press(Message::ExportMap(MapFormat::Html)),
+                button("SVG per Layer")
+                    .padding(5.0)
+                    .on_press(Message::ExportLayerSvgs),
+            ]
+            .spacing(5.0)
+            .into());
+        let export_map = 
+            column(export_rows)
+                .spacing(3.0)
+                .into();
+
+        let legend = 
+            row![
+                button("Legend Colors")
+                    .padding(5.0)
+                    .on_press(Message::Legend(LegendSource::Colors)),
+                button("Tags")
+                    .padding(5.0)
+                    .on_press(Message::Legend(LegendSource::Tags)),
+            ]
+            .spacing(5.0)
+            .into();
+
+        let mut title_rows: Vec<Element<Message>> = 
+            TitleField::all().into_iter()
+                .map(|field| {
+                    text_input(field.label(), field.value(&self.canvas_state.metadata))
+                        .on_input(move |value| Message::Metadata(field, value))
+                        .into()
+                })
+                .collect();
+        title_rows.push(
+            row![
+                text_input("Artboard(800x600)", &self.canvas_state.frame_size_str)
+                    .on_input(Message::FrameSize)
+                    .on_submit(Message::TitleBlock),
+                button("Title Block")
+                    .padding(5.0)
+                    .on_press_maybe(
+                        parse_frame_size(&self.canvas_state.frame_size_str).is_ok().then_some(Message::TitleBlock)),
+            ]
+            .spacing(5.0)
+            .into());
+        let title_block = 
+            column(title_rows)
+                .spacing(3.0)
+                .into();
+
+        let palette = 
+            pick_list(
+                ColorVision::options(), 
+                self.canvas_state.selected_palette.string(), 
+                Message::PaletteSelected
+            ).into();
+
+        // Clicking a swatch sets the draw color.
+        let swatches: Vec<Element<Message>> = 
+            get_palette(self.canvas_state.selected_palette)
+                .into_iter()
+                .map(|color| {
+                    button(text(""))
+                        .width(16.0)
+                        .height(16.0)
+                        .on_press(Message::SubmitDrawColor(color))
+                        .style(move|theme: &Theme, status| {
+                            get_button_styling(theme, status, color)
+                        })
+                        .into()
+                })
+                .collect();
+        let swatch_row = 
+            row(swatches)
+                .spacing(3.0)
+                .into();
+
+        // The colors last picked, newest first.
+        let recent: Vec<Element<Message>> = 
+            self.canvas_state.recent_colors.iter()
+                .map(|&color| {
+                    button(text(""))
+                        .width(16.0)
+                        .height(16.0)
+                        .on_press(Message::SubmitDrawColor(color))
+                        .style(move|theme: &Theme, status| {
+                            get_button_styling(theme, status, color)
+                        })
+                        .into()
+                })
+                .collect();
+        let recent_row = 
+            row(recent)
+                .spacing(3.0)
+                .into();
+
+        let simulate = 
+            pick_list(
+                ColorVision::options(), 
+                self.canvas_state.simulated_vision.string(), 
+                Message::SimulateVision
+            )
+            .placeholder("Simulate")
+            .into();
+
+        let component_name = 
+            text_input("Component name", &self.canvas_state.component_name)
+                .on_input(Message::ComponentName)
+                .into();
+
+        let save_component = 
+            button("Save Component")
+                .padding(5.0)
+                .on_press_maybe(
+                    (!self.canvas_state.selected_ids.is_empty() && is_component_name(&self.canvas_state.component_name))
+                        .then_some(Message::SaveComponent))
+                .into();
+
+        let components = 
+            pick_list(
+                self.canvas_state.component_names.clone(), 
+                self.canvas_state.selected_component.clone(), 
+                Message::ComponentSelected,
+            )
+            .placeholder("Component")
+            .into();
+
+        let update_instances = 
+            button("Update Instances")
+                .padding(5.0)
+                .on_press(Message::UpdateInstances)
+                .into();
+
+        let has_selection = !self.canvas_state.selected_ids.is_empty();
+        let group = 
+            row![
+                button("Group")
+                    .padding(5.0)
+                    .on_press_maybe(
+                        (self.canvas_state.selected_ids.len() > 1).then_some(Message::GroupSelection)),
+                button("Ungroup")
+                    .padding(5.0)
+                    .on_press_maybe(has_selection.then_some(Message::UngroupSelection)),
+            ]
+            .spacing(5.0)
+            .into();
+
+        let query = 
+            text_input("type:circle AND tag:valve", &self.canvas_state.query)
+                .on_input(Message::QueryInput)
+                .on_submit(Message::FindWidgets)
+                .into();
+
+        let find = 
+            row![
+                button("Find")
+                    .padding(5.0)
+                    .on_press(Message::FindWidgets),
+                button("Same Width")
+                    .padding(5.0)
+                    .on_press_maybe(has_selection.then_some(Message::SelectSameWidth)),
+            ]
+            .spacing(5.0)
+            .into();
+
+        let import = 
+            button("Import SVG")
+                .padding(5.0)
+                .on_press(Message::ImportSvg)
+                .into();
+
+        let resize = 
+            row![
+                text_input("Scale(2.0, 800x600)", &self.canvas_state.resize_str)
+                    .on_input(Message::ResizeInput)
+                    .on_submit(Message::ResizeDocument),
+                button("Resize")
+                    .padding(5.0)
+                    .on_press_maybe(self.canvas_state.resize_preview.map(|_| Message::ResizeDocument)),
+            ]
+            .spacing(5.0)
+            .into();
+
+        let replace = 
+            column(vec![
+                text(format!("Color tolerance {:.2}", self.canvas_state.replace_tolerance)).size(12.0).into(),
+                slider(TOLERANCE_RANGE, self.canvas_state.replace_tolerance, Message::ReplaceTolerance)
+                    .step(0.01)
+                    .into(),
+                row![
+                    button("Replace Color")
+                        .padding(5.0)
+                        .on_press_maybe(has_selection.then_some(Message::ReplaceColor)),
+                    button("Undo Replace")
+                        .padding(5.0)
+                        .on_press_maybe(
+                            (!self.canvas_state.replaced_colors.is_empty()).then_some(Message::UndoReplaceColor)),
+                ]
+                .spacing(5.0)
+                .into(),
+            ])
+            .into();
+
+        // The colors of the drawing, remapped to a palette
+        // with a preview before it's applied.
+        let document_colors: Vec<Element<Message>> = 
+            self.canvas_state.document_colors.iter()
+                .map(|&color| {
+                    button(text(""))
+                        .width(16.0)
+                        .height(16.0)
+                        .on_press(Message::SubmitDrawColor(color))
+                        .style(move|theme: &Theme, status| {
+                            get_button_styling(theme, status, color)
+                        })
+                        .into()
+                })
+                .collect();
+        let mut remap_options = ColorVision::options();
+        remap_options.push(RECENT_COLORS.to_string());
+        let mut remap_vec: Vec<Element<Message>> = vec![
+            row![
+                button("Document Colors")
+                    .padding(5.0)
+                    .on_press(Message::ScanColors),
+                pick_list(remap_options, self.canvas_state.remap_palette.clone(), Message::RemapPalette)
+                    .placeholder("Remap to"),
+            ]
+            .spacing(5.0)
+            .into(),
+            row(document_colors).spacing(3.0).into(),
+        ];
+        if self.canvas_state.palette_remap.is_some() {
+            remap_vec.push(
+                row![
+                    button("Apply Remap").padding(5.0).on_press(Message::ApplyRemap),
+                    button("Cancel").padding(5.0).on_press(Message::CancelRemap),
+                ]
+                .spacing(5.0)
+                .into());
+        }
+        let remap = column(remap_vec).spacing(5.0).into();
+
+        let scale_widths = 
+            row![
+                text_input("Widths x(1.5)", &self.canvas_state.width_scale_str)
+                    .on_input(Message::WidthScaleInput)
+                    .on_submit(Message::ScaleWidths),
+                button(if has_selection { "Scale Selected" } else { "Scale Widths" })
+                    .padding(5.0)
+                    .on_press_maybe(
+                        parse_factor(&self.canvas_state.width_scale_str).is_ok().then_some(Message::ScaleWidths)),
+            ]
+            .spacing(5.0)
+            .into();
+
+        // The top layer is listed first.
+        let active_layer = self.canvas_state.active_layer;
+        let mut layer_rows: Vec<Element<Message>> = vec![];
+        for (index, layer) in self.canvas_state.layers.iter().enumerate().rev() {
+            let visible = 
+                checkbox("", layer.visible)
+                    .on_toggle(move |visible| Message::LayerVisible(index, visible));
+            let style = if index == active_layer { button::primary } else { button::secondary };
+            let name = 
+                button(text(layer.name.clone()).size(12.0))
+                    .padding(3.0)
+                    .on_press(Message::LayerSelected(index))
+                    .style(style);
+            layer_rows.push(row![visible, name].spacing(5.0).into());
+        }
+        layer_rows.push(
+            text_input("Layer name", &self.canvas_state.layers[active_layer].name)
+                .on_input(Message::LayerName)
+                .into());
+        layer_rows.push(
+            row![
+                button("New").padding(3.0).on_press(Message::NewLayer),
+                button("Delete")
+                    .padding(3.0)
+                    .on_press_maybe((self.canvas_state.layers.len() > 1).then_some(Message::DeleteLayer)),
+            ].spacing(5.0).into());
+        layer_rows.push(
+            row![
+                button("Up").padding(3.0).on_press(Message::MoveLayer(true)),
+                button("Down").padding(3.0).on_press(Message::MoveLayer(false)),
+            ].spacing(5.0).into());
+        let layers = 
+            column(layer_rows)
+                .spacing(5.0)
+                .into();
+
+        let load_save_row = 
+            row(vec![load, save, save_format, optimize])
+                .spacing(5.0)
+                .into();
+
+        let examples = 
+            pick_list(example_names(), None::<String>, Message::Example)
+                .placeholder("Examples")
+                .into();
+
+        let templates = 
+            pick_list(self.canvas_state.template_names.clone(), None::<String>, Message::NewFromTemplate)
+                .placeholder("New from Template");
+        let template_row = 
+            column![
+                templates,
+                row![
+                    text_input("Template name", &self.canvas_state.template_name)
+                        .on_input(Message::TemplateName)
+                        .on_submit(Message::SaveTemplate),
+                    button("Save as Template")
+                        .padding(5.0)
+                        .on_press_maybe(
+                            is_template_name(&self.canvas_state.template_name).then_some(Message::SaveTemplate)),
+                ]
+                .spacing(5.0),
+            ]
+            .spacing(5.0)
+            .into();
+
+        let mut example_row = vec![examples];
+        if self.canvas_state.read_only {
+            example_row.push(button("Duplicate to Edit").padding(5.0).on_press(Message::DuplicateExample).into());
+        }
+        let example_row = 
+            row(example_row)
+                .spacing(5.0)
+                .align_y(alignment::Vertical::Center)
+                .into();
+
+        let precision_row = 
+            row(vec![precision, round_coordinates, backups])
+                .spacing(5.0)
+                .align_y(alignment::Vertical::Center)
+                .into();
+            
+        let mut col_vec = 
+            vec![
+            clear_btn,
+            arc, 
+            bezier, 
+            circle,
+            elipse, 
+            gauge,
+            line,
+            polygon,
+            polyline,
+            r_triangle,
+            rounded_rect,
+            freehand,
+            txt,
+            mode,
+            load_save_row,
+            example_row,
+            template_row,
+            precision_row,
+            theme,
+            classic_modes,
+            resize_widths,
+            grab_radius,
+            preview_quality,
+            import,
+            resize,
+            scale_widths,
+            draw_color,
+            draw_opacity,
+            replace,
+            remap,
+            canvas_color,
+            canvas_opacity,
+            palette,
+            swatch_row,
+            recent_row,
+            simulate,
+            widths,
+            presets,
+            show_intersections,
+            snap_intersections,
+            snap_objects,
+            show_rulers,
+            precise_shape,
+            object_list,
+            describe,
+            export_data,
+            export_map,
+            legend,
+            title_block,
+            component_name,
+            save_component,
+            components,
+            update_instances,
+            group,
+            query,
+            find,
+            layers,
+            ];
+
+            #[cfg(feature = "live")]
+            {
+                let live =
+                    checkbox("Live Data", self.live)
+                        .on_toggle(Message::Live);
+                col_vec.push(live.into());
+                let live_url =
+                    text_input("ws://127.0.0.1:9001", &self.canvas_state.live_url)
+                        .on_input(Message::LiveUrl);
+                col_vec.push(live_url.into());
+            }
+
+            #[cfg(feature = "image")]
+            {
+                col_vec.push(button("Background Image").on_press(Message::LoadBackground).into());
+                if let Some(background) = &self.canvas_state.background {
+                    col_vec.push(
+                        row![
+                            text_input("X, Y(0, 0)", &background.position_str)
+                                .on_input(Message::BackgroundPosition),
+                            text_input("Scale(1)", &background.scale_str)
+                                .on_input(Message::BackgroundScale),
+                        ].spacing(5.0).into());
+                    col_vec.push(text(format!("Opacity {:.2}", background.opacity)).size(12.0).into());
+                    col_vec.push(
+                        slider(BACKGROUND_OPACITY_RANGE, background.opacity, Message::BackgroundOpacity)
+                            .step(0.05)
+                            .into());
+                    col_vec.push(
+                        row![
+                            button("Export Annotated").on_press(Message::ExportAnnotated),
+                            button("Remove").on_press(Message::RemoveBackground),
+                        ].spacing(5.0).into());
+                }
+            }
+
+            if let Some(preview) = self.canvas_state.resize_preview {
+                col_vec.push(text(format!("Resized {:.0} x {:.0}", preview.width, preview.height)).size(12.0).into());
+            }
+
+            if let Some(error) = &self.canvas_state.query_error {
+                col_vec.push(text(error).size(12.0).style(text::danger).into());
+            }
+
+            if !self.canvas_state.selected_ids.is_empty() {
+                let tags = 
+                    text_input("Tags(valve, inlet)", &self.canvas_state.tags_str)
+                        .on_input(Message::TagsInput);
+                col_vec.push(tags.into());
+
+                let excluded = 
+                    self.canvas_state.selected_ids.iter()
+                        .all(|id| is_excluded(&self.canvas_state.tags, id));
+                col_vec.push(
+                    checkbox("Exclude from export", excluded)
+                        .on_toggle(Message::ExcludeFromExport)
+                        .into());
+
+                let closed_selected = 
+                    self.canvas_state.selected_ids.iter()
+                        .filter_map(|id| self.canvas_state.layers.iter().find_map(|layer| layer.curves.get(id)))
+                        .any(|widget| get_closed_outline(widget).is_some());
+                if closed_selected {
+                    col_vec.push(
+                        pick_list(HatchPattern::options(), self.canvas_state.hatch_pattern.string(), Message::HatchPattern)
+                            .into());
+                    col_vec.push(text(format!("Hatch spacing {:.0}", self.canvas_state.hatch_spacing)).size(12.0).into());
+                    col_vec.push(
+                        slider(SPACING_RANGE, self.canvas_state.hatch_spacing, Message::HatchSpacing)
+                            .step(1.0)
+                            .into());
+                    col_vec.push(text(format!("Hatch angle {:.0}", self.canvas_state.hatch_angle)).size(12.0).into());
+                    col_vec.push(
+                        slider(ANGLE_RANGE, self.canvas_state.hatch_angle, Message::HatchAngle)
+                            .step(5.0)
+                            .into());
+                    col_vec.push(
+                        row![
+                            button("Hatch").padding(3.0).on_press(Message::HatchSelection),
+                            button("Remove Hatch").padding(3.0).on_press(Message::RemoveHatch),
+                        ].spacing(5.0).into());
+                }
+
+                let kind = 
+                    pick_list(ConstraintKind::options(), self.canvas_state.constraint_kind.string(), 
+                        Message::ConstraintKind);
+                col_vec.push(kind.into());
+                col_vec.push(
+                    row![
+                        button("Constrain").padding(3.0).on_press(Message::AddConstraint),
+                        button("Free").padding(3.0).on_press(Message::RemoveConstraints),
+                    ].spacing(5.0).into());
+            }
+            
+            if self.canvas_state.selected_radio_widget == Some(Widget::Polygon) ||
+             self.canvas_state.selected_radio_widget == Some(Widget::PolyLine) {
+                col_vec.push(poly_pts_input);
+            }
+            if self.canvas_state.selected_radio_widget == Some(Widget::Arc) {
+                let closure = 
+                    pick_list(
+                        ArcClosure::options(), 
+                        self.canvas_state.arc_closure.string(), 
+                        Message::ArcClosure);
+                col_vec.push(closure.into());
+            }
+            if self.canvas_state.selected_radio_widget == Some(Widget::PolyLine) {
+                let center = 
+                    pick_list(
+                        PolyLineCenter::options(), 
+                        self.canvas_state.polyline_center.string(), 
+                        Message::PolyLineCenter);
+                col_vec.push(center.into());
+                col_vec.push(
+                    checkbox("Closed", self.canvas_state.polyline_closed)
+                        .on_toggle(Message::PolyLineClosed)
+                        .into());
+            }
+
+            let text_selected = 
+                self.canvas_state.selected_ids.iter()
+                    .any(|id| matches!(self.canvas_state.layers.iter().find_map(|layer| layer.curves.get(id)), Some(CanvasWidget::Text(_))));
+            if self.canvas_state.selected_radio_widget == Some(Widget::Text) || text_selected {
+                let style = self.canvas_state.text_style;
+                let (size_error, height_error) = (self.canvas_state.text_size_error, self.canvas_state.line_height_error);
+                let family = 
+                    pick_list(FontFamily::options(), style.family.string(), Message::FontFamily);
+                col_vec.push(family.into());
+                col_vec.push(
+                    row![
+                        text_input("Size(16)", &self.canvas_state.text_size_str)
+                            .on_input(Message::TextSize)
+                            .style(move |theme: &Theme, status| input_style(theme, status, size_error)),
+                        text_input("Line(1.2)", &self.canvas_state.line_height_str)
+                            .on_input(Message::TextLineHeight)
+                            .style(move |theme: &Theme, status| input_style(theme, status, height_error)),
+                    ].spacing(5.0).into());
+                col_vec.push(
+                    row![
+                        checkbox("Bold", style.bold).on_toggle(Message::TextBold),
+                        checkbox("Italic", style.italic).on_toggle(Message::TextItalic),
+                    ].spacing(10.0).into());
+            }
+
+            if self.canvas_state.selected_radio_widget == Some(Widget::Text) {
+                let h_text_alignment = 
+                    pick_list(HTextAlignment::options(), self.canvas_state.selected_h_text_alignment.string(), 
+                        Message::HTextAlignment);
+                col_vec.push(h_text_alignment.into());
+
+                let v_text_alignment = 
+                    pick_list(VTextAlignment::options(), self.canvas_state.selected_v_text_alignment.string(), 
+                        Message::VTextAlignment);
+                col_vec.push(v_text_alignment.into());
+            }
+
+            if self.canvas_state.selected_radio_widget == Some(Widget::FreeHand) {
+                let preset = 
+                    pick_list(
+                        PressurePreset::options(), 
+                        self.canvas_state.pressure_curve.preset.string(), 
+                        Message::PressurePreset,
+                    );
+                col_vec.push(preset.into());
+
+                let editor = 
+                    canvas(PressureCurveEditor { curve: self.canvas_state.pressure_curve })
+                        .width(150.0)
+                        .height(80.0);
+                col_vec.push(Element::from(editor).map(Message::PressureCurve));
+
+                let stabilizer = 
+                    checkbox("Stabilizer", self.canvas_state.stabilizer)
+                        .on_toggle(Message::Stabilizer);
+                col_vec.push(stabilizer.into());
+
+                if self.canvas_state.stabilizer {
+                    let strength = 
+                        slider(
+                            2.0..=60.0, 
+                            self.canvas_state.stabilizer_strength, 
+                            Message::StabilizerStrength,
+                        );
+                    col_vec.push(strength.into());
+                    col_vec.push(text(format!("Strength {:.0}", self.canvas_state.stabilizer_strength)).size(12.0).into());
+                }
+
+                let smoothing = 
+                    checkbox("Smooth", self.canvas_state.smoothing)
+                        .on_toggle(Message::Smoothing);
+                col_vec.push(smoothing.into());
+
+                if self.canvas_state.smoothing {
+                    let strength = 
+                        slider(
+                            0.5..=10.0, 
+                            self.canvas_state.smoothing_strength, 
+                            Message::SmoothingStrength,
+                        )
+                        .step(0.5);
+                    col_vec.push(strength.into());
+                    col_vec.push(text(format!("Smoothing {:.1}", self.canvas_state.smoothing_strength)).size(12.0).into());
+                }
+            }
+
+            if self.canvas_state.draw_mode == DrawMode::Number {
+                let numbering = &self.canvas_state.numbering;
+                let style = 
+                    pick_list(NumberStyle::options(), numbering.style.string(), Message::NumberStyle);
+                col_vec.push(style.into());
+                col_vec.push(
+                    text_input("Prefix", &numbering.prefix)
+                        .on_input(Message::NumberPrefix)
+                        .into());
+                col_vec.push(
+                    text_input("Suffix", &numbering.suffix)
+                        .on_input(Message::NumberSuffix)
+                        .into());
+                let next = 
+                    if numbering.next_str.is_empty() {
+                        numbering.next.to_string()
+                    } else {
+                        numbering.next_str.clone()
+                    };
+                col_vec.push(
+                    text_input("Next", &next)
+                        .on_input(Message::NumberNext)
+                        .into());
+                col_vec.push(text(format!("Next label {}", numbering.label(numbering.next))).size(12.0).into());
+                col_vec.push(
+                    button("Renumber")
+                        .padding(5.0)
+                        .on_press(Message::Renumber)
+                        .into());
+            }
+
+            if self.canvas_state.precise_shape.enabled {
+                if let Some(widget) = self.canvas_state.selected_radio_widget {
+                    for field in PreciseShape::fields(widget) {
+                        let input = 
+                            text_input(field.label(), self.canvas_state.precise_shape.get(field))
+                                .on_input(move |input| Message::PreciseInput(field, input));
+                        col_vec.push(input.into());
+                    }
+                }
+            }
+
+            // The selected widget is the last one edited or rotated.
+            let selected = 
+                self.canvas_state.edit_widget_id.as_ref()
+                    .and_then(|id| self.canvas_state.curves().get(id));
+            if let Some(widget) = selected {
+                col_vec.push(text(format!("Selected: {}", describe_widget(widget))).size(12.0).into());
+            }
+
+            // Text contrast against the canvas
+            if let Some(CanvasWidget::Text(txt)) = selected {
+                let ratio = get_contrast_ratio(txt.color, self.canvas_state.selected_canvas_color);
+                if ratio < CONTRAST_THRESHOLD {
+                    let warning = 
+                        text(format!("\u{26A0} Contrast {:.1}:1", ratio))
+                            .size(12.0)
+                            .style(text::danger);
+                    let fix = 
+                        button("Fix")
+                            .padding(3.0)
+                            .on_press(Message::FixTextContrast);
+                    col_vec.push(row![warning, fix].spacing(5.0).into());
+                } else {
+                    col_vec.push(text(format!("Contrast {:.1}:1", ratio)).size(12.0).into());
+                }
+            }
+
+            // The typed text is shown until it is a different value
+            if let Some(CanvasWidget::Gauge(gauge)) = selected {
+                for field in [GaugeField::Min, GaugeField::Max, GaugeField::Value] {
+                    let value = 
+                        match &self.canvas_state.gauge_input {
+                            Some((input_field, input)) if *input_field == field && 
+                                input.trim().parse::<f32>().map_or(true, |v| v == gauge.get(field)) => input.clone(),
+                            _ => gauge.get(field).to_string(),
+                        };
+                    let input = 
+                        text_input(field.label(), &value)
+                            .on_input(move |input| Message::GaugeInput(field, input));
+                    col_vec.push(row![text(field.label()).size(12.0).width(40.0), input].spacing(5.0).into());
+                }
+            }
+
+            if let Some(CanvasWidget::Circle(cir)) = selected {
+                let value = 
+                    match &self.canvas_state.radius_input {
+                        Some(input) if input.trim().parse::<f32>().map_or(true, |v| v == cir.radius) => input.clone(),
+                        _ => cir.radius.to_string(),
+                    };
+                let input = 
+                    text_input("Radius", &value)
+                        .on_input(Message::CircleRadius);
+                col_vec.push(row![text("Radius").size(12.0).width(40.0), input].spacing(5.0).into());
+                col_vec.push(text(format!("Diameter {:.1}", cir.radius * 2.0)).size(12.0).into());
+            }
+
+            // Overrides of the selected component instance
+            if let Some(index) = find_instance(&self.canvas_state.instances, &self.canvas_state.selected_ids) {
+                let instance = &self.canvas_state.instances[index];
+                col_vec.push(text(format!("Instance of {}", instance.name)).size(12.0).into());
+
+                let color_row = 
+                    row![
+                        button("Use Draw Color")
+                            .padding(3.0)
+                            .on_press(Message::InstanceColor(Some(self.canvas_state.selected_draw_color))),
+                        button("Reset")
+                            .padding(3.0)
+                            .on_press(Message::InstanceColor(None)),
+                    ].spacing(5.0);
+                col_vec.push(color_row.into());
+
+                col_vec.push(
+                    text_input("Scale(1.0)", &instance.scale_str)
+                        .on_input(Message::InstanceScale)
+                        .into());
+                col_vec.push(
+                    text_input("Text", instance.text.as_deref().unwrap_or(""))
+                        .on_input(Message::InstanceText)
+                        .into());
+                col_vec.push(
+                    button("Detach")
+                        .padding(5.0)
+                        .on_press(Message::DetachInstance)
+                        .into());
+            }
+
+            if self.show_object_list {
+                let descriptions: Vec<Element<Message>> = 
+                    describe_widgets(self.canvas_state.curves())
+                        .into_iter()
+                        .map(|d| text(d).size(12.0).into())
+                        .collect();
+                col_vec.push(
+                    scrollable(column(descriptions).spacing(5.0))
+                        .height(200.0)
+                        .into());
+            }
+
+        let col: Element<Message> = column(col_vec)
+            .width(175.0)
+            .spacing(10.0)
+            .padding(10.0)
+            .into();
+
+        let draw: Element<Message> =  
+            container(self.canvas_state
+            .view(self.canvas_state.curves())
+            .map(Message::Canvas))
+            .into();
+
+        // A file that didn't load leaves the drawing as it was.
+        let draw = 
+            match &self.canvas_state.load_error {
+                Some(error) => {
+                    let banner = 
+                        row![
+                            text(error).size(14.0).style(text::danger),
+                            button("Dismiss")
+                                .padding(3.0)
+                                .on_press(Message::DismissLoadError),
+                        ]
+                        .spacing(10.0)
+                        .padding(5.0);
+                    column![banner, draw].into()
+                },
+                None => draw,
+            };
+         
+        Element::from(row(vec![col, draw]))
+
+    }
+
+}
+
+fn get_button_styling(theme: &Theme,
+                        status: button::Status, 
+                        bg_color: Color,
+                        ) -> button::Style {
+
+    let mut base_style = button::primary(theme, status);
+    let mut hover_style = button::primary(theme, status);
+
+    let background = Background::new(bg_color, Color::WHITE);
+
+    base_style.background = Some(iced::Background::Color(bg_color));
+    base_style.text_color = background.base.text;
+
+    hover_style.background = Some(iced::Background::Color(background.strong.color));
+    hover_style.text_color = background.weak.text;
+
+    match status {
+        button::Status::Active | button::Status::Pressed => base_style,
+        button::Status::Hovered => hover_style,
+        button::Status::Disabled => disabled(base_style),
+    }
+}
+
+fn disabled(style: button::Style) -> button::Style {
+    button::Style {
+        background: style
+            .background
+            .map(|background| background.scale_alpha(0.5)),
+        text_color: style.text_color.scale_alpha(0.5),
+        ..style
+    }
+}
+
+// The dialogs open in the folder of the last file.
+fn file_dialog(last: &Path, filter: &str, extensions: &[&str]) -> rfd::AsyncFileDialog {
+    let dialog = rfd::AsyncFileDialog::new().add_filter(filter, extensions);
+    match last.parent() {
+        Some(dir) => dialog.set_directory(dir),
+        None => dialog,
+    }
+}
+
+async fn pick_load_path(last: PathBuf) -> Option<PathBuf> {
+    file_dialog(&last, "json", &["json"]).pick_file().await.map(|file| file.path().to_path_buf())
+}
+
+// Named after the drawing.
+async fn pick_description_path(last: PathBuf) -> Option<PathBuf> {
+    let stem = last.file_stem().map_or("drawing".to_string(), |stem| stem.to_string_lossy().to_string());
+    file_dialog(&last, "text", &["txt"])
+        .set_file_name(format!("{}-description.txt", stem))
+        .save_file().await.map(|file| file.path().to_path_buf())
+}
+
+async fn pick_svg_path(last: PathBuf) -> Option<PathBuf> {
+    file_dialog(&last, "svg", &["svg"]).pick_file().await.map(|file| file.path().to_path_buf())
+}
+
+#[cfg(feature = "image")]
+async fn pick_image_path(last: PathBuf) -> Option<PathBuf> {
+    file_dialog(&last, "image", &["png", "jpg", "jpeg"]).pick_file().await.map(|file| file.path().to_path_buf())
+}
+
+// Next to the background, named after it.
+#[cfg(feature = "image")]
+async fn pick_annotated_path(background: PathBuf) -> Option<PathBuf> {
+    let stem = background.file_stem().map_or("annotated".to_string(), |stem| stem.to_string_lossy().to_string());
+    file_dialog(&background, "png", &["png"])
+        .set_file_name(format!("{}-annotated.png", stem))
+        .save_file().await.map(|file| file.path().to_path_buf())
+}
+
+async fn pick_save_path(last: PathBuf, format: SaveFormat) -> Option<PathBuf> {
+    let name = last.file_name().map_or("data.json".to_string(), |name| name.to_string_lossy().to_string());
+    let name = format.file_name(&name);
+    file_dialog(&last, "json", &["json"]).set_file_name(name).save_file().await.map(|file| file.path().to_path_buf())
+}
+
+// iced Point does not derive any serialization 
+// so had to use own version for saving data.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ExportPoint{
+    x: f32,
+    y: f32,
+}
+
+impl ExportPoint {
+    pub fn convert(point: &Point) -> Self {
+        ExportPoint {x: point.x, y: point.y}
+    }
+
+    pub fn round(&self, places: u8) -> Self {
+        ExportPoint {x: round_to(self.x, places), y: round_to(self.y, places)}
+    }
+
+    pub fn distance(&self, to: Self) -> f32
+    {
+        let a = self.x - to.x;
+        let b = self.y - to.y;
+
+        a.hypot(b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ExportColor {
+    /// Red component, 0.0 - 1.0
+    pub r: f32,
+    /// Green component, 0.0 - 1.0
+    pub g: f32,
+    /// Blue component, 0.0 - 1.0
+    pub b: f32,
+    /// Transparency, 0.0 - 1.0
+    pub a: f32,
+}
+
+impl ExportColor {
+    pub const fn from_rgba(color: &Color) -> ExportColor {
+        ExportColor { r: color.r, g: color.g, b: color.b, a: color.a }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExportHorizontal {
+   Left,
+   Center,
+   Right,
+   None,
+}
+
+
+pub(crate) fn convert_to_export_horizontal(h: alignment::Horizontal) -> ExportHorizontal {
+    match h {
+        alignment::Horizontal::Left => ExportHorizontal::Left,
+        alignment::Horizontal::Center => ExportHorizontal::Center,
+        alignment::Horizontal::Right => ExportHorizontal::Right,
+    }
+}
+
+pub(crate) fn convert_to_iced_horizontal(h: ExportHorizontal) -> alignment::Horizontal {
+    match h {
+        ExportHorizontal::Left => alignment::Horizontal::Left,
+        ExportHorizontal::Center => alignment::Horizontal::Center,
+        ExportHorizontal::Right => alignment::Horizontal::Right,
+        ExportHorizontal::None => panic!("no matching iced alingmnet::Horizontal"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExportVertical {
+   Top,
+   Center,
+   Bottom,
+   None,
+}
+
+pub(crate) fn convert_to_export_vertical(v: alignment::Vertical) -> ExportVertical {
+    match v {
+        alignment::Vertical::Top => ExportVertical::Top,
+        alignment::Vertical::Center => ExportVertical::Center,
+        alignment::Vertical::Bottom => ExportVertical::Bottom,
+    }
+}
+
+pub(crate) fn convert_to_iced_vertical(v: ExportVertical) -> alignment::Vertical {
+    match v {
+        ExportVertical::Top => alignment::Vertical::Top,
+        ExportVertical::Center => alignment::Vertical::Center,
+        ExportVertical::Bottom => alignment::Vertical::Bottom,
+        ExportVertical::None => panic!("no matching iced alingmnet::Vertical"),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportWidget {
+    pub name: Widget,
+    pub content: String,
+    pub points: Vec<ExportPoint>,
+    pub poly_points: usize,
+    pub mid_point: ExportPoint,
+    pub other_point: ExportPoint,
+    pub rotation: f32,
+    pub radius: f32,
+    pub color: ExportColor,
+    pub width: f32,
+    #[serde(default)]
+    pub widths: Vec<f32>,
+    #[serde(default)]
+    pub smooth: bool,
+    #[serde(default)]
+    pub value: f32,
+    pub horizontal_alignment: ExportHorizontal,
+    pub vertical_alignment: ExportVertical,
+    #[serde(default)]
+    pub instance: Option<ExportInstance>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub layer: Option<ExportLayer>,
+    // None in the files saved before text was styled
+    #[serde(default)]
+    pub text_style: Option<TextStyle>,
+    #[serde(default)]
+    pub pl_center: Option<PolyLineCenter>,
+    #[serde(default)]
+    pub pl_closed: bool,
+    #[serde(default)]
+    pub arc_closure: Option<ArcClosure>,
+    #[serde(default)]
+    pub hatch: Option<Hatch>,
+    // the index of the group the widget is in
+    #[serde(default)]
+    pub group: Option<usize>,
+}
+
+impl ExportWidget {
+    // The fields every widget saves, the rest are
+    // filled in by the shape's to_export.
+    pub fn new(name: Widget, color: Color, width: f32) -> Self {
+        ExportWidget {
+            name,
+            content: String::new(),
+            points: vec![],
+            poly_points: 0,
+            mid_point: ExportPoint::convert(&Point::ORIGIN),
+            other_point: ExportPoint::convert(&Point::ORIGIN),
+            rotation: 0.0,
+            radius: 0.0,
+            color: ExportColor::from_rgba(&color),
+            width,
+            widths: vec![],
+            smooth: false,
+            value: 0.0,
+            horizontal_alignment: ExportHorizontal::None,
+            vertical_alignment: ExportVertical::None,
+            instance: None,
+            tags: vec![],
+            layer: None,
+            text_style: None,
+            pl_center: None,
+            pl_closed: false,
+            arc_closure: None,
+            hatch: None,
+            group: None,
+        }
+    }
+
+    pub fn get_points(&self) -> Vec<Point> {
+        self.points.iter().map(convert_to_point).collect()
+    }
+
+    pub fn get_mid_point(&self) -> Point {
+        convert_to_point(&self.mid_point)
+    }
+
+    pub fn get_other_point(&self) -> Point {
+        convert_to_point(&self.other_point)
+    }
+
+    pub fn get_color(&self) -> Color {
+        convert_to_color(&self.color)
+    }
+}
+
+// The placed component the widget belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportInstance {
+    pub instance: usize,
+    pub name: String,
+    pub origin: ExportPoint,
+    #[serde(default)]
+    pub color: Option<ExportColor>,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+// The layer the widget is on, files without
+// layers are loaded into a single layer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportLayer {
+    pub layer: usize,
+    pub name: String,
+    pub visible: bool,
+    #[serde(default = "default_export")]
+    pub export: bool,
+}
+
+fn default_export() -> bool {
+    true
+}
+
+pub fn import_widget(widget: &ExportWidget) -> CanvasWidget {
+    match widget.name {
+        Widget::None => CanvasWidget::None,
+        Widget::Arc => CanvasWidget::Arc(Arc::from_export(widget)),
+        Widget::Bezier => CanvasWidget::Bezier(Bezier::from_export(widget)),
+        Widget::Circle => CanvasWidget::Circle(Circle::from_export(widget)),
+        Widget::Gauge => CanvasWidget::Gauge(Gauge::from_export(widget)),
+        Widget::Ellipse => CanvasWidget::Ellipse(Ellipse::from_export(widget)),
+        Widget::Line => CanvasWidget::Line(Line::from_export(widget)),
+        Widget::Polygon => CanvasWidget::Polygon(Polygon::from_export(widget)),
+        Widget::PolyLine => CanvasWidget::PolyLine(PolyLine::from_export(widget)),
+        Widget::RightTriangle => CanvasWidget::RightTriangle(RightTriangle::from_export(widget)),
+        Widget::RoundedRect => CanvasWidget::RoundedRect(RoundedRect::from_export(widget)),
+        Widget::FreeHand => CanvasWidget::FreeHand(FreeHand::from_export(widget)),
+        Widget::Text => CanvasWidget::Text(Text::from_export(widget)),
+    }
+}
+
+// The layers, instances, tags and groups of a loaded drawing.
+type ImportedDrawing = (Vec<Layer>, Vec<ComponentInstance>, HashMap<WidgetId, Vec<String>>, Vec<Group>);
+
+// Widgets saved with an instance are linked again to their component,
+// and the grouped ones to their group.  The layers left empty are
+// only in the layer list.
+fn import_widgets(widgets: Vec<ExportWidget>, layer_links: &[ExportLayer]) -> ImportedDrawing {
+    
+    let mut layers: Vec<Layer> = vec![Layer::new("Layer 1".to_string())];
+    let mut instances: Vec<ComponentInstance> = vec![];
+    let mut tags: HashMap<WidgetId, Vec<String>> = HashMap::new();
+    let mut instance_index: HashMap<usize, usize> = HashMap::new();
+    let mut groups: Vec<Group> = vec![];
+    let mut group_index: HashMap<usize, usize> = HashMap::new();
+    let layer_slots = 
+        get_layer_slots(
+            widgets.iter()
+                .map(|widget| widget.layer.as_ref().map(|link| link.layer).unwrap_or(0))
+                .chain(layer_links.iter().map(|link| link.layer))
+        );
+
+    for widget in widgets.iter() {
+        let canvas_widget = import_widget(widget);
+        if matches!(canvas_widget, CanvasWidget::None) {
+            continue
+        }
+        let id = get_widget_id(&canvas_widget);
+        let index = layer_slots[&widget.layer.as_ref().map(|link| link.layer).unwrap_or(0)];
+        while layers.len() <= index {
+            layers.push(Layer::new(get_new_layer_name(&layers)));
+        }
+        if let Some(link) = &widget.layer {
+            import_layer(&mut layers[index], link);
+        }
+        layers[index].insert(id, canvas_widget);
+
+        if !widget.tags.is_empty() {
+            tags.insert(id, widget.tags.clone());
+        }
+
+        if let Some(link) = &widget.instance {
+            let index = *instance_index.entry(link.instance).or_insert_with(|| {
+                let mut instance = ComponentInstance::new(link.name.clone(), convert_to_point(&link.origin));
+                instance.color = link.color.as_ref().map(convert_to_color);
+                instance.scale = link.scale;
+                instance.text = link.text.clone();
+                instances.push(instance);
+                instances.len() - 1
+            });
+            instances[index].widget_ids.push(id);
+        }
+
+        if let Some(group) = widget.group {
+            let index = *group_index.entry(group).or_insert_with(|| {
+                groups.push(Group::default());
+                groups.len() - 1
+            });
+            groups[index].widget_ids.push(id);
+        }
+    }
+    for link in layer_links.iter() {
+        let index = layer_slots[&link.layer];
+        while layers.len() <= index {
+            layers.push(Layer::new(get_new_layer_name(&layers)));
+        }
+        import_layer(&mut layers[index], link);
+    }
+    // a group left with one widget is no longer a group
+    groups.retain(|group| group.widget_ids.len() > 1);
+
+    (layers, instances, tags, groups)
+
+}
+
+// The saved layer indices in order numbered from 0, so a gap
+// or a huge index in an edited file adds no empty layers.
+fn get_layer_slots(indices: impl Iterator<Item = usize>) -> HashMap<usize, usize> {
+    let mut indices: Vec<usize> = indices.collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices.into_iter()
+        .enumerate()
+        .map(|(slot, index)| (index, slot))
+        .collect()
+}
+
+fn import_layer(layer: &mut Layer, link: &ExportLayer) {
+    layer.name = link.name.clone();
+    layer.visible = link.visible;
+    layer.export = link.export;
+}
+
+fn convert_to_export_layer(index: usize, layer: &Layer) -> ExportLayer {
+    ExportLayer {
+        layer: index,
+        name: layer.name.clone(),
+        visible: layer.visible,
+        export: layer.export,
+    }
+}
+
+// The drawing with its canvas color, size and layers.
+fn convert_to_document(state: &draw_canvas::CanvasState) -> ExportDocument {
+    let widgets = convert_to_export(&state.layers, &state.instances, &state.groups, &state.tags);
+    ExportDocument {
+        canvas_color: Some(ExportColor::from_rgba(&state.selected_canvas_color)),
+        drawing_size: 
+            get_drawing_bounds(&state.layers)
+                .map(|bounds| ExportSize { width: bounds.x + bounds.width, height: bounds.y + bounds.height }),
+        layers: 
+            state.layers.iter()
+                .enumerate()
+                .map(|(index, layer)| convert_to_export_layer(index, layer))
+                .collect(),
+        metadata: state.metadata.clone(),
+        ..ExportDocument::new(widgets)
+    }
+}
+
+fn convert_to_export(layers: &[Layer], 
+                    instances: &[ComponentInstance],
+                    groups: &[Group],
+                    tags: &HashMap<WidgetId, Vec<String>>,
+                    ) -> Vec<ExportWidget> {
+    
+    let mut export = vec![];
+
+    for (layer_index, layer) in layers.iter().enumerate() {
+        for (id, widget) in layer.curves.iter() {
+            let mut x_widget = convert_widget_to_export(widget);
+            x_widget.instance = 
+                instances.iter()
+                    .position(|inst| inst.widget_ids.contains(id))
+                    .map(|index| ExportInstance {
+                        instance: index,
+                        name: instances[index].name.clone(),
+                        origin: ExportPoint::convert(&instances[index].origin),
+                        color: instances[index].color.as_ref().map(ExportColor::from_rgba),
+                        scale: instances[index].scale,
+                        text: instances[index].text.clone(),
+                    });
+            x_widget.group = find_group(groups, id);
+            x_widget.tags = tags.get(id).cloned().unwrap_or_default();
+            x_widget.layer = Some(convert_to_export_layer(layer_index, layer));
+            export.push(x_widget);
+        }
+    }
+    
+    export
+
+}
+
+pub fn convert_widget_to_export(widget: &CanvasWidget) -> ExportWidget {
+    widget.as_trait()
+        .map_or_else(|| ExportWidget::new(Widget::None, Color::TRANSPARENT, 0.0), |wt| wt.to_export())
+}
+
+fn convert_to_point(point: &ExportPoint) -> Point {
+    Point { x: point.x, y: point.y }
+}
+
+fn convert_to_color(color: &ExportColor) -> Color {
+    Color::from_rgba(color.r, color.g, color.b, color.a)
+}
@@ -0,0 +1,228 @@
+//! background
+//! A png or jpeg image under the drawing to trace over, only built
+//! with the image feature.  It's placed by its top left corner and
+//! scaled from its pixel size, it isn't saved with the drawing.
+//! The annotated image is the background at its own resolution with
+//! the curves drawn over it.
+
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use iced::advanced::image::Renderer as _;
+use iced::widget::canvas::{self, Frame};
+use iced::widget::image::Handle;
+use iced::{Point, Rectangle, Renderer, Size};
+use tiny_skia::{ColorU8, FillRule, LineCap, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+use crate::draw_canvas::CanvasWidget;
+use crate::hatch::{get_widget_hatch_marks, DOT_RADIUS, HATCH_WIDTH};
+use crate::intersections::{get_widget_primitives, Primitive};
+
+
+pub const BACKGROUND_SCALE_RANGE: RangeInclusive<f32> = 0.05..=20.0;
+pub const BACKGROUND_OPACITY_RANGE: RangeInclusive<f32> = 0.1..=1.0;
+// faint enough to draw over
+const DEFAULT_OPACITY: f32 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct BackgroundImage {
+    pub path: PathBuf,
+    handle: Handle,
+    pub position: Point,
+    pub position_str: String,
+    pub scale: f32,
+    pub scale_str: String,
+    pub opacity: f32,
+}
+
+impl BackgroundImage {
+    pub fn new(path: PathBuf) -> Self {
+        BackgroundImage {
+            handle: Handle::from_path(&path),
+            path,
+            position: Point::ORIGIN,
+            position_str: String::new(),
+            scale: 1.0,
+            scale_str: String::new(),
+            opacity: DEFAULT_OPACITY,
+        }
+    }
+
+    // The image's pixel size times the scale.
+    pub fn get_bounds(&self, size: Size<u32>) -> Rectangle {
+        Rectangle::new(
+            self.position,
+            Size::new(size.width as f32 * self.scale, size.height as f32 * self.scale),
+        )
+    }
+
+    // A canvas point in the image's pixels.
+    pub fn to_image(&self, point: Point) -> Point {
+        Point::new(
+            (point.x - self.position.x) / self.scale,
+            (point.y - self.position.y) / self.scale,
+        )
+    }
+
+    pub fn draw(&self, renderer: &Renderer, frame: &mut Frame) {
+        let bounds = self.get_bounds(renderer.measure_image(&self.handle));
+        frame.draw_image(bounds, canvas::Image::new(self.handle.clone()).opacity(self.opacity));
+    }
+}
+
+// The background with the widgets stroked over it and their hatch
+// filled, saved as a png.  The texts need a font rasterizer so
+// they aren't drawn.
+pub fn export_annotated_image(background: &BackgroundImage, widgets: &[&CanvasWidget], path: &Path) -> Result<(), String> {
+    let image = image::open(&background.path).map_err(|error| error.to_string())?.to_rgba8();
+    let mut pixmap = Pixmap::new(image.width(), image.height()).ok_or("the image is empty")?;
+    for (pixel, color) in pixmap.pixels_mut().iter_mut().zip(image.pixels()) {
+        let [r, g, b, a] = color.0;
+        *pixel = ColorU8::from_rgba(r, g, b, a).premultiply();
+    }
+    for widget in widgets {
+        stroke_widget(&mut pixmap, background, widget);
+    }
+    pixmap.save_png(path).map_err(|error| error.to_string())
+}
+
+fn stroke_widget(pixmap: &mut Pixmap, background: &BackgroundImage, widget: &CanvasWidget) {
+    let Some(wt) = widget.as_trait() else {
+        return
+    };
+    let color = wt.color();
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(
+        (color.r * 255.0) as u8, 
+        (color.g * 255.0) as u8, 
+        (color.b * 255.0) as u8, 
+        (color.a * 255.0) as u8,
+    );
+    paint.anti_alias = true;
+    let stroke = Stroke { width: wt.width() / background.scale, line_cap: LineCap::Round, ..Stroke::default() };
+
+    // the joined segments are one path so the joins aren't drawn twice
+    let mut builder = PathBuilder::new();
+    let mut last: Option<Point> = None;
+    for primitive in get_widget_primitives(widget) {
+        match primitive {
+            Primitive::Segment(start, end) => {
+                let (start, end) = (background.to_image(start), background.to_image(end));
+                if last != Some(start) {
+                    builder.move_to(start.x, start.y);
+                }
+                builder.line_to(end.x, end.y);
+                last = Some(end);
+            },
+            Primitive::Circle(center, radius) => {
+                let center = background.to_image(center);
+                builder.push_circle(center.x, center.y, radius / background.scale);
+                last = None;
+            },
+        }
+    }
+    if let Some(path) = builder.finish() {
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+
+    let marks = get_widget_hatch_marks(widget);
+    let mut builder = PathBuilder::new();
+    for (start, end) in marks.lines {
+        let (start, end) = (background.to_image(start), background.to_image(end));
+        builder.move_to(start.x, start.y);
+        builder.line_to(end.x, end.y);
+    }
+    if let Some(path) = builder.finish() {
+        let stroke = Stroke { width: HATCH_WIDTH / background.scale, ..Stroke::default() };
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+    let mut builder = PathBuilder::new();
+    for center in marks.dots {
+        let center = background.to_image(center);
+        builder.push_circle(center.x, center.y, DOT_RADIUS / background.scale);
+    }
+    if let Some(path) = builder.finish() {
+        pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+    }
+}
+
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ["png", "jpg", "jpeg"].contains(&ext.to_lowercase().as_str()))
+}
+
+// The top left corner as x, y or x y.
+pub fn parse_position(input: &str) -> Option<Point> {
+    let values: Vec<f32> =
+        input.split([',', ' '])
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse::<f32>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+    match values.as_slice() {
+        [x, y] if x.is_finite() && y.is_finite() => Some(Point::new(*x, *y)),
+        _ => None,
+    }
+}
+
+
+#[test]
+fn test_parse_position() {
+    assert_eq!(Some(Point::new(120.0, 80.5)), parse_position("120, 80.5"));
+    assert_eq!(Some(Point::new(-10.0, 0.0)), parse_position(" -10 0 "));
+    assert_eq!(None, parse_position("120"));
+    assert_eq!(None, parse_position("a, 2"));
+    assert_eq!(None, parse_position("1, 2, 3"));
+}
+
+#[test]
+fn test_background_bounds() {
+    let mut background = BackgroundImage::new(PathBuf::from("trace.png"));
+    background.position = Point::new(10.0, 20.0);
+    background.scale = 0.5;
+    assert_eq!(
+        Rectangle::new(Point::new(10.0, 20.0), Size::new(200.0, 100.0)),
+        background.get_bounds(Size::new(400, 200)),
+    );
+    assert!(is_image_path(Path::new("a/trace.JPG")));
+    assert!(!is_image_path(Path::new("trace.svg")));
+    assert_eq!(Point::new(20.0, 40.0), background.to_image(Point::new(20.0, 40.0)));
+}
+
+#[test]
+fn test_export_annotated_image() {
+    use crate::draw_canvas::{Circle, Line};
+    use crate::hatch::{Hatch, HatchPattern};
+    use crate::test_widgets::{circle, line};
+
+    let dir = std::env::temp_dir().join(format!("draw_test_annotated_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("background.png");
+    let target = dir.join("annotated.png");
+    image::RgbaImage::from_pixel(40, 20, image::Rgba([255, 255, 255, 255])).save(&source).unwrap();
+
+    // shown at twice its size, a line across the canvas is across the image
+    let mut background = BackgroundImage::new(source);
+    background.scale = 2.0;
+    let line = CanvasWidget::Line(Line { width: 4.0, ..line(Point::new(0.0, 20.0), Point::new(80.0, 20.0)) });
+    // the dots of the hatch are filled
+    let circle = CanvasWidget::Circle(Circle {
+        width: 0.5,
+        hatch: Some(Hatch { pattern: HatchPattern::Dots, spacing: 4.0, angle: 0.0 }),
+        ..circle(Point::new(60.0, 10.0), 8.0)
+    });
+    export_annotated_image(&background, &[&line, &circle], &target).unwrap();
+
+    let annotated = image::open(&target).unwrap().to_rgba8();
+    assert_eq!((40, 20), annotated.dimensions());
+    assert_eq!([0, 0, 0, 255], annotated.get_pixel(20, 10).0);
+    assert_eq!([255, 255, 255, 255], annotated.get_pixel(20, 2).0);
+    // the dots near the circle's center, inside its outline
+    assert!((28..33).flat_map(|x| (3..8).map(move |y| (x, y)))
+        .any(|(x, y)| annotated.get_pixel(x, y).0 != [255, 255, 255, 255]));
+
+    // a folder that isn't there is an error to report
+    assert!(export_annotated_image(&background, &[&line], &dir.join("missing").join("annotated.png")).is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,370 @@
+//! canvas_update
+//! The canvas messages handled on the CanvasState itself, so the
+//! application around it doesn't follow the modes and statuses of the
+//! widgets being drawn.  A message that changes the drawing or the
+//! selection gives a CanvasEvent for the application to follow.
+
+use iced::Point;
+
+use crate::components::{load_component, place_instance, ComponentInstance};
+use crate::constraints::solve_constraints;
+use crate::draw_canvas::{get_draw_mode_and_status, get_widget_id, resize_widget, scale_widget, set_widget_mode_or_status,
+    translate_widget, CanvasMessage, CanvasState, CanvasWidget, DrawMode, DrawStatus};
+use crate::groups::{expand_to_groups, find_group, remove_from_groups};
+use crate::ids::WidgetId;
+use crate::numbering::NUMBER_TAG;
+use crate::split_join::{join_widgets, split_widget, JOIN_DISTANCE};
+use crate::widget_trait::{get_selection_center, rotate_widgets_about};
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CanvasEvent {
+    // a finished new widget, or the widgets of a placed component
+    WidgetAdded(Vec<WidgetId>),
+    // the widgets edited, rotated or moved
+    WidgetChanged(Vec<WidgetId>),
+    WidgetDeleted(Vec<WidgetId>),
+    SelectionChanged,
+}
+
+impl CanvasState {
+    pub fn update(&mut self, message: CanvasMessage) -> Option<CanvasEvent> {
+        match message {
+            CanvasMessage::Widget(widget) => self.apply_widget(widget),
+            CanvasMessage::Select(id) => {
+                // a grouped widget comes and goes with its group
+                let ids = expand_to_groups(&self.groups, &[id]);
+                if self.selected_ids.contains(&id) {
+                    self.selected_ids.retain(|selected| !ids.contains(selected));
+                } else {
+                    for id in ids {
+                        if !self.selected_ids.contains(&id) {
+                            self.selected_ids.push(id);
+                        }
+                    }
+                }
+                Some(CanvasEvent::SelectionChanged)
+            },
+            CanvasMessage::SelectOnly(id) => {
+                let ids: Vec<WidgetId> = id.into_iter().collect();
+                self.selected_ids = expand_to_groups(&self.groups, &ids);
+                self.edit_widget_id = id;
+                Some(CanvasEvent::SelectionChanged)
+            },
+            CanvasMessage::SelectArea(area) => {
+                // shift adds the box to the selection
+                if !self.modifiers.shift() {
+                    self.selected_ids.clear();
+                }
+                for id in expand_to_groups(&self.groups, &self.widgets_inside(area)) {
+                    if !self.selected_ids.contains(&id) {
+                        self.selected_ids.push(id);
+                    }
+                }
+                Some(CanvasEvent::SelectionChanged)
+            },
+            CanvasMessage::RotateSelection(step_degrees, pivot) => {
+                self.rotate_selection(step_degrees, pivot)
+            },
+            CanvasMessage::MoveSelection(offset) => {
+                self.change_selection(|widget| translate_widget(widget, offset))
+            },
+            CanvasMessage::ScaleSelection(factor, pivot) => {
+                let ids = self.selected_ids.clone();
+                let center = 
+                    get_selection_center(self.layers.iter()
+                        .flat_map(|layer| ids.iter().filter_map(|id| layer.curves.get(id))));
+                let pivot = pivot.or(center)?;
+                self.change_selection(|widget| scale_widget(widget, pivot, factor))
+            },
+            CanvasMessage::Delete(id) => {
+                self.delete_widgets(&[id]);
+                Some(CanvasEvent::WidgetDeleted(vec![id]))
+            },
+            CanvasMessage::DeleteSelection => {
+                let ids = std::mem::take(&mut self.selected_ids);
+                self.delete_widgets(&ids);
+                Some(CanvasEvent::WidgetDeleted(ids))
+            },
+            CanvasMessage::Modifiers(modifiers) => {
+                self.modifiers = modifiers;
+                None
+            },
+            CanvasMessage::Reselect(previous, widget) => {
+                // the widget left is drawn again as it was
+                if let Some(left) = self.active_layer_mut().curves.get_mut(&previous) {
+                    *left = set_widget_mode_or_status(left.clone(), Some(DrawMode::DrawAll), Some(DrawStatus::Completed));
+                }
+                self.request_layer_redraw(self.active_layer);
+                self.request_text_redraw_of(&[previous]);
+                self.apply_widget(widget)
+            },
+            CanvasMessage::PolyPoints(points) => {
+                self.selected_poly_points = points;
+                self.selected_poly_points_str = points.to_string();
+                self.poly_points_error = false;
+                None
+            },
+            CanvasMessage::Place(origin) => self.place_component(origin),
+            CanvasMessage::Number(position) => {
+                let widget = self.numbering.new_label(position, self.selected_draw_color);
+                let id = get_widget_id(&widget);
+                self.tags.insert(id, vec![NUMBER_TAG.to_string()]);
+                self.active_layer_mut().insert(id, widget);
+                self.request_text_redraw_of(&[id]);
+                Some(CanvasEvent::WidgetAdded(vec![id]))
+            },
+            CanvasMessage::Split(id, point) => self.split_stroke(id, point),
+            CanvasMessage::Join(first, second) => self.join_strokes(first, second),
+            CanvasMessage::Resize(id, factor) => {
+                let widths = self.resize_widths;
+                let widget = self.active_layer_mut().curves.get_mut(&id)?;
+                *widget = resize_widget(widget.clone(), factor, widths);
+                // the constraints can move widgets on the other layers
+                solve_constraints(&self.constraints, &mut self.layers, &[id]);
+                if self.constraints.is_empty() {
+                    self.request_layer_redraw(self.active_layer);
+                } else {
+                    self.request_redraw();
+                }
+                self.request_text_redraw_of(&[id]);
+                Some(CanvasEvent::WidgetChanged(vec![id]))
+            },
+        }
+    }
+
+    // Removes the widgets from their layers with their tags,
+    // selection and instance links.
+    pub fn delete_widgets(&mut self, ids: &[WidgetId]) {
+        let mut changed_layers = vec![];
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            if ids.iter().any(|id| layer.contains(id)) {
+                changed_layers.push(index);
+            }
+            for id in ids.iter() {
+                layer.remove(id);
+            }
+        }
+        for id in ids.iter() {
+            self.tags.remove(id);
+        }
+        self.selected_ids.retain(|id| !ids.contains(id));
+        for instance in self.instances.iter_mut() {
+            instance.widget_ids.retain(|id| !ids.contains(id));
+        }
+        self.instances.retain(|instance| !instance.widget_ids.is_empty());
+        remove_from_groups(&mut self.groups, ids);
+        self.constraints.retain(|constraint| !constraint.involves(ids));
+        for layer in changed_layers {
+            self.request_layer_redraw(layer);
+        }
+        self.request_text_redraw_of(ids);
+    }
+
+    // Since the text widget may have a blinking cursor, the only way to use a timer
+    // is to use the main subscription one at this time, canvas lacks a time event.
+    // Therefore, the pending has to return the curve also at each change so that
+    // the curves can be updated.  The subscription clears the text cache at each tick.
+    fn apply_widget(&mut self, mut widget: CanvasWidget) -> Option<CanvasEvent> {
+        let (draw_mode, draw_status) = get_draw_mode_and_status(&widget);
+        let id = get_widget_id(&widget);
+        let event = match draw_status {
+            DrawStatus::Completed if draw_mode == DrawMode::New => Some(CanvasEvent::WidgetAdded(vec![id])),
+            DrawStatus::Completed => Some(CanvasEvent::WidgetChanged(vec![id])),
+            DrawStatus::Delete => Some(CanvasEvent::WidgetDeleted(vec![id])),
+            DrawStatus::Inprogress => None,
+        };
+
+        match widget {
+            CanvasWidget::Text(_) => {
+                match draw_status {
+                    DrawStatus::Completed => {
+                        widget = set_widget_mode_or_status(widget, Some(DrawMode::DrawAll), None);
+                        self.active_layer_mut().curves.entry(id).and_modify(|k| *k= widget.clone());
+                        self.timer_event_enabled = false;
+                        // the Select tool stays on after a drag
+                        if self.draw_mode != DrawMode::Select {
+                            self.draw_mode = DrawMode::DrawAll;
+                        }
+                    },
+                    DrawStatus::Delete => {
+                        self.delete_widgets(&[id]);
+                        self.timer_event_enabled = false;
+                    },
+                    DrawStatus::Inprogress => {
+                        // the cursor blinks while typing, also into a text from the edit
+                        if draw_mode == DrawMode::New {
+                            self.timer_event_enabled = true;
+                        }
+                        // Since the text always returns a new curve or updated curve,
+                        // a check for the first return is need to see if a text is present.
+                        let present = self.curves().get(&id);
+                        if present.is_none() {
+                            self.active_layer_mut().curves.insert(id, widget.clone());
+                        } else {
+                            self.active_layer_mut().curves.entry(id).and_modify(|k| *k= widget.clone());
+                        }
+                    },
+                }
+                match draw_mode {
+                    DrawMode::Edit | DrawMode::Rotate => {
+                        self.edit_widget_id = Some(id);
+                        self.active_layer_mut().curves.entry(id).and_modify(|k| *k= widget);
+                    },
+                    _ => (),
+                }
+                self.request_text_redraw_of(&[id]);
+            },
+            _ => {
+                match draw_status {
+                    DrawStatus::Completed => {
+                        widget = set_widget_mode_or_status(widget, Some(DrawMode::DrawAll), None);
+                    },
+                    DrawStatus::Delete => {
+                        self.delete_widgets(&[id]);
+                    },
+                    _ => (),
+                }
+                if draw_mode == DrawMode::New {
+                    let widget = set_widget_mode_or_status(widget, Some(DrawMode::DrawAll), Some(DrawStatus::Completed));
+                    self.active_layer_mut().curves.insert(id, widget);
+                } else {
+                    // if not new must be in edit or rotate mode so modify.
+                    self.edit_widget_id = Some(id);
+                    self.active_layer_mut().curves.entry(id).and_modify(|k| *k= widget);
+                    // the edited widget stays where it was put
+                    if draw_status == DrawStatus::Completed {
+                        solve_constraints(&self.constraints, &mut self.layers, &[id]);
+                    }
+                }
+
+                self.update_spatial_index(&[id]);
+                // the constraints can move widgets on the other layers
+                if self.constraints.is_empty() {
+                    self.request_layer_redraw(self.active_layer);
+                } else {
+                    self.request_redraw();
+                }
+            },
+        }
+        event
+    }
+
+    // About the center of the box around them all unless a pivot is given.
+    fn rotate_selection(&mut self, step_degrees: f32, pivot: Option<Point>) -> Option<CanvasEvent> {
+        let ids = self.selected_ids.clone();
+        let center =
+            get_selection_center(self.layers.iter()
+                .flat_map(|layer| ids.iter().filter_map(|id| layer.curves.get(id))));
+        let pivot = pivot.or(center)?;
+        let mut changed_layers = vec![];
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            if ids.iter().any(|id| layer.contains(id)) {
+                changed_layers.push(index);
+            }
+            rotate_widgets_about(
+                layer.curves.iter_mut()
+                    .filter(|(id, _)| ids.contains(id))
+                    .map(|(_, widget)| widget),
+                pivot,
+                step_degrees,
+            );
+        }
+        // the constraints can move widgets on the other layers
+        solve_constraints(&self.constraints, &mut self.layers, &ids);
+        if self.constraints.is_empty() {
+            for layer in changed_layers {
+                self.request_layer_redraw(layer);
+            }
+        } else {
+            self.request_redraw();
+        }
+        self.request_text_redraw_of(&ids);
+        Some(CanvasEvent::WidgetChanged(ids))
+    }
+
+    // Each selected widget on any layer changed the same way.
+    fn change_selection(&mut self, change: impl Fn(CanvasWidget) -> CanvasWidget) -> Option<CanvasEvent> {
+        let ids = self.selected_ids.clone();
+        let mut changed_layers = vec![];
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            for (_, widget) in layer.curves.iter_mut().filter(|(id, _)| ids.contains(id)) {
+                *widget = change(widget.clone());
+                if !changed_layers.contains(&index) {
+                    changed_layers.push(index);
+                }
+            }
+        }
+        // the constraints can move widgets on the other layers
+        solve_constraints(&self.constraints, &mut self.layers, &ids);
+        if self.constraints.is_empty() {
+            for layer in changed_layers {
+                self.request_layer_redraw(layer);
+            }
+        } else {
+            self.request_redraw();
+        }
+        self.request_text_redraw_of(&ids);
+        Some(CanvasEvent::WidgetChanged(ids))
+    }
+
+    // The part split off gets the tags and group of the stroke.
+    fn split_stroke(&mut self, id: WidgetId, point: Point) -> Option<CanvasEvent> {
+        let widget = self.curves().get(&id)?;
+        let (first, second) = split_widget(widget, point, self.grab_radius)?;
+        let second_id = get_widget_id(&second);
+        self.active_layer_mut().insert(id, first);
+        self.active_layer_mut().insert(second_id, second);
+        if let Some(tags) = self.tags.get(&id).cloned() {
+            self.tags.insert(second_id, tags);
+        }
+        if let Some(index) = find_group(&self.groups, &id) {
+            self.groups[index].widget_ids.push(second_id);
+        }
+        self.update_spatial_index(&[id, second_id]);
+        self.request_layer_redraw(self.active_layer);
+        Some(CanvasEvent::WidgetAdded(vec![second_id]))
+    }
+
+    // The first stroke takes in the second, which is deleted.
+    fn join_strokes(&mut self, first: WidgetId, second: WidgetId) -> Option<CanvasEvent> {
+        let joined = join_widgets(self.curves().get(&first)?, self.curves().get(&second)?, JOIN_DISTANCE)?;
+        self.delete_widgets(&[second]);
+        self.active_layer_mut().insert(first, joined);
+        self.update_spatial_index(&[first]);
+        self.request_layer_redraw(self.active_layer);
+        Some(CanvasEvent::WidgetChanged(vec![first]))
+    }
+
+    // The selected component's widgets on the active layer.
+    fn place_component(&mut self, origin: Point) -> Option<CanvasEvent> {
+        let name = self.selected_component.clone()?;
+        let component = load_component(&name)?;
+        let mut instance = ComponentInstance::new(name, origin);
+        place_instance(&component, &mut instance, self.active_layer_mut());
+        let ids = instance.widget_ids.clone();
+        self.instances.push(instance);
+        self.request_layer_redraw(self.active_layer);
+        self.request_text_redraw_of(&ids);
+        Some(CanvasEvent::WidgetAdded(ids))
+    }
+}
+
+
+#[test]
+fn test_canvas_events() {
+    use crate::settings::Settings;
+
+    let mut state = CanvasState::with_settings(Settings::default());
+    let Some(CanvasEvent::WidgetAdded(ids)) = state.update(CanvasMessage::Number(Point::new(50.0, 50.0))) else {
+        panic!("no widget added")
+    };
+    assert_eq!(1, state.curves().len());
+    assert_eq!(None, state.update(CanvasMessage::Modifiers(iced::keyboard::Modifiers::SHIFT)));
+
+    assert_eq!(Some(CanvasEvent::SelectionChanged), state.update(CanvasMessage::Select(ids[0])));
+    assert_eq!(ids, state.selected_ids);
+    assert_eq!(Some(CanvasEvent::WidgetDeleted(ids.clone())), state.update(CanvasMessage::DeleteSelection));
+    assert!(state.curves().is_empty());
+    assert!(state.tags.is_empty());
+}
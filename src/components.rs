@@ -0,0 +1,186 @@
+//! components
+//! A selection of widgets saved by name in the user library and
+//! placed as linked instances that can be updated from the library.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use iced::{Color, Point, Vector};
+use serde::{Deserialize, Serialize};
+
+use crate::draw_canvas::{get_widget_id, get_widget_points, scale_widget, set_widget_color, translate_widget, CanvasWidget};
+use crate::ids::WidgetId;
+use crate::layers::{find_layer, Layer};
+use crate::app::{convert_widget_to_export, import_widget, ExportWidget};
+
+
+pub const COMPONENTS_DIR: &str = "./resources/components";
+
+// The widgets of a component are saved relative to its origin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Component {
+    pub name: String,
+    pub widgets: Vec<ExportWidget>,
+}
+
+// A placed component, the widgets are in the curves.
+// The geometry comes from the library and the overrides
+// are applied to each widget when placed.
+#[derive(Debug, Clone)]
+pub struct ComponentInstance {
+    pub name: String,
+    pub origin: Point,
+    pub widget_ids: Vec<WidgetId>,
+    pub color: Option<Color>,
+    pub scale: f32,
+    pub scale_str: String,
+    pub text: Option<String>,
+}
+
+impl ComponentInstance {
+    pub fn new(name: String, origin: Point) -> Self {
+        ComponentInstance {
+            name,
+            origin,
+            widget_ids: vec![],
+            color: None,
+            scale: 1.0,
+            scale_str: String::new(),
+            text: None,
+        }
+    }
+}
+
+// A name is used as the file name, so no folders.
+pub fn is_component_name(name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty() && !name.contains(['/', '\\', '.'])
+}
+
+// None for a name that would be outside the components folder.
+fn get_component_path(name: &str) -> Option<PathBuf> {
+    is_component_name(name).then(|| Path::new(COMPONENTS_DIR).join(format!("{}.json", name.trim())))
+}
+
+pub fn list_components() -> Vec<String> {
+    let mut names: Vec<String> =
+        match fs::read_dir(COMPONENTS_DIR) {
+            Ok(entries) => {
+                entries.filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                    .collect()
+            },
+            Err(_) => vec![],
+        };
+    names.sort();
+    names
+}
+
+// The center of the bounds of all the widget points.
+pub fn get_widgets_center(widgets: &[CanvasWidget]) -> Point {
+    let points: Vec<Point> = widgets.iter().flat_map(get_widget_points).collect();
+    if points.is_empty() {
+        return Point::ORIGIN
+    }
+    let (mut min, mut max) = (points[0], points[0]);
+    for point in points.iter() {
+        min = Point::new(min.x.min(point.x), min.y.min(point.y));
+        max = Point::new(max.x.max(point.x), max.y.max(point.y));
+    }
+    Point::new((min.x + max.x) / 2.0, (min.y + max.y) / 2.0)
+}
+
+// Saving with the name of an existing component replaces its definition.
+pub fn save_component(name: &str, widgets: &[CanvasWidget]) -> std::io::Result<()> {
+    let Some(path) = get_component_path(name) else {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "not a component name"))
+    };
+    let origin = get_widgets_center(widgets);
+    let component = Component {
+        name: name.to_string(),
+        widgets:
+            widgets.iter()
+                .map(|widget| {
+                    convert_widget_to_export(
+                        &translate_widget(widget.clone(), Point::ORIGIN - origin))
+                })
+                .collect(),
+    };
+    fs::create_dir_all(COMPONENTS_DIR)?;
+    let data = serde_json::to_string_pretty(&component)?;
+    fs::write(path, data)
+}
+
+pub fn load_component(name: &str) -> Option<Component> {
+    let data = fs::read_to_string(get_component_path(name)?).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+// Adds the component widgets at the instance origin with new ids.
+pub fn place_instance(component: &Component,
+                    instance: &mut ComponentInstance,
+                    layer: &mut Layer,
+                    ) {
+    instance.widget_ids.clear();
+    for export in component.widgets.iter() {
+        let mut widget = scale_widget(import_widget(export), Point::ORIGIN, instance.scale);
+        widget = translate_widget(widget, Vector::new(instance.origin.x, instance.origin.y));
+        if let Some(color) = instance.color {
+            widget = set_widget_color(widget, color);
+        }
+        if let (Some(text), CanvasWidget::Text(txt)) = (&instance.text, &mut widget) {
+            txt.content = text.clone();
+        }
+
+        if matches!(widget, CanvasWidget::None) {
+            continue
+        }
+        let id = get_widget_id(&widget);
+        layer.insert(id, widget);
+        instance.widget_ids.push(id);
+    }
+}
+
+pub fn remove_instance_widgets(instance: &ComponentInstance, layer: &mut Layer) {
+    for id in instance.widget_ids.iter() {
+        layer.remove(id);
+    }
+}
+
+// Replaces the widgets of every instance of the component
+// with the current library definition, keeping the overrides.
+// Each instance stays on the layer it was on.
+pub fn update_instances(component: &Component,
+                        instances: &mut [ComponentInstance],
+                        layers: &mut [Layer],
+                        active_layer: usize,
+                        ) {
+    for instance in instances.iter_mut().filter(|inst| inst.name == component.name) {
+        let index = 
+            instance.widget_ids.first()
+                .and_then(|id| find_layer(layers, id))
+                .unwrap_or(active_layer);
+        remove_instance_widgets(instance, &mut layers[index]);
+        place_instance(component, instance, &mut layers[index]);
+    }
+}
+
+// The instance holding any of the ids.
+pub fn find_instance(instances: &[ComponentInstance], ids: &[WidgetId]) -> Option<usize> {
+    instances.iter()
+        .position(|inst| inst.widget_ids.iter().any(|id| ids.contains(id)))
+}
+
+
+#[test]
+fn test_component_names() {
+    assert!(is_component_name(" Valve "));
+    for name in ["../settings", "dir/valve", "dir\\valve", "valve.old", " "] {
+        assert!(!is_component_name(name), "{name}");
+        assert!(get_component_path(name).is_none(), "{name}");
+    }
+    assert!(save_component("../outside", &[]).is_err());
+    assert!(load_component("../settings").is_none());
+}
@@ -0,0 +1,360 @@
+//! constraints
+//! Relationships kept between the lines and circles of a sketch.
+//! After a widget is edited the others are nudged, one constraint
+//! at a time for a few rounds, until the constraints hold again.
+//! The edited widget is pinned so it stays where it was put.
+//! The constraints are kept while the drawing is open.
+
+use std::collections::HashMap;
+
+use iced::{Point, Vector};
+
+use crate::draw_canvas::CanvasWidget;
+use crate::helpers::{get_horizontal_angle_of_vector, get_mid_point};
+use crate::ids::WidgetId;
+use crate::layers::{find_layer, Layer};
+
+
+const ROUNDS: usize = 100;
+// Close enough in pixels to stop nudging.
+const TOLERANCE: f32 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintKind {
+    Coincident,
+    Horizontal,
+    Vertical,
+    EqualLength,
+    Distance,
+    Tangent,
+}
+
+impl ConstraintKind {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            ConstraintKind::Coincident => Some("Coincident".to_string()),
+            ConstraintKind::Horizontal => Some("Horizontal".to_string()),
+            ConstraintKind::Vertical => Some("Vertical".to_string()),
+            ConstraintKind::EqualLength => Some("Equal Length".to_string()),
+            ConstraintKind::Distance => Some("Distance".to_string()),
+            ConstraintKind::Tangent => Some("Tangent".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Horizontal" => ConstraintKind::Horizontal,
+            "Vertical" => ConstraintKind::Vertical,
+            "Equal Length" => ConstraintKind::EqualLength,
+            "Distance" => ConstraintKind::Distance,
+            "Tangent" => ConstraintKind::Tangent,
+            _ => ConstraintKind::Coincident,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Coincident".to_string(), "Horizontal".to_string(), "Vertical".to_string(),
+            "Equal Length".to_string(), "Distance".to_string(), "Tangent".to_string()]
+    }
+}
+
+// One end of a line, index 0 or 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineEnd {
+    pub id: WidgetId,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constraint {
+    Coincident(LineEnd, LineEnd),
+    Horizontal(WidgetId),
+    Vertical(WidgetId),
+    EqualLength(WidgetId, WidgetId),
+    Distance(LineEnd, LineEnd, f32),
+    Tangent {
+        line: WidgetId,
+        circle: WidgetId,
+    },
+}
+
+impl Constraint {
+    pub fn ids(&self) -> Vec<WidgetId> {
+        match self {
+            Constraint::Coincident(a, b) | Constraint::Distance(a, b, _) => vec![a.id, b.id],
+            Constraint::Horizontal(id) | Constraint::Vertical(id) => vec![*id],
+            Constraint::EqualLength(a, b) => vec![*a, *b],
+            Constraint::Tangent { line, circle } => vec![*line, *circle],
+        }
+    }
+
+    pub fn involves(&self, ids: &[WidgetId]) -> bool {
+        self.ids().iter().any(|id| ids.contains(id))
+    }
+}
+
+// The constraints of the kind between the selected widgets, empty
+// when the selection doesn't suit the kind.  Two ends are joined at
+// the ends of the first two lines closest to each other.
+pub fn build_constraints(kind: ConstraintKind,
+                        selected: &[WidgetId],
+                        curves: &HashMap<WidgetId, CanvasWidget>,
+                        ) -> Vec<Constraint> {
+    let lines: Vec<(WidgetId, [Point; 2])> =
+        selected.iter()
+            .filter_map(|id| match curves.get(id) {
+                Some(CanvasWidget::Line(line)) => Some((*id, [line.points[0], line.points[1]])),
+                _ => None,
+            })
+            .collect();
+    let circle =
+        selected.iter().find(|id| matches!(curves.get(id), Some(CanvasWidget::Circle(_))));
+
+    match kind {
+        ConstraintKind::Horizontal => lines.iter().map(|(id, _)| Constraint::Horizontal(*id)).collect(),
+        ConstraintKind::Vertical => lines.iter().map(|(id, _)| Constraint::Vertical(*id)).collect(),
+        ConstraintKind::EqualLength => {
+            lines.iter().skip(1).map(|(id, _)| Constraint::EqualLength(lines[0].0, *id)).collect()
+        },
+        ConstraintKind::Coincident | ConstraintKind::Distance => {
+            if lines.len() < 2 {
+                return vec![]
+            }
+            let (a, b) = (&lines[0], &lines[1]);
+            let mut closest = (f32::INFINITY, 0, 0);
+            for i in 0..2 {
+                for j in 0..2 {
+                    let distance = a.1[i].distance(b.1[j]);
+                    if distance < closest.0 {
+                        closest = (distance, i, j);
+                    }
+                }
+            }
+            let end_a = LineEnd { id: a.0, index: closest.1 };
+            let end_b = LineEnd { id: b.0, index: closest.2 };
+            if kind == ConstraintKind::Coincident {
+                vec![Constraint::Coincident(end_a, end_b)]
+            } else {
+                vec![Constraint::Distance(end_a, end_b, closest.0)]
+            }
+        },
+        ConstraintKind::Tangent => {
+            match (lines.first(), circle) {
+                (Some((line, _)), Some(circle)) => vec![Constraint::Tangent { line: *line, circle: *circle }],
+                _ => vec![],
+            }
+        },
+    }
+}
+
+// The shapes reduced to what the constraints change.
+struct Sketch {
+    lines: HashMap<WidgetId, [Point; 2]>,
+    circles: HashMap<WidgetId, (Point, f32)>,
+    pinned: Vec<WidgetId>,
+}
+
+impl Sketch {
+    // 0.0 for a pinned widget, it isn't moved.
+    fn weight(&self, id: &WidgetId) -> f32 {
+        if self.pinned.contains(id) { 0.0 } else { 1.0 }
+    }
+
+    // Nudges the shapes towards the constraint and
+    // returns how far off it was.
+    fn apply(&mut self, constraint: &Constraint) -> f32 {
+        match constraint {
+            Constraint::Coincident(a, b) => self.apply_distance(a, b, 0.0),
+            Constraint::Distance(a, b, distance) => self.apply_distance(a, b, *distance),
+            Constraint::Horizontal(id) | Constraint::Vertical(id) => {
+                let horizontal = matches!(constraint, Constraint::Horizontal(_));
+                if self.weight(id) == 0.0 {
+                    return 0.0
+                }
+                let Some(pts) = self.lines.get_mut(id) else {
+                    return 0.0
+                };
+                if horizontal {
+                    let y = (pts[0].y + pts[1].y) / 2.0;
+                    let error = (pts[0].y - pts[1].y).abs();
+                    pts[0].y = y;
+                    pts[1].y = y;
+                    error
+                } else {
+                    let x = (pts[0].x + pts[1].x) / 2.0;
+                    let error = (pts[0].x - pts[1].x).abs();
+                    pts[0].x = x;
+                    pts[1].x = x;
+                    error
+                }
+            },
+            Constraint::EqualLength(a, b) => {
+                let (Some(pa), Some(pb)) = (self.lines.get(a).copied(), self.lines.get(b).copied()) else {
+                    return 0.0
+                };
+                let (wa, wb) = (self.weight(a), self.weight(b));
+                if wa + wb == 0.0 {
+                    return 0.0
+                }
+                // the pinned line keeps its length
+                let (la, lb) = (pa[0].distance(pa[1]), pb[0].distance(pb[1]));
+                let length = (la * wb + lb * wa) / (wa + wb);
+                if wa > 0.0 {
+                    self.lines.insert(*a, set_length(pa, length));
+                }
+                if wb > 0.0 {
+                    self.lines.insert(*b, set_length(pb, length));
+                }
+                (la - lb).abs()
+            },
+            Constraint::Tangent { line, circle } => {
+                let (Some(pts), Some((center, radius))) =
+                    (self.lines.get(line).copied(), self.circles.get(circle).copied()) else {
+                    return 0.0
+                };
+                let along = pts[1] - pts[0];
+                let length = (along.x * along.x + along.y * along.y).sqrt();
+                if length == 0.0 {
+                    return 0.0
+                }
+                let normal = Vector::new(-along.y / length, along.x / length);
+                let offset = center - pts[0];
+                let side = offset.x * normal.x + offset.y * normal.y;
+                let error = side.abs() - radius;
+                if self.weight(circle) > 0.0 {
+                    self.circles.insert(*circle, (center, side.abs()));
+                } else if self.weight(line) > 0.0 {
+                    let shift = normal * (side - side.signum() * radius);
+                    self.lines.insert(*line, [pts[0] + shift, pts[1] + shift]);
+                }
+                error.abs()
+            },
+        }
+    }
+
+    fn apply_distance(&mut self, a: &LineEnd, b: &LineEnd, distance: f32) -> f32 {
+        let (Some(pa), Some(pb)) = (self.lines.get(&a.id).copied(), self.lines.get(&b.id).copied()) else {
+            return 0.0
+        };
+        let (wa, wb) = (self.weight(&a.id), self.weight(&b.id));
+        if wa + wb == 0.0 {
+            return 0.0
+        }
+        let (start, end) = (pa[a.index], pb[b.index]);
+        let current = start.distance(end);
+        let error = current - distance;
+        // the ends move apart or together along the line between them
+        let step =
+            if current > 0.0 {
+                (end - start) * (error / current)
+            } else {
+                Vector::new(distance, 0.0)
+            };
+        let mut pa = pa;
+        let mut pb = pb;
+        pa[a.index] = start + step * (wa / (wa + wb));
+        pb[b.index] = end - step * (wb / (wa + wb));
+        self.lines.insert(a.id, pa);
+        self.lines.insert(b.id, pb);
+        error.abs()
+    }
+}
+
+// The line grown or shrunk about its middle.
+fn set_length(pts: [Point; 2], length: f32) -> [Point; 2] {
+    let mid = get_mid_point(pts[0], pts[1]);
+    let current = pts[0].distance(pts[1]);
+    if current == 0.0 {
+        return pts
+    }
+    let half = (pts[1] - pts[0]) * (length / current / 2.0);
+    [mid - half, mid + half]
+}
+
+// The distances follow the drawing when it is resized.
+pub fn scale_constraints(constraints: &mut [Constraint], factor: f32) {
+    for constraint in constraints.iter_mut() {
+        if let Constraint::Distance(_, _, distance) = constraint {
+            *distance *= factor;
+        }
+    }
+}
+
+// Moves the constrained lines and circles until the constraints hold,
+// the pinned widgets are left where they are.
+pub fn solve_constraints(constraints: &[Constraint], layers: &mut [Layer], pinned: &[WidgetId]) {
+    let mut sketch = Sketch { lines: HashMap::new(), circles: HashMap::new(), pinned: pinned.to_vec() };
+    for id in constraints.iter().flat_map(|constraint| constraint.ids()) {
+        let Some(layer) = find_layer(layers, &id) else {
+            continue
+        };
+        match layers[layer].curves.get(&id) {
+            Some(CanvasWidget::Line(line)) => {
+                sketch.lines.insert(id, [line.points[0], line.points[1]]);
+            },
+            Some(CanvasWidget::Circle(cir)) => {
+                sketch.circles.insert(id, (cir.center, cir.radius));
+            },
+            _ => (),
+        }
+    }
+
+    for _ in 0..ROUNDS {
+        let mut error: f32 = 0.0;
+        for constraint in constraints.iter() {
+            error = error.max(sketch.apply(constraint));
+        }
+        if error < TOLERANCE {
+            break
+        }
+    }
+
+    for layer in layers.iter_mut() {
+        for (id, pts) in sketch.lines.iter() {
+            if let Some(CanvasWidget::Line(line)) = layer.curves.get_mut(id) {
+                line.points = pts.to_vec();
+                line.mid_point = get_mid_point(pts[0], pts[1]);
+                line.degrees = get_horizontal_angle_of_vector(line.mid_point, pts[1]);
+            }
+        }
+        for (id, (center, radius)) in sketch.circles.iter() {
+            if let Some(CanvasWidget::Circle(cir)) = layer.curves.get_mut(id) {
+                // the circle point keeps its direction
+                let along = cir.circle_point - cir.center;
+                let length = (along.x * along.x + along.y * along.y).sqrt();
+                let along = if length > 0.0 { along * (1.0 / length) } else { Vector::new(1.0, 0.0) };
+                cir.center = *center;
+                cir.radius = *radius;
+                cir.circle_point = *center + along * *radius;
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_solve_constraints() {
+    use crate::test_widgets::line;
+
+    let new_line = |p0: Point, p1: Point| CanvasWidget::Line(line(p0, p1));
+    let a = new_line(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+    let b = new_line(Point::new(12.0, 3.0), Point::new(20.0, 15.0));
+    let (id_a, id_b) = (crate::draw_canvas::get_widget_id(&a), crate::draw_canvas::get_widget_id(&b));
+    let mut layer = Layer::new("Layer 1".to_string());
+    layer.insert(id_a, a);
+    layer.insert(id_b, b);
+    let mut layers = vec![layer];
+
+    let curves = crate::layers::merge_layers(&layers);
+    let mut constraints = build_constraints(ConstraintKind::Coincident, &[id_a, id_b], &curves);
+    constraints.extend(build_constraints(ConstraintKind::Vertical, &[id_b], &curves));
+    assert_eq!(Constraint::Coincident(LineEnd { id: id_a, index: 1 }, LineEnd { id: id_b, index: 0 }), constraints[0]);
+
+    solve_constraints(&constraints, &mut layers, &[id_a]);
+    let Some(CanvasWidget::Line(b)) = layers[0].curves.get(&id_b) else {
+        panic!("line b is missing")
+    };
+    // the pinned line stays, the other end joins it and stands upright
+    assert!(b.points[0].distance(Point::new(10.0, 0.0)) < 0.1);
+    assert!((b.points[0].x - b.points[1].x).abs() < 0.1);
+}
@@ -0,0 +1,251 @@
+//! control
+//! A local http endpoint so other programs and tests can drive
+//! the drawing.  Only built with the control feature and only
+//! bound to localhost.  A browser sends an Origin header with its
+//! requests, so those are refused and a web page can't reach it.
+//!
+//! POST /widget        body is a widget in the saved json format
+//! POST /delete        body is a query, see query.rs
+//! POST /export/csv    writes the widget data table next to the drawing,
+//!                     a failed write is shown in the app
+//! POST /export/json
+//! POST /load          loads the last drawing, resources/data.json at first
+//! POST /save          saves the last drawing, resources/data.json at first
+//! POST /clear
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use iced::futures::channel::mpsc;
+use iced::futures::executor::block_on;
+use iced::futures::{SinkExt, Stream};
+
+use crate::data_export::DataFormat;
+use crate::query::Query;
+use crate::app::{ExportHorizontal, ExportVertical, ExportWidget};
+use crate::draw_canvas::Widget;
+use crate::numeric_input::POLY_POINTS_RANGE;
+
+
+pub const CONTROL_ADDRESS: &str = "127.0.0.1:7878";
+// A widget or query is far smaller.
+const MAX_BODY: usize = 1 << 20;
+// An idle connection holds up the next one only this long.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub enum ControlCommand {
+    AddWidget(Box<ExportWidget>),
+    Delete(String),
+    Export(DataFormat),
+    Load,
+    Save,
+    Clear,
+    // the endpoint couldn't be started
+    Failed(String),
+}
+
+// The commands received by the endpoint.
+pub fn listen() -> impl Stream<Item = ControlCommand> {
+    iced::stream::channel(100, |output| async move {
+        thread::spawn(move || serve(output));
+        // the thread sends the commands, keep the stream open
+        std::future::pending::<()>().await
+    })
+}
+
+fn serve(mut output: mpsc::Sender<ControlCommand>) {
+    let listener = match TcpListener::bind(CONTROL_ADDRESS) {
+        Ok(listener) => listener,
+        Err(error) => {
+            let message = format!("Couldn't start the control endpoint on {}: {}", CONTROL_ADDRESS, error);
+            let _ = block_on(output.send(ControlCommand::Failed(message)));
+            return
+        },
+    };
+    for stream in listener.incoming().flatten() {
+        if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+            continue
+        }
+        let reply =
+            match read_request(&stream) {
+                Ok((method, path, body)) => {
+                    match parse_command(&method, &path, &body) {
+                        Ok(command) => {
+                            if block_on(output.send(command)).is_err() {
+                                return
+                            }
+                            reply(202, "Accepted")
+                        },
+                        Err(error) => reply(400, &error),
+                    }
+                },
+                Err((status, error)) => reply(status, &error),
+            };
+        let _ = (&stream).write_all(reply.as_bytes());
+    }
+}
+
+// The method, path and body, or the status and error to reply with.
+fn read_request(stream: &TcpStream) -> Result<(String, String, String), (u16, String)> {
+    let bad_request = |e: std::io::Error| (400, e.to_string());
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(bad_request)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).map_err(bad_request)?;
+        let header = header.trim();
+        if header.is_empty() {
+            break
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim();
+            if name.eq_ignore_ascii_case("origin") {
+                return Err((403, "Requests from a web page are refused".to_string()))
+            }
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| (400, "Bad Content-Length".to_string()))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        return Err((413, format!("The body is over {} bytes", MAX_BODY)))
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(bad_request)?;
+    let body = String::from_utf8(body).map_err(|e| (400, e.to_string()))?;
+
+    Ok((method, path, body))
+}
+
+fn parse_command(method: &str, path: &str, body: &str) -> Result<ControlCommand, String> {
+    if method != "POST" {
+        return Err(format!("Method {} not supported, use POST", method))
+    }
+    match path {
+        "/widget" => {
+            let widget: ExportWidget = serde_json::from_str(body).map_err(|e| e.to_string())?;
+            // a bad widget is the caller's error, not a crash
+            check_export_widget(&widget)?;
+            Ok(ControlCommand::AddWidget(Box::new(widget)))
+        },
+        "/delete" => {
+            // checked here so the error goes back to the caller
+            Query::parse(body)?;
+            Ok(ControlCommand::Delete(body.to_string()))
+        },
+        "/export/csv" => Ok(ControlCommand::Export(DataFormat::Csv)),
+        "/export/json" => Ok(ControlCommand::Export(DataFormat::Json)),
+        "/load" => Ok(ControlCommand::Load),
+        "/save" => Ok(ControlCommand::Save),
+        "/clear" => Ok(ControlCommand::Clear),
+        _ => Err(format!("Unknown path {}", path)),
+    }
+}
+
+// The fewest points each widget is drawn from, a closed
+// shape has its first point again at the end.
+fn get_min_points(widget: &ExportWidget) -> usize {
+    match widget.name {
+        Widget::None | Widget::Text => 0,
+        Widget::Circle | Widget::Gauge => 1,
+        Widget::Line | Widget::PolyLine | Widget::FreeHand => 2,
+        Widget::Arc | Widget::Bezier | Widget::Ellipse => 3,
+        Widget::RightTriangle => 4,
+        Widget::RoundedRect => 5,
+        Widget::Polygon => widget.poly_points + 1,
+    }
+}
+
+// A widget from outside the app is checked before it's
+// imported, the shapes index their points.
+fn check_export_widget(widget: &ExportWidget) -> Result<(), String> {
+    match widget.name {
+        Widget::None => return Err("The widget has no type".to_string()),
+        Widget::Polygon if !POLY_POINTS_RANGE.contains(&widget.poly_points) => {
+            return Err(format!("A Polygon has {} to {} sides", POLY_POINTS_RANGE.start(), POLY_POINTS_RANGE.end()))
+        },
+        Widget::Text if widget.horizontal_alignment == ExportHorizontal::None 
+                        || widget.vertical_alignment == ExportVertical::None => {
+            return Err("A Text needs its horizontal and vertical alignment".to_string())
+        },
+        _ => (),
+    }
+    let min_points = get_min_points(widget);
+    if widget.points.len() < min_points {
+        return Err(format!("A {:?} needs {} points, it has {}", widget.name, min_points, widget.points.len()))
+    }
+    Ok(())
+}
+
+fn reply(status: u16, message: &str) -> String {
+    let reason = match status {
+        202 => "Accepted",
+        403 => "Forbidden",
+        413 => "Payload Too Large",
+        _ => "Bad Request",
+    };
+    format!("HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, message.len(), message)
+}
+
+
+#[test]
+fn test_parse_command() {
+    assert!(matches!(parse_command("POST", "/export/csv", ""), Ok(ControlCommand::Export(DataFormat::Csv))));
+    assert!(matches!(parse_command("POST", "/delete", "tag:valve"), Ok(ControlCommand::Delete(_))));
+    assert!(parse_command("POST", "/delete", "tag:valve AND").is_err());
+    assert!(parse_command("GET", "/load", "").is_err());
+    assert!(parse_command("POST", "/unknown", "").is_err());
+}
+
+#[test]
+fn test_parse_widget() {
+    use iced::{Color, Point};
+    use crate::app::ExportPoint;
+
+    // the points are empty until the shape fills them in
+    let mut circle = ExportWidget::new(Widget::Circle, Color::BLACK, 2.0);
+    assert!(parse_command("POST", "/widget", &serde_json::to_string(&circle).unwrap()).is_err());
+    circle.points = vec![ExportPoint::convert(&Point::new(10.0, 0.0))];
+    assert!(matches!(parse_command("POST", "/widget", &serde_json::to_string(&circle).unwrap()),
+        Ok(ControlCommand::AddWidget(_))));
+
+    let mut text = ExportWidget::new(Widget::Text, Color::BLACK, 2.0);
+    text.content = "label".to_string();
+    assert!(parse_command("POST", "/widget", &serde_json::to_string(&text).unwrap()).is_err());
+    text.horizontal_alignment = ExportHorizontal::Left;
+    text.vertical_alignment = ExportVertical::Top;
+    assert!(parse_command("POST", "/widget", &serde_json::to_string(&text).unwrap()).is_ok());
+
+    assert!(parse_command("POST", "/widget", "{}").is_err());
+}
+
+#[test]
+fn test_read_request() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let read = |request: &str| {
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_read_timeout(Some(READ_TIMEOUT)).unwrap();
+        read_request(&stream)
+    };
+
+    assert_eq!(Ok(("POST".to_string(), "/delete".to_string(), "tag:a".to_string())),
+        read("POST /delete HTTP/1.1\r\nContent-Length: 5\r\n\r\ntag:a"));
+    // a page in a browser can't clear the drawing
+    assert_eq!(Some(403),
+        read("POST /clear HTTP/1.1\r\nOrigin: http://example.com\r\nContent-Type: text/plain\r\n\r\n").err().map(|e| e.0));
+    assert_eq!(Some(413), read("POST /widget HTTP/1.1\r\nContent-Length: 999999999\r\n\r\n").err().map(|e| e.0));
+}
@@ -0,0 +1,174 @@
+//! data_export
+//! A table of the widgets for use in other programs,
+//! the geometry is only summarized by the bounds.
+
+use std::collections::HashMap;
+
+use iced::Point;
+use serde::Serialize;
+
+use crate::components::ComponentInstance;
+use crate::descriptions::describe_color;
+use crate::draw_canvas::{get_widget_color, get_widget_degrees, get_widget_points, get_widget_type, CanvasWidget};
+use crate::helpers::to_degrees;
+use crate::ids::WidgetId;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Csv,
+    Json,
+}
+
+impl DataFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DataFormat::Csv => "csv",
+            DataFormat::Json => "json",
+        }
+    }
+}
+
+// The name is the component the widget was placed from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WidgetRecord {
+    pub id: usize,
+    #[serde(rename = "type")]
+    pub widget_type: String,
+    pub name: String,
+    pub center_x: f32,
+    pub center_y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub rotation: f32,
+    pub color: String,
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+const CSV_HEADER: &str = "id,type,name,center_x,center_y,width,height,rotation,color,text,tags";
+
+pub fn get_widget_record(widget: &CanvasWidget, name: &str, tags: &[String]) -> WidgetRecord {
+    let points = get_widget_points(widget);
+    let (min, max) = get_bounds(&points);
+    let rotation =
+        match widget {
+            CanvasWidget::Arc(arc) => to_degrees(&arc.start_angle.0),
+            _ => get_widget_degrees(widget).unwrap_or(0.0),
+        };
+    let text =
+        match widget {
+            CanvasWidget::Text(txt) => txt.content.replace('\r', "\n"),
+            _ => String::new(),
+        };
+
+    WidgetRecord {
+        id: 0,
+        widget_type: format!("{:?}", get_widget_type(widget)),
+        name: name.to_string(),
+        center_x: (min.x + max.x) / 2.0,
+        center_y: (min.y + max.y) / 2.0,
+        width: max.x - min.x,
+        height: max.y - min.y,
+        rotation,
+        color: describe_color(get_widget_color(widget)),
+        text,
+        tags: tags.to_vec(),
+    }
+}
+
+// The records are sorted by type and position so that the
+// ids do not change between exports of the same drawing.
+pub fn get_widget_records(curves: &HashMap<WidgetId, CanvasWidget>,
+                        instances: &[ComponentInstance],
+                        tags: &HashMap<WidgetId, Vec<String>>,
+                        ) -> Vec<WidgetRecord> {
+    let mut records: Vec<WidgetRecord> =
+        curves.iter()
+            .filter(|(_, widget)| !matches!(widget, CanvasWidget::None))
+            .map(|(id, widget)| {
+                let name =
+                    instances.iter()
+                        .find(|inst| inst.widget_ids.contains(id))
+                        .map(|inst| inst.name.as_str())
+                        .unwrap_or("");
+                let widget_tags = tags.get(id).map(|t| t.as_slice()).unwrap_or(&[]);
+                get_widget_record(widget, name, widget_tags)
+            })
+            .collect();
+
+    records.sort_by(|a, b| {
+        a.widget_type.cmp(&b.widget_type)
+            .then(a.center_x.total_cmp(&b.center_x))
+            .then(a.center_y.total_cmp(&b.center_y))
+    });
+    for (index, record) in records.iter_mut().enumerate() {
+        record.id = index + 1;
+    }
+    records
+}
+
+pub fn records_to_string(records: &[WidgetRecord], format: DataFormat) -> String {
+    match format {
+        DataFormat::Csv => records_to_csv(records),
+        DataFormat::Json => serde_json::to_string_pretty(records).unwrap_or_default(),
+    }
+}
+
+fn records_to_csv(records: &[WidgetRecord]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for r in records.iter() {
+        csv.push_str(&format!("{},{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{},{},{}\n",
+            r.id,
+            r.widget_type,
+            csv_field(&r.name),
+            r.center_x,
+            r.center_y,
+            r.width,
+            r.height,
+            r.rotation,
+            r.color,
+            csv_field(&r.text),
+            csv_field(&r.tags.join(";"))));
+    }
+    csv
+}
+
+// Fields with commas, quotes or line breaks are quoted.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn get_bounds(points: &[Point]) -> (Point, Point) {
+    if points.is_empty() {
+        return (Point::ORIGIN, Point::ORIGIN)
+    }
+    let mut min = Point::new(f32::INFINITY, f32::INFINITY);
+    let mut max = Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for pt in points.iter() {
+        min.x = min.x.min(pt.x);
+        min.y = min.y.min(pt.y);
+        max.x = max.x.max(pt.x);
+        max.y = max.y.max(pt.y);
+    }
+    (min, max)
+}
+
+
+#[test]
+fn test_csv_field() {
+    assert_eq!("valve", csv_field("valve"));
+    assert_eq!("\"a, b\"", csv_field("a, b"));
+    assert_eq!("\"say \"\"hi\"\"\"", csv_field("say \"hi\""));
+}
+
+#[test]
+fn test_get_bounds() {
+    let (min, max) = get_bounds(&[Point::new(1.0, 5.0), Point::new(4.0, -2.0)]);
+    assert_eq!(Point::new(1.0, -2.0), min);
+    assert_eq!(Point::new(4.0, 5.0), max);
+}
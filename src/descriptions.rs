@@ -0,0 +1,156 @@
+//! descriptions
+//! Plain text descriptions of the widgets so the drawing
+//! can be read without seeing the canvas.
+
+use std::collections::HashMap;
+
+use iced::{Color, Point};
+
+use crate::draw_canvas::CanvasWidget;
+use crate::helpers::to_degrees;
+use crate::ids::WidgetId;
+
+
+pub fn describe_widget(widget: &CanvasWidget) -> String {
+    match widget {
+        CanvasWidget::None => String::new(),
+        CanvasWidget::Arc(arc) => {
+            format!("Arc centered at {}, radius {:.1}, from {:.1} to {:.1} degrees, {}",
+                describe_point(arc.mid_point),
+                arc.radius,
+                to_degrees(&arc.start_angle.0),
+                to_degrees(&arc.end_angle.0),
+                describe_stroke(arc.color, arc.width))
+        },
+        CanvasWidget::Bezier(bz) => {
+            format!("Bezier curve from {} to {} with control point {}, {}",
+                describe_point(bz.points[0]),
+                describe_point(bz.points[1]),
+                describe_point(bz.points[2]),
+                describe_stroke(bz.color, bz.width))
+        },
+        CanvasWidget::Circle(cir) => {
+            format!("Circle centered at {}, radius {:.1}, {}",
+                describe_point(cir.center),
+                cir.radius,
+                describe_stroke(cir.color, cir.width))
+        },
+        CanvasWidget::Gauge(gauge) => {
+            format!("Gauge centered at {}, radius {:.1}, value {} from {} to {}, {}",
+                describe_point(gauge.center),
+                gauge.radius,
+                gauge.value,
+                gauge.min,
+                gauge.max,
+                describe_stroke(gauge.color, gauge.width))
+        },
+        CanvasWidget::Ellipse(ell) => {
+            format!("Ellipse centered at {}, radii {:.1} by {:.1}, rotated {:.1} degrees, {}",
+                describe_point(ell.center),
+                ell.radii.x,
+                ell.radii.y,
+                to_degrees(&ell.rotation.0),
+                describe_stroke(ell.color, ell.width))
+        },
+        CanvasWidget::Line(ln) => {
+            format!("Line from {} to {}, length {:.1}, {:.1} degrees, {}",
+                describe_point(ln.points[0]),
+                describe_point(ln.points[1]),
+                ln.points[0].distance(ln.points[1]),
+                ln.degrees,
+                describe_stroke(ln.color, ln.width))
+        },
+        CanvasWidget::PolyLine(pl) => {
+            format!("{}PolyLine with {} points starting at {}, {}",
+                if pl.closed { "Closed " } else { "" },
+                pl.points.len(),
+                describe_point(pl.points[0]),
+                describe_stroke(pl.color, pl.width))
+        },
+        CanvasWidget::Polygon(pg) => {
+            format!("Polygon with {} sides centered at {}, radius {:.1}, {}",
+                pg.poly_points,
+                describe_point(pg.mid_point),
+                pg.mid_point.distance(pg.pg_point),
+                describe_stroke(pg.color, pg.width))
+        },
+        CanvasWidget::RightTriangle(tr) => {
+            format!("Right triangle with corners {}, {} and {}, {}",
+                describe_point(tr.points[0]),
+                describe_point(tr.points[1]),
+                describe_point(tr.points[2]),
+                describe_stroke(tr.color, tr.width))
+        },
+        CanvasWidget::RoundedRect(rr) => {
+            format!("Rounded rectangle centered at {}, {:.1} by {:.1}, corner radius {:.1}, {}",
+                describe_point(rr.mid_point),
+                rr.points[0].distance(rr.points[1]),
+                rr.points[0].distance(rr.points[3]),
+                rr.radius,
+                describe_stroke(rr.color, rr.width))
+        },
+        CanvasWidget::FreeHand(fh) => {
+            format!("FreeHand with {} points starting at {}, {}",
+                fh.points.len(),
+                describe_point(fh.points.first().unwrap_or_default()),
+                describe_stroke(fh.color, fh.width))
+        },
+        CanvasWidget::Text(txt) => {
+            format!("Text \"{}\" at {}, rotated {:.1} degrees, color {}",
+                txt.content.replace('\r', " "),
+                describe_point(txt.position),
+                txt.degrees,
+                describe_color(txt.color))
+        },
+    }
+}
+
+// The descriptions are sorted so that the order does
+// not change between each call.
+pub fn describe_widgets(curves: &HashMap<WidgetId, CanvasWidget>) -> Vec<String> {
+    let mut descriptions: Vec<String> =
+        curves.values()
+            .map(describe_widget)
+            .filter(|d| !d.is_empty())
+            .collect();
+    descriptions.sort();
+    descriptions
+}
+
+pub fn describe_drawing(curves: &HashMap<WidgetId, CanvasWidget>,
+                        canvas_color: Color,
+                        ) -> String {
+    let descriptions = describe_widgets(curves);
+    let mut drawing =
+        format!("Drawing with {} objects on a canvas colored {}\n",
+            descriptions.len(),
+            describe_color(canvas_color));
+    for (index, description) in descriptions.iter().enumerate() {
+        drawing.push_str(&format!("{}. {}\n", index + 1, description));
+    }
+    drawing
+}
+
+fn describe_point(point: Point) -> String {
+    format!("({:.0}, {:.0})", point.x, point.y)
+}
+
+fn describe_stroke(color: Color, width: f32) -> String {
+    format!("width {:.1}, color {}", width, describe_color(color))
+}
+
+pub fn describe_color(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    if a == 255 {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+}
+
+
+#[test]
+fn test_describe_color() {
+    assert_eq!("#FF0000", describe_color(Color::from_rgb(1.0, 0.0, 0.0)));
+    assert_eq!("#00000080", describe_color(Color::from_rgba8(0, 0, 0, 128.0/255.0)));
+}
@@ -0,0 +1,162 @@
+//! document
+//! The saved drawing, its widgets with the canvas color, the size the
+//! drawing covers, the layers, empty ones too, and the metadata shown
+//! in the title block.  The first files were the widgets alone,
+//! version 1, they are migrated when loaded.  A file from a newer
+//! version loads what this one knows, the fields it doesn't know are
+//! ignored and the widgets it can't read skipped.
+
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app::{ExportColor, ExportLayer, ExportWidget};
+use crate::drawing_file::read_drawing;
+
+
+pub const DOCUMENT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExportSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+// The fields of the title block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentMetadata {
+    pub project: String,
+    pub author: String,
+    pub date: String,
+    pub scale: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportDocument {
+    #[serde(default = "first_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub canvas_color: Option<ExportColor>,
+    // the right and bottom edges of the drawing, None when empty,
+    // the first version 2 files called it the canvas size
+    #[serde(default, alias = "canvas_size")]
+    pub drawing_size: Option<ExportSize>,
+    #[serde(default)]
+    pub layers: Vec<ExportLayer>,
+    #[serde(default)]
+    pub metadata: DocumentMetadata,
+    #[serde(default)]
+    pub widgets: Vec<ExportWidget>,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+impl ExportDocument {
+    pub fn new(widgets: Vec<ExportWidget>) -> Self {
+        Self {
+            version: DOCUMENT_VERSION,
+            canvas_color: None,
+            drawing_size: None,
+            layers: get_widget_layers(&widgets),
+            metadata: DocumentMetadata::default(),
+            widgets,
+        }
+    }
+}
+
+// The layers named by the widgets, for the files without a layer list.
+fn get_widget_layers(widgets: &[ExportWidget]) -> Vec<ExportLayer> {
+    let mut layers: Vec<ExportLayer> = widgets.iter().filter_map(|widget| widget.layer.clone()).collect();
+    layers.sort_by_key(|layer| layer.layer);
+    layers.dedup_by_key(|layer| layer.layer);
+    layers
+}
+
+// Each widget on its own so one that can't be read isn't the whole file.
+fn parse_widgets(widgets: Vec<Value>) -> Vec<ExportWidget> {
+    widgets.into_iter()
+        .filter_map(|widget| serde_json::from_value(widget).ok())
+        .collect()
+}
+
+// Any version of the document as the current one, None when it isn't a drawing.
+pub fn migrate_document(value: Value) -> Option<ExportDocument> {
+    let mut document =
+        match value {
+            Value::Array(widgets) => ExportDocument {
+                version: first_version(),
+                ..ExportDocument::new(parse_widgets(widgets))
+            },
+            Value::Object(mut map) => {
+                let widgets = match map.remove("widgets") {
+                    Some(Value::Array(widgets)) => parse_widgets(widgets),
+                    _ => vec![],
+                };
+                let mut document: ExportDocument = serde_json::from_value(Value::Object(map)).ok()?;
+                document.widgets = widgets;
+                document
+            },
+            _ => return None,
+        };
+    if document.layers.is_empty() {
+        document.layers = get_widget_layers(&document.widgets);
+    }
+    document.version = document.version.max(DOCUMENT_VERSION);
+    Some(document)
+}
+
+pub fn parse_document(data: &str) -> Option<ExportDocument> {
+    migrate_document(serde_json::from_str(data).ok()?)
+}
+
+// Pretty, compact or gzipped, of any version.
+pub fn read_document(path: &Path) -> io::Result<ExportDocument> {
+    migrate_document(read_drawing(path)?)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a drawing"))
+}
+
+
+#[test]
+fn test_migrate_document() {
+    use crate::examples::EXAMPLES;
+
+    // the examples are saved in the first version
+    let (_, data) = EXAMPLES[0];
+    let widgets: Vec<ExportWidget> = serde_json::from_str(data).unwrap();
+    let document = parse_document(data).unwrap();
+    assert_eq!(DOCUMENT_VERSION, document.version);
+    assert_eq!(widgets.len(), document.widgets.len());
+    assert_eq!(None, document.canvas_color);
+
+    let mut saved = ExportDocument::new(widgets);
+    saved.canvas_color = Some(ExportColor { r: 1.0, g: 1.0, b: 0.9, a: 1.0 });
+    saved.drawing_size = Some(ExportSize { width: 400.0, height: 300.0 });
+    saved.layers.push(ExportLayer { layer: 3, name: "Notes".to_string(), visible: false, export: true });
+    saved.metadata.project = "Pump House".to_string();
+    let data = serde_json::to_string(&saved).unwrap();
+    let document = parse_document(&data).unwrap();
+    assert_eq!(saved.canvas_color, document.canvas_color);
+    assert_eq!(saved.drawing_size, document.drawing_size);
+    assert_eq!(Some("Notes"), document.layers.last().map(|layer| layer.name.as_str()));
+    assert_eq!(saved.metadata, document.metadata);
+
+    // a newer file with a field and a widget this version doesn't know
+    let mut value: Value = serde_json::from_str(&data).unwrap();
+    value["version"] = 3.into();
+    value["grid"] = "dots".into();
+    value["widgets"].as_array_mut().unwrap().push(serde_json::json!({"name": "Spiral"}));
+    let document = migrate_document(value).unwrap();
+    assert_eq!(3, document.version);
+    assert_eq!(saved.widgets.len(), document.widgets.len());
+
+    let older = data.replace("drawing_size", "canvas_size");
+    assert_eq!(saved.drawing_size, parse_document(&older).unwrap().drawing_size);
+
+    assert!(parse_document("\"drawing\"").is_none());
+    assert!(parse_document("{\"version\": \"two\"}").is_none());
+}
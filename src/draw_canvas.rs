@@ -1,21 +1,82 @@
 //! draw_canvas
-use std::collections::HashMap;
-
-use iced::keyboard::Key;
-use iced::widget::container::Id;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::TAU;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use iced::keyboard::{Key, Modifiers};
 use iced::widget::text::{LineHeight, Shaping};
-use iced::{alignment, mouse, Color, Font, Pixels, Radians, Vector};
+use iced::{alignment, mouse, Color, Font, Pixels, Radians, Rectangle, Vector};
 use iced::widget::canvas::event::{self, Event};
 use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
 use iced::{Element, Fill, Point, Renderer, Theme};
 use serde::{Deserialize, Serialize};
 
-use crate::helpers::{build_polygon, get_angle_of_vectors, get_horizontal_angle_of_vector, get_line_from_slope_intercept, get_linear_regression, get_mid_point, iced_h_text_alignment, iced_v_text_alignment, rotate_geometry, to_degrees, to_radians, translate_geometry};
-use crate::path_builds::{build_arc_path, build_bezier_path, build_circle_path, 
-    build_ellipse_path, build_free_hand_path, build_line_path, 
-    build_polygon_path, build_polyline_path, build_right_triangle_path, build_text_path};
-
-
+use crate::helpers::{build_polygon, get_horizontal_angle_of_vector, get_lazy_point, get_mid_point, get_polyline_center, get_parallel_line, get_perpendicular_foot, get_tangent_points, iced_h_text_alignment, iced_v_text_alignment, scale_geometry, simplify_points, to_degrees, to_radians, translate_geometry};
+use crate::ids::WidgetId;
+use crate::text_edit::TextCursor;
+use crate::numeric_input::POLY_POINTS_RANGE;
+use crate::spatial_index::SpatialIndex;
+use crate::render_cache::RenderCache;
+use crate::rulers::{draw_cursor_readout, draw_rulers};
+use crate::nodes::{insert_node, remove_node};
+use crate::preview::{get_preview_pending, PreviewQuality};
+use crate::object_snap::{build_snap_marker, get_closest_snap, get_snap_points, SnapKind, SnapPoint};
+use crate::drawing_file::SaveFormat;
+#[cfg(feature = "image")]
+use crate::background::BackgroundImage;
+use crate::intersections::{get_all_intersections, get_arc_points, get_closest_point, get_distance_to_primitives, 
+    get_distance_to_segment, get_line_params, get_point_at_param, get_projection_param, 
+    get_widget_primitives, PARAM_EPSILON, SNAP_DISTANCE};
+use crate::palettes::{simulate_color_vision, ColorVision};
+use crate::pressure::{get_pen_pressure, PressureCurve};
+use crate::settings::{load_settings, Settings};
+use crate::components::{list_components, ComponentInstance};
+use crate::layers::{is_excluded, Layer};
+use crate::style_presets::StylePreset;
+use crate::themes::AppTheme;
+use crate::templates::{list_templates, TEMPLATES_DIR};
+use crate::document::DocumentMetadata;
+use crate::hatch::{Hatch, HatchPattern, DEFAULT_ANGLE, DEFAULT_SPACING};
+use crate::replace_color::{get_remapped_color, get_remapped_curves, DEFAULT_TOLERANCE};
+use crate::groups::{expand_to_groups, Group};
+use crate::numbering::Numbering;
+use crate::stroke::StrokePoints;
+use crate::constraints::{Constraint, ConstraintKind};
+use crate::text_style::TextStyle;
+use crate::widget_trait::{get_points_bounds, get_text_box};
+use crate::path_builds::{build_markers_path, build_primitives_path, build_text_path, 
+    build_tool_preview_path, PathOptions, WidgetPath};
+
+
+// The time between the two clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+
+// How far the Select tool moves before a press becomes a drag.
+const DRAG_DISTANCE: f32 = 3.0;
+
+// Each wheel step with shift scales the Select tool's widgets by this,
+// and each step of the Resize mode the widget under the cursor.
+const SCALE_STEP: f32 = 1.1;
+
+// Where an arc's readouts are drawn from their handles.
+const READOUT_OFFSET: Vector = Vector::new(8.0, -14.0);
+
+// A Resize drag doesn't shrink the widget to nothing.
+const MIN_RESIZE: f32 = 0.05;
+
+// How far the rotation handle is above the selected widget.
+const ROTATE_HANDLE_OFFSET: f32 = 24.0;
+
+// The tool preview sits below and right of the cursor, with
+// a width that is kept from filling it in.
+const TOOL_PREVIEW_OFFSET: Vector = Vector::new(16.0, 16.0);
+const TOOL_PREVIEW_WIDTH: f32 = 4.0;
+
+// The side of the square drawn at each point a click can grab.
+const HANDLE_SIZE: f32 = 7.0;
 
 #[derive(Debug, Clone, Default)]
 pub enum CanvasWidget {
@@ -29,8 +90,45 @@ pub enum CanvasWidget {
     PolyLine(PolyLine),
     Polygon(Polygon),
     RightTriangle(RightTriangle),
+    RoundedRect(RoundedRect),
     Text(Text),
     FreeHand(FreeHand),
+    Gauge(Gauge),
+}
+
+// The messages of the canvas that are not a single widget
+#[derive(Debug, Clone)]
+pub enum CanvasMessage {
+    Widget(CanvasWidget),
+    Select(WidgetId),
+    Place(Point),
+    Number(Point),
+    Delete(WidgetId),
+    // the Delete key removes the Select tool's selection
+    DeleteSelection,
+    // the modifiers held, an alt click cycles the edit selection
+    Modifiers(Modifiers),
+    // the first widget is left and the second is selected for the edit
+    Reselect(WidgetId, CanvasWidget),
+    // the points of the polygon or polyline being drawn
+    PolyPoints(usize),
+    // the Select tool selects only this widget, None clears it
+    SelectOnly(Option<WidgetId>),
+    // the widgets inside the box dragged by the Select tool
+    SelectArea(Rectangle),
+    // the Select tool's several widgets turn together by the degrees,
+    // about their center or the point
+    RotateSelection(f32, Option<Point>),
+    // the Select tool's several widgets moved by the offset
+    MoveSelection(Vector),
+    // and scaled by the factor about their center or the point
+    ScaleSelection(f32, Option<Point>),
+    // the stroke split in two at the point
+    Split(WidgetId, Point),
+    // the second stroke joined onto the first
+    Join(WidgetId, WidgetId),
+    // the widget scaled by the factor about its mid point
+    Resize(WidgetId, f32),
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq,)]
@@ -40,6 +138,18 @@ pub enum DrawMode {
     Edit,
     New,
     Rotate,
+    Trim,
+    Extend,
+    Parallel,
+    Perpendicular,
+    Tangent,
+    Select,
+    Place,
+    Number,
+    Delete,
+    Split,
+    Join,
+    Resize,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq,)]
@@ -57,6 +167,18 @@ impl DrawMode {
             DrawMode::New => Some("New".to_string()),
             DrawMode::Edit => Some("Edit".to_string()),
             DrawMode::Rotate => Some("Rotate".to_string()),
+            DrawMode::Trim => Some("Trim".to_string()),
+            DrawMode::Extend => Some("Extend".to_string()),
+            DrawMode::Parallel => Some("Parallel".to_string()),
+            DrawMode::Perpendicular => Some("Perpendicular".to_string()),
+            DrawMode::Tangent => Some("Tangent".to_string()),
+            DrawMode::Select => Some("Select".to_string()),
+            DrawMode::Place => Some("Place".to_string()),
+            DrawMode::Number => Some("Number".to_string()),
+            DrawMode::Delete => Some("Delete".to_string()),
+            DrawMode::Split => Some("Split".to_string()),
+            DrawMode::Join => Some("Join".to_string()),
+            DrawMode::Resize => Some("Resize".to_string()),
         }
     }
 
@@ -66,11 +188,33 @@ impl DrawMode {
             "Edit" => DrawMode::Edit,
             "New" => DrawMode::New,
             "Rotate" => DrawMode::Rotate,
+            "Trim" => DrawMode::Trim,
+            "Extend" => DrawMode::Extend,
+            "Parallel" => DrawMode::Parallel,
+            "Perpendicular" => DrawMode::Perpendicular,
+            "Tangent" => DrawMode::Tangent,
+            "Select" => DrawMode::Select,
+            "Place" => DrawMode::Place,
+            "Number" => DrawMode::Number,
+            "Delete" => DrawMode::Delete,
+            "Split" => DrawMode::Split,
+            "Join" => DrawMode::Join,
+            "Resize" => DrawMode::Resize,
             _ => DrawMode::DrawAll,
         }
     }
-    pub fn options() -> Vec<String> {
-        vec!["DrawAll".to_string(), "New".to_string(), "Edit".to_string(), "Rotate".to_string(),]
+    // Edit and Rotate are only listed with the classic modes,
+    // otherwise the Select tool does both.
+    pub fn options(classic_modes: bool) -> Vec<String> {
+        let mut options = 
+            vec!["DrawAll".to_string(), "New".to_string(), "Edit".to_string(), "Rotate".to_string(),
+                "Trim".to_string(), "Extend".to_string(), "Parallel".to_string(), "Perpendicular".to_string(),
+                "Tangent".to_string(), "Select".to_string(), "Place".to_string(),
+                "Number".to_string(), "Delete".to_string(), "Split".to_string(), "Join".to_string(), "Resize".to_string(),];
+        if !classic_modes {
+            options.retain(|option| option != "Edit" && option != "Rotate");
+        }
+        options
     }
 }
 
@@ -132,94 +276,754 @@ impl VTextAlignment {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreciseField {
+    Radius,
+    Width,
+    Height,
+    Length,
+    Angle,
+}
+
+impl PreciseField {
+    pub fn label(&self) -> &'static str {
+        match &self {
+            PreciseField::Radius => "Radius",
+            PreciseField::Width => "Width",
+            PreciseField::Height => "Height",
+            PreciseField::Length => "Length",
+            PreciseField::Angle => "Angle(degrees)",
+        }
+    }
+}
+
+// The dimensions entered for creating a shape with a single click.
+// The values are kept as entered and parsed when the shape is placed.
+#[derive(Debug, Clone, Default)]
+pub struct PreciseShape {
+    pub enabled: bool,
+    pub radius: String,
+    pub width: String,
+    pub height: String,
+    pub length: String,
+    pub angle: String,
+}
+
+impl PreciseShape {
+    pub fn get(&self, field: PreciseField) -> &str {
+        match field {
+            PreciseField::Radius => &self.radius,
+            PreciseField::Width => &self.width,
+            PreciseField::Height => &self.height,
+            PreciseField::Length => &self.length,
+            PreciseField::Angle => &self.angle,
+        }
+    }
+
+    pub fn set(&mut self, field: PreciseField, value: String) {
+        match field {
+            PreciseField::Radius => self.radius = value,
+            PreciseField::Width => self.width = value,
+            PreciseField::Height => self.height = value,
+            PreciseField::Length => self.length = value,
+            PreciseField::Angle => self.angle = value,
+        }
+    }
+
+    pub fn value(&self, field: PreciseField) -> Option<f32> {
+        self.get(field).trim().parse().ok()
+    }
+
+    // The polyline is used for the rectangle.
+    pub fn fields(widget: Widget) -> Vec<PreciseField> {
+        match widget {
+            Widget::Circle => vec![PreciseField::Radius],
+            Widget::Line => vec![PreciseField::Length, PreciseField::Angle],
+            Widget::Polygon => vec![PreciseField::Radius],
+            Widget::PolyLine => vec![PreciseField::Width, PreciseField::Height],
+            _ => vec![],
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct CanvasState {
-    cache: canvas::Cache,
-    text_cache: Vec<canvas::Cache>,
-    pub curves: HashMap<Id, CanvasWidget>,
-    pub text_curves: HashMap<Id, CanvasWidget>,
+    render_cache: RenderCache,
+    pub layers: Vec<Layer>,
+    pub active_layer: usize,
     pub draw_mode: DrawMode,
-    pub edit_widget_id: Option<Id>,
+    pub edit_widget_id: Option<WidgetId>,
     pub escape_pressed: bool,
+    pub modifiers: Modifiers,
     pub selected_radio_widget: Option<Widget>,
+    pub app_theme: AppTheme,
     pub selected_draw_color: Color,
     pub selected_canvas_color: Color,
     pub selected_poly_points: usize,
     pub selected_poly_points_str: String,
+    pub poly_points_error: bool,
+    pub polyline_center: PolyLineCenter,
+    // new polylines are closed
+    pub polyline_closed: bool,
+    // how new arcs are closed
+    pub arc_closure: ArcClosure,
+    // the pattern fill of the selected closed shapes
+    pub hatch_pattern: HatchPattern,
+    pub hatch_spacing: f32,
+    pub hatch_angle: f32,
     pub selected_step_degrees: f32,
     pub selected_width: f32,
     pub selected_width_str: String,
+    pub width_error: bool,
     pub selected_h_text_alignment: HTextAlignment,
     pub selected_v_text_alignment: VTextAlignment,
+    pub text_style: TextStyle,
+    pub text_size_str: String,
+    pub text_size_error: bool,
+    pub line_height_str: String,
+    pub line_height_error: bool,
     pub timer_event_enabled: bool,
     pub timer_duration: u64,
     pub elapsed_time: u64,
     pub blink: bool,
+    pub show_intersections: bool,
+    // the rulers and the cursor position
+    pub show_rulers: bool,
+    pub snap_to_intersections: bool,
+    // the ends, middles and centers of the widgets
+    pub snap_to_objects: bool,
+    pub object_snap_points: Vec<SnapPoint>,
+    pub intersection_points: Vec<Point>,
+    pub precise_shape: PreciseShape,
+    pub selected_palette: ColorVision,
+    pub recent_colors: Vec<Color>,
+    pub simulated_vision: ColorVision,
+    pub pressure_curve: PressureCurve,
+    pub stabilizer: bool,
+    pub stabilizer_strength: f32,
+    pub smoothing: bool,
+    // the distance in pixels the simplified stroke may move
+    pub smoothing_strength: f32,
+    pub live_url: String,
+    // removes repeated points on load and save
+    pub optimize: bool,
+    pub classic_modes: bool,
+    pub grab_radius: f32,
+    pub preview_quality: PreviewQuality,
+    pub resize_widths: bool,
+    pub precision: u8,
+    pub round_coordinates: bool,
+    // pretty, compact or gzipped json
+    pub save_format: SaveFormat,
+    pub backups: u8,
+    // the image traced over, under the layers
+    #[cfg(feature = "image")]
+    pub background: Option<BackgroundImage>,
+    // the drawing last loaded or saved, where the dialogs start
+    pub data_path: PathBuf,
+    // an example is open, the canvas ignores the mouse and keys
+    pub read_only: bool,
+    pub selected_ids: Vec<WidgetId>,
+    pub instances: Vec<ComponentInstance>,
+    pub component_name: String,
+    pub component_names: Vec<String>,
+    pub selected_component: Option<String>,
+    pub template_name: String,
+    pub template_names: Vec<String>,
+    // the title block fields and the artboard typed in for its frame
+    pub metadata: DocumentMetadata,
+    pub frame_size_str: String,
+    pub tags: HashMap<WidgetId, Vec<String>>,
+    pub tags_str: String,
+    pub query: String,
+    pub query_error: Option<String>,
+    // a failed load or save, shown above the canvas
+    // until dismissed, the drawing is kept
+    pub load_error: Option<String>,
+    pub gauge_input: Option<(GaugeField, String)>,
+    pub radius_input: Option<String>,
+    pub numbering: Numbering,
+    pub constraints: Vec<Constraint>,
+    pub constraint_kind: ConstraintKind,
+    pub groups: Vec<Group>,
+    pub resize_str: String,
+    // the factor the stroke widths are multiplied by
+    pub width_scale_str: String,
+    // the drawing bounds after the resize typed in
+    pub resize_preview: Option<Rectangle>,
+    // how near a color is replaced and the colors
+    // the last replace changed, for Undo Replace
+    pub replace_tolerance: f32,
+    pub replaced_colors: Vec<(WidgetId, Color)>,
+    // the colors of the drawing when last scanned and, while
+    // previewed, each with the palette color it's remapped to
+    pub document_colors: Vec<Color>,
+    // the named styles of the drawing
+    pub style_presets: Vec<StylePreset>,
+    pub preset_name: String,
+    pub selected_preset: Option<String>,
+    pub remap_palette: Option<String>,
+    pub palette_remap: Option<Vec<(Color, Color)>>,
+    // the drawing last cleared, for Undo Clear
+    pub cleared: Option<Drawing>,
+    // built when first asked, the widgets changed are put in again
+    // and a redraw of the whole drawing drops it
+    spatial_index: RefCell<Option<SpatialIndex>>,
+}
+
+// The widgets of a document and what hangs on their ids, moved
+// out by a clear and back by its undo.
+#[derive(Debug, Clone)]
+pub struct Drawing {
+    pub layers: Vec<Layer>,
+    pub active_layer: usize,
+    pub instances: Vec<ComponentInstance>,
+    pub tags: HashMap<WidgetId, Vec<String>>,
+    pub constraints: Vec<Constraint>,
+    pub groups: Vec<Group>,
 }
 
 impl Default for CanvasState {
     fn default() -> Self {
-        let mut text_cache = vec![];
-        for _ in 0..20 {
-            text_cache.push(canvas::Cache::new());
-        }
+        Self::with_settings(load_settings())
+    }
+}
+
+impl CanvasState {
+    pub fn with_settings(settings: Settings) -> Self {
         Self { 
-            cache: canvas::Cache::new(),
-            text_cache,
-            curves: HashMap::new(),
-            text_curves: HashMap::new(),
+            render_cache: RenderCache::default(),
+            layers: vec![Layer::new("Layer 1".to_string())],
+            active_layer: 0,
             draw_mode: DrawMode::DrawAll,
             edit_widget_id: None,
             escape_pressed: false,
+            modifiers: Modifiers::default(),
             selected_radio_widget: None,
-            selected_draw_color: Color::from_rgb(0.961, 0.871, 0.702),
-            selected_canvas_color: Color::from_rgb(0.0, 0.502, 0.502),
+            app_theme: settings.theme,
+            selected_draw_color: settings.theme.default_colors().0,
+            selected_canvas_color: settings.theme.default_colors().1,
             selected_poly_points: 3,
             selected_poly_points_str: String::new(),
+            poly_points_error: false,
+            polyline_center: PolyLineCenter::default(),
+            polyline_closed: false,
+            arc_closure: ArcClosure::Open,
+            hatch_pattern: HatchPattern::default(),
+            hatch_spacing: DEFAULT_SPACING,
+            hatch_angle: DEFAULT_ANGLE,
             selected_step_degrees: 6.0,
             selected_width: 2.0,
             selected_width_str: String::new(),
+            width_error: false,
             selected_h_text_alignment: HTextAlignment::Center,
             selected_v_text_alignment: VTextAlignment::Center,
+            text_style: TextStyle::default(),
+            text_size_str: String::new(),
+            text_size_error: false,
+            line_height_str: String::new(),
+            line_height_error: false,
             timer_event_enabled: false,
             timer_duration: 750,
             elapsed_time: 0,
             blink: false,
+            show_intersections: false,
+            show_rulers: false,
+            snap_to_intersections: false,
+            snap_to_objects: false,
+            object_snap_points: vec![],
+            intersection_points: vec![],
+            precise_shape: PreciseShape::default(),
+            selected_palette: ColorVision::Normal,
+            recent_colors: settings.recent_colors.iter()
+                .map(|&[r, g, b, a]| Color::from_rgba(r, g, b, a))
+                .collect(),
+            simulated_vision: ColorVision::Normal,
+            pressure_curve: settings.pressure_curve,
+            stabilizer: settings.stabilizer,
+            stabilizer_strength: settings.stabilizer_strength,
+            smoothing: settings.smoothing,
+            smoothing_strength: settings.smoothing_strength,
+            live_url: settings.live_url,
+            optimize: settings.optimize,
+            classic_modes: settings.classic_modes,
+            grab_radius: settings.grab_radius,
+            preview_quality: settings.preview_quality,
+            resize_widths: settings.resize_widths,
+            precision: settings.precision,
+            round_coordinates: settings.round_coordinates,
+            save_format: settings.save_format,
+            backups: settings.backups,
+            #[cfg(feature = "image")]
+            background: None,
+            data_path: PathBuf::from("./resources/data.json"),
+            read_only: false,
+            selected_ids: vec![],
+            instances: vec![],
+            component_name: String::new(),
+            component_names: list_components(),
+            template_name: String::new(),
+            template_names: list_templates(std::path::Path::new(TEMPLATES_DIR)),
+            metadata: DocumentMetadata::default(),
+            frame_size_str: String::new(),
+            selected_component: None,
+            tags: HashMap::new(),
+            tags_str: String::new(),
+            query: String::new(),
+            query_error: None,
+            load_error: None,
+            gauge_input: None,
+            radius_input: None,
+            numbering: Numbering::default(),
+            constraints: vec![],
+            groups: vec![],
+            constraint_kind: ConstraintKind::Coincident,
+            resize_str: String::new(),
+            width_scale_str: String::new(),
+            resize_preview: None,
+            replace_tolerance: DEFAULT_TOLERANCE,
+            replaced_colors: vec![],
+            document_colors: vec![],
+            style_presets: vec![],
+            preset_name: String::new(),
+            selected_preset: None,
+            remap_palette: None,
+            palette_remap: None,
+            cleared: None,
+            spatial_index: RefCell::new(None),
         }
     }
-}
 
-impl CanvasState {
-    pub fn view<'a>(&'a self, curves: &'a HashMap<Id, CanvasWidget>, text_curves: &'a HashMap<Id, CanvasWidget>) -> Element<'a, CanvasWidget> {
+    // Leaves an empty drawing with a single layer.
+    pub fn take_drawing(&mut self) -> Drawing {
+        self.selected_ids.clear();
+        self.edit_widget_id = None;
+        self.request_redraw();
+        self.request_text_redraw();
+        Drawing {
+            layers: std::mem::replace(&mut self.layers, vec![Layer::new("Layer 1".to_string())]),
+            active_layer: std::mem::take(&mut self.active_layer),
+            instances: std::mem::take(&mut self.instances),
+            tags: std::mem::take(&mut self.tags),
+            constraints: std::mem::take(&mut self.constraints),
+            groups: std::mem::take(&mut self.groups),
+        }
+    }
+
+    pub fn set_drawing(&mut self, drawing: Drawing) {
+        self.layers = drawing.layers;
+        self.active_layer = drawing.active_layer;
+        self.instances = drawing.instances;
+        self.tags = drawing.tags;
+        self.constraints = drawing.constraints;
+        self.groups = drawing.groups;
+        self.request_redraw();
+        self.request_text_redraw();
+    }
+
+    // The colors, widths, points and mode go back to their first values,
+    // the drawing, the document and the saved settings stay.
+    pub fn reset_tool_settings(&mut self) {
+        self.draw_mode = DrawMode::DrawAll;
+        self.edit_widget_id = None;
+        self.selected_radio_widget = None;
+        self.selected_draw_color = self.app_theme.default_colors().0;
+        self.selected_poly_points = 3;
+        self.selected_poly_points_str.clear();
+        self.poly_points_error = false;
+        self.polyline_center = PolyLineCenter::default();
+        self.polyline_closed = false;
+        self.arc_closure = ArcClosure::Open;
+        self.hatch_pattern = HatchPattern::default();
+        self.hatch_spacing = DEFAULT_SPACING;
+        self.hatch_angle = DEFAULT_ANGLE;
+        self.selected_step_degrees = 6.0;
+        self.selected_width = 2.0;
+        self.selected_width_str.clear();
+        self.width_error = false;
+        self.selected_h_text_alignment = HTextAlignment::Center;
+        self.selected_v_text_alignment = VTextAlignment::Center;
+        self.text_style = TextStyle::default();
+        self.text_size_str.clear();
+        self.text_size_error = false;
+        self.line_height_str.clear();
+        self.line_height_error = false;
+        self.request_redraw();
+    }
+
+    // Only offered until something is drawn on the cleared canvas.
+    pub fn can_undo_clear(&self) -> bool {
+        self.cleared.is_some() && 
+            self.layers.iter().all(|layer| layer.curves.is_empty())
+    }
+
+    pub fn view<'a>(&'a self, curves: &'a HashMap<WidgetId, CanvasWidget>) -> Element<'a, CanvasMessage> {
         Canvas::new(DrawPending {
             state: self,
             curves,
-            text_curves,
         })
         .width(Fill)
         .height(Fill)
         .into()
     }
 
+    // The widgets are edited on the active layer.
+    pub fn curves(&self) -> &HashMap<WidgetId, CanvasWidget> {
+        &self.layers[self.active_layer].curves
+    }
+
+    pub fn active_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layers[self.active_layer]
+    }
+
     pub fn request_redraw(&mut self) {
-        self.cache.clear();
+        if self.show_intersections || self.snap_to_intersections {
+            self.update_intersections();
+        }
+        if self.snap_to_objects {
+            self.update_object_snap_points();
+        }
+        self.render_cache.invalidate_drawing();
+        *self.spatial_index.get_mut() = None;
+    }
+
+    pub fn request_background_redraw(&mut self) {
+        self.render_cache.invalidate_background();
+    }
+
+    // Only the layer's widgets are drawn again, the other layers
+    // keep their caches.
+    pub fn request_layer_redraw(&mut self, layer: usize) {
+        if self.show_intersections || self.snap_to_intersections {
+            self.update_intersections();
+        }
+        if self.snap_to_objects {
+            self.update_object_snap_points();
+        }
+        self.render_cache.invalidate_layer(layer);
+    }
+
+    // The ids of the active layer's widgets whose bounds are within the
+    // distance of the point.
+    pub fn widgets_near(&self, point: Point, distance: f32) -> Vec<WidgetId> {
+        self.with_spatial_index(|index| index.query_point(point, distance))
+    }
+
+    // The ids of the active layer's widgets whose bounds are inside the area.
+    pub fn widgets_inside(&self, area: Rectangle) -> Vec<WidgetId> {
+        let ids = self.with_spatial_index(|index| index.query_rect(area));
+        ids.into_iter()
+            .filter(|id| 
+                self.curves().get(id)
+                    .and_then(get_widget_bounds)
+                    .is_some_and(|bounds| bounds.is_within(&area)))
+            .collect()
+    }
+
+    // The widgets added, changed or deleted take their new place in
+    // the index, the whole drawing redrawn builds it again.
+    pub fn update_spatial_index(&mut self, ids: &[WidgetId]) {
+        let active_layer = self.active_layer;
+        let Some(index) = self.spatial_index.get_mut().as_mut().filter(|index| index.layer == active_layer) else {
+            return
+        };
+        for id in ids.iter() {
+            match self.layers[active_layer].curves.get(id).and_then(CanvasWidget::as_trait) {
+                Some(wt) => index.update(*id, wt.bounds()),
+                None => index.remove(id),
+            }
+        }
+    }
+
+    fn with_spatial_index<T>(&self, query: impl Fn(&SpatialIndex) -> T) -> T {
+        let mut spatial_index = self.spatial_index.borrow_mut();
+        if spatial_index.as_ref().is_none_or(|index| index.layer != self.active_layer) {
+            let entries = 
+                self.curves().values()
+                    .filter_map(|cw| cw.as_trait())
+                    .map(|wt| (wt.id(), wt.bounds()))
+                    .collect();
+            *spatial_index = Some(SpatialIndex::new(self.active_layer, entries));
+        }
+        query(spatial_index.as_ref().unwrap())
+    }
+
+    // The widgets being edited or rotated are skipped since
+    // their position is changing.
+    pub fn update_intersections(&mut self) {
+        let widgets: Vec<&CanvasWidget> = 
+            self.layers.iter()
+                .filter(|layer| layer.visible)
+                .flat_map(|layer| layer.curves.values())
+                .filter(|w| get_draw_mode_and_status(w).1 != DrawStatus::Inprogress)
+                .collect();
+
+        self.intersection_points = get_all_intersections(&widgets);
+    }
+
+    // Like the intersections, without the widgets being edited.
+    pub fn update_object_snap_points(&mut self) {
+        self.object_snap_points = 
+            self.layers.iter()
+                .filter(|layer| layer.visible)
+                .flat_map(|layer| layer.curves.values())
+                .filter(|w| get_draw_mode_and_status(w).1 != DrawStatus::Inprogress)
+                .flat_map(get_snap_points)
+                .collect();
+    }
+
+    // Returns the intersection or object point near the cursor if
+    // snapping is on, otherwise the cursor.
+    pub fn get_snap_point(&self, cursor: Point) -> Point {
+        self.get_snap(cursor)
+            .map(|snap| snap.point)
+            .unwrap_or(cursor)
+    }
+
+    // The nearest of the intersections and the object points
+    // that are snapped to.
+    pub fn get_snap(&self, cursor: Point) -> Option<SnapPoint> {
+        let intersection = 
+            get_closest_point(&self.intersection_points, cursor, SNAP_DISTANCE)
+                .filter(|_| self.snap_to_intersections)
+                .map(|point| SnapPoint::new(point, SnapKind::Intersection));
+        let object = 
+            get_closest_snap(&self.object_snap_points, cursor, SNAP_DISTANCE)
+                .filter(|_| self.snap_to_objects);
+        intersection.into_iter()
+            .chain(object)
+            .min_by(|a, b| a.point.distance(cursor).total_cmp(&b.point.distance(cursor)))
+    }
+
+    pub fn get_settings(&self) -> Settings {
+        Settings {
+            pressure_curve: self.pressure_curve,
+            stabilizer: self.stabilizer,
+            stabilizer_strength: self.stabilizer_strength,
+            smoothing: self.smoothing,
+            smoothing_strength: self.smoothing_strength,
+            live_url: self.live_url.clone(),
+            optimize: self.optimize,
+            classic_modes: self.classic_modes,
+            grab_radius: self.grab_radius,
+            preview_quality: self.preview_quality,
+            resize_widths: self.resize_widths,
+            theme: self.app_theme,
+            recent_colors: self.recent_colors.iter()
+                .map(|color| [color.r, color.g, color.b, color.a])
+                .collect(),
+            precision: self.precision,
+            round_coordinates: self.round_coordinates,
+            save_format: self.save_format,
+            backups: self.backups,
+        }
     }
 
     pub fn request_text_redraw(&mut self) {
-        for i in 0..20 {
-            self.text_cache[i].clear();
+        self.render_cache.invalidate_texts();
+        *self.spatial_index.get_mut() = None;
+    }
+
+    // The blink only changes the texts being typed, the other texts keep
+    // their caches and nothing moved so the spatial index stays.
+    pub fn request_caret_redraw(&mut self) {
+        let typing: Vec<WidgetId> =
+            self.curves().iter()
+                .filter(|(_, widget)| matches!(widget, CanvasWidget::Text(txt) if txt.draw_mode == DrawMode::New))
+                .map(|(id, _)| *id)
+                .collect();
+        for id in typing.iter() {
+            self.render_cache.invalidate(id);
+        }
+    }
+
+    // Only the changed texts are drawn again, the others keep their caches.
+    pub fn request_text_redraw_of(&mut self, ids: &[WidgetId]) {
+        for id in ids.iter() {
+            self.render_cache.invalidate(id);
         }
+        self.update_spatial_index(ids);
     }
 }
 
-struct DrawPending<'a> {
-    state: &'a CanvasState,
-    curves: &'a HashMap<Id, CanvasWidget>,
-    text_curves: &'a HashMap<Id, CanvasWidget>,
+pub struct DrawPending<'a> {
+    pub state: &'a CanvasState,
+    pub curves: &'a HashMap<WidgetId, CanvasWidget>,
+}
+
+impl DrawPending<'_> {
+    // The widgets whose bounds are within the grab radius of the
+    // point, from the spatial index, without the text if not asked.
+    fn widgets_near(&self, point: Point, with_text: bool) -> Vec<&CanvasWidget> {
+        self.state.widgets_near(point, self.state.grab_radius)
+            .iter()
+            .filter_map(|id| 
+                self.curves.get(id)
+                    .filter(|widget| with_text || !check_if_text_widget(widget)))
+            .collect()
+    }
+
+    // A width or color given to the widget from the side panel
+    // while it's being edited goes to the pending copy too.
+    fn restyle_pending(&self, program_state: &mut Option<Pending>) {
+        let widgets = match program_state {
+            Some(Pending::EditSecond { widget, .. }) | 
+            Some(Pending::EditThird { widget, .. }) | 
+            Some(Pending::Rotate { widget, .. }) => vec![widget],
+            Some(Pending::Drag { original, widget, .. }) => vec![original, widget],
+            _ => return,
+        };
+        for widget in widgets {
+            let id = get_widget_id(widget);
+            let Some(stored) = self.curves.get(&id).and_then(CanvasWidget::as_trait) else {
+                continue
+            };
+            let (width, color) = (stored.width(), stored.color());
+            if let Some(wt) = widget.as_trait_mut() {
+                wt.set_width(width);
+                wt.set_color(color);
+            }
+        }
+    }
+
+    // The Select tool's one widget, None for none or several.
+    fn get_selected_widget(&self) -> Option<CanvasWidget> {
+        match self.state.selected_ids.as_slice() {
+            [id] => self.curves.get(id).cloned(),
+            _ => None,
+        }
+    }
+
+    // A press of the Select tool.  On a handle of the selected widget
+    // it starts rotating or editing it, on a widget it selects it and
+    // starts moving it, shift adds or removes it from the selection,
+    // and on nothing the selection is cleared.
+    fn select_press(&self, program_state: &mut Option<Pending>, cursor: Point) -> (event::Status, Option<CanvasMessage>) {
+        let shift = self.state.modifiers.shift();
+        if let Some(selected) = self.get_selected_widget().filter(|_| !shift) {
+            let action = 
+                if get_rotate_handle(&selected).is_some_and(|handle| handle.distance(cursor) <= self.state.grab_radius) {
+                    Some(DragAction::Rotate)
+                } else {
+                    let (index, mid_point, other_point) = find_closest_point_index(&selected, cursor);
+                    get_edit_point(&selected, index, mid_point, other_point)
+                        .filter(|point| point.distance(cursor) <= self.state.grab_radius)
+                        .map(|_| DragAction::Point { index, mid_point, other_point })
+                };
+            if let Some(action) = action {
+                *program_state = Some(Pending::Drag {
+                    original: set_widget_mode_or_status(selected.clone(), Some(DrawMode::Rotate), Some(DrawStatus::Inprogress)),
+                    widget: selected,
+                    action,
+                    start: cursor,
+                    moved: false,
+                });
+                return (event::Status::Captured, None)
+            }
+        }
+
+        let Some(widget) = find_widgets_at(self.widgets_near(cursor, true), cursor, self.state.grab_radius).first().copied() else {
+            *program_state = Some(Pending::Band { start: cursor });
+            // shift keeps the selection for the box to add to
+            return (event::Status::Captured, (!shift).then_some(CanvasMessage::SelectOnly(None)))
+        };
+        let id = get_widget_id(widget);
+        if shift {
+            return (event::Status::Captured, Some(CanvasMessage::Select(id)))
+        }
+        // a widget of the selection or of a group moves them all
+        let selected = self.state.selected_ids.contains(&id);
+        let ids = 
+            if selected && self.state.selected_ids.len() > 1 {
+                self.state.selected_ids.clone()
+            } else {
+                expand_to_groups(&self.state.groups, &[id])
+            };
+        if ids.len() > 1 {
+            *program_state = Some(Pending::MoveSelection {
+                widgets: 
+                    self.state.layers.iter()
+                        .flat_map(|layer| ids.iter().filter_map(|id| layer.curves.get(id)))
+                        .cloned()
+                        .collect(),
+                start: cursor,
+                offset: Vector::ZERO,
+                moved: false,
+            });
+            return (event::Status::Captured, (!selected).then_some(CanvasMessage::SelectOnly(Some(id))))
+        }
+        *program_state = Some(Pending::Drag {
+            original: set_widget_mode_or_status(widget.clone(), Some(DrawMode::Rotate), Some(DrawStatus::Inprogress)),
+            widget: widget.clone(),
+            action: DragAction::Move,
+            start: cursor,
+            moved: false,
+        });
+        (event::Status::Captured, Some(CanvasMessage::SelectOnly(Some(id))))
+    }
+
+    // Selects the widget after the one selected among those under the
+    // selection point, back to the first after the last.
+    fn cycle_selection(&self, program_state: &mut Option<Pending>) -> (event::Status, Option<CanvasMessage>) {
+        let Some(Pending::EditSecond { widget, selected_at, selected_from }) = program_state else {
+            return (event::Status::Ignored, None)
+        };
+        let candidates = find_widgets_at(self.widgets_near(*selected_from, true), *selected_from, self.state.grab_radius);
+        if candidates.is_empty() {
+            return (event::Status::Captured, None)
+        }
+        let previous = get_widget_id(widget);
+        let next =
+            candidates.iter()
+                .position(|cw| get_widget_id(cw) == previous)
+                .map_or(0, |index| (index + 1) % candidates.len());
+        *widget =
+            set_widget_mode_or_status(
+                candidates[next].clone(),
+                Some(DrawMode::Edit),
+                Some(DrawStatus::Inprogress),
+            );
+        *selected_at = Instant::now();
+        (event::Status::Captured, Some(CanvasMessage::Reselect(previous, widget.clone())))
+    }
+
+    // During a rotation the typed digits turn the widget to that angle,
+    // Enter finishes it and Escape puts it back as it was.
+    fn rotate_keypress(&self, program_state: &mut Option<Pending>, modified_key: Key) -> (event::Status, Option<CanvasMessage>) {
+        let Some(Pending::Rotate { widget, degrees, typed, .. }) = program_state else {
+            return (event::Status::Ignored, None)
+        };
+        match modified_key.as_ref() {
+            Key::Character(c) if c.chars().all(|c| c.is_ascii_digit() || c == '.') || (c == "-" && typed.is_empty()) => {
+                typed.push_str(c);
+            },
+            Key::Named(iced::keyboard::key::Named::Backspace) => {
+                typed.pop();
+            },
+            Key::Named(iced::keyboard::key::Named::Enter) => {
+                update_rotated_widget(widget, 0.0, Some(DrawStatus::Completed));
+                let rotated_widget = std::mem::replace(widget, CanvasWidget::None);
+                *program_state = None;
+                return (event::Status::Captured, Some(CanvasMessage::Widget(rotated_widget)))
+            },
+            Key::Named(iced::keyboard::key::Named::Escape) => {
+                // the stored widget still has its angle
+                let original = 
+                    self.curves.get(&get_widget_id(widget))
+                        .map(|stored| set_widget_mode_or_status(stored.clone(), None, Some(DrawStatus::Completed)));
+                *program_state = None;
+                return (event::Status::Captured, original.map(CanvasMessage::Widget))
+            },
+            _ => return (event::Status::Ignored, None),
+        }
+        if let (Ok(angle), Some(current)) = (typed.parse::<f32>(), get_widget_degrees(widget)) {
+            *degrees = Some(update_rotated_widget(widget, angle - current, None));
+        }
+        (event::Status::Captured, None)
+    }
 }
 
-impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
+impl<'a> canvas::Program<CanvasMessage> for DrawPending<'a> {
     type State = Option<Pending>;
 
     fn update(
@@ -228,10 +1032,14 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
         event: Event,
         bounds: iced::Rectangle,
         cursor: mouse::Cursor,
-    ) -> (event::Status, Option<CanvasWidget>) {
+    ) -> (event::Status, Option<CanvasMessage>) {
         let Some(cursor_position) = cursor.position_in(bounds) else {
             return (event::Status::Ignored, None);
         };
+        if self.state.read_only {
+            return (event::Status::Ignored, None);
+        }
+        self.restyle_pending(program_state);
         
         match event {
             Event::Mouse(mouse_event) => {
@@ -254,7 +1062,7 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                     // 3 - finish
                                     None => {
                                         let widget_opt = 
-                                            find_closest_widget(self.curves, self.text_curves, cursor_position);
+                                            find_closest_widget(self.widgets_near(cursor_position, true), cursor_position, self.state.grab_radius);
                                         
                                         let selected_widget = 
                                             match widget_opt {
@@ -271,6 +1079,8 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                             );
                                         *program_state = Some(Pending::EditSecond {
                                             widget: widget.clone(),
+                                            selected_at: Instant::now(),
+                                            selected_from: cursor_position,
                                         });
                                         // returning DrawCurve so that the curve
                                         // being editied will not show after the refresh
@@ -285,26 +1095,51 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                     // and replace with cursor
                                     Some(Pending::EditSecond { 
                                         widget,
+                                        selected_at,
+                                        selected_from,
                                     }) => {
+                                        // An alt click on a segment of a polyline or freehand
+                                        // adds a node there, elsewhere it goes on to the next
+                                        // widget under it.
+                                        if self.state.modifiers.alt() {
+                                            if insert_node(widget, cursor_position, self.state.grab_radius) {
+                                                return (event::Status::Captured, Some(CanvasMessage::Widget(widget.clone())))
+                                            }
+                                            *selected_from = cursor_position;
+                                            return self.cycle_selection(program_state)
+                                        }
+                                        // A double click on a text goes back to typing,
+                                        // with the cursor at the end.
+                                        if let CanvasWidget::Text(txt) = widget {
+                                            if selected_at.elapsed() < DOUBLE_CLICK && 
+                                                get_text_box(txt).contains(cursor_position) {
+                                                let mut txt = txt.clone();
+                                                txt.cursor = TextCursor { index: txt.content.chars().count(), anchor: None };
+                                                txt.draw_mode = DrawMode::New;
+                                                let widget = CanvasWidget::Text(txt);
+                                                *program_state = Some(Pending::New { widget: widget.clone(), shift: false });
+                                                return (event::Status::Captured, Some(CanvasMessage::Widget(widget)))
+                                            }
+                                        }
                                         // Find for closest point to edit in selected widget
                                         // which might be either a mid point(translate) or 
                                         // curve point (move point).
                                         let (point_index, mid_point, other_point) = 
                                             find_closest_point_index(widget, cursor_position);
-                                        let widget = widget.clone();
+                                        // the widget moves to the next pending
+                                        let widget = std::mem::replace(widget, CanvasWidget::None);
+                                        // If a text widget, need to send back the curve so that the
+                                        // cursor can be seen.  No access to the time event during pending.
+                                        let text_widget = 
+                                            (self.state.selected_radio_widget == Some(Widget::Text))
+                                                .then(|| widget.clone());
                                         *program_state = Some(Pending::EditThird {
-                                            widget: widget.clone(),
+                                            widget,
                                             edit_point_index: point_index,
                                             edit_mid_point: mid_point,
                                             edit_other_point: other_point,
                                         });
-                                        // If a text widget, need to send back the curve so that the
-                                        // cursor can be seen.  No access to the time event during pending.
-                                        if self.state.selected_radio_widget == Some(Widget::Text) {
-                                            Some(widget.clone())
-                                        } else {
-                                            None
-                                        }
+                                        text_widget
                                     },
                                     // The third click will send back the DrawCurve
                                     // with the finally updated curve
@@ -314,10 +1149,10 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                         edit_mid_point,
                                         edit_other_point, 
                                     }) => {
-
+                                        let cursor_position = self.state.get_snap_point(cursor_position);
                                         let edited_widget: CanvasWidget = 
                                                 update_edited_widget(
-                                                    widget.clone(), 
+                                                    std::mem::replace(widget, CanvasWidget::None), 
                                                     cursor_position, 
                                                     *edit_point_index, 
                                                     *edit_mid_point,
@@ -328,10 +1163,25 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                         *program_state = None;
                                         Some(edited_widget)
                                     },
+                                    // a click ends the typing into a text
+                                    Some(Pending::New { 
+                                        widget,
+                                        ..
+                                    }) => {
+                                        let widget = 
+                                            set_widget_mode_or_status(
+                                                std::mem::replace(widget, CanvasWidget::None), 
+                                                Some(DrawMode::DrawAll), 
+                                                Some(DrawStatus::Completed),
+                                            );
+                                        *program_state = None;
+                                        Some(widget)
+                                    },
                                     _ => None,
                                 }
                             },
                             DrawMode::New => {
+                                let cursor_position = self.state.get_snap_point(cursor_position);
                                 match program_state {
                                     // First mouse click sets the state of the first Pending point
                                     // return a none since no Curve yet
@@ -341,7 +1191,7 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                         if self.state.selected_radio_widget.is_none() {
                                             return (event::Status::Ignored, None)
                                         }
-                                        let selected_widget = 
+                                        let mut selected_widget = 
                                             add_new_widget(
                                                 self.state.selected_radio_widget.unwrap(), 
                                                 self.state.selected_poly_points,
@@ -351,48 +1201,75 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                                 self.state.selected_h_text_alignment,
                                                 self.state.selected_v_text_alignment,
                                             );
+                                        if let CanvasWidget::Text(txt) = &mut selected_widget {
+                                            self.state.text_style.apply(txt);
+                                        }
+                                        if let CanvasWidget::PolyLine(pl) = &mut selected_widget {
+                                            pl.center = self.state.polyline_center;
+                                            pl.closed = self.state.polyline_closed;
+                                        }
+                                        if let CanvasWidget::Arc(arc) = &mut selected_widget {
+                                            arc.closure = self.state.arc_closure;
+                                        }
 
-                                        let (widget, _) = 
-                                            set_widget_point(
-                                                &selected_widget, 
-                                                cursor_position,
-                                            );
-                                        *program_state = Some(Pending::New {
-                                            widget: widget.clone(),
-                                        });
+                                        // A precise shape is completed with the first click
+                                        // using the entered dimensions.
+                                        if self.state.precise_shape.enabled && 
+                                            !PreciseShape::fields(self.state.selected_radio_widget.unwrap()).is_empty() {
+                                            return (
+                                                event::Status::Captured, 
+                                                add_precise_widget(
+                                                    selected_widget, 
+                                                    cursor_position, 
+                                                    &self.state.precise_shape,
+                                                ).map(CanvasMessage::Widget),
+                                            )
+                                        }
+
+                                        let mut widget = selected_widget;
+                                        set_widget_point(
+                                            &mut widget, 
+                                            cursor_position,
+                                            &self.state.pressure_curve,
+                                        );
 
                                         // If a text widget, need to send back the curve so that the
                                         // cursor can be seen.  No access to the time event during pending.
-                                        if self.state.selected_radio_widget == Some(Widget::Text) {
-                                            Some(widget)
-                                        } else {
-                                            None
-                                        }
+                                        let text_widget = 
+                                            (self.state.selected_radio_widget == Some(Widget::Text))
+                                                .then(|| widget.clone());
+                                        // A freehand follows the cursor until the button is released.
+                                        *program_state = 
+                                            if matches!(widget, CanvasWidget::FreeHand(_)) {
+                                                Some(Pending::Stroke { widget })
+                                            } else {
+                                                Some(Pending::New { widget, shift: false })
+                                            };
+                                        text_widget
                                     },
                                     // The second click is a Some() since it was created above
                                     // The pending is carrying the previous info
                                     Some(Pending::New { 
                                             widget, 
+                                            shift,
                                     }) => {
+                                        let cursor_position = 
+                                            if *shift { get_locked_cursor(widget, cursor_position) } else { cursor_position };
 
-                                        let (widget, completed) = 
-                                            set_widget_point(widget, cursor_position);
+                                        // The point is added to the pending widget in place.
+                                        let completed = 
+                                            set_widget_point(widget, cursor_position, &self.state.pressure_curve);
                                         
                                         // if completed, we return the CanvasWidget and set the state to none
                                         // if not, then this is repeated until completed.
                                         if completed {
+                                            let widget = std::mem::replace(widget, CanvasWidget::None);
                                             *program_state = None;
                                             complete_new_widget(widget, cursor_position)
+                                        } else if check_if_text_widget(widget) {
+                                            Some(widget.clone())
                                         } else {
-                                            *program_state = Some(Pending::New {
-                                                widget: widget.clone(),
-                                            });
-                                            
-                                            if check_if_text_widget(&widget) {
-                                                Some(widget)
-                                            } else {
-                                                None
-                                            }
+                                            None
                                         }
                                     },
                                     _ => None,
@@ -406,7 +1283,7 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                     // 2 - click to finish
                                     None => {
                                         let widget_opt = 
-                                            find_closest_widget(self.curves, self.text_curves, cursor_position);
+                                            find_closest_widget(self.widgets_near(cursor_position, true), cursor_position, self.state.grab_radius);
                                         
                                         let selected_widget = 
                                             match widget_opt {
@@ -429,6 +1306,7 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                             widget: widget.clone(),
                                             step_degrees: self.state.selected_step_degrees,
                                             degrees: get_widget_degrees(&widget),
+                                            typed: String::new(),
                                         });
 
                                         // returning CanvasWidget so that the curve
@@ -440,15 +1318,14 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                     // After the final rotation completed
                                     Some(Pending::Rotate {
                                         widget,
-                                        step_degrees: _,
-                                        degrees: _,
+                                        ..
                                     }) => {
-                                        let (rotated_widget, _) = 
-                                            update_rotated_widget(
-                                                widget,
-                                                0.0,
-                                                Some(DrawStatus::Completed),
-                                            );
+                                        update_rotated_widget(
+                                            widget,
+                                            0.0,
+                                            Some(DrawStatus::Completed),
+                                        );
+                                        let rotated_widget = std::mem::replace(widget, CanvasWidget::None);
 
                                         *program_state = None;
 
@@ -457,66 +1334,534 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                     _ => None,
                                 }
                             },
-                        }
-                    },
-                    mouse::Event::WheelScrolled { delta} => {
-                        match self.state.draw_mode {
-                            DrawMode::Rotate => {
+                            DrawMode::Trim => {
+                                // trim is a single click on the line or polyline,
+                                // the side of the closest intersection that
+                                // was clicked is removed, a click away from
+                                // every line trims nothing.
+                                let widget_opt =
+                                    find_closest_widget_of_type(
+                                        self.widgets_near(cursor_position, false),
+                                        cursor_position,
+                                        &[Widget::Line, Widget::PolyLine],
+                                        None,
+                                        self.state.grab_radius,
+                                    );
+
+                                match widget_opt {
+                                    Some(widget) => trim_widget(&widget, self.curves, cursor_position),
+                                    None => return (event::Status::Ignored, None),
+                                }
+                            },
+                            DrawMode::Extend => {
                                 match program_state {
-                                    None => None,
-                                    Some(Pending::Rotate { 
-                                        widget,
-                                        step_degrees,
-                                        degrees: _,  
+                                    // extend consists of 2 clicks
+                                    // 1 - select the boundary widget
+                                    // 2 - select the line near the end to extend
+                                    None => {
+                                        let widget_opt =
+                                            find_closest_widget(self.widgets_near(cursor_position, false), cursor_position, self.state.grab_radius);
+
+                                        let boundary =
+                                            match widget_opt {
+                                                Some(w) => w,
+                                                None => return (event::Status::Ignored, None),
+                                            };
+                                        *program_state = Some(Pending::Extend {
+                                            boundary,
+                                        });
+                                        None
+                                    },
+                                    Some(Pending::Extend {
+                                        boundary,
                                     }) => {
-                                        let delta = match delta {
-                                            mouse::ScrollDelta::Lines { x:_, y } => y,
-                                            mouse::ScrollDelta::Pixels { x:_, y } => y,
+                                        let widget_opt =
+                                            find_closest_widget_of_type(
+                                                self.widgets_near(cursor_position, false),
+                                                cursor_position,
+                                                &[Widget::Line],
+                                                Some(&get_widget_id(boundary)),
+                                                self.state.grab_radius,
+                                            );
+
+                                        let extended_widget = match widget_opt {
+                                            Some(widget) => extend_widget(&widget, boundary, cursor_position),
+                                            None => None,
                                         };
 
-                                        // Setting the widget draw_mode at each mouse wheel
-                                        // since it was set to DrawAll initially.
-                                        // Otherwise needed to have another pending type
-                                        // and duplicate a lot of code.  Had to clone anyway.
-                                        let (widget, degrees) = 
-                                            update_rotated_widget(
-                                                widget, 
-                                                *step_degrees*delta,
-                                                None, 
+                                        *program_state = None;
+                                        extended_widget
+                                    },
+                                    _ => None,
+                                }
+                            },
+                            DrawMode::Parallel | DrawMode::Perpendicular => {
+                                match program_state {
+                                    // construction consists of 2 clicks
+                                    // 1 - select the reference line
+                                    // 2 - click the point the new line goes through
+                                    None => {
+                                        let widget_opt =
+                                            find_closest_widget_of_type(
+                                                self.widgets_near(cursor_position, false),
+                                                cursor_position,
+                                                &[Widget::Line],
+                                                None,
+                                                self.state.grab_radius,
                                             );
-                                        
-                                        *program_state = Some(Pending::Rotate{
-                                            widget,
-                                            step_degrees: *step_degrees,
-                                            degrees: Some(degrees),
-                                        });
-                                        None
+
+                                        match widget_opt {
+                                            Some(CanvasWidget::Line(line)) => {
+                                                *program_state = Some(Pending::Construct {
+                                                    line,
+                                                    mode: self.state.draw_mode,
+                                                });
+                                                None
+                                            },
+                                            _ => return (event::Status::Ignored, None),
+                                        }
+                                    },
+                                    Some(Pending::Construct {
+                                        line,
+                                        mode,
+                                    }) => {
+                                        let cursor_position = self.state.get_snap_point(cursor_position);
+                                        let new_widget =
+                                            construct_line(
+                                                *mode,
+                                                line,
+                                                cursor_position,
+                                                self.state.selected_draw_color,
+                                                self.state.selected_width,
+                                            );
+
+                                        *program_state = None;
+                                        new_widget
                                     },
                                     _ => None,
                                 }
                             },
-                            _ => None,
-                        }
-                    },
-                    _ => None,
-                };
-                (event::Status::Captured, message)
-            },
-            Event::Keyboard(key_event) => {
-                let message = match key_event {
-                    iced::keyboard::Event::KeyPressed { 
-                        key:_, 
-                        modified_key, 
-                        physical_key:_, 
-                        location:_, 
-                        modifiers:_, 
-                        text:_ } => {
-                            match program_state {
-                                None => None,
-                                Some(Pending::New { 
-                                    widget }) => {
-                                        let (widget, completed) = 
-                                            add_keypress(widget, modified_key);
+                            DrawMode::Tangent => {
+                                match program_state {
+                                    // tangent consists of 3 or more clicks
+                                    // 1 - select the circle
+                                    // 2 - click the point outside the circle
+                                    // 3 - click near a tangent to add it,
+                                    //     repeat for the other tangent
+                                    None => {
+                                        let widget_opt =
+                                            find_closest_widget_of_type(
+                                                self.widgets_near(cursor_position, false),
+                                                cursor_position,
+                                                &[Widget::Circle],
+                                                None,
+                                                self.state.grab_radius,
+                                            );
+
+                                        match widget_opt {
+                                            Some(CanvasWidget::Circle(circle)) => {
+                                                *program_state = Some(Pending::Tangent {
+                                                    circle,
+                                                    point: None,
+                                                    tangents: vec![],
+                                                });
+                                                None
+                                            },
+                                            _ => return (event::Status::Ignored, None),
+                                        }
+                                    },
+                                    Some(Pending::Tangent {
+                                        circle,
+                                        point: None,
+                                        tangents,
+                                    }) => {
+                                        let cursor_position = self.state.get_snap_point(cursor_position);
+                                        // ignore clicks inside the circle
+                                        *tangents = 
+                                            get_tangent_points(
+                                                circle.center, 
+                                                circle.radius, 
+                                                cursor_position,
+                                            );
+                                        if !tangents.is_empty() {
+                                            *program_state = Some(Pending::Tangent {
+                                                circle: circle.clone(),
+                                                point: Some(cursor_position),
+                                                tangents: tangents.clone(),
+                                            });
+                                        }
+                                        None
+                                    },
+                                    Some(Pending::Tangent {
+                                        circle: _,
+                                        point: Some(point),
+                                        tangents,
+                                    }) => {
+                                        let index = get_closest_tangent_index(*point, tangents, cursor_position);
+                                        let tangent = tangents.remove(index);
+                                        let new_widget = 
+                                            set_widget_mode_or_status(
+                                                new_line(
+                                                    vec![*point, tangent],
+                                                    self.state.selected_draw_color,
+                                                    self.state.selected_width,
+                                                ),
+                                                Some(DrawMode::New),
+                                                None,
+                                            );
+
+                                        if tangents.is_empty() {
+                                            *program_state = None;
+                                        }
+                                        Some(new_widget)
+                                    },
+                                    _ => None,
+                                }
+                            },
+                            DrawMode::Select => {
+                                return self.select_press(program_state, cursor_position)
+                            },
+                            DrawMode::Place => {
+                                let cursor_position = self.state.get_snap_point(cursor_position);
+                                return (event::Status::Captured, Some(CanvasMessage::Place(cursor_position)))
+                            },
+                            DrawMode::Number => {
+                                let cursor_position = self.state.get_snap_point(cursor_position);
+                                return (event::Status::Captured, Some(CanvasMessage::Number(cursor_position)))
+                            },
+                            DrawMode::Delete => {
+                                // a single click removes the closest widget
+                                let id = 
+                                    find_closest_widget(self.widgets_near(cursor_position, true), cursor_position, self.state.grab_radius)
+                                        .map(|widget| get_widget_id(&widget));
+                                return (event::Status::Captured, id.map(CanvasMessage::Delete))
+                            },
+                            DrawMode::Resize => {
+                                // a press on a widget grabs it to scale
+                                let Some(widget) = find_widgets_at(self.widgets_near(cursor_position, true), cursor_position, self.state.grab_radius).first().copied() else {
+                                    return (event::Status::Ignored, None)
+                                };
+                                *program_state = Some(Pending::Resize {
+                                    widget: widget.clone(),
+                                    start: cursor_position,
+                                    factor: 1.0,
+                                    widths: self.state.resize_widths,
+                                });
+                                return (event::Status::Captured, None)
+                            },
+                            DrawMode::Split => {
+                                // the closest stroke is cut where it's clicked
+                                let widget_opt =
+                                    find_closest_widget_of_type(
+                                        self.widgets_near(cursor_position, false),
+                                        cursor_position,
+                                        &[Widget::FreeHand, Widget::PolyLine],
+                                        None,
+                                        self.state.grab_radius,
+                                    );
+                                let Some(widget) = widget_opt else {
+                                    return (event::Status::Ignored, None)
+                                };
+                                return (event::Status::Captured, Some(CanvasMessage::Split(get_widget_id(&widget), cursor_position)))
+                            },
+                            DrawMode::Join => {
+                                // join consists of 2 clicks, the stroke
+                                // kept and the stroke joined onto it
+                                let exclude_id = match program_state {
+                                    Some(Pending::Join { first }) => Some(get_widget_id(first)),
+                                    _ => None,
+                                };
+                                let widget_opt =
+                                    find_closest_widget_of_type(
+                                        self.widgets_near(cursor_position, false),
+                                        cursor_position,
+                                        &[Widget::FreeHand, Widget::PolyLine],
+                                        exclude_id.as_ref(),
+                                        self.state.grab_radius,
+                                    );
+                                let Some(widget) = widget_opt else {
+                                    return (event::Status::Ignored, None)
+                                };
+                                match exclude_id {
+                                    None => {
+                                        *program_state = Some(Pending::Join { first: widget });
+                                        return (event::Status::Captured, None)
+                                    },
+                                    Some(first) => {
+                                        *program_state = None;
+                                        return (event::Status::Captured, Some(CanvasMessage::Join(first, get_widget_id(&widget))))
+                                    },
+                                }
+                            },
+                        }
+                    },
+                    mouse::Event::WheelScrolled { delta} => {
+                        match self.state.draw_mode {
+                            DrawMode::Rotate => {
+                                match program_state {
+                                    None => None,
+                                    Some(Pending::Rotate { 
+                                        widget,
+                                        step_degrees,
+                                        degrees,  
+                                        typed,
+                                    }) => {
+                                        // the wheel turns from where it is
+                                        typed.clear();
+                                        let delta = match delta {
+                                            mouse::ScrollDelta::Lines { x:_, y } => y,
+                                            mouse::ScrollDelta::Pixels { x:_, y } => y,
+                                        };
+
+                                        // The pending widget is rotated in place
+                                        // at each mouse wheel, no clone needed.
+                                        *degrees = 
+                                            Some(update_rotated_widget(
+                                                widget, 
+                                                *step_degrees*delta,
+                                                None, 
+                                            ));
+                                        None
+                                    },
+                                    _ => None,
+                                }
+                            },
+                            // The wheel over the selected widget turns it a step.
+                            DrawMode::Select if program_state.is_none() => {
+                                let delta = match delta {
+                                    mouse::ScrollDelta::Lines { x:_, y } => y,
+                                    mouse::ScrollDelta::Pixels { x:_, y } => y,
+                                };
+                                // several selected turn together about their
+                                // center, with ctrl about the cursor
+                                if self.state.selected_ids.len() > 1 {
+                                    let over = self.state.selected_ids.iter()
+                                        .filter_map(|id| self.curves.get(id).and_then(CanvasWidget::as_trait))
+                                        .any(|wt| wt.contains(cursor_position) || wt.distance_to(cursor_position) <= self.state.grab_radius);
+                                    if !over || delta == 0.0 {
+                                        return (event::Status::Ignored, None)
+                                    }
+                                    let pivot = self.state.modifiers.control().then_some(cursor_position);
+                                    // with shift they're scaled instead
+                                    if self.state.modifiers.shift() {
+                                        return (
+                                            event::Status::Captured, 
+                                            Some(CanvasMessage::ScaleSelection(SCALE_STEP.powf(delta), pivot)),
+                                        )
+                                    }
+                                    return (
+                                        event::Status::Captured, 
+                                        Some(CanvasMessage::RotateSelection(self.state.selected_step_degrees*delta, pivot)),
+                                    )
+                                }
+                                let Some(mut widget) = self.get_selected_widget() else {
+                                    return (event::Status::Ignored, None)
+                                };
+                                let over = widget.as_trait().is_some_and(|wt| 
+                                    wt.degrees().is_some() && 
+                                    (wt.contains(cursor_position) || wt.distance_to(cursor_position) <= self.state.grab_radius));
+                                if !over || delta == 0.0 {
+                                    return (event::Status::Ignored, None)
+                                }
+                                update_rotated_widget(
+                                    &mut widget, 
+                                    self.state.selected_step_degrees*delta, 
+                                    Some(DrawStatus::Completed),
+                                );
+                                Some(widget)
+                            },
+                            // The wheel over a widget scales it a step.
+                            DrawMode::Resize if program_state.is_none() => {
+                                let delta = match delta {
+                                    mouse::ScrollDelta::Lines { x:_, y } => y,
+                                    mouse::ScrollDelta::Pixels { x:_, y } => y,
+                                };
+                                let Some(widget) = find_widgets_at(self.widgets_near(cursor_position, true), cursor_position, self.state.grab_radius).first().copied() else {
+                                    return (event::Status::Ignored, None)
+                                };
+                                if delta == 0.0 {
+                                    return (event::Status::Ignored, None)
+                                }
+                                return (
+                                    event::Status::Captured, 
+                                    Some(CanvasMessage::Resize(get_widget_id(widget), SCALE_STEP.powf(delta))),
+                                )
+                            },
+                            // The wheel adds or takes away a point of the
+                            // polygon or polyline being drawn.
+                            DrawMode::New => {
+                                let Some(Pending::New { widget, .. }) = program_state else {
+                                    return (event::Status::Ignored, None)
+                                };
+                                let delta = match delta {
+                                    mouse::ScrollDelta::Lines { x:_, y } => y,
+                                    mouse::ScrollDelta::Pixels { x:_, y } => y,
+                                };
+                                if delta == 0.0 {
+                                    return (event::Status::Ignored, None)
+                                }
+                                let step = if delta > 0.0 { 1 } else { -1 };
+                                match step_poly_points(widget, step) {
+                                    Some(points) => {
+                                        return (event::Status::Captured, Some(CanvasMessage::PolyPoints(points)))
+                                    },
+                                    None => None,
+                                }
+                            },
+                            _ => None,
+                        }
+                    },
+                    // A released stroke of a single point carries on
+                    // a point per click, finished with Enter.
+                    mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                        match program_state.take() {
+                            Some(Pending::Stroke { 
+                                widget: CanvasWidget::FreeHand(mut fh), 
+                            }) => {
+                                if fh.points.len() < 2 {
+                                    *program_state = Some(Pending::New { 
+                                        widget: CanvasWidget::FreeHand(fh), 
+                                        shift: false,
+                                    });
+                                    None
+                                } else {
+                                    fh.completed = true;
+                                    if self.state.smoothing {
+                                        fh.simplify(self.state.smoothing_strength);
+                                    }
+                                    Some(CanvasWidget::FreeHand(fh))
+                                }
+                            },
+                            Some(Pending::Band { start }) => {
+                                if start.distance(cursor_position) < DRAG_DISTANCE {
+                                    return (event::Status::Captured, None)
+                                }
+                                let area = get_points_bounds(&[start, cursor_position], 0.0);
+                                return (event::Status::Captured, Some(CanvasMessage::SelectArea(area)))
+                            },
+                            Some(Pending::MoveSelection { offset, moved, .. }) => {
+                                return (event::Status::Captured, moved.then_some(CanvasMessage::MoveSelection(offset)))
+                            },
+                            Some(Pending::Resize { widget, factor, .. }) => {
+                                let id = get_widget_id(&widget);
+                                return (event::Status::Captured, (factor != 1.0).then_some(CanvasMessage::Resize(id, factor)))
+                            },
+                            Some(Pending::Drag { widget, moved, .. }) => {
+                                // without a move it was only a click to select
+                                moved.then(|| 
+                                    set_widget_mode_or_status(
+                                        widget, 
+                                        Some(DrawMode::DrawAll), 
+                                        Some(DrawStatus::Completed),
+                                    ))
+                            },
+                            other => {
+                                *program_state = other;
+                                None
+                            },
+                        }
+                    },
+                    // The stabilizer adds a freehand point each time the 
+                    // cursor pulls the trailing brush point.
+                    mouse::Event::CursorMoved { .. } => {
+                        match program_state {
+                            Some(Pending::Stroke { 
+                                widget: CanvasWidget::FreeHand(fh), 
+                            }) => {
+                                let last = fh.points.last().unwrap_or(cursor_position);
+                                if !self.state.stabilizer {
+                                    if last != cursor_position {
+                                        fh.add_point(cursor_position, &self.state.pressure_curve);
+                                    }
+                                } else if let Some(point) = 
+                                    get_lazy_point(last, cursor_position, self.state.stabilizer_strength) {
+                                        fh.add_point(point, &self.state.pressure_curve);
+                                }
+                                None
+                            },
+                            Some(Pending::New { 
+                                widget: CanvasWidget::FreeHand(fh), 
+                                ..
+                            }) if self.state.stabilizer && !fh.points.is_empty() => {
+                                let brush = fh.points.last().unwrap_or(cursor_position);
+                                if let Some(point) = 
+                                    get_lazy_point(brush, cursor_position, self.state.stabilizer_strength) {
+                                        fh.add_point(point, &self.state.pressure_curve);
+                                }
+                                None
+                            },
+                            Some(Pending::Resize { widget, start, factor, .. }) => {
+                                if let Some(mid_point) = widget.as_trait().map(|wt| wt.mid_point()) {
+                                    let from = start.distance(mid_point);
+                                    if from > 0.0 {
+                                        *factor = (cursor_position.distance(mid_point) / from).max(MIN_RESIZE);
+                                    }
+                                }
+                                None
+                            },
+                            Some(Pending::MoveSelection { start, offset, moved, .. }) => {
+                                if !*moved && start.distance(cursor_position) < DRAG_DISTANCE {
+                                    return (event::Status::Captured, None)
+                                }
+                                *moved = true;
+                                *offset = self.state.get_snap_point(cursor_position) - *start;
+                                None
+                            },
+                            Some(Pending::Drag { 
+                                original, 
+                                widget, 
+                                action, 
+                                start, 
+                                moved, 
+                            }) => {
+                                if !*moved && start.distance(cursor_position) < DRAG_DISTANCE {
+                                    return (event::Status::Captured, None)
+                                }
+                                let cursor_position = 
+                                    if *action == DragAction::Rotate { cursor_position } else { self.state.get_snap_point(cursor_position) };
+                                *widget = get_dragged_widget(original.clone(), *action, *start, cursor_position);
+                                // the first move hides the widget in the cache,
+                                // the pending draws it until the release
+                                (!std::mem::replace(moved, true)).then(|| widget.clone())
+                            },
+                            _ => None,
+                        }
+                    },
+                    _ => None,
+                };
+                (event::Status::Captured, message.map(CanvasMessage::Widget))
+            },
+            Event::Keyboard(key_event) => {
+                let message = match key_event {
+                    iced::keyboard::Event::KeyPressed { 
+                        key:_, 
+                        modified_key, 
+                        physical_key:_, 
+                        location:_, 
+                        modifiers, 
+                        text:_ } => {
+                            match program_state {
+                                None if self.state.draw_mode == DrawMode::Select && 
+                                    !self.state.selected_ids.is_empty() && 
+                                    get_del_key(modified_key.clone()) => {
+                                        return (event::Status::Captured, Some(CanvasMessage::DeleteSelection))
+                                    },
+                                None => None,
+                                Some(Pending::New { 
+                                    widget, .. }) if get_step_key(&modified_key).is_some() && 
+                                        matches!(widget, CanvasWidget::Polygon(_) | CanvasWidget::PolyLine(_)) => {
+                                        let step = get_step_key(&modified_key).unwrap_or_default();
+                                        return (
+                                            event::Status::Captured, 
+                                            step_poly_points(widget, step).map(CanvasMessage::PolyPoints),
+                                        )
+                                    },
+                                Some(Pending::New { 
+                                    widget,
+                                    shift }) => {
+                                        let shift = *shift;
+                                        let (widget, completed) = 
+                                            add_keypress(widget, modified_key, modifiers);
                                         match widget {
                                             Some(widget) => {
                                                 // if not completed, keep doing the pending
@@ -527,10 +1872,17 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                                 if !completed {
                                                     *program_state = Some(Pending::New { 
                                                         widget: widget.clone(), 
+                                                        shift,
                                                     });
                                                     Some(widget)
                                                 } else {
                                                     *program_state = None;
+                                                    let mut widget = widget;
+                                                    if let CanvasWidget::FreeHand(fh) = &mut widget {
+                                                        if self.state.smoothing {
+                                                            fh.simplify(self.state.smoothing_strength);
+                                                        }
+                                                    }
                                                     Some(widget)
                                                 }
                                             },
@@ -540,9 +1892,16 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                             }
                                         }
                                     },
+                                    Some(Pending::EditSecond { .. }) if get_tab_key(modified_key.clone()) => {
+                                        return self.cycle_selection(program_state)
+                                    },
                                     Some(Pending::EditSecond { 
-                                        widget }) => {
+                                        widget, .. }) => {
                                             let del_key = get_del_key(modified_key);
+                                            // over a node of a polyline or freehand only the node goes
+                                            if del_key && remove_node(widget, cursor_position, self.state.grab_radius) {
+                                                return (event::Status::Captured, Some(CanvasMessage::Widget(widget.clone())))
+                                            }
                                             let del_widget = if del_key {
                                                 set_widget_mode_or_status(
                                                     widget.clone(), 
@@ -556,14 +1915,29 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                                             *program_state = None;
                                             Some(del_widget)
                                     },
+                                    Some(Pending::Rotate { .. }) => {
+                                        return self.rotate_keypress(program_state, modified_key)
+                                    },
+                                    Some(Pending::Tangent { .. }) => {
+                                        // escape finishes without adding the other tangent
+                                        if get_escape_key(modified_key) {
+                                            *program_state = None;
+                                        }
+                                        None
+                                    },
                                     _ => None,
                             }
                         },
                     iced::keyboard::Event::KeyReleased {key: _, location:_, modifiers:_ } => None,
-                    iced::keyboard::Event::ModifiersChanged(_) => None,
+                    iced::keyboard::Event::ModifiersChanged(modifiers) => {
+                        if let Some(Pending::New { shift, .. }) = program_state {
+                            *shift = modifiers.shift();
+                        }
+                        return (event::Status::Ignored, Some(CanvasMessage::Modifiers(modifiers)))
+                    },
                 };
 
-                (event::Status::Captured, message)
+                (event::Status::Captured, message.map(CanvasMessage::Widget))
             },
             _ => (event::Status::Ignored, None),
         }
@@ -578,14 +1952,74 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
         cursor: mouse::Cursor,
     ) -> Vec<Geometry> {
         
-        let content =
-            self.state.cache.draw(renderer, bounds.size(), 
-                            |frame| {
-
+        let mut content = vec![
+            self.state.render_cache.draw_background(renderer, bounds.size(), |frame| {
                 let background = Path::rectangle(Point::ORIGIN, frame.size());
-                frame.fill(&background, self.state.selected_canvas_color);
+                frame.fill(
+                    &background, 
+                    simulate_color_vision(self.state.selected_canvas_color, self.state.simulated_vision),
+                );
+                #[cfg(feature = "image")]
+                if let Some(image) = &self.state.background {
+                    image.draw(renderer, frame);
+                }
+            })];
+
+        // the first layer is at the bottom
+        for (index, layer) in self.state.layers.iter().enumerate().filter(|(_, layer)| layer.visible) {
+            content.push(self.state.render_cache.draw_layer(index, renderer, bounds.size(), |frame| {
+                // the palette remap is previewed without changing the widgets
+                match &self.state.palette_remap {
+                    Some(remap) => {
+                        let remapped = get_remapped_curves(&layer.curves, remap);
+                        DrawCurve::draw_all(&remapped, self.state.simulated_vision, frame, theme);
+                    },
+                    None => DrawCurve::draw_all(&layer.curves, self.state.simulated_vision, frame, theme),
+                }
+            }));
+        }
+
+        content.push(
+            self.state.render_cache.draw_overlay(renderer, bounds.size(), |frame| {
+                if let Some(preview) = self.state.resize_preview {
+                    frame.stroke(
+                        &Path::rectangle(preview.position(), preview.size()),
+                        Stroke {
+                            line_dash: canvas::LineDash { segments: &[6.0, 4.0], offset: 0 },
+                            ..Stroke::default()
+                                .with_width(1.5)
+                                .with_color(theme.palette().primary)
+                        },
+                    );
+                }
+
+                // the widgets left out of the exports are boxed faintly
+                for layer in self.state.layers.iter().filter(|layer| layer.visible) {
+                    for (id, widget) in layer.curves.iter() {
+                        let Some(bounds) = get_widget_bounds(widget)
+                            .filter(|_| is_excluded(&self.state.tags, id)) else {
+                            continue
+                        };
+                        frame.stroke(
+                            &Path::rectangle(bounds.position(), bounds.size()),
+                            Stroke {
+                                line_dash: canvas::LineDash { segments: &[2.0, 4.0], offset: 0 },
+                                ..Stroke::default()
+                                    .with_width(1.0)
+                                    .with_color(Color { a: 0.4, ..theme.palette().text })
+                            },
+                        );
+                    }
+                }
 
-                DrawCurve::draw_all(self.curves, frame, theme);
+                if self.state.show_intersections {
+                    frame.stroke(
+                        &build_markers_path(&self.state.intersection_points),
+                        Stroke::default()
+                            .with_width(1.5)
+                            .with_color(theme.palette().danger),
+                    );
+                }
 
                 frame.stroke(
                     &Path::rectangle(Point::ORIGIN, frame.size()),
@@ -593,26 +2027,123 @@ impl<'a> canvas::Program<CanvasWidget> for DrawPending<'a> {
                         .with_width(2.0)
                         .with_color(theme.palette().text),
                 );
-            });
+            }));
 
         let mut text_content = vec![];
-        for (i, (_id, text_curve)) in self.text_curves.iter().enumerate() {
-            text_content.push(self.state.text_cache[i].draw(renderer, bounds.size(), |frame| {
-                DrawCurve::draw_text(text_curve, self.state.blink, frame, theme);
+        let text_curves: Vec<&CanvasWidget> = 
+            self.state.layers.iter()
+                .filter(|layer| layer.visible)
+                .flat_map(|layer| layer.texts().map(|(_, text_curve)| text_curve))
+                .collect();
+        // drop the caches of deleted or hidden texts
+        let text_ids: HashSet<WidgetId> = text_curves.iter().map(|text_curve| get_widget_id(text_curve)).collect();
+        self.state.render_cache.retain_texts(|id| text_ids.contains(id));
+        for text_curve in text_curves {
+            text_content.push(self.state.render_cache.draw_text(get_widget_id(text_curve), renderer, bounds.size(), |frame| {
+                match &self.state.palette_remap {
+                    Some(remap) => {
+                        let color = get_remapped_color(remap, get_widget_color(text_curve));
+                        let remapped = set_widget_color(text_curve.clone(), color);
+                        DrawCurve::draw_text(&remapped, self.state.blink, self.state.simulated_vision, frame, theme);
+                    },
+                    None => DrawCurve::draw_text(text_curve, self.state.blink, self.state.simulated_vision, frame, theme),
+                }
             }));
         }
+
+        // outline the selected widgets
+        if !self.state.selected_ids.is_empty() {
+            let mut frame = Frame::new(renderer, bounds.size());
+            for id in self.state.selected_ids.iter() {
+                match self.curves.get(id) {
+                    Some(CanvasWidget::Text(txt)) => {
+                        frame.stroke(
+                            &build_markers_path(&[txt.position]),
+                            Stroke::default()
+                                .with_width(2.0)
+                                .with_color(theme.palette().primary),
+                        );
+                    },
+                    Some(widget) => {
+                        frame.stroke(
+                            &build_primitives_path(&get_widget_primitives(widget)),
+                            Stroke::default()
+                                .with_width(get_widget_width(widget) + 4.0)
+                                .with_color(Color { a: 0.5, ..theme.palette().primary }),
+                        );
+                    },
+                    None => (),
+                }
+            }
+            // the handles of the Select tool's one widget
+            if let (DrawMode::Select, Some(widget)) = (self.state.draw_mode, self.get_selected_widget()) {
+                let stroke = Stroke::default()
+                    .with_width(2.0)
+                    .with_color(theme.palette().primary);
+                draw_edit_handles(&mut frame, &widget, theme);
+                if let (Some(handle), Some(bounds)) = (get_rotate_handle(&widget), get_widget_bounds(&widget)) {
+                    frame.stroke(&Path::line(Point::new(handle.x, bounds.y), handle), stroke);
+                    frame.stroke(&Path::circle(handle, 5.0), stroke);
+                }
+            }
+            text_content.push(frame.into_geometry());
+        }
+
+        // show the point the cursor will snap to
+        if let Some(snap) = cursor.position_in(bounds).and_then(|position| self.state.get_snap(position)) {
+            let mut frame = Frame::new(renderer, bounds.size());
+            frame.stroke(
+                &build_snap_marker(&snap),
+                Stroke::default()
+                    .with_width(2.0)
+                    .with_color(theme.palette().success),
+            );
+            text_content.push(frame.into_geometry());
+        }
             
 
         if let Some(pending) = state {
-            let mut content = vec![content, pending.draw(renderer, theme, bounds, cursor)];
-            content.append(&mut text_content);
-            content
+            // the pending preview follows the snapped cursor
+            let cursor = match cursor.position_in(bounds) {
+                Some(position) => {
+                    let snap_point = self.state.get_snap_point(position);
+                    mouse::Cursor::Available(snap_point + Vector::new(bounds.x, bounds.y))
+                },
+                None => cursor,
+            };
+            // a complex widget is dragged with fewer points
+            let preview = get_preview_pending(pending, self.state.preview_quality);
+            content.push(preview.as_ref().unwrap_or(pending).draw(renderer, theme, bounds, cursor));
         } else {
-            let mut content = vec![content];
-            content.append(&mut text_content);
-            content
+            // a faint sketch of the tool by the cursor before the first click
+            if let (DrawMode::New, Some(widget), Some(position)) = 
+                (self.state.draw_mode, self.state.selected_radio_widget, cursor.position_in(bounds)) {
+                let mut frame = Frame::new(renderer, bounds.size());
+                let center = self.state.get_snap_point(position) + TOOL_PREVIEW_OFFSET;
+                frame.stroke(
+                    &build_tool_preview_path(widget, center, self.state.selected_poly_points),
+                    Stroke::default()
+                        .with_width(self.state.selected_width.min(TOOL_PREVIEW_WIDTH))
+                        .with_color(Color { a: 0.4, ..self.state.selected_draw_color }),
+                );
+                content.push(frame.into_geometry());
+            }
         }
+        content.append(&mut text_content);
 
+        // the rulers are over everything, the readout is
+        // redrawn at each move so it isn't cached
+        if self.state.show_rulers {
+            content.push(self.state.render_cache.draw_rulers(renderer, bounds.size(), |frame| {
+                draw_rulers(frame, theme);
+            }));
+            if let Some(position) = cursor.position_in(bounds) {
+                let mut frame = Frame::new(renderer, bounds.size());
+                draw_cursor_readout(&mut frame, position, theme);
+                content.push(frame.into_geometry());
+            }
+        }
+        content
     }
 
     fn mouse_interaction(
@@ -635,187 +2166,23 @@ pub struct DrawCurve {
 }
 
 impl DrawCurve {
-    fn draw_all(curves: &HashMap<Id, CanvasWidget>, frame: &mut Frame, _theme: &Theme) {
+    fn draw_all(curves: &HashMap<WidgetId, CanvasWidget>, vision: ColorVision, frame: &mut Frame, _theme: &Theme) {
         // This draw only occurs at the completion of the 
         // widget(update occurs) and cache is cleared
         for (_id, widget) in curves.iter() {
-            // if first click, skip the curve to be edited so that it 
-            // will not be seen until the second click.  Otherwise is shows
-            // during editing because there is no way to refresh
-            // The pending routine will diplay the curve
-
-            let (path, color, width) = 
-                match &widget {
-                    CanvasWidget::Arc(arc) => {
-                        // skip if being editied or rotated
-                        if arc.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let (path, _, _,_,_,_) = 
-                                build_arc_path(
-                                arc, 
-                                arc.draw_mode, 
-                                None, 
-                                None, 
-                                false,
-                            );
-
-                            (Some(path), Some(arc.color), Some(arc.width))
-                        }
-                    },
-                    CanvasWidget::Bezier(bz) => {
-                        // skip if being editied or rotated
-                        if bz.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let (path, _, _) = 
-                                build_bezier_path(
-                                bz, 
-                                bz.draw_mode, 
-                                None, 
-                                None, 
-                                false,
-                                None,
-                            );
-
-                            (Some(path), Some(bz.color), Some(bz.width))
-                        }
-                    },
-                    CanvasWidget::Circle(cir) => {
-                        // skip if being editied or rotated
-                        if cir.status== DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let path = 
-                                build_circle_path(
-                                    cir, 
-                                    cir.draw_mode,
-                                    None, 
-                                    None, 
-                                    false
-                                );
-                            (Some(path), Some(cir.color), Some(cir.width))
-                        }
-                    },
-                    CanvasWidget::Ellipse(ell) => {
-                        // skip if being editied or rotated
-                        if ell.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let path = 
-                                build_ellipse_path(
-                                    ell, 
-                                    ell.draw_mode,
-                                    None, 
-                                    None, 
-                                    false,
-                                );
-                            (Some(path), Some(ell.color), Some(ell.width))
-                        }
-                    },
-                    CanvasWidget::Line(line) => {
-                        // skip if being editied or rotated
-                        if line.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let (path, _, _) = 
-                                build_line_path(
-                                    line, 
-                                    line.draw_mode, 
-                                    None, 
-                                    None, 
-                                    false,
-                                    None,
-                                    );
-
-                            (Some(path), Some(line.color), Some(line.width))
-                        }
-                    },
-                    CanvasWidget::PolyLine(pl) => {
-                        // skip if being editied or rotated
-                        if pl.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let (path, _, _) = 
-                                build_polyline_path(
-                                    pl, 
-                                    pl.draw_mode, 
-                                    None, 
-                                    None, 
-                                    false,
-                                    false,
-                                    None,
-                                );
-                            (Some(path), Some(pl.color), Some(pl.width))
-                        }
-                    },
-                    CanvasWidget::Polygon(pg) => {
-                        // skip if being editied or rotated
-                        if pg.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let (path, _, _) = 
-                                build_polygon_path(
-                                    pg, 
-                                    pg.draw_mode, 
-                                    None,  
-                                    false,
-                                    false,
-                                    None,
-                                );
-                                
-                            (Some(path), Some(pg.color), Some(pg.width))
-                        }
-                    }
-                    CanvasWidget::RightTriangle(tr) => {
-                        // skip if being editied or rotated
-                        if tr.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let (path, _, _, _) = 
-                                build_right_triangle_path(
-                                    tr, 
-                                    tr.draw_mode, 
-                                    None, 
-                                    None, 
-                                    false,
-                                    false,
-                                    None,
-                                );
-                                
-                            (Some(path), Some(tr.color), Some(tr.width))
-                        }
-                    },
-                    CanvasWidget::FreeHand(fh) => {
-                        // skip if being editied or rotated
-                        if fh.status == DrawStatus::Inprogress {
-                            (None, None, None)
-                        } else {
-                            let path = 
-                                build_free_hand_path(
-                                    fh, 
-                                    fh.draw_mode, 
-                                    None, 
-                                    None, 
-                                );
-                            (Some(path), Some(fh.color), Some(fh.width))
-                        }
-                    },
-                    
-                    _ => (None, None, None),
-                };
-
-                if let Some(path) = path { frame.stroke(
-                    &path,
-                    Stroke::default()
-                    .with_width(width.unwrap())
-                    .with_color(color.unwrap()),
-                    ) }
+            // the widget being edited or rotated is skipped so that it
+            // is not seen until it is done, the pending routine displays it
+            let Some(wt) = widget.as_trait() else {
+                continue
+            };
+            if wt.mode_and_status().1 != DrawStatus::Inprogress {
+                wt.draw(frame, vision);
+            }
         }
 
     }
 
-    fn draw_text(text_curve: &CanvasWidget, mut blink: bool, frame: &mut Frame, _theme: &Theme) {
+    fn draw_text(text_curve: &CanvasWidget, mut blink: bool, vision: ColorVision, frame: &mut Frame, _theme: &Theme) {
 
         let (path, color, width) = 
             match &text_curve {
@@ -828,12 +2195,13 @@ impl DrawCurve {
                             blink = false;
                         }
                         frame.translate(Vector::new(txt.position.x, txt.position.y));
-                        let (text, path) = 
+                        let (mut text, path) = 
                             build_text_path (
                                 txt,
                                 txt.draw_mode,
                                 blink,
                             );
+                        text.color = simulate_color_vision(text.color, vision);
                         frame.rotate(to_radians(&txt.degrees));
                         frame.fill_text(text);
                         
@@ -849,7 +2217,7 @@ impl DrawCurve {
                 &path,
                 Stroke::default()
                 .with_width(width.unwrap())
-                .with_color(color.unwrap()),
+                .with_color(simulate_color_vision(color.unwrap(), vision)),
                 ) }
         
     }
@@ -859,12 +2227,22 @@ impl DrawCurve {
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
-enum Pending {
+pub enum Pending {
     New {
         widget: CanvasWidget, 
+        // held while drawing, an ellipse is locked to a circle
+        shift: bool,
+    },
+    // A freehand drawn while the left button is held.
+    Stroke {
+        widget: CanvasWidget,
     },
     EditSecond {
         widget: CanvasWidget, 
+        // a second click on a text soon after types into it
+        selected_at: Instant,
+        // Tab cycles through the widgets under this point
+        selected_from: Point,
         },
     EditThird {
         widget: CanvasWidget, 
@@ -876,7 +2254,122 @@ enum Pending {
         widget: CanvasWidget,
         step_degrees: f32,
         degrees: Option<f32>,
+        // an angle being typed in
+        typed: String,
+    },
+    Extend {
+        boundary: CanvasWidget,
+    },
+    // the first stroke picked to join
+    Join {
+        first: CanvasWidget,
+    },
+    // A drag of the Resize mode, the widget is scaled by how much
+    // further the cursor is from its mid point than the press.
+    Resize {
+        widget: CanvasWidget,
+        start: Point,
+        factor: f32,
+        // the stroke width is scaled too
+        widths: bool,
+    },
+    Construct {
+        line: Line,
+        mode: DrawMode,
+    },
+    Tangent {
+        circle: Circle,
+        point: Option<Point>,
+        tangents: Vec<Point>,
+    },
+    // A drag of the Select tool, the widget is worked out again
+    // from the original at each move and put down on the release.
+    Drag {
+        original: CanvasWidget,
+        widget: CanvasWidget,
+        action: DragAction,
+        start: Point,
+        moved: bool,
+    },
+    // The box dragged from an empty place by the Select tool.
+    Band {
+        start: Point,
     },
+    // A drag of the Select tool's several widgets, drawn from
+    // their copies at the offset until the release.
+    MoveSelection {
+        widgets: Vec<CanvasWidget>,
+        start: Point,
+        offset: Vector,
+        moved: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragAction {
+    Move,
+    // a point named the way find_closest_point_index does
+    Point {
+        index: Option<usize>,
+        mid_point: bool,
+        other_point: bool,
+    },
+    Rotate,
+}
+
+// The radius of a circle or the radii of an ellipse by the cursor
+// while it is drawn or edited, in the theme's text color like the
+// other readouts so it shows on the light canvases too.
+fn draw_cursor_label(frame: &mut Frame, theme: &Theme, cursor: Point, content: String) {
+    frame.fill_text(canvas::Text {
+        position: Point::new(cursor.x + 10.0, cursor.y - 15.0),
+        color: theme.palette().text,
+        size: 10.0.into(),
+        content,
+        horizontal_alignment: alignment::Horizontal::Left,
+        vertical_alignment: alignment::Vertical::Center,
+        ..canvas::Text::default()
+    });
+}
+
+// With shift held, the last point of an ellipse is put
+// at the first radius so it is drawn as a circle.
+fn get_locked_cursor(widget: &CanvasWidget, cursor: Point) -> Point {
+    widget.as_trait().map_or(cursor, |wt| wt.locked_cursor(cursor))
+}
+
+// The angles by the mid point of the widget being drawn, a
+// typed angle in place of the one above it until Enter.
+fn draw_degrees(frame: &mut Frame, pending: &WidgetPath, typed: &str, theme: &Theme) {
+    let mid_point = pending.mid_point;
+    if let Some(degrees) = pending.degrees_left {
+        frame.fill_text(canvas::Text {
+            position: Point::new(mid_point.x-30.0, mid_point.y-10.0),
+            color: theme.palette().text,
+            size: 10.0.into(),
+            content: format!("{:.1}", degrees),
+            horizontal_alignment: alignment::Horizontal::Center,
+            vertical_alignment: alignment::Vertical::Center,
+            ..canvas::Text::default()
+        });
+    }
+    let readout = 
+        if typed.is_empty() {
+            pending.degrees_center.map(|degrees| format!("{:.1}", degrees))
+        } else {
+            Some(format!("{}_", typed))
+        };
+    if let Some(degrees) = readout {
+        frame.fill_text(canvas::Text {
+            position: Point::new(mid_point.x-10.0, mid_point.y-20.0),
+            color: if typed.is_empty() { theme.palette().text } else { theme.palette().primary },
+            size: 10.0.into(),
+            content: degrees,
+            horizontal_alignment: alignment::Horizontal::Center,
+            vertical_alignment: alignment::Vertical::Center,
+            ..canvas::Text::default()
+        });
+    }
 }
 
 impl Pending {
@@ -887,316 +2380,126 @@ impl Pending {
         bounds: iced::Rectangle,
         cursor: mouse::Cursor,
     ) -> Geometry {
-        let _ = theme;
         let mut frame = Frame::new(renderer, bounds.size());
 
-        if let Some(cursor) = cursor.position_in(bounds) {
+        // the moved copies of the selection
+        if let Pending::MoveSelection { widgets, offset, .. } = self {
+            for widget in widgets.iter() {
+                let moved = translate_widget(widget.clone(), *offset);
+                frame.stroke(
+                    &build_primitives_path(&get_widget_primitives(&moved)),
+                    Stroke::default()
+                        .with_width(get_widget_width(&moved))
+                        .with_color(get_widget_color(&moved)),
+                );
+                if let CanvasWidget::Text(txt) = &moved {
+                    frame.stroke(&build_markers_path(&[txt.position]), Stroke::default().with_width(2.0).with_color(txt.color));
+                }
+            }
+            return frame.into_geometry()
+        }
+
+        // the scaled copy, the corners of its box and the scale
+        if let Pending::Resize { widget, factor, widths, .. } = self {
+            let resized = resize_widget(widget.clone(), *factor, *widths);
+            frame.stroke(
+                &build_primitives_path(&get_widget_primitives(&resized)),
+                Stroke::default()
+                    .with_width(get_widget_width(&resized))
+                    .with_color(get_widget_color(&resized)),
+            );
+            if let (Some(bounds), Some(wt)) = (get_widget_bounds(widget), widget.as_trait()) {
+                let corners = [
+                    bounds.position(),
+                    Point::new(bounds.x + bounds.width, bounds.y),
+                    Point::new(bounds.x + bounds.width, bounds.y + bounds.height),
+                    Point::new(bounds.x, bounds.y + bounds.height),
+                ];
+                frame.stroke(
+                    &build_markers_path(&scale_geometry(&corners, wt.mid_point(), *factor)),
+                    Stroke::default()
+                        .with_width(2.0)
+                        .with_color(theme.palette().primary),
+                );
+            }
+            if let Some(cursor) = cursor.position_in(bounds) {
+                frame.fill_text(canvas::Text {
+                    position: cursor + TOOL_PREVIEW_OFFSET,
+                    color: theme.palette().text,
+                    size: 12.0.into(),
+                    content: format!("{:.0}%", factor * 100.0),
+                    ..canvas::Text::default()
+                });
+            }
+            return frame.into_geometry()
+        }
+
+        // an arc's handle dragged shows what it changes
+        if let Pending::Drag { widget: CanvasWidget::Arc(arc), action: DragAction::Point { .. }, .. } = self {
+            frame.stroke(
+                &build_primitives_path(&get_widget_primitives(&CanvasWidget::Arc(arc.clone()))),
+                Stroke::default()
+                    .with_width(arc.width)
+                    .with_color(arc.color),
+            );
+            draw_arc_readouts(&mut frame, arc, theme);
+            return frame.into_geometry()
+        }
+
+        // a drag is drawn the way a rotation is, with its mid point
+        if let Pending::Drag { widget, .. } = self {
+            let rotate = 
+                Pending::Rotate { 
+                    widget: widget.clone(), 
+                    step_degrees: 0.0, 
+                    degrees: get_widget_degrees(widget),
+                    typed: String::new(),
+                };
+            return rotate.draw(renderer, theme, bounds, cursor)
+        }
+
+        if let Some(cursor) = cursor.position_in(bounds) {
             // This draw happens when the mouse is moved and the state is none.
             match self {
                 Pending::New { 
                     widget, 
-                } => {
-                    let (path, 
-                        color, 
-                        width,
-                        mid_point, 
-                        degrees_left,
-                        degrees_center,
-                        ) = 
-                    match widget {
-                        CanvasWidget::Arc(arc) => {
-                            let (path, _, 
-                                _, 
-                                _, 
-                                degrees_left,
-                                degrees_center) = 
-                                build_arc_path(
-                                    arc, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                );
-                            (path, arc.color, arc.width, Some(arc.points[0]), degrees_left, degrees_center)
-                        },
-                        CanvasWidget::Bezier(bz) => {
-                            let (path, degrees, _) = 
-                                build_bezier_path(
-                                    bz, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                    None,
-                                );
-                                
-                            (path, bz.color, bz.width, Some(bz.points[0]), None, Some(degrees))
-                        },
-                        CanvasWidget::Circle(cir) => {
-                            let path = 
-                                build_circle_path(
-                                    cir, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                );
-                            (path, cir.color, cir.width, None, None, None)
-                        },
-                        CanvasWidget::Ellipse(ell) => {
-                            let path = 
-                                build_ellipse_path(
-                                    ell, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                );
-                            (path, ell.color, ell.width, Some(ell.points[0]), None, None)
-                        }
-                        CanvasWidget::Line(line) => {
-                            let (path, degrees, _) = 
-                                build_line_path(
-                                    line, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                    None,
-                                );
-                            (path, line.color, line.width, Some(line.points[0]), Some(degrees), None)
-                        },
-                        CanvasWidget::Polygon(pg) => {
-                            let (path, degrees, mid_point) = 
-                                build_polygon_path(
-                                    pg,
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    false,
-                                    false,
-                                    None,
-                                );
-                            
-                            (path, pg.color, pg.width, Some(mid_point), Some(degrees), None)
-                        },
-                        // return points as they are set
-                        CanvasWidget::PolyLine(pl) => {
-                            let (path, degrees, mid_point) = 
-                                build_polyline_path(
-                                    pl, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                    false,
-                                    None,
-                                );
-                            (path, pl.color, pl.width, Some(mid_point), Some(degrees), None)
-                        },
-                        CanvasWidget::RightTriangle(r_tr) => {
-                            let (path, degrees, mid_point, _) = 
-                                build_right_triangle_path(
-                                    r_tr, 
-                                    DrawMode::New, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                    false,
-                                    None,
-                                );
-                            (path, r_tr.color, r_tr.width, Some(mid_point), Some(degrees), None)
-                        },
-                        CanvasWidget::FreeHand(fh) => {
-                            let path = 
-                                build_free_hand_path(
-                                    fh, 
-                                    DrawMode::New, 
-                                    Some(cursor), 
-                                    None,
-                                );
-                            (path, fh.color, fh.width, None, None, None)
-                        }
-                        CanvasWidget::Text(_txt) => {
-                            (Path::new(|_| {}), Color::TRANSPARENT, 0.0, None, None, None)  
+                    ..
+                } | Pending::Stroke { widget } => {
+                    if let Some(wt) = widget.as_trait() {
+                        let cursor = 
+                            match self {
+                                Pending::New { shift: true, .. } => wt.locked_cursor(cursor),
+                                _ => cursor,
+                            };
+                        let options = PathOptions { cursor: Some(cursor), ..PathOptions::new(DrawMode::New) };
+                        let pending = wt.draw_pending(&mut frame, &options, theme);
+                        draw_degrees(&mut frame, &pending, "", theme);
+                        if let Some(label) = wt.cursor_label(&options) {
+                            draw_cursor_label(&mut frame, theme, cursor, label);
                         }
-                        CanvasWidget::None => {
-                            (Path::new(|_| {}), Color::TRANSPARENT, 0.0, None, None, None)
-                        }
-                    };
-
-                    if degrees_center.is_some() {
-                        let degrees = format!("{:.prec$}", degrees_center.unwrap(), prec = 1);
-                        let mid_point = mid_point.unwrap();
-                        let position_center = Point::new(mid_point.x-10.0, mid_point.y-20.0);
-                        frame.fill_text(canvas::Text {
-                            position: position_center,
-                            color: Color::WHITE,
-                            size: 10.0.into(),
-                            content: degrees,
-                            horizontal_alignment: alignment::Horizontal::Center,
-                            vertical_alignment: alignment::Vertical::Center,
-                            ..canvas::Text::default()
-                        });
-                    }
-                    if degrees_left.is_some() {
-                        let degrees = format!("{:.prec$}", degrees_left.unwrap(), prec = 1);
-                        let mid_point = mid_point.unwrap();
-                        let position_left = Point::new(mid_point.x-30.0, mid_point.y-10.0);
-                        frame.fill_text(canvas::Text {
-                            position: position_left,
-                            color: Color::WHITE,
-                            size: 10.0.into(),
-                            content: degrees,
-                            horizontal_alignment: alignment::Horizontal::Center,
-                            vertical_alignment: alignment::Vertical::Center,
-                            ..canvas::Text::default()
-                        });
+                        frame.stroke(
+                            &pending.path,
+                            Stroke::default()
+                                .with_width(wt.width())
+                                .with_color(wt.color()),
+                        );
                     }
-                    
-                    frame.stroke(
-                        &path,
-                        Stroke::default()
-                            .with_width(width)
-                            .with_color(color),
-                    );
                 },
                 Pending::EditSecond{
                     widget, 
+                    ..
                 } => {
-                    let (path, color, width) = 
-                        match widget {
-                            CanvasWidget::None => {
-                                (Path::new(|_| {}), Color::TRANSPARENT, 0.0)
-                            },
-                            CanvasWidget::Arc(arc) => {
-                                let (path, _, _, _,_,_) = 
-                                build_arc_path(
-                                    arc, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    None,
-                                    false,
-                                );
-
-                                (path, arc.color, arc.width)
-                            },
-                            CanvasWidget::Bezier(bz) => {
-                                let (path, _, _) = 
-                                build_bezier_path(
-                                    bz, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    None, 
-                                    false,
-                                    None,
-                                );
-                           
-                                (path, bz.color, bz.width)
-                            },
-                            CanvasWidget::Circle(cir) => {
-                                let path = 
-                                build_circle_path(
-                                    cir, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    None, 
-                                    false,
-                                );
-                                (path, cir.color, cir.width)
-                            },
-                            CanvasWidget::Ellipse(ell) => {
-                                let path = 
-                                build_ellipse_path(
-                                    ell, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    None, 
-                                    false,
-                                );
-                                (path, ell.color, ell.width)
-                            },
-                            CanvasWidget::Line(line) => {
-                                let (path, _, _) = 
-                                build_line_path(
-                                    line, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    None, 
-                                    false,
-                                    None,
-                                );
-                            
-                                (path, line.color, line.width)
-                            },
-                            CanvasWidget::Polygon(pg) => {
-                                let (path, _, _) = 
-                                build_polygon_path(
-                                    pg, 
-                                    DrawMode::Edit, 
-                                    Some(cursor), 
-                                    false,
-                                    false,
-                                    None,
-                                );
-                                (path, pg.color, pg.width)
-                            },
-                            CanvasWidget::PolyLine(pl) => {
-                                let (path, _, _) = 
-                                    build_polyline_path(
-                                        pl, 
-                                        DrawMode::Edit, 
-                                        Some(cursor),
-                                        None, 
-                                        false,
-                                        false,
-                                        None,
-                                    );
-                                (path, pl.color, pl.width)
-                            },
-                            CanvasWidget::RightTriangle(tr) => {
-                                let (path, _, _, _) = 
-                                build_right_triangle_path(
-                                    tr, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    None, 
-                                    false,
-                                    false,
-                                    None,
-                                );
-                                (path, tr.color, tr.width)
-                            },
-                            CanvasWidget::FreeHand(fh) => {
-                                let path = 
-                                    build_free_hand_path(
-                                        fh, 
-                                        DrawMode::Edit, 
-                                        Some(cursor),
-                                        None, 
-                                    );
-                                (path, fh.color, fh.width)
-                            },
-                            CanvasWidget::Text(txt) => {
-                                frame.translate(Vector::new(txt.position.x, txt.position.y));
-                                let (text, path) = 
-                                    build_text_path (
-                                        txt,
-                                        DrawMode::Edit,
-                                        false,
-                                    );
-                                    
-                                frame.rotate(to_radians(&txt.degrees));
-                                frame.fill_text(text);
-                                (path.unwrap(), txt.color, 2.0)
-                            }
-                        };
-
-                    frame.stroke(
-                    &path,
-                    Stroke::default()
-                        .with_width(width)
-                        .with_color(color),
-                    );
+                    draw_edit_handles(&mut frame, widget, theme);
+                    if let Some(wt) = widget.as_trait() {
+                        let options = PathOptions { cursor: Some(cursor), ..PathOptions::new(DrawMode::Edit) };
+                        let pending = wt.draw_pending(&mut frame, &options, theme);
+                        frame.stroke(
+                            &pending.path,
+                            Stroke::default()
+                                .with_width(wt.width())
+                                .with_color(wt.color()),
+                        );
+                    }
                 },
                 Pending::EditThird { 
                     widget,
@@ -1204,357 +2507,131 @@ impl Pending {
                     edit_mid_point, 
                     edit_other_point, 
                 } => {
-
-                    let (path, 
-                        color, 
-                        width, 
-                        mid_point, 
-                        degrees_left,
-                        degrees_center,
-                        ) = match widget {
-
-                        CanvasWidget::None => {
-                            (Path::new(|_| {}), Color::TRANSPARENT, 0.0, Point::default(), None, None)
-                        },
-                        CanvasWidget::Arc(arc) => {
-                            let (path, 
-                                mid_point, 
-                                _, 
-                                _,
-                                degrees_left,
-                                degrees_center,
-                                ) = 
-                                build_arc_path(
-                                    arc, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                );
-
-                            (path, arc.color, arc.width, mid_point, degrees_left, degrees_center)
-                        },
-                        CanvasWidget::Bezier(bz) => {
-                            let (path, degrees, mid_point) = 
-                                build_bezier_path(
-                                    bz, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                    None,
-                                );
-                           
-                            (path, bz.color, bz.width, mid_point, None, Some(degrees))
-                        },
-                        CanvasWidget::Circle(cir) => {
-                            let path = 
-                                build_circle_path(
-                                    cir, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                );
-                            (path, cir.color, cir.width, cir.center, None, None)
-                        },
-                        CanvasWidget::Ellipse(ell) => {
-                            let path = 
-                                build_ellipse_path(
-                                    ell, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                );
-                            (path, ell.color, ell.width, ell.center, None, None)
-                        },
-                        CanvasWidget::Line(line) => {
-                            let (path, degrees, mid_point) = 
-                                build_line_path(
-                                    line, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                    None,
-                                );
-                            
-                            (path, line.color, line.width, mid_point, None, Some(degrees))
-                        },
-                        CanvasWidget::Polygon(pg) => {
-                            let (path, degrees, mid_point) = 
-                                build_polygon_path(
-                                    pg, 
-                                    DrawMode::Edit, 
-                                    Some(cursor), 
-                                    *edit_mid_point,
-                                    *edit_other_point,
-                                    None,
-                                );
-                            (path, pg.color, pg.width, mid_point, None, Some(degrees))
-                        },
-                        CanvasWidget::PolyLine(pl) => {
-                            let (path, degrees, mid_point) = 
-                                build_polyline_path(
-                                    pl, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                    *edit_other_point,
-                                    None,
-                                );
-                            (path, pl.color, pl.width, mid_point, None, Some(degrees))
-                        },
-                        CanvasWidget::RightTriangle(tr) => {
-                            let (path, degrees, mid_point, _) = 
-                                build_right_triangle_path(
-                                    tr, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                    *edit_mid_point,
-                                    *edit_other_point,
-                                    None,
-                                );
-                            (path, tr.color, tr.width, mid_point, None, Some(degrees))
-                        },
-                        CanvasWidget::FreeHand(fh) => {
-                            let path= 
-                                build_free_hand_path(
-                                    fh, 
-                                    DrawMode::Edit, 
-                                    Some(cursor),
-                                    *edit_point_index, 
-                                );
-                            (path, fh.color, fh.width, Point::default(), None, None)
-                        },
-                        CanvasWidget::Text(txt) => {
-                            frame.translate(Vector::new(cursor.x, cursor.y));
-                            let (text, path) = 
-                                build_text_path (
-                                        txt,
-                                        DrawMode::Edit,
-                                        false,
-                                    );
-
-                            frame.rotate(to_radians(&txt.degrees));
-                            frame.fill_text(text);
-                            (path.unwrap(), Color::TRANSPARENT, 0.0, Point::default(), None, None)
+                    if let Some(wt) = widget.as_trait() {
+                        let options = 
+                            PathOptions { 
+                                cursor: Some(cursor), 
+                                edit_point_index: *edit_point_index, 
+                                edit_mid_point: *edit_mid_point, 
+                                edit_other_point: *edit_other_point, 
+                                ..PathOptions::new(DrawMode::Edit) 
+                            };
+                        let pending = wt.draw_pending(&mut frame, &options, theme);
+                        draw_degrees(&mut frame, &pending, "", theme);
+                        if let Some(label) = wt.cursor_label(&options) {
+                            draw_cursor_label(&mut frame, theme, cursor, label);
                         }
-                    };
-
-                    if degrees_left.is_some() {
-                        let degrees = format!("{:.prec$}", degrees_left.unwrap(), prec = 1);
-                        let position = Point::new(mid_point.x-30.0, mid_point.y-10.0);
-                        frame.fill_text(canvas::Text {
-                            position,
-                            color: Color::WHITE,
-                            size: 10.0.into(),
-                            content: degrees,
-                            horizontal_alignment: alignment::Horizontal::Center,
-                            vertical_alignment: alignment::Vertical::Center,
-                            ..canvas::Text::default()
-                        });
-                    }
-
-                    if degrees_center.is_some() {
-                        let degrees = format!("{:.prec$}", degrees_center.unwrap(), prec = 1);
-                        let position = Point::new(mid_point.x-10.0, mid_point.y-20.0);
-                        frame.fill_text(canvas::Text {
-                            position,
-                            color: Color::WHITE,
-                            size: 10.0.into(),
-                            content: degrees,
-                            horizontal_alignment: alignment::Horizontal::Center,
-                            vertical_alignment: alignment::Vertical::Center,
-                            ..canvas::Text::default()
-                        });
+                        frame.stroke(
+                            &pending.path,
+                            Stroke::default()
+                                .with_width(wt.width())
+                                .with_color(wt.color()),
+                        );
                     }
-
-                    frame.stroke(
-                        &path,
-                        Stroke::default()
-                            .with_width(width)
-                            .with_color(color),
-                    );
                 },
                 
                 Pending::Rotate {
                     widget,
                     step_degrees: _,
                     degrees, 
+                    typed,
                 } => {
-                    let (path, 
-                        color, 
-                        width, 
-                        mid_point, 
-                        degrees_left,
-                        degrees_center,
-                    ) = match widget {
-                        CanvasWidget::Arc(arc) => {
-                            let (path, 
-                                _, 
-                                _, 
-                                _, 
-                                degrees_left,
-                                degrees_center,) = 
-                                build_arc_path(
-                                    arc, 
-                                    arc.draw_mode,
-                                    None,
-                                    None, 
-                                    false,
-                                );
+                    if let Some(wt) = widget.as_trait() {
+                        let options = PathOptions { degrees: *degrees, ..PathOptions::new(DrawMode::Rotate) };
+                        let pending = wt.draw_pending(&mut frame, &options, theme);
+                        draw_degrees(&mut frame, &pending, typed, theme);
+                        frame.stroke(
+                            &pending.path,
+                            Stroke::default()
+                                .with_width(wt.width())
+                                .with_color(wt.color()),
+                        );
+                    }
+                },
+                Pending::Extend { boundary } | Pending::Join { first: boundary } => {
+                    // highlight the boundary or stroke until the next is selected
+                    let path = 
+                        build_primitives_path(
+                            &get_widget_primitives(boundary),
+                        );
+                    
+                    frame.stroke(
+                        &path,
+                        Stroke::default()
+                            .with_width(3.0)
+                            .with_color(theme.palette().primary),
+                    );
+                },
+                Pending::Construct { 
+                    line,
+                    mode,
+                } => {
+                    frame.stroke(
+                        &Path::line(line.points[0], line.points[1]),
+                        Stroke::default()
+                            .with_width(line.width + 2.0)
+                            .with_color(theme.palette().primary),
+                    );
 
-                            (path, arc.color, arc.width, arc.mid_point, degrees_left, degrees_center)
-                        },
-                        CanvasWidget::Bezier(bz) => {
-                            let (path, pending_degrees, _) = 
-                                build_bezier_path(
-                                    bz, 
-                                    bz.draw_mode,
-                                    None,
-                                    None, 
-                                    false,
-                                    *degrees,
-                                );
-                            (path, bz.color, bz.width, bz.mid_point, None, Some(pending_degrees))
-                        },
-                        CanvasWidget::Circle(cir) => {
-                        let path = 
-                            build_circle_path(
-                                cir, 
-                                DrawMode::Rotate, 
-                                None,
-                                None,
-                                false,
-                            );
-                            (path, cir.color, cir.width, cir.center, None, None)
-                        },
-                        CanvasWidget::Ellipse(ell) => {
-                            let path = 
-                                build_ellipse_path(
-                                    ell, 
-                                    DrawMode::Rotate, 
-                                    None,
-                                    None,
-                                    false,
-                                );
-                                (path, ell.color, ell.width, ell.center, None, Some(to_degrees(&ell.rotation.0)))
-                            },
-                        CanvasWidget::Line(line) => {
-                            let (path, pending_degrees, _) = 
-                                build_line_path(
-                                    line, 
-                                    line.draw_mode, 
-                                    None,
-                                    None,
-                                    false,
-                                    *degrees,
-                                );
-                            (path, line.color, line.width, line.mid_point, None, Some(pending_degrees))
-                        },
-                        CanvasWidget::Polygon(pg) => {
-                            let (path, pending_degrees, _) = 
-                                build_polygon_path(
-                                    pg, 
-                                    pg.draw_mode, 
-                                    None,
-                                    false,
-                                    false,
-                                    *degrees,
-                                );
-                            (path, pg.color, pg.width, pg.mid_point, None, Some(pending_degrees))
-                        },
-                        CanvasWidget::PolyLine(pl) => {
-                            let (path, pending_degrees, _) = 
-                                build_polyline_path(
-                                    pl, 
-                                    DrawMode::Rotate, 
-                                    None,
-                                    None,
-                                    false,
-                                    false,
-                                    *degrees,
-                                );
-                            (path, pl.color, pl.width, pl.mid_point, None, Some(pending_degrees))
-                        },
-                        CanvasWidget::RightTriangle(tr) => {
-                            let (path, pending_degrees, _, _) = 
-                                build_right_triangle_path(
-                                    tr, 
-                                    DrawMode::Rotate, 
-                                    None,
-                                    None,
-                                    false,
-                                    false,
-                                    *degrees,
-                                );
-                            (path, tr.color, tr.width, tr.mid_point, None, Some(pending_degrees))
+                    if let Some(CanvasWidget::Line(new_line)) = 
+                        construct_line(*mode, line, cursor, line.color, line.width) {
+                        frame.stroke(
+                            &Path::line(new_line.points[0], new_line.points[1]),
+                            Stroke::default()
+                                .with_width(new_line.width)
+                                .with_color(new_line.color),
+                        );
+                    }
+                },
+                Pending::Tangent { 
+                    circle, 
+                    point, 
+                    tangents,
+                } => {
+                    frame.stroke(
+                        &Path::circle(circle.center, circle.radius),
+                        Stroke::default()
+                            .with_width(circle.width + 2.0)
+                            .with_color(theme.palette().primary),
+                    );
+
+                    // Until the point is set, the tangents follow the cursor.
+                    // Afterwards the tangent that will be added is highlighted.
+                    let (point, tangents, selected) = match point {
+                        Some(point) => {
+                            let index = get_closest_tangent_index(*point, tangents, cursor);
+                            (*point, tangents.clone(), Some(index))
                         },
-                        CanvasWidget::FreeHand(fh) => {
-                            let path = 
-                                build_free_hand_path(
-                                    fh, 
-                                    DrawMode::Rotate, 
-                                    None,
-                                    None,
-                                );
-                            (path, fh.color, fh.width, Point::default(), None, None)
+                        None => {
+                            (cursor, get_tangent_points(circle.center, circle.radius, cursor), None)
                         },
-                        CanvasWidget::Text(txt) => {
-                            frame.translate(Vector::new(txt.position.x, txt.position.y));
-                            let (text, path) = 
-                                build_text_path (
-                                        txt,
-                                        DrawMode::Rotate,
-                                        false,
-                                    );
-                            frame.rotate(to_radians(&degrees.unwrap()));
-                            frame.fill_text(text.clone());
-                            
-                            (path.unwrap(), text.color, 2.0, Point::default(), None, None)
-                        }
-                        CanvasWidget::None => {
-                            (Path::new(|_| {}), Color::TRANSPARENT, 0.0, Point::default(), None, None)
-                        }
                     };
 
-                    if degrees_left.is_some() {
-                        let degrees = format!("{:.prec$}", degrees_left.unwrap(), prec = 1);
-                        let position = Point::new(mid_point.x-30.0, mid_point.y-10.0);
-                        frame.fill_text(canvas::Text {
-                            position,
-                            color: Color::WHITE,
-                            size: 10.0.into(),
-                            content: degrees,
-                            horizontal_alignment: alignment::Horizontal::Center,
-                            vertical_alignment: alignment::Vertical::Center,
-                            ..canvas::Text::default()
-                        });
-                    }
-
-                    if degrees_center.is_some() {
-                        let degrees = format!("{:.prec$}", degrees_center.unwrap(), prec = 1);
-                        let position = Point::new(mid_point.x-10.0, mid_point.y-20.0);
-                        frame.fill_text(canvas::Text {
-                            position,
-                            color: Color::WHITE,
-                            size: 10.0.into(),
-                            content: degrees,
-                            horizontal_alignment: alignment::Horizontal::Center,
-                            vertical_alignment: alignment::Vertical::Center,
-                            ..canvas::Text::default()
-                        });
+                    for (index, tangent) in tangents.iter().enumerate() {
+                        let color = if Some(index) == selected {
+                            theme.palette().primary
+                        } else {
+                            circle.color
+                        };
+                        frame.stroke(
+                            &Path::line(point, *tangent),
+                            Stroke::default()
+                                .with_width(circle.width)
+                                .with_color(color),
+                        );
                     }
-
+                },
+                // drawn as a rotation above, the moved selection before
+                Pending::Drag { .. } | Pending::MoveSelection { .. } | Pending::Resize { .. } => (),
+                Pending::Band { start } => {
+                    let area = get_points_bounds(&[*start, cursor], 0.0);
+                    let band = Path::rectangle(area.position(), area.size());
+                    frame.fill(&band, Color { a: 0.1, ..theme.palette().primary });
                     frame.stroke(
-                        &path,
+                        &band,
                         Stroke::default()
-                            .with_width(width)
-                            .with_color(color),
+                            .with_width(1.0)
+                            .with_color(theme.palette().primary),
                     );
                 },
             };
@@ -1564,23 +2641,57 @@ impl Pending {
     }
 }
 
+// How the ends of an Arc are joined, across for a chord
+// and to the center for a pie sector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArcClosure {
+    #[default]
+    Open,
+    Chord,
+    Pie,
+}
+
+impl ArcClosure {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            ArcClosure::Open => Some("Open".to_string()),
+            ArcClosure::Chord => Some("Chord".to_string()),
+            ArcClosure::Pie => Some("Pie".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Chord" => ArcClosure::Chord,
+            "Pie" => ArcClosure::Pie,
+            _ => ArcClosure::Open,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Open".to_string(), "Chord".to_string(), "Pie".to_string()]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Arc {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub mid_point: Point,
     pub radius: f32,
     pub color: Color,
     pub width: f32,
+    pub hatch: Option<Hatch>,
     pub start_angle: Radians,
     pub end_angle: Radians,
+    pub closure: ArcClosure,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct Bezier {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub mid_point: Point,
     pub color: Color,
@@ -1592,32 +2703,34 @@ pub struct Bezier {
 
 #[derive(Debug, Clone)]
 pub struct Circle {
-    pub id: Id,
+    pub id: WidgetId,
     pub center: Point,
     pub circle_point: Point,
     pub radius: f32,
     pub color: Color,
     pub width: f32,
+    pub hatch: Option<Hatch>,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct Ellipse {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub center: Point,
     pub radii: Vector,
     pub rotation: Radians,
     pub color: Color,
     pub width: f32,
+    pub hatch: Option<Hatch>,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct Line {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub mid_point: Point,
     pub color: Color,
@@ -1627,15 +2740,49 @@ pub struct Line {
     pub status: DrawStatus,
 }
 
+// Where the mid point of a PolyLine is put, it is
+// moved and rotated about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolyLineCenter {
+    #[default]
+    Centroid,
+    BoundsCenter,
+}
+
+impl PolyLineCenter {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            PolyLineCenter::Centroid => Some("Centroid".to_string()),
+            PolyLineCenter::BoundsCenter => Some("Bounds Center".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Bounds Center" => PolyLineCenter::BoundsCenter,
+            _ => PolyLineCenter::Centroid,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Centroid".to_string(), "Bounds Center".to_string()]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PolyLine {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub poly_points: usize,
     pub mid_point: Point,
+    // the rotation handle, kept apart from the points
     pub pl_point: Point,
+    pub center: PolyLineCenter,
+    // the last point is joined to the first
+    pub closed: bool,
     pub color: Color,
     pub width: f32,
+    pub hatch: Option<Hatch>,
     pub degrees: f32,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
@@ -1643,13 +2790,14 @@ pub struct PolyLine {
 
 #[derive(Debug, Clone)]
 pub struct Polygon {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub poly_points: usize,
     pub mid_point: Point,
     pub pg_point: Point,
     pub color: Color,
     pub width: f32,
+    pub hatch: Option<Hatch>,
     pub degrees: f32,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
@@ -1657,12 +2805,30 @@ pub struct Polygon {
 
 #[derive(Debug, Clone)]
 pub struct RightTriangle {
-    pub id: Id,
+    pub id: WidgetId,
     pub points: Vec<Point>,
     pub mid_point: Point,
     pub tr_point: Point,
     pub color: Color,
     pub width: f32,
+    pub hatch: Option<Hatch>,
+    pub degrees: f32,
+    pub draw_mode: DrawMode,
+    pub status: DrawStatus,
+}
+
+// The points are the four corners, in order from the first click,
+// and the first again to close it.  A radius of half the shorter
+// side makes a capsule.
+#[derive(Debug, Clone)]
+pub struct RoundedRect {
+    pub id: WidgetId,
+    pub points: Vec<Point>,
+    pub mid_point: Point,
+    pub radius: f32,
+    pub color: Color,
+    pub width: f32,
+    pub hatch: Option<Hatch>,
     pub degrees: f32,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
@@ -1670,7 +2836,7 @@ pub struct RightTriangle {
 
 #[derive(Debug, Clone)]
 pub struct Text {
-    pub id: Id,
+    pub id: WidgetId,
     pub content: String,
     pub position: Point,
     pub color: Color,
@@ -1681,73 +2847,369 @@ pub struct Text {
     pub vertical_alignment: alignment::Vertical,
     pub shaping: Shaping,
     pub degrees: f32,
+    // only moved while the text is typed
+    pub cursor: TextCursor,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct FreeHand {
-    pub id: Id,
-    pub points: Vec<Point>,
+    pub id: WidgetId,
+    pub points: StrokePoints,
      pub color: Color,
     pub width: f32,
+    // the width at each point after the pressure curve
+    pub widths: Vec<f32>,
+    // drawn as a curve through the points
+    pub smooth: bool,
     pub draw_mode: DrawMode,
     pub status: DrawStatus,
     pub completed: bool,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq,)]
-pub enum Widget {
-    None,
-    Arc,
-    Bezier,
-    Circle,
-    Ellipse,
-    Line,
-    PolyLine,
-    Polygon,
-    RightTriangle,
-    Text,
-    FreeHand,
+impl FreeHand {
+    // Added in place, the stroke is not cloned for each point.
+    pub fn add_point(&mut self, point: Point, pressure_curve: &PressureCurve) {
+        self.points.push(point);
+        self.widths.push(self.width * pressure_curve.get_factor(get_pen_pressure()));
+    }
+
+    // Drops the points within the tolerance of the stroke,
+    // the points left are drawn as a smooth curve.
+    pub fn simplify(&mut self, tolerance: f32) {
+        let points = self.points.to_vec();
+        let keep = simplify_points(&points, tolerance);
+        if self.widths.len() == points.len() {
+            self.widths = keep.iter().map(|index| self.widths[*index]).collect();
+        }
+        self.points = keep.iter().map(|index| points[*index]).collect();
+        self.smooth = true;
+    }
 }
 
-fn check_if_text_widget(canvas_widget: &CanvasWidget) -> bool {
-    matches!(canvas_widget, CanvasWidget::Text(_))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GaugeField {
+    Min,
+    Max,
+    Value,
 }
 
-fn add_new_widget(widget: Widget, 
-                    poly_points: usize, 
-                    color: Color,
-                    width: f32,
-                    draw_mode: DrawMode,
-                    h_alignment: HTextAlignment,
-                    v_alignment: VTextAlignment,
-                    ) 
-                    -> CanvasWidget {
-    match widget {
-        Widget::None => {
-            CanvasWidget::None
-        },
-        Widget::Arc => {
-            CanvasWidget::Arc(
-                Arc {
-                    id: Id::unique(),
-                    points: vec![],
-                    mid_point: Point::default(),
-                    radius: 0.0,
-                    color,
-                    width,
-                    start_angle: Radians::PI,
-                    end_angle: Radians::PI,
-                    draw_mode,
-                    status: DrawStatus::Inprogress,
+impl GaugeField {
+    pub fn label(&self) -> &'static str {
+        match &self {
+            GaugeField::Min => "Min",
+            GaugeField::Max => "Max",
+            GaugeField::Value => "Value",
+        }
+    }
+}
+
+// A scale from min to max with a needle at the value,
+// edited like a circle with the gauge point setting the radius.
+#[derive(Debug, Clone)]
+pub struct Gauge {
+    pub id: WidgetId,
+    pub center: Point,
+    pub gauge_point: Point,
+    pub radius: f32,
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    pub color: Color,
+    pub width: f32,
+    pub draw_mode: DrawMode,
+    pub status: DrawStatus,
+}
+
+// The points are the end of the first leg, the right angle corner,
+// the end of the second leg and the first point again to close it.
+impl RightTriangle {
+    // Each leg is lengthened along itself, the corner slides along
+    // the first leg taking the second with it, so the angle stays right.
+    pub fn set_leg_point(&mut self, index: usize, cursor: Point) {
+        let corner = self.points[1];
+        let along = |end: Point| {
+            let leg = end - corner;
+            let length = leg.x.hypot(leg.y);
+            if length == 0.0 {
+                return Vector::new(0.0, 0.0)
+            }
+            let unit = leg * (1.0 / length);
+            unit * ((cursor.x - corner.x) * unit.x + (cursor.y - corner.y) * unit.y)
+        };
+        match index {
+            0 | 3 => self.points[0] = corner + along(self.points[0]),
+            1 => {
+                let offset = along(self.points[0]);
+                self.points[1] = corner + offset;
+                self.points[2] = self.points[2] + offset;
+            },
+            2 => self.points[2] = corner + along(self.points[2]),
+            _ => (),
+        }
+        self.points[3] = self.points[0];
+        self.set_handle();
+    }
+
+    // Turned about the corner, the handle is opposite the second leg.
+    pub fn set_handle(&mut self) {
+        self.mid_point = self.points[1];
+        self.tr_point = self.points[1] - (self.points[2] - self.points[1]);
+    }
+}
+
+impl RoundedRect {
+    // An upright rectangle from a corner to the opposite one.
+    pub fn set_corners(&mut self, first: Point, opposite: Point) {
+        self.points = vec![
+            first,
+            Point::new(opposite.x, first.y),
+            opposite,
+            Point::new(first.x, opposite.y),
+            first,
+        ];
+        self.mid_point = get_mid_point(first, opposite);
+        self.degrees = get_horizontal_angle_of_vector(self.points[0], self.points[1]);
+    }
+
+    // The directions of the first and last side from the first corner,
+    // the axes when the rectangle has no size yet.
+    pub fn sides(&self) -> (Vector, Vector) {
+        let unit = |side: Vector, default: Vector| {
+            let length = side.x.hypot(side.y);
+            if length > 0.0 { side * (1.0 / length) } else { default }
+        };
+        (
+            unit(self.points[1] - self.points[0], Vector::new(1.0, 0.0)),
+            unit(self.points[3] - self.points[0], Vector::new(0.0, 1.0)),
+        )
+    }
+
+    // Half the shorter side, the radius of a capsule.
+    pub fn max_radius(&self) -> f32 {
+        let width = self.points[0].distance(self.points[1]);
+        let height = self.points[0].distance(self.points[3]);
+        width.min(height) / 2.0
+    }
+
+    // The handle is on the first side, the radius in from the corner.
+    pub fn radius_point(&self) -> Point {
+        self.points[0] + self.sides().0 * self.radius
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius.clamp(0.0, self.max_radius());
+    }
+
+    // The handle only slides along the first side.
+    pub fn set_radius_point(&mut self, cursor: Point) {
+        let (along, _) = self.sides();
+        let offset = cursor - self.points[0];
+        self.set_radius(offset.x * along.x + offset.y * along.y);
+    }
+
+    // The first or third corner follows the cursor, the opposite one
+    // stays put and the sides keep their directions.
+    pub fn set_corner(&mut self, index: usize, cursor: Point) {
+        let (along, across) = self.sides();
+        let (corner, opposite) =
+            match index {
+                0 | 4 => (cursor, self.points[2]),
+                2 => (self.points[0], cursor),
+                _ => return,
+            };
+        let diagonal = opposite - corner;
+        let width = diagonal.x * along.x + diagonal.y * along.y;
+        let height = diagonal.x * across.x + diagonal.y * across.y;
+        self.points = vec![
+            corner,
+            corner + along * width,
+            opposite,
+            corner + across * height,
+            corner,
+        ];
+        self.mid_point = get_mid_point(corner, opposite);
+        self.set_radius(self.radius);
+    }
+}
+
+impl Ellipse {
+    // The cursor along the turned axis of the point sets the radius.
+    pub fn set_axis_point(&mut self, index: usize, cursor: Point) {
+        let (sin, cos) = self.rotation.0.sin_cos();
+        let d = cursor - self.center;
+        if index == 1 {
+            let vx = (d.x*cos + d.y*sin).abs();
+            self.points[1] = self.center + Vector::new(vx*cos, vx*sin);
+            self.radii = Vector{ x: vx, y: self.radii.y };
+        } else if index == 2 {
+            let vy = (d.y*cos - d.x*sin).abs();
+            self.points[2] = self.center + Vector::new(-vy*sin, vy*cos);
+            self.radii = Vector{ x: self.radii.x, y: vy };
+        }
+    }
+}
+
+impl Arc {
+    // The handle in the middle of the arc that sets the radius.
+    pub fn radius_point(&self) -> Point {
+        let angle = (self.start_angle.0 + self.end_angle.0) / 2.0;
+        self.mid_point + Vector::new(angle.cos(), angle.sin()) * self.radius
+    }
+
+    // The start turns to the cursor, the end stays where it is.
+    pub fn set_start_angle(&mut self, cursor: Point) {
+        let start = get_arc_angle(self.mid_point, cursor);
+        let sweep = (self.end_angle.0 - start).rem_euclid(TAU);
+        self.start_angle = Radians(start);
+        self.end_angle = Radians(start + sweep);
+        self.update_points();
+    }
+
+    // The end turns to the cursor, the start stays where it is.
+    pub fn set_end_angle(&mut self, cursor: Point) {
+        let sweep = (get_arc_angle(self.mid_point, cursor) - self.start_angle.0).rem_euclid(TAU);
+        self.end_angle = Radians(self.start_angle.0 + sweep);
+        self.update_points();
+    }
+
+    // The angles stay as they are.
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+        self.update_points();
+    }
+
+    // The ends of the arc follow its angles and radius.
+    fn update_points(&mut self) {
+        if self.points.len() < 3 {
+            return
+        }
+        let on_arc = |angle: f32| self.mid_point + Vector::new(angle.cos(), angle.sin()) * self.radius;
+        self.points = vec![self.mid_point, on_arc(self.start_angle.0), on_arc(self.end_angle.0)];
+    }
+
+    // The points along the arc and back to its start,
+    // through the center for a pie.
+    pub fn outline(&self) -> Vec<Point> {
+        let mut points = get_arc_points(self);
+        match self.closure {
+            ArcClosure::Open => (),
+            ArcClosure::Chord => points.push(points[0]),
+            ArcClosure::Pie => points.extend([self.mid_point, points[0]]),
+        }
+        points
+    }
+}
+
+fn get_arc_angle(center: Point, point: Point) -> f32 {
+    (point.y - center.y).atan2(point.x - center.x)
+}
+
+impl Circle {
+    // Resized about the center, the circle point keeps its direction.
+    pub fn set_radius(&mut self, radius: f32) {
+        let out = self.circle_point - self.center;
+        let length = out.x.hypot(out.y);
+        let direction = if length > 0.0 { out * (1.0 / length) } else { Vector::new(1.0, 0.0) };
+        self.circle_point = self.center + direction * radius;
+        self.radius = radius;
+    }
+}
+
+impl PolyLine {
+    // Moves the mid point to the center of the points,
+    // the rotation handle goes along with it.
+    pub fn recenter(&mut self) {
+        let mid_point = get_polyline_center(&self.points, self.center);
+        self.pl_point = self.pl_point + (mid_point - self.mid_point);
+        self.mid_point = mid_point;
+    }
+
+    // The points with the first again at the end when closed.
+    pub fn outline(&self) -> Vec<Point> {
+        let mut points = self.points.clone();
+        if self.closed && points.len() > 2 {
+            points.push(points[0]);
+        }
+        points
+    }
+}
+
+impl Gauge {
+    pub fn get(&self, field: GaugeField) -> f32 {
+        match field {
+            GaugeField::Min => self.min,
+            GaugeField::Max => self.max,
+            GaugeField::Value => self.value,
+        }
+    }
+
+    pub fn set(&mut self, field: GaugeField, value: f32) {
+        match field {
+            GaugeField::Min => self.min = value,
+            GaugeField::Max => self.max = value,
+            GaugeField::Value => self.value = value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq,)]
+pub enum Widget {
+    None,
+    Arc,
+    Bezier,
+    Circle,
+    Ellipse,
+    Line,
+    PolyLine,
+    Polygon,
+    RightTriangle,
+    RoundedRect,
+    Text,
+    FreeHand,
+    Gauge,
+}
+
+fn check_if_text_widget(canvas_widget: &CanvasWidget) -> bool {
+    matches!(canvas_widget, CanvasWidget::Text(_))
+}
+
+fn add_new_widget(widget: Widget, 
+                    poly_points: usize, 
+                    color: Color,
+                    width: f32,
+                    draw_mode: DrawMode,
+                    h_alignment: HTextAlignment,
+                    v_alignment: VTextAlignment,
+                    ) 
+                    -> CanvasWidget {
+    match widget {
+        Widget::None => {
+            CanvasWidget::None
+        },
+        Widget::Arc => {
+            CanvasWidget::Arc(
+                Arc {
+                    id: WidgetId::unique(),
+                    points: vec![],
+                    mid_point: Point::default(),
+                    radius: 0.0,
+                    color,
+                    width,
+                    hatch: None,
+                    start_angle: Radians::PI,
+                    end_angle: Radians::PI,
+                    closure: ArcClosure::Open,
+                    draw_mode,
+                    status: DrawStatus::Inprogress,
                 })
         
         },
         Widget::Bezier => {
             CanvasWidget::Bezier(
                 Bezier { 
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     points: vec![],
                     mid_point: Point::default(),
                     color, 
@@ -1761,12 +3223,30 @@ fn add_new_widget(widget: Widget,
         Widget::Circle => {
             CanvasWidget::Circle(
                 Circle {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     center: Point::default(),
                     circle_point: Point::default(),
                     radius: 0.0,
                     color,
                     width,
+                    hatch: None,
+                    draw_mode,
+                    status: DrawStatus::Inprogress,
+                }
+            )
+        },
+        Widget::Gauge => {
+            CanvasWidget::Gauge(
+                Gauge {
+                    id: WidgetId::unique(),
+                    center: Point::default(),
+                    gauge_point: Point::default(),
+                    radius: 0.0,
+                    min: 0.0,
+                    max: 100.0,
+                    value: 0.0,
+                    color,
+                    width,
                     draw_mode,
                     status: DrawStatus::Inprogress,
                 }
@@ -1775,13 +3255,14 @@ fn add_new_widget(widget: Widget,
         Widget::Ellipse => {
             CanvasWidget::Ellipse(
                 Ellipse {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     points: vec![],
                     center: Point::default(),
                     radii: Vector{x: 0.0, y: 0.0},
                     rotation: Radians(0.0),
                     color,
                     width,
+                    hatch: None,
                     draw_mode,
                     status: DrawStatus::Inprogress,
                 }
@@ -1790,7 +3271,7 @@ fn add_new_widget(widget: Widget,
         Widget::Line => {
             CanvasWidget::Line(
                 Line {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     points: vec![],
                     mid_point: Point::default(),
                     color,
@@ -1804,13 +3285,16 @@ fn add_new_widget(widget: Widget,
         Widget::PolyLine => {
             CanvasWidget::PolyLine(
                 PolyLine {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     points: vec![],
                     poly_points,
                     mid_point: Point::default(),
                     pl_point: Point::default(),
+                    center: PolyLineCenter::default(),
+                    closed: false,
                     color,
                     width,
+                    hatch: None,
                     degrees: 0.0,
                     draw_mode,
                     status: DrawStatus::Inprogress,
@@ -1820,13 +3304,14 @@ fn add_new_widget(widget: Widget,
         Widget::Polygon => {
             CanvasWidget::Polygon(
                 Polygon {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     points: vec![],
                     poly_points,
                     mid_point: Point::default(),
                     pg_point: Point::default(),
                     color,
                     width,
+                    hatch: None,
                     degrees: 0.0,
                     draw_mode,
                     status: DrawStatus::Inprogress,
@@ -1836,12 +3321,29 @@ fn add_new_widget(widget: Widget,
         Widget::RightTriangle => {
             CanvasWidget::RightTriangle(
                 RightTriangle {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     points: vec![],
                     mid_point: Point::default(),
                     tr_point: Point::default(),
                     color,
                     width,
+                    hatch: None,
+                    degrees: 0.0,
+                    draw_mode,
+                    status: DrawStatus::Inprogress,
+                }
+            )
+        },
+        Widget::RoundedRect => {
+            CanvasWidget::RoundedRect(
+                RoundedRect {
+                    id: WidgetId::unique(),
+                    points: vec![],
+                    mid_point: Point::default(),
+                    radius: 0.0,
+                    color,
+                    width,
+                    hatch: None,
                     degrees: 0.0,
                     draw_mode,
                     status: DrawStatus::Inprogress,
@@ -1851,10 +3353,12 @@ fn add_new_widget(widget: Widget,
         Widget::FreeHand => {
             CanvasWidget::FreeHand(
                 FreeHand {
-                    id: Id::unique(),
-                    points: vec![],
+                    id: WidgetId::unique(),
+                    points: StrokePoints::default(),
                     color,
                     width,
+                    widths: vec![],
+                    smooth: false,
                     draw_mode,
                     status: DrawStatus::Inprogress,
                     completed: false,
@@ -1866,7 +3370,7 @@ fn add_new_widget(widget: Widget,
             let v_align = iced_v_text_alignment(v_alignment);
             CanvasWidget::Text(
                 Text {
-                    id: Id::unique(),
+                    id: WidgetId::unique(),
                     content: String::new(),
                     position: Point::default(),
                     color,
@@ -1877,6 +3381,7 @@ fn add_new_widget(widget: Widget,
                     vertical_alignment: v_align,
                     shaping: Shaping::Basic,
                     degrees: 0.0,
+                    cursor: TextCursor::default(),
                     draw_mode,
                     status: DrawStatus::Inprogress,
                 }
@@ -1905,10 +3410,13 @@ fn complete_new_widget(widget: CanvasWidget, cursor: Point) -> Option<CanvasWidg
         CanvasWidget::Circle(cir) => { 
             Some(CanvasWidget::Circle(cir))
         },
+        CanvasWidget::Gauge(gauge) => { 
+            Some(CanvasWidget::Gauge(gauge))
+        },
         CanvasWidget::Ellipse(mut ell) => {
             ell.center = ell.points[0];
             let vx = ell.points[1].distance(ell.center);
-            let vy = cursor.distance(ell.center);
+            let vy = ell.points[2].distance(ell.center);
             ell.radii = Vector{ x: vx, y: vy };
             Some(CanvasWidget::Ellipse(ell))
         },
@@ -1943,19 +3451,7 @@ fn complete_new_widget(widget: CanvasWidget, cursor: Point) -> Option<CanvasWidg
             Some(CanvasWidget::Polygon(pg))
         },
         CanvasWidget::PolyLine(mut pl) => {
-            let (slope, intercept) =
-                get_linear_regression(&pl.points);
-            
-            let line = 
-                get_line_from_slope_intercept(
-                    &pl.points, 
-                    slope, 
-                    intercept
-                );
-            pl.mid_point = 
-                get_mid_point(
-                    line.0, 
-                    line.1);
+            pl.mid_point = get_polyline_center(&pl.points, pl.center);
             pl.pl_point = 
                 Point::new(
                     pl.mid_point.x + 100.0, 
@@ -1970,20 +3466,20 @@ fn complete_new_widget(widget: CanvasWidget, cursor: Point) -> Option<CanvasWidg
             Some(CanvasWidget::PolyLine(pl))
         },
         CanvasWidget::RightTriangle(mut tr) => {
-            tr.mid_point = tr.points[1];
-            let trans_pts = translate_geometry(&tr.points, Point::default(), tr.points[1]);
-            let opp = Point::new(-trans_pts[2].x, -trans_pts[2].y);
-            tr.tr_point = Point::new(opp.x+tr.points[1].x, opp.y+tr.points[1].y);
-            if tr.points[1].x > tr.points[2].x {
-                tr.degrees = 180.0;
-            } else {
-                tr.degrees = 0.0;
-            }
+            tr.set_handle();
+            tr.degrees = get_horizontal_angle_of_vector(tr.mid_point, tr.tr_point);
             
             Some(CanvasWidget::RightTriangle(tr))
         },
+        CanvasWidget::RoundedRect(mut rr) => {
+            // a quarter of the shorter side to start with
+            rr.set_radius(rr.max_radius() / 2.0);
+            Some(CanvasWidget::RoundedRect(rr))
+        },
         CanvasWidget::FreeHand(mut fh) => {
             fh.points.push(cursor);
+            let width = fh.widths.last().copied().unwrap_or(fh.width);
+            fh.widths.push(width);
             Some(CanvasWidget::FreeHand(fh))
         }
         CanvasWidget::Text(mut txt) => {
@@ -1994,407 +3490,117 @@ fn complete_new_widget(widget: CanvasWidget, cursor: Point) -> Option<CanvasWidg
     }
 }
 
-fn update_edited_widget(widget: CanvasWidget,
-                        cursor: Point, 
-                        index: Option<usize>, 
-                        mid_point: bool,
-                        other_point: bool,
-                        status: DrawStatus,
-                    ) -> CanvasWidget {
+// Builds the new widget from the entered dimensions with the origin
+// being the center, start point or top left corner.
+fn add_precise_widget(widget: CanvasWidget, 
+                    origin: Point, 
+                    precise: &PreciseShape,
+                    ) -> Option<CanvasWidget> {
     match widget {
-        CanvasWidget::None => {
-            CanvasWidget::None
-        },
-        CanvasWidget::Arc(mut arc) => {
-            if index.is_some() {
-                arc.points[index.unwrap()] = cursor;
-                if index.unwrap() == 1 {
-                    arc.radius = arc.mid_point.distance(cursor);
-                    arc.start_angle = get_angle_of_vectors(
-                            arc.mid_point, 
-                            Point::new(-arc.mid_point.x, arc.mid_point.y), 
-                            cursor) + Radians::PI;
-                    arc.end_angle = 
-                            get_angle_of_vectors(
-                                arc.mid_point, 
-                                cursor, 
-                                arc.points[2]) + arc.start_angle;
-                }
-                if index.unwrap() == 2 {
-                    arc.end_angle = 
-                        get_angle_of_vectors(
-                            arc.points[0], 
-                            arc.points[1], 
-                            cursor
-                        ) + arc.start_angle;
-                }
-                // calc the end_angle point        
-                let r = arc.radius;
-                let b = arc.end_angle.0;
-                let point_b = Point::new(r*b.cos(), r*b.sin());
-                arc.points[2] = translate_geometry(&[point_b], arc.mid_point, Point::default())[0];
-
-            } else if mid_point {
-                arc.points = 
-                    translate_geometry(
-                        &arc.points, 
-                        cursor,
-                        arc.mid_point, 
-                        );
-                arc.mid_point = cursor;
-            }
-            arc.status = status;
-            CanvasWidget::Arc(arc)
-        },
-        CanvasWidget::Bezier(mut bz) => {
-            if index.is_some() {
-                bz.points[index.unwrap()] = cursor;
-                bz.mid_point = get_mid_point(bz.points[0], bz.points[1]);
-            } else if mid_point {
-                bz.points = 
-                    translate_geometry(
-                        &bz.points, 
-                        cursor,
-                        bz.mid_point, 
-                        );
-                bz.mid_point = cursor;
-            }
-            let degrees = 
-                get_horizontal_angle_of_vector(
-                    bz.points[0],
-                    bz.points[1], 
-                );
-            bz.degrees = degrees;
-            bz.status = status;
-            CanvasWidget::Bezier(bz)
-        },
         CanvasWidget::Circle(mut cir) => {
-            if index.is_some() {
-                cir.circle_point = cursor;
-                cir.radius = cir.center.distance(cursor);
-            } else if mid_point {
-                let mut points = vec![cir.circle_point];
-                points = 
-                    translate_geometry(
-                        &points, 
-                        cursor,
-                        cir.center,
-                    );
-                cir.center = cursor;
-                cir.circle_point = points[0];
-            }
-            cir.status = status;
-            CanvasWidget::Circle(cir)
-        },
-        CanvasWidget::Ellipse(mut ell) => {
-           if mid_point {
-                let points = 
-                    translate_geometry(
-                        &ell.points, 
-                        cursor,
-                        ell.center,
-                    );
-                ell.center = cursor;
-                ell.points = points;
-            }
-            if index == Some(1) {
-                let p1 = Point::new(cursor.x, ell.center.y);
-                let vx = p1.distance(ell.center);
-                let vy = ell.points[2].distance(ell.center);
-                ell.points[1] = p1;
-                ell.radii = Vector{ x: vx, y: vy };
-            } else if index == Some(2) {
-                let p2 = Point::new(ell.center.x, cursor.y);
-                let vx = ell.points[1].distance(ell.center);
-                let vy = p2.distance(ell.center);
-                ell.points[2] = p2;
-                ell.radii = Vector{ x: vx, y: vy };
-            }
-
-            ell.status = status;
-            CanvasWidget::Ellipse(ell)
+            let radius = precise.value(PreciseField::Radius).filter(|r| *r > 0.0)?;
+            cir.center = origin;
+            cir.radius = radius;
+            cir.circle_point = Point::new(origin.x + radius, origin.y);
+            complete_new_widget(CanvasWidget::Circle(cir), origin)
         },
-        CanvasWidget::Line(mut line) => {
-            if index.is_some() {
-                line.points[index.unwrap()] = cursor;
-                line.mid_point = get_mid_point(line.points[0], line.points[1]);
-            } else if mid_point {
-                line.points = 
-                    translate_geometry(
-                        &line.points, 
-                        cursor,
-                        line.mid_point, 
-                        );
-                line.mid_point = cursor;
-            }
-
-            let degrees = 
-                get_horizontal_angle_of_vector(
-                    line.points[0],  
-                    line.points[1], 
-                );
-            line.degrees = degrees;
-            line.status = status;
-            CanvasWidget::Line(line)
+        CanvasWidget::Line(mut ln) => {
+            let length = precise.value(PreciseField::Length).filter(|l| *l > 0.0)?;
+            let angle = to_radians(&precise.value(PreciseField::Angle).unwrap_or(0.0));
+            // the angle is counter clockwise with y pointing down
+            let end = Point::new(origin.x + length * angle.cos(), origin.y - length * angle.sin());
+            ln.points = vec![origin, end];
+            ln.mid_point = get_mid_point(origin, end);
+            complete_new_widget(CanvasWidget::Line(ln), end)
         },
         CanvasWidget::Polygon(mut pg) => {
-            if other_point {
-                pg.pg_point = cursor;
-                pg.degrees = get_horizontal_angle_of_vector(pg.mid_point, cursor);
-                pg.points = 
-                    build_polygon(
-                        pg.mid_point, 
-                        pg.pg_point, 
-                        pg.poly_points,
-                        pg.degrees,
-                );
-            } else if mid_point {
-                let trans_pts = 
-                    translate_geometry(
-                        &[pg.pg_point], 
-                        cursor,
-                        pg.mid_point, 
-                    );
-                pg.points = 
-                    build_polygon(
-                        cursor, 
-                        trans_pts[0], 
-                        pg.poly_points,
-                        pg.degrees,
-                    );
-                pg.mid_point = cursor;
-                pg.pg_point = trans_pts[0];
+            let radius = precise.value(PreciseField::Radius).filter(|r| *r > 0.0)?;
+            if pg.poly_points < 3 {
+                return None
             }
-            pg.status = status;
-            CanvasWidget::Polygon(pg)
+            pg.mid_point = origin;
+            complete_new_widget(CanvasWidget::Polygon(pg), Point::new(origin.x, origin.y - radius))
         },
         CanvasWidget::PolyLine(mut pl) => {
-            if index.is_some() {
-                pl.points[index.unwrap()] = cursor;
-                let mid_point = 
-                    get_mid_geometry(
-                        &pl.points, 
-                        Widget::PolyLine
-                    );
-                pl.pl_point = 
-                    translate_geometry(
-                        &[pl.pl_point], 
-                        mid_point, 
-                        pl.mid_point
-                    )[0];
-                pl.mid_point = mid_point;
-                pl.degrees = 
-                    get_horizontal_angle_of_vector(
-                        pl.mid_point, 
-                        pl.pl_point
-                    );
-            }  else if mid_point {
-                let mut pts = pl.points.clone();
-                pts.push(pl.pl_point);
-                pts = 
-                    translate_geometry(
-                        &pts, 
-                        cursor,
-                        pl.mid_point, 
-                    );
-                pl.mid_point = cursor;
-                pl.pl_point = pts.pop().unwrap();
-                pl.points = pts;
-            } else if other_point {
-                let degrees = get_horizontal_angle_of_vector(pl.mid_point, cursor);
-                let step_degrees = degrees-pl.degrees;
-                pl.points = rotate_geometry(&pl.points, &pl.mid_point, &step_degrees, Widget::PolyLine);
-                pl.pl_point = cursor;
-                pl.degrees = degrees;
-            }
-            pl.status = status;
-            CanvasWidget::PolyLine(pl)
-        },
-        CanvasWidget::RightTriangle(mut tr) => {
-            if index.is_some() {
-                let index = index.unwrap();
-                if index == 0 {
-                    tr.points[index].y = cursor.y;
-                }
-                if index == 1 {
-                    tr.points[1].y = cursor.y;
-                    tr.points[2].y = cursor.y;
-                }
-                if index == 2 {
-                    tr.points[2].x = cursor.x;
-                }
-                let mid = get_mid_point(tr.points[1], tr.points[2]);
-                let dist_b_mid = Point::new(mid.x-tr.points[2].x, mid.y-tr.points[2].y);
-                tr.tr_point = Point::new(tr.points[2].x+dist_b_mid.x, tr.points[2].y+dist_b_mid.y);
-            } else if mid_point {
-                let mut pts = tr.points.clone();
-                pts.push(tr.tr_point);
-                pts = 
-                    translate_geometry(
-                        &pts, 
-                        cursor,
-                        tr.mid_point, 
-                    );
-                tr.mid_point = cursor;
-                tr.tr_point = pts.pop().unwrap();
-                tr.points = pts;
-            } else if other_point {
-                let degrees = get_horizontal_angle_of_vector(tr.mid_point, cursor);
-                let step_degrees = degrees-tr.degrees;
-                tr.points = rotate_geometry(&tr.points, &tr.mid_point, &step_degrees, Widget::RightTriangle);
-                tr.tr_point = cursor;
-                tr.degrees = degrees;
-            }
-            tr.status = status;
-            CanvasWidget::RightTriangle(tr)
-        },
-        CanvasWidget::FreeHand(mut fh) => {
-            if index.is_some() {
-                fh.points[index.unwrap()] = cursor;
-            }
-            fh.status = status;
-            CanvasWidget::FreeHand(fh)
+            let width = precise.value(PreciseField::Width).filter(|w| *w > 0.0)?;
+            let height = precise.value(PreciseField::Height).filter(|h| *h > 0.0)?;
+            pl.points = vec![
+                origin,
+                Point::new(origin.x + width, origin.y),
+                Point::new(origin.x + width, origin.y + height),
+                Point::new(origin.x, origin.y + height),
+                origin,
+            ];
+            pl.poly_points = pl.points.len();
+            complete_new_widget(CanvasWidget::PolyLine(pl), origin)
         },
-        CanvasWidget::Text(mut txt) => {
-            txt.position = cursor;
-            txt.status = status;
-            txt.draw_mode = DrawMode::DrawAll;
-            CanvasWidget::Text(txt)
+        _ => None,
+    }
+}
+
+pub(crate) fn update_edited_widget(widget: CanvasWidget,
+                        cursor: Point, 
+                        index: Option<usize>, 
+                        mid_point: bool,
+                        other_point: bool,
+                        status: DrawStatus,
+                    ) -> CanvasWidget {
+    let mut widget = widget;
+    if let Some(wt) = widget.as_trait_mut() {
+        // Dragging the mid point moves the whole widget.
+        if mid_point {
+            wt.translate(cursor - wt.mid_point());
         }
+        wt.edit_point(cursor, index, other_point);
+        wt.set_mode_or_status(None, Some(status));
     }
+    widget
 }
 
 fn update_rotated_widget(widget: &mut CanvasWidget, 
                         step_degrees: f32,
                         status: Option<DrawStatus>,
-                    ) -> (CanvasWidget, f32) {
-    match widget {
-        CanvasWidget::None => (CanvasWidget::None, 0.0),
-        CanvasWidget::Arc(arc) => {
-            arc.points = rotate_geometry(&arc.points, &arc.mid_point, &step_degrees, Widget::Arc);
-            arc.start_angle = 
-                get_angle_of_vectors(
-                    arc.points[0], 
-                    Point::new(-arc.points[0].x, arc.points[0].y), 
-                    arc.points[1]) + Radians::PI;
-            arc.end_angle = 
-                get_angle_of_vectors(
-                    arc.points[0], 
-                    arc.points[1], 
-                    arc.points[2]) + arc.start_angle;
-
-            // calc the end_angle point        
-            let r = arc.radius;
-            let b = arc.end_angle.0;
-            let point_b = Point::new(r*b.cos(), r*b.sin());
-
-            arc.points[2] = translate_geometry(&[point_b], arc.mid_point, Point::default())[0];
-            
-            if status.is_some() {
-                arc.status = status.unwrap();
-            }
-            (CanvasWidget::Arc(arc.clone()), Radians::into(arc.start_angle))
-        },
-        CanvasWidget::Bezier(bz) => {
-            bz.points = rotate_geometry(&bz.points, &bz.mid_point, &step_degrees, Widget::Bezier);
-            bz.degrees = get_horizontal_angle_of_vector(bz.mid_point, bz.points[1]);
-            if status.is_some() {
-                bz.status = status.unwrap();
-            }
-            (CanvasWidget::Bezier(bz.clone()), bz.degrees)
-        },
-        CanvasWidget::Circle(cir) => {
-            (CanvasWidget::Circle(cir.clone()), 0.0)
-        },
-        CanvasWidget::Ellipse(ell) => {
-            let rads = to_radians(&step_degrees) + ell.rotation.0;
-            ell.rotation = Radians(rads);
-            if status.is_some() {
-                ell.status = status.unwrap();
-            }
-            (CanvasWidget::Ellipse(ell.clone()), to_degrees(&rads))
-        },
-        CanvasWidget::Line(ln) => {
-            ln.points = rotate_geometry(&ln.points, &ln.mid_point, &step_degrees, Widget::Line);
-            ln.degrees = get_horizontal_angle_of_vector(ln.mid_point, ln.points[1]);
-            if status.is_some() {
-                ln.status = status.unwrap();
-            }
-            (CanvasWidget::Line(ln.clone()), ln.degrees)
-        },
-        CanvasWidget::Polygon(pg) => {
-            pg.points = rotate_geometry(&pg.points, &pg.mid_point, &step_degrees, Widget::Polygon);
-            pg.pg_point = rotate_geometry(&[pg.pg_point], &pg.mid_point, &step_degrees, Widget::Line)[0];
-            pg.degrees = get_horizontal_angle_of_vector(pg.mid_point, pg.pg_point);
-            if status.is_some() {
-                pg.status = status.unwrap();
-            }
-            (CanvasWidget::Polygon(pg.clone()), pg.degrees)
-        },
-        CanvasWidget::PolyLine(pl) => {
-            let mut pts = pl.points.clone();
-            pts.push(pl.pl_point);
-            pts = rotate_geometry(&pts, &pl.mid_point, &step_degrees, Widget::PolyLine);
-            pl.pl_point = pts.pop().unwrap();
-            pl.points = pts;
-            pl.degrees = get_horizontal_angle_of_vector(pl.mid_point, pl.pl_point);
-            if status.is_some() {
-                pl.status = status.unwrap();
-            }
-            (CanvasWidget::PolyLine(pl.clone()), pl.degrees)
-        },
-        CanvasWidget::RightTriangle(tr) => {
-            let mut pts = tr.points.clone();
-            pts.push(tr.tr_point);
-            pts = rotate_geometry(&pts, &tr.mid_point, &step_degrees, Widget::RightTriangle);
-            tr.tr_point = pts.pop().unwrap();
-            tr.points = pts;
-            tr.degrees = get_horizontal_angle_of_vector(tr.mid_point, tr.tr_point);
-            if status.is_some() {
-                tr.status = status.unwrap();
-            }
-            (CanvasWidget::RightTriangle(tr.clone()), tr.degrees)
-        },
-        CanvasWidget::FreeHand(fh) => {
-            (CanvasWidget::FreeHand(fh.clone()), 0.0)
-        },
-        CanvasWidget::Text(txt) => {
-            txt.degrees += step_degrees;
-            if status.is_some() {
-                txt.status = status.unwrap();
-            }
-            (CanvasWidget::Text(txt.clone()), txt.degrees)
-        }
-    }
+                    ) -> f32 {
+    let Some(wt) = widget.as_trait_mut() else {
+        return 0.0
+    };
+    let degrees = wt.rotate(step_degrees);
+    wt.set_mode_or_status(None, status);
+    degrees
 }
 
-fn add_keypress(widget: &mut CanvasWidget, modified: Key) -> (Option<CanvasWidget>, bool) {
+// The text is typed at its cursor, shift with the arrows,
+// Home or End selects and the typing replaces the selection.
+fn add_keypress(widget: &mut CanvasWidget, modified: Key, modifiers: Modifiers) -> (Option<CanvasWidget>, bool) {
     let mut escape = false;
     match widget {
         CanvasWidget::Text(txt) => {
+            let shift = modifiers.shift();
+            let content = &mut txt.content;
             match modified.as_ref() {
                 Key::Named(named) => {
                     match named {
                         iced::keyboard::key::Named::Enter => {
-                            txt.content.push('\r');
+                            txt.cursor.insert(content, "\r");
                         },
                         iced::keyboard::key::Named::Tab => {
-                            txt.content.push_str("    ");
+                            txt.cursor.insert(content, "    ");
                         },
                         iced::keyboard::key::Named::Space => {
-                            txt.content.push(' ');
+                            txt.cursor.insert(content, " ");
                         },
                         iced::keyboard::key::Named::Escape => escape = true,
-                        iced::keyboard::key::Named::Backspace => {
-                            if !txt.content.is_empty() {
-                                txt.content.pop();
-                            }
-                        } 
+                        iced::keyboard::key::Named::Backspace => txt.cursor.backspace(content),
+                        iced::keyboard::key::Named::Delete => txt.cursor.delete(content),
+                        iced::keyboard::key::Named::ArrowLeft => txt.cursor.left(shift),
+                        iced::keyboard::key::Named::ArrowRight => txt.cursor.right(content, shift),
+                        iced::keyboard::key::Named::Home => txt.cursor.home(content, shift),
+                        iced::keyboard::key::Named::End => txt.cursor.end(content, shift),
                         _ => ()
                     }
                 },
                 Key::Character(c) => {
-                    txt.content.push_str(c);
+                    txt.cursor.insert(content, c);
                 },
                 Key::Unidentified => (),
             }
@@ -2427,558 +3633,530 @@ fn get_del_key(modified: Key) -> bool {
     }
 }
 
-pub fn set_widget_mode_or_status(widget: CanvasWidget, 
-                    mode: Option<DrawMode>,
-                    status: Option<DrawStatus>,
-                    ) -> CanvasWidget {
-    match widget {
-        CanvasWidget::None => {
-            CanvasWidget::None
-        },
-        CanvasWidget::Arc(mut arc) => {
-            if mode.is_some() {
-                arc.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                arc.status = status.unwrap();
-            }
-            CanvasWidget::Arc(arc)
+// The widget after a drag of the Select tool from the start to the cursor.
+fn get_dragged_widget(widget: CanvasWidget, action: DragAction, start: Point, cursor: Point) -> CanvasWidget {
+    match action {
+        DragAction::Move => translate_widget(widget, cursor - start),
+        DragAction::Point { index, mid_point, other_point } => {
+            update_edited_widget(widget, cursor, index, mid_point, other_point, DrawStatus::Inprogress)
         },
-        CanvasWidget::Bezier(mut bz) => {
-            if mode.is_some() {
-                bz.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                bz.status = status.unwrap();
-            }
-            CanvasWidget::Bezier(bz)
+        DragAction::Rotate => {
+            let mut widget = widget;
+            let Some(center) = widget.as_trait().map(|wt| wt.mid_point()) else {
+                return widget
+            };
+            let angle = |pt: Point| (pt.y - center.y).atan2(pt.x - center.x);
+            update_rotated_widget(&mut widget, to_degrees(&(angle(cursor) - angle(start))), None);
+            widget
         },
-        CanvasWidget::Circle(mut cir) => {
-            if mode.is_some() {
-                cir.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                cir.status = status.unwrap();
-            }
-            CanvasWidget::Circle(cir)
-        },
-        CanvasWidget::Ellipse(mut ell) => {
-            if mode.is_some() {
-                ell.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                ell.status = status.unwrap();
-            }
-            CanvasWidget::Ellipse(ell)
-        },
-        CanvasWidget::Line(mut ln) => {
-            if mode.is_some() {
-                ln.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                ln.status = status.unwrap();
-            }
-            CanvasWidget::Line(ln)
-        },
-        CanvasWidget::PolyLine(mut pl) => {
-            if mode.is_some() {
-                pl.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                pl.status = status.unwrap();
-            }
-            CanvasWidget::PolyLine(pl)
-        },
-        CanvasWidget::Polygon(mut pg) => {
-            if mode.is_some() {
-                pg.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                pg.status = status.unwrap();
-            }
-            CanvasWidget::Polygon(pg)
-        },
-        CanvasWidget::RightTriangle(mut tr) => {
-            if mode.is_some() {
-                tr.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                tr.status = status.unwrap();
-            }
-            CanvasWidget::RightTriangle(tr)
+    }
+}
+
+// Where the point find_closest_point_index picked is.
+fn get_edit_point(widget: &CanvasWidget, index: Option<usize>, mid_point: bool, other_point: bool) -> Option<Point> {
+    let wt = widget.as_trait()?;
+    if mid_point {
+        return Some(wt.mid_point())
+    }
+    wt.handle_point(index, other_point)
+}
+
+// The points an edit moves and the other point named the way
+// find_closest_point_index does, a rotation or radius handle.
+fn get_edit_handles(widget: &CanvasWidget) -> (Vec<Point>, Option<Point>) {
+    widget.as_trait().map_or((vec![], None), |wt| wt.edit_handles())
+}
+
+// Squares at the points, a dot at the mid point, a ring at the
+// other point and the box around the widget, so what a click can
+// grab is seen.
+fn draw_edit_handles(frame: &mut Frame, widget: &CanvasWidget, theme: &Theme) {
+    let Some(wt) = widget.as_trait() else {
+        return
+    };
+    let palette = theme.palette();
+    let stroke = Stroke::default().with_width(1.5).with_color(palette.primary);
+    let bounds = wt.bounds();
+    frame.stroke(
+        &Path::rectangle(bounds.position(), bounds.size()),
+        Stroke::default()
+            .with_width(1.0)
+            .with_color(Color { a: 0.5, ..palette.primary }),
+    );
+
+    let (points, other) = get_edit_handles(widget);
+    let half = HANDLE_SIZE / 2.0;
+    for point in points.iter() {
+        let square = Path::rectangle(*point - Vector::new(half, half), iced::Size::new(HANDLE_SIZE, HANDLE_SIZE));
+        frame.fill(&square, palette.background);
+        frame.stroke(&square, stroke);
+    }
+    frame.fill(&Path::circle(wt.mid_point(), half), palette.primary);
+    if let Some(other) = other {
+        let ring = Path::circle(other, half);
+        frame.fill(&ring, palette.background);
+        frame.stroke(&ring, stroke);
+    }
+    if let CanvasWidget::Arc(arc) = widget {
+        draw_arc_readouts(frame, arc, theme);
+    }
+}
+
+// The start and end angles by their handles and the radius by its own.
+pub(crate) fn draw_arc_readouts(frame: &mut Frame, arc: &Arc, theme: &Theme) {
+    if arc.points.len() < 3 {
+        return
+    }
+    let readouts = [
+        (arc.points[1], format!("{:.1}°", to_degrees(&arc.start_angle.0).rem_euclid(360.0))),
+        (arc.points[2], format!("{:.1}°", to_degrees(&arc.end_angle.0).rem_euclid(360.0))),
+        (arc.radius_point(), format!("r {:.1}", arc.radius)),
+    ];
+    for (position, content) in readouts {
+        frame.fill_text(canvas::Text {
+            position: position + READOUT_OFFSET,
+            color: theme.palette().text,
+            size: 10.0.into(),
+            content,
+            ..canvas::Text::default()
+        });
+    }
+}
+
+// The rotation handle of the Select tool above the widget,
+// None for a widget that can't be turned.
+pub(crate) fn get_rotate_handle(widget: &CanvasWidget) -> Option<Point> {
+    let wt = widget.as_trait()?;
+    wt.degrees()?;
+    let bounds = wt.bounds();
+    Some(Point::new(bounds.center_x(), bounds.y - ROTATE_HANDLE_OFFSET))
+}
+
+// Plus, or the equals key it shares, adds a point and minus takes one away.
+fn get_step_key(modified: &Key) -> Option<i32> {
+    match modified.as_ref() {
+        Key::Character("+") | Key::Character("=") => Some(1),
+        Key::Character("-") => Some(-1),
+        _ => None,
+    }
+}
+
+// A polygon keeps at least 3 sides and a polyline at least one point
+// more than already clicked, neither goes past the side panel limit.
+fn step_poly_points(widget: &mut CanvasWidget, step: i32) -> Option<usize> {
+    let (poly_points, min) = 
+        match widget {
+            CanvasWidget::Polygon(pg) => (&mut pg.poly_points, 3),
+            CanvasWidget::PolyLine(pl) => {
+                let min = (pl.points.len() + 1).max(2);
+                (&mut pl.poly_points, min)
+            },
+            _ => return None,
+        };
+    *poly_points = 
+        poly_points.saturating_add_signed(step as isize)
+            .min(*POLY_POINTS_RANGE.end())
+            .max(min);
+    Some(*poly_points)
+}
+
+fn get_tab_key(modified: Key) -> bool {
+    match modified.as_ref() {
+        Key::Named(named) => {
+            matches!(named, iced::keyboard::key::Named::Tab)
         },
-        CanvasWidget::FreeHand(mut fh) => {
-            if mode.is_some() {
-                fh.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                fh.status = status.unwrap();
-            }
-            CanvasWidget::FreeHand(fh)
+        _ => false,
+    }
+}
+
+fn get_escape_key(modified: Key) -> bool {
+    match modified.as_ref() {
+        Key::Named(named) => {
+            matches!(named, iced::keyboard::key::Named::Escape)
         },
-        CanvasWidget::Text(mut txt) => {
-            if mode.is_some() {
-                txt.draw_mode = mode.unwrap();
-            }
-            if status.is_some() {
-                txt.status = status.unwrap();
-            }
-            CanvasWidget::Text(txt)
-        }
+        _ => false,
+    }
+}
+
+pub fn set_widget_mode_or_status(mut widget: CanvasWidget, 
+                    mode: Option<DrawMode>,
+                    status: Option<DrawStatus>,
+                    ) -> CanvasWidget {
+    if let Some(wt) = widget.as_trait_mut() {
+        wt.set_mode_or_status(mode, status);
     }
+    widget
 }
 
 // Adds a cursor position to the points then determines 
 // if finish by returning the widget and the boolean
-fn set_widget_point(widget: &CanvasWidget, cursor: Point) -> (CanvasWidget, bool) {
-    match widget {
-        CanvasWidget::None => (CanvasWidget::None, true),
-        CanvasWidget::Arc(arc) => {
-            let mut arc = arc.clone();
-            arc.points.push(cursor);
+pub(crate) fn set_widget_point(widget: &mut CanvasWidget, 
+                    cursor: Point, 
+                    pressure_curve: &PressureCurve,
+                    ) -> bool {
+    widget.as_trait_mut().is_none_or(|wt| wt.set_point(cursor, pressure_curve))
+}
 
-            let finished = match arc.points.len() {
-                1 => {
-                    arc.mid_point = arc.points[0];
-                    false
-                },
-                2 => {
-                    arc.radius = arc.points[0].distance(arc.points[1]);
-                    arc.start_angle = 
-                        get_angle_of_vectors(
-                            arc.points[0], 
-                            Point::new(-arc.points[0].x, arc.points[0].y), 
-                            arc.points[1]) + Radians::PI;
-                    false
-                },
-                3 => {
-                    arc.end_angle = 
-                        get_angle_of_vectors(
-                            arc.points[0], 
-                            arc.points[1], 
-                            cursor) + arc.start_angle;
-                    // calc the end_angle point        
-                    let r = arc.radius;
-                    let b = arc.end_angle.0;
-                    let point_b = Point::new(r*b.cos(), r*b.sin());
-                    arc.points[2] = translate_geometry(&[point_b], arc.mid_point, Point::default())[0];
-                    true
-                },
-                _ => false
-            };
+// A filled shape holding the cursor is found before the closest
+// outline, None when no outline is within the grab radius.
+fn find_closest_widget(candidates: Vec<&CanvasWidget>, 
+                        cursor: Point,
+                        grab_radius: f32) 
+                        -> Option<CanvasWidget> {
+    find_widgets_at(candidates, cursor, grab_radius).first().copied().cloned()
+}
 
-            (CanvasWidget::Arc(arc), finished)
-        },
-        CanvasWidget::Bezier(bezier) => {
-            let mut bz = bezier.clone();
-            let mut finished = false;
-            bz.points.push(cursor);
+// The widgets the cursor is inside of or near, the ones holding
+// it first, then by the distance to the outline.  The id keeps the
+// order the same between calls when the distances are equal.
+fn find_widgets_at(candidates: Vec<&CanvasWidget>, 
+                    cursor: Point,
+                    grab_radius: f32) 
+                    -> Vec<&CanvasWidget> {
+    let mut hits = vec![];
+    for cw in candidates {
+        let Some(wt) = cw.as_trait() else {
+            continue
+        };
+        let inside = wt.contains(cursor);
+        let distance = wt.distance_to(cursor);
+        if inside || distance <= grab_radius {
+            hits.push(((!inside, distance, wt.id()), cw));
+        }
+    }
+    hits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    hits.into_iter().map(|(_, cw)| cw).collect()
+}
 
-            if bz.points.len() == 2 {
-                bz.degrees = get_horizontal_angle_of_vector(bz.points[0], bz.points[1]);
-            }
-            if bz.points.len() == 3 {
-                finished = true;
-            }
-            
-            (CanvasWidget::Bezier(bz), finished)
-        },
-        CanvasWidget::Circle(circle) => {
-            let mut cir = circle.clone();
-            let finished = if cir.center == Point::default() {
-                cir.center = cursor;
-                false
-            } else {
-                cir.radius = cir.center.distance(cursor);
-                cir.circle_point = cursor;
-                true
-            };
-            
-            (CanvasWidget::Circle(cir), finished)
-        },
-        CanvasWidget::Ellipse(ell) => {
-            let mut ell = ell.clone();
-            let finished = if ell.points.is_empty() {
-                ell.points.push(cursor);
-                false
-            } else if ell.points.len() == 1 {
-                let p1 = Point::new(cursor.x, ell.points[0].y);
-                ell.points.push(p1);
-                false
-            } else if ell.points.len() == 2 {
-                let p2 = Point::new(ell.points[0].x, cursor.y);
-                ell.points.push(p2);
-                true
-            } else {
-                false
-            };
-            
-            (CanvasWidget::Ellipse(ell), finished)
-        },
+// Finds the closest widget of the given types by the distance to
+// its geometry rather than its mid point, None when none is within
+// the grab radius so a click on empty canvas edits nothing.
+fn find_closest_widget_of_type(candidates: Vec<&CanvasWidget>,
+                                cursor: Point,
+                                widget_types: &[Widget],
+                                exclude_id: Option<&WidgetId>,
+                                grab_radius: f32,
+                                ) -> Option<CanvasWidget> {
+    candidates.into_iter()
+        .filter(|cw| Some(&get_widget_id(cw)) != exclude_id && widget_types.contains(&get_widget_type(cw)))
+        .map(|cw| (get_distance_to_primitives(&get_widget_primitives(cw), cursor), cw))
+        .filter(|(distance, _)| *distance <= grab_radius)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, cw)| cw.clone())
+}
+
+// Cuts the line or polyline at the intersection with another widget
+// closest to the cursor and removes the side the cursor is on.
+fn trim_widget(widget: &CanvasWidget,
+                curves: &HashMap<WidgetId, CanvasWidget>,
+                cursor: Point,
+                ) -> Option<CanvasWidget> {
+    let id = get_widget_id(widget);
+    let mut others = vec![];
+    for (other_id, other) in curves.iter() {
+        if *other_id != id {
+            others.extend(get_widget_primitives(other));
+        }
+    }
+
+    match widget {
         CanvasWidget::Line(line) => {
             let mut ln = line.clone();
-            ln.points.push(cursor);
-
-            let finished = if ln.points.len() == 2 {
-                ln.mid_point = get_mid_point(ln.points[0], ln.points[1]);
-                true
+            let params: Vec<f32> =
+                get_line_params(ln.points[0], ln.points[1], &others)
+                    .into_iter()
+                    .filter(|t| *t > PARAM_EPSILON && *t < 1.0 - PARAM_EPSILON)
+                    .collect();
+
+            let cursor_param = get_projection_param(ln.points[0], ln.points[1], cursor);
+            let cut_param = get_nearest_param(&params, cursor_param)?;
+            let cut_point = get_point_at_param(ln.points[0], ln.points[1], cut_param);
+
+            if cursor_param < cut_param {
+                ln.points[0] = cut_point;
             } else {
-                false
-            };
-            
-            (CanvasWidget::Line(ln), finished)
+                ln.points[1] = cut_point;
+            }
+            ln.mid_point = get_mid_point(ln.points[0], ln.points[1]);
+            ln.degrees =
+                get_horizontal_angle_of_vector(
+                    ln.points[0],
+                    ln.points[1],
+                );
+            ln.status = DrawStatus::Completed;
+            Some(CanvasWidget::Line(ln))
         },
         CanvasWidget::PolyLine(poly_line) => {
             let mut pl = poly_line.clone();
-            pl.points.push(cursor);
-            let finished = if pl.points.len() == pl.poly_points {
-                pl.mid_point = get_mid_geometry(&pl.points, Widget::PolyLine);
-                true
+            let last_param = (pl.points.len() - 1) as f32;
+
+            // The params along the polyline are the segment index
+            // plus the param along that segment.
+            let mut params = vec![];
+            let mut cursor_param = 0.0;
+            let mut cursor_dist = f32::INFINITY;
+            for (idx, pts) in pl.points.windows(2).enumerate() {
+                for t in get_line_params(pts[0], pts[1], &others) {
+                    if (0.0..=1.0).contains(&t) {
+                        params.push(idx as f32 + t);
+                    }
+                }
+                let dist = get_distance_to_segment(pts[0], pts[1], cursor);
+                if dist < cursor_dist {
+                    cursor_dist = dist;
+                    cursor_param = idx as f32 +
+                        get_projection_param(pts[0], pts[1], cursor).clamp(0.0, 1.0);
+                }
+            }
+            params.retain(|s| *s > PARAM_EPSILON && *s < last_param - PARAM_EPSILON);
+
+            let cut_param = get_nearest_param(&params, cursor_param)?;
+            let idx = (cut_param.floor() as usize).min(pl.points.len() - 2);
+            let cut_point =
+                get_point_at_param(
+                    pl.points[idx],
+                    pl.points[idx+1],
+                    cut_param - idx as f32,
+                );
+
+            if cursor_param < cut_param {
+                let mut pts = vec![cut_point];
+                pts.extend_from_slice(&pl.points[idx+1..]);
+                pl.points = pts;
             } else {
-                false
-            };
-            
-            (CanvasWidget::PolyLine(pl), finished)
+                pl.points.truncate(idx+1);
+                pl.points.push(cut_point);
+            }
+            pl.poly_points = pl.points.len();
+
+            let mid_point =
+                get_mid_geometry(
+                    &pl.points,
+                    Widget::PolyLine
+                );
+            pl.pl_point =
+                translate_geometry(
+                    &[pl.pl_point],
+                    mid_point,
+                    pl.mid_point
+                )[0];
+            pl.mid_point = mid_point;
+            pl.degrees =
+                get_horizontal_angle_of_vector(
+                    pl.mid_point,
+                    pl.pl_point
+                );
+            pl.status = DrawStatus::Completed;
+            Some(CanvasWidget::PolyLine(pl))
         },
-        CanvasWidget::Polygon(polygon) => {
-            let mut pg = polygon.clone();
-            let finished = if pg.mid_point == Point::default() {
-                pg.mid_point = cursor;
-                false
+        _ => None,
+    }
+}
+
+// Lengthens the end of the line closest to the cursor until
+// it meets the boundary widget.
+fn extend_widget(widget: &CanvasWidget,
+                boundary: &CanvasWidget,
+                cursor: Point,
+                ) -> Option<CanvasWidget> {
+    match widget {
+        CanvasWidget::Line(line) => {
+            let mut ln = line.clone();
+            let extend_end = cursor.distance(ln.points[1]) < cursor.distance(ln.points[0]);
+            let (start, end) = if extend_end {
+                (ln.points[0], ln.points[1])
             } else {
-                pg.pg_point = cursor;
-                true
+                (ln.points[1], ln.points[0])
             };
-            if finished {
-                pg.degrees = get_horizontal_angle_of_vector(pg.mid_point, pg.pg_point)
+
+            let param =
+                get_line_params(start, end, &get_widget_primitives(boundary))
+                    .into_iter()
+                    .filter(|t| *t > 1.0 + PARAM_EPSILON)
+                    .min_by(|a, b| a.total_cmp(b))?;
+
+            let new_point = get_point_at_param(start, end, param);
+            if extend_end {
+                ln.points[1] = new_point;
+            } else {
+                ln.points[0] = new_point;
             }
-            (CanvasWidget::Polygon(pg), finished)
+            ln.mid_point = get_mid_point(ln.points[0], ln.points[1]);
+            ln.degrees =
+                get_horizontal_angle_of_vector(
+                    ln.points[0],
+                    ln.points[1],
+                );
+            ln.status = DrawStatus::Completed;
+            Some(CanvasWidget::Line(ln))
         },
-        CanvasWidget::RightTriangle(right_triangle) => {
-            let mut rt = right_triangle.clone();
-            rt.points.push(cursor);
-            if rt.points.len() > 1 {
-            rt.points[1].x = rt.points[0].x;
-            }
-            if rt.points.len() > 2 {
-                rt.points[2].y = rt.points[1].y;
-            }
-            let finished = if rt.points.len() == 3 {
-                // close the triangle
-                rt.points.push(right_triangle.points[0]);
-                rt.mid_point = get_mid_geometry(&rt.points, Widget::RightTriangle);
-                true
-            } else {
-                false
-            };
-            
-            (CanvasWidget::RightTriangle(rt), finished)
+        _ => None,
+    }
+}
+
+// Builds a new line from the reference line and the cursor, either
+// parallel through the cursor or perpendicular from the cursor to the line.
+fn construct_line(mode: DrawMode,
+                line: &Line,
+                cursor: Point,
+                color: Color,
+                width: f32,
+                ) -> Option<CanvasWidget> {
+    let points = match mode {
+        DrawMode::Parallel => {
+            let (p1, p2) = get_parallel_line(line.points[0], line.points[1], cursor);
+            vec![p1, p2]
         },
-        CanvasWidget::FreeHand(fh) => {
-            let mut fh = fh.clone();
-            fh.points.push(cursor);
-            let finished = fh.completed;
-            
-            (CanvasWidget::FreeHand(fh), finished)
+        DrawMode::Perpendicular => {
+            let foot = get_perpendicular_foot(line.points[0], line.points[1], cursor);
+            // the cursor is on the line
+            if foot.distance(cursor) < 1.0 {
+                return None
+            }
+            vec![cursor, foot]
         },
-        CanvasWidget::Text(text) => {
-            let mut txt = text.clone();
-            
-            let finished = if txt.position == Point::default() {
-                txt.position = cursor;
-                false
-            } else {
-                txt.status = DrawStatus::Completed;
-                txt.draw_mode = DrawMode::DrawAll;
-                true
-            };
+        _ => return None,
+    };
+
+    Some(set_widget_mode_or_status(new_line(points, color, width), Some(DrawMode::New), None))
+}
 
-            (CanvasWidget::Text(txt), finished)
+// A completed line from its two points.
+pub fn new_line(points: Vec<Point>, color: Color, width: f32) -> CanvasWidget {
+    let mid_point = get_mid_point(points[0], points[1]);
+    let degrees = 
+        get_horizontal_angle_of_vector(
+            points[0],
+            points[1],
+        );
+
+    CanvasWidget::Line(
+        Line {
+            id: WidgetId::unique(),
+            points,
+            mid_point,
+            color,
+            width,
+            degrees,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
         }
-    }
+    )
 }
 
-fn find_closest_widget(curves: &HashMap<Id, CanvasWidget>, 
-                        text_curves: &HashMap<Id, CanvasWidget>, 
-                        cursor: Point) 
-                        -> Option<CanvasWidget> {
+// The index of the tangent whose line from the point is closest to the cursor.
+fn get_closest_tangent_index(point: Point, tangents: &[Point], cursor: Point) -> usize {
     let mut closest = f32::INFINITY;
-    let mut closest_id = None;
-    for (id, cw) in curves.iter() {
-        let distance: f32 = get_distance_to_mid_point(cw, cursor);
-        if distance < closest {
-            closest = distance;
-            closest_id = Some(id);
-        }
-    }
-    let mut text_id = false;
-    for(id, text) in text_curves.iter() {
-        let distance: f32 = get_distance_to_mid_point(text, cursor);
-        if distance < closest {
-            closest = distance;
-            closest_id = Some(id);
-            text_id = true;
-        }
-    }
-  
-    let dc_opt = 
-        if text_id {
-            match closest_id {
-                Some(id) => text_curves.get(id).cloned(),
-                None => None,
-            }
-        } else {
-            match closest_id {
-                Some(id) => curves.get(id).cloned(),
-                None => None,
-            }
-        };
-        
-    dc_opt
-    
+    let mut closest_index = 0;
+    for (index, tangent) in tangents.iter().enumerate() {
+        let dist = get_distance_to_segment(point, *tangent, cursor);
+        if dist < closest {
+            closest = dist;
+            closest_index = index;
+        }
+    }
+    closest_index
+}
+
+fn get_nearest_param(params: &[f32], param: f32) -> Option<f32> {
+    params.iter()
+        .copied()
+        .min_by(|a, b| (a - param).abs().total_cmp(&(b - param).abs()))
 }
 
 // returns a bool if mid_point and an optional usize 
 // if a point in points.
-fn find_closest_point_index(widget: &CanvasWidget,
+pub(crate) fn find_closest_point_index(widget: &CanvasWidget,
                             cursor: Point, 
                             ) -> (Option<usize>, bool, bool) {
+    widget.as_trait().map_or((None, false, false), |wt| wt.closest_point(cursor))
+}
 
-    let mut point_dist: f32 = f32::INFINITY;
-    let mut point_index = 0;
 
-    match widget {
-        CanvasWidget::None => (None, false, false),
-        CanvasWidget::Arc(arc) => {
-            for (idx, point) in arc.points.iter().enumerate() {
-                // skip first point since its a mid_point too.
-                if idx == 0 {
-                    continue;
-                } else {
-                    let dist = cursor.distance(*point);
-                    if  dist < point_dist {
-                        point_index = idx;
-                        point_dist = dist;
-                    }
-                }
-                
-            };
-            
-            let mid_dist = arc.mid_point.distance(cursor);
+pub fn get_widget_id(widget: &CanvasWidget) -> WidgetId {
+    widget.as_trait().map_or(WidgetId::NONE, |wt| wt.id())
+}
 
-            if mid_dist < point_dist {
-                (None, true, false)
-            } else {
-                (Some(point_index), false, false)
-            }
-        },
-        CanvasWidget::Bezier(bezier) => {
-            for (idx, point) in bezier.points.iter().enumerate() {
-                let dist = cursor.distance(*point);
-                if  dist < point_dist {
-                    point_index = idx;
-                    point_dist = dist;
-                }
-            };
-            
-            let mid_dist = bezier.mid_point.distance(cursor);
+pub fn get_widget_type(widget: &CanvasWidget) -> Widget {
+    widget.as_trait().map_or(Widget::None, |wt| wt.widget_type())
+}
 
-            if mid_dist < point_dist {
-                (None, true, false)
-            } else {
-                (Some(point_index), false, false)
-            }
-        },
-        CanvasWidget::Circle(cir) => {
-            let center_dist = cursor.distance(cir.center);
-            let point_dist = cursor.distance(cir.circle_point);
-            if center_dist < point_dist {
-                (None, true, false)
-            } else {
-                (Some(1), false, false)
-            }
-        }
-        CanvasWidget::Ellipse(ell) => {
-            let center_dist = cursor.distance(ell.center);
-            let point_1_dist = cursor.distance(ell.points[1]);
-            let point_2_dist = cursor.distance(ell.points[2]);
-            if center_dist < point_1_dist && center_dist < point_2_dist {
-                (None, true, false)
-            } else if point_1_dist < point_2_dist {
-                (Some(1), false, false)
-            } else {
-                (Some(2), false, false)
-            }
-        }
-        CanvasWidget::Line(line) => {
-            for (idx, point) in line.points.iter().enumerate() {
-                let dist = cursor.distance(*point);
-                if  dist < point_dist {
-                    point_index = idx;
-                    point_dist = dist;
-                }
-            };
-            
-            let mid_dist = cursor.distance(line.mid_point);
+pub fn get_widget_width(widget: &CanvasWidget) -> f32 {
+    widget.as_trait().map_or(0.0, |wt| wt.width())
+}
 
-            if mid_dist < point_dist {
-                (None, true, false)
-            } else {
-                (Some(point_index), false, false)
-            }
-        },
-        CanvasWidget::Polygon(pg) => {
-            let pg_center = cursor.distance(pg.mid_point);
-            let pg_point = cursor.distance(pg.pg_point);
-            if pg_center <= pg_point {
-                (None, true, false)
-            } else {
-                (None, false, true)
-            }
-        },
-        CanvasWidget::PolyLine(pl) => {
-            for (idx, point) in pl.points.iter().enumerate() {
-                let dist = cursor.distance(*point);
-                if  dist < point_dist {
-                    point_index = idx;
-                    point_dist = dist;
-                }
-            };
-            
-            let mid_dist = pl.mid_point.distance(cursor);
-            let pl_pt_dist = pl.pl_point.distance(cursor);
+pub fn get_widget_color(widget: &CanvasWidget) -> Color {
+    widget.as_trait().map_or(Color::TRANSPARENT, |wt| wt.color())
+}
 
-            if point_dist < mid_dist && point_dist < pl_pt_dist {
-                (Some(point_index), false, false)
-            } else if mid_dist < pl_pt_dist {
-                (None, true, false)
-            } else {
-                (None, false, true)
-            }
-        },
-        CanvasWidget::RightTriangle(tr) => {
-            for (idx, point) in tr.points.iter().enumerate() {
-                let dist = cursor.distance(*point);
-                if  dist < point_dist {
-                    point_index = idx;
-                    point_dist = dist;
-                }
-            };
-            
-            let mid_dist = tr.mid_point.distance(cursor);
-            let tr_pt_dist = tr.tr_point.distance(cursor);
+// All of the defining points of the widget.
+pub fn get_widget_points(widget: &CanvasWidget) -> Vec<Point> {
+    widget.as_trait().map_or(vec![], |wt| wt.points())
+}
 
-            if point_dist < mid_dist && point_dist < tr_pt_dist {
-                (Some(point_index), false, false)
-            } else if mid_dist < tr_pt_dist {
-                (None, true, false)
-            } else {
-                (None, false, true)
-            }
-        },
-        CanvasWidget::FreeHand(fh) => {
-            for (idx, point) in fh.points.iter().enumerate() {
-                let dist = cursor.distance(*point);
-                if  dist < point_dist {
-                    point_index = idx;
-                    point_dist = dist;
-                }
-            };
-            (Some(point_index), false, false)
-        },
-        CanvasWidget::Text(_) => {
-            // just using the edit_other_point to indicate the position point
-            (None, false, true)
-        }
+// Top to bottom then left to right by the first point, the
+// hashmap order changes between runs so the exports are sorted.
+pub fn compare_widget_positions(a: &CanvasWidget, b: &CanvasWidget) -> Ordering {
+    let a = get_widget_points(a).first().copied().unwrap_or(Point::ORIGIN);
+    let b = get_widget_points(b).first().copied().unwrap_or(Point::ORIGIN);
+    a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+}
+
+// None for CanvasWidget::None, which has nothing drawn.
+pub fn get_widget_bounds(widget: &CanvasWidget) -> Option<Rectangle> {
+    widget.as_trait().map(|wt| wt.bounds())
+}
+
+// Moves every point of the widget by the offset.
+pub fn translate_widget(mut widget: CanvasWidget, offset: Vector) -> CanvasWidget {
+    if let Some(wt) = widget.as_trait_mut() {
+        wt.translate(offset);
     }
-    
+    widget
 }
 
+// Scales the widget about its mid point, with the stroke width when asked.
+pub fn resize_widget(mut widget: CanvasWidget, factor: f32, widths: bool) -> CanvasWidget {
+    if let Some(wt) = widget.as_trait_mut() {
+        wt.scale(wt.mid_point(), factor);
+        if widths {
+            wt.scale_width(factor);
+        }
+    }
+    widget
+}
 
-pub fn get_widget_id(widget: &CanvasWidget) -> Id {
-    match widget {
-        CanvasWidget::None => Id::new("None"),
-        CanvasWidget::Arc(arc) => arc.id.clone(),
-        CanvasWidget::Bezier(bz) => bz.id.clone(),
-        CanvasWidget::Circle(cir) => cir.id.clone(),
-        CanvasWidget::Ellipse(ell) => ell.id.clone(),
-        CanvasWidget::Line(line) => line.id.clone(),
-        CanvasWidget::PolyLine(pl) => pl.id.clone(),
-        CanvasWidget::Polygon(pg) => pg.id.clone(),
-        CanvasWidget::RightTriangle(tr) => tr.id.clone(),
-        CanvasWidget::FreeHand(fh) => fh.id.clone(),
-        CanvasWidget::Text(txt) => txt.id.clone(),
+// Scales the widget about the center, the stroke widths are not changed.
+pub fn scale_widget(mut widget: CanvasWidget, center: Point, factor: f32) -> CanvasWidget {
+    if let Some(wt) = widget.as_trait_mut() {
+        wt.scale(center, factor);
     }
+    widget
 }
 
-fn get_widget_degrees(widget: &CanvasWidget) -> Option<f32> {
-    match widget {
-        CanvasWidget::None => Some(0.0),
-        CanvasWidget::Arc(arc) => Some(Radians::into(arc.start_angle)),
-        CanvasWidget::Bezier(bezier) => Some(bezier.degrees),
-        CanvasWidget::Circle(_circle) => Some(0.0),
-        CanvasWidget::Ellipse(_ell) => Some(0.0),
-        CanvasWidget::Line(line) => Some(line.degrees),
-        CanvasWidget::PolyLine(poly_line) => Some(poly_line.degrees),
-        CanvasWidget::Polygon(polygon) => Some(polygon.degrees),
-        CanvasWidget::RightTriangle(right_triangle) => Some(right_triangle.degrees),
-        CanvasWidget::FreeHand(_) => None,
-        CanvasWidget::Text(txt) => Some(txt.degrees),
+pub fn set_widget_color(mut widget: CanvasWidget, color: Color) -> CanvasWidget {
+    if let Some(wt) = widget.as_trait_mut() {
+        wt.set_color(color);
     }
+    widget
 }
 
-pub fn get_draw_mode_and_status(widget: &CanvasWidget) -> (DrawMode, DrawStatus) {
-    match widget {
-        CanvasWidget::None => (DrawMode::DrawAll, DrawStatus::Completed),
-        CanvasWidget::Arc(arc) => (arc.draw_mode, arc.status),
-        CanvasWidget::Bezier(bz) => (bz.draw_mode, bz.status),
-        CanvasWidget::Circle(cir) => (cir.draw_mode, cir.status),
-        CanvasWidget::Ellipse(ell) => (ell.draw_mode, ell.status),
-        CanvasWidget::Line(ln) => (ln.draw_mode, ln.status),
-        CanvasWidget::PolyLine(pl) => (pl.draw_mode, pl.status),
-        CanvasWidget::Polygon(pg) => (pg.draw_mode, pg.status),
-        CanvasWidget::RightTriangle(tr) => (tr.draw_mode, tr.status),
-        CanvasWidget::FreeHand(fh) => (fh.draw_mode, fh.status),
-        CanvasWidget::Text(txt) => (txt.draw_mode, txt.status),
-    }
-}
-
-fn get_distance_to_mid_point(widget: &CanvasWidget, cursor: Point) -> f32 {
-
-        match &widget {
-            CanvasWidget::None => f32::INFINITY,
-            CanvasWidget::Arc(arc) => {
-                cursor.distance(arc.mid_point)
-            },
-            CanvasWidget::Bezier(bz) => {
-                cursor.distance(bz.mid_point)
-            },
-            CanvasWidget::Circle(cir) => {
-                cursor.distance(cir.center)
-            },
-            CanvasWidget::Ellipse(ell) => {
-                cursor.distance(ell.center)
-            },
-            CanvasWidget::Line(line) => {
-                cursor.distance(line.mid_point)
-            },
-            CanvasWidget::Polygon(pg) => {
-                cursor.distance(pg.mid_point)
-            },
-            CanvasWidget::PolyLine(pl) => {
-                cursor.distance(pl.mid_point)
-            },
-            CanvasWidget::RightTriangle(tr) => {
-                cursor.distance(tr.mid_point)
-            },
-            CanvasWidget::FreeHand(fh) => {
-                cursor.distance(fh.points[0])
-            }
-            CanvasWidget::Text(txt) => {
-                cursor.distance(txt.position)
-            }
-        }
+// The pattern fill, None for the shapes that can't have one.
+pub fn get_widget_hatch(widget: &CanvasWidget) -> Option<Hatch> {
+    widget.as_trait().and_then(|wt| wt.hatch())
+}
+
+// False when the widget can't have a hatch.
+pub fn set_widget_hatch(widget: &mut CanvasWidget, hatch: Option<Hatch>) -> bool {
+    widget.as_trait_mut().is_some_and(|wt| wt.set_hatch(hatch))
+}
+
+pub fn get_widget_degrees(widget: &CanvasWidget) -> Option<f32> {
+    widget.as_trait().map_or(Some(0.0), |wt| wt.degrees())
+}
 
+pub fn get_draw_mode_and_status(widget: &CanvasWidget) -> (DrawMode, DrawStatus) {
+    widget.as_trait().map_or((DrawMode::DrawAll, DrawStatus::Completed), |wt| wt.mode_and_status())
 }
 
 pub fn get_mid_geometry(pts: &[Point], curve_type: Widget) -> Point {
@@ -3001,13 +4179,7 @@ pub fn get_mid_geometry(pts: &[Point], curve_type: Widget) -> Point {
             get_mid_point(pts[0], pts[1])
         },
         Widget::PolyLine => {
-
-            let (slope, intercept) = get_linear_regression(pts);
-
-            let (p1, p2) = get_line_from_slope_intercept(pts, slope, intercept);
-
-            get_mid_point(p1, p2)
-
+            get_polyline_center(pts, PolyLineCenter::default())
         },
         Widget::Polygon => {
             // return the center point
@@ -3018,12 +4190,19 @@ pub fn get_mid_geometry(pts: &[Point], curve_type: Widget) -> Point {
             let y = (pts[0].y + pts[1].y + pts[2].y)/3.0;
             Point {x, y}
         },
+        Widget::RoundedRect => {
+            get_mid_point(pts[0], pts[2])
+        },
         Widget::FreeHand => {
             pts[0]
         }
         Widget::Text => {
             pts[0]
         }
+        Widget::Gauge => {
+            // return the center point
+            pts[0]
+        },
         Widget::None => Point::default(),
     }
     
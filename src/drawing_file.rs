@@ -0,0 +1,187 @@
+//! drawing_file
+//! Reads and writes the saved drawing.  It's pretty json by default,
+//! compact json on one line or compact json in gzip for the large
+//! drawings.  A gzip file is found by its first bytes when loading,
+//! whatever its name.  A save over a drawing keeps the old one as
+//! data.json.bak1, the one before as bak2 and so on.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+
+// The first bytes of every gzip file.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveFormat {
+    #[default]
+    Pretty,
+    Compact,
+    Gzip,
+}
+
+impl SaveFormat {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            SaveFormat::Pretty => Some("Pretty".to_string()),
+            SaveFormat::Compact => Some("Compact".to_string()),
+            SaveFormat::Gzip => Some("Gzip".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Compact" => SaveFormat::Compact,
+            "Gzip" => SaveFormat::Gzip,
+            _ => SaveFormat::Pretty,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Pretty".to_string(), "Compact".to_string(), "Gzip".to_string()]
+    }
+
+    // --compact or --gzip on the command line, the last one given.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        args.into_iter()
+            .filter_map(|arg| match arg.as_str() {
+                "--pretty" => Some(SaveFormat::Pretty),
+                "--compact" => Some(SaveFormat::Compact),
+                "--gzip" => Some(SaveFormat::Gzip),
+                _ => None,
+            })
+            .last()
+    }
+
+    // The name offered in the save dialog, data.json.gz for gzip.
+    pub fn file_name(&self, name: &str) -> String {
+        let name = name.strip_suffix(".gz").unwrap_or(name);
+        match self {
+            SaveFormat::Gzip => format!("{}.gz", name),
+            _ => name.to_string(),
+        }
+    }
+}
+
+// A name ending in .gz is gzipped whatever the format.  The newline
+// is in the gzip data, nothing may follow the gzip member.
+pub fn write_drawing(path: &Path, data: &impl Serialize, format: SaveFormat) -> io::Result<()> {
+    let mut w = BufWriter::new(File::create(path)?);
+    let gzip = format == SaveFormat::Gzip || path.extension().is_some_and(|ext| ext == "gz");
+    if gzip {
+        let mut encoder = GzEncoder::new(w, Compression::default());
+        serde_json::to_writer(&mut encoder, data)?;
+        encoder.write_all(b"\n")?;
+        w = encoder.finish()?;
+    } else if format == SaveFormat::Compact {
+        serde_json::to_writer(&mut w, data)?;
+        w.write_all(b"\n")?;
+    } else {
+        serde_json::to_writer_pretty(&mut w, data)?;
+        w.write_all(b"\n")?;
+    }
+    w.flush()
+}
+
+// Moves each backup one up, the last is dropped, and the
+// file becomes bak1.  Nothing is kept with no backups.
+pub fn rotate_backups(path: &Path, backups: usize) -> io::Result<()> {
+    if backups == 0 || !path.exists() {
+        return Ok(())
+    }
+    let last = get_backup_path(path, backups);
+    if last.exists() {
+        fs::remove_file(last)?;
+    }
+    for number in (1..backups).rev() {
+        let backup = get_backup_path(path, number);
+        if backup.exists() {
+            fs::rename(backup, get_backup_path(path, number + 1))?;
+        }
+    }
+    fs::rename(path, get_backup_path(path, 1))
+}
+
+fn get_backup_path(path: &Path, number: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".bak{}", number));
+    PathBuf::from(name)
+}
+
+pub fn read_drawing<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut data = String::new();
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut data)?;
+        return Ok(serde_json::from_str(&data)?)
+    }
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+
+#[test]
+fn test_save_formats() {
+    let data = vec![vec![1.5_f32, 2.0], vec![3.25]];
+    let dir = std::env::temp_dir();
+    for (format, name) in [(SaveFormat::Pretty, "draw_test.json"), (SaveFormat::Compact, "draw_test_compact.json"),
+                            (SaveFormat::Gzip, "draw_test.json"), (SaveFormat::Pretty, "draw_test.json.gz")] {
+        let path = dir.join(name);
+        write_drawing(&path, &data, format).unwrap();
+        let loaded: Vec<Vec<f32>> = read_drawing(&path).unwrap();
+        assert_eq!(data, loaded);
+        let gzipped = fs::read(&path).unwrap().starts_with(&GZIP_MAGIC);
+        assert_eq!(format == SaveFormat::Gzip || name.ends_with(".gz"), gzipped);
+    }
+    assert_eq!("[[1.5,2.0],[3.25]]\n", fs::read_to_string(dir.join("draw_test_compact.json")).unwrap());
+}
+
+#[test]
+fn test_gzip_single_member() {
+    // gzip -t and zcat fail on bytes after the member
+    let path = std::env::temp_dir().join("draw_test_member.json.gz");
+    write_drawing(&path, &vec![1, 2, 3], SaveFormat::Gzip).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    let mut decoder = flate2::bufread::GzDecoder::new(bytes.as_slice());
+    let mut data = String::new();
+    decoder.read_to_string(&mut data).unwrap();
+    assert_eq!("[1,2,3]\n", data);
+    assert!(decoder.into_inner().is_empty());
+}
+
+#[test]
+fn test_save_format_args() {
+    let args = |args: &[&str]| SaveFormat::from_args(args.iter().map(|arg| arg.to_string()));
+    assert_eq!(None, args(&[]));
+    assert_eq!(Some(SaveFormat::Gzip), args(&["--compact", "--gzip"]));
+    assert_eq!("data.json.gz", SaveFormat::Gzip.file_name("data.json"));
+    assert_eq!("data.json", SaveFormat::Compact.file_name("data.json.gz"));
+}
+
+#[test]
+fn test_rotate_backups() {
+    let dir = std::env::temp_dir().join("draw_test_backups");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("data.json");
+    // four saves with two backups keep the two before the last
+    for save in 1..=4 {
+        rotate_backups(&path, 2).unwrap();
+        write_drawing(&path, &save, SaveFormat::Compact).unwrap();
+    }
+    let saved = |path: PathBuf| read_drawing::<i32>(&path).unwrap();
+    assert_eq!(4, saved(path.clone()));
+    assert_eq!(3, saved(dir.join("data.json.bak1")));
+    assert_eq!(2, saved(dir.join("data.json.bak2")));
+    assert!(!get_backup_path(&path, 3).exists());
+
+    // none kept
+    rotate_backups(&path, 0).unwrap();
+    assert!(path.exists());
+}
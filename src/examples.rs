@@ -0,0 +1,39 @@
+//! examples
+//! The sample drawings built into the app, a flowchart, a floor plan
+//! and a freehand sketch.  They open read only so they aren't saved
+//! over by mistake, Duplicate to Edit lets them be changed and saved.
+
+use crate::document::{parse_document, ExportDocument};
+
+
+pub const EXAMPLES: [(&str, &str); 3] = [
+    ("Flowchart", include_str!("../resources/examples/flowchart.json")),
+    ("Floor Plan", include_str!("../resources/examples/floor_plan.json")),
+    ("Freehand Sketch", include_str!("../resources/examples/sketch.json")),
+];
+
+pub fn example_names() -> Vec<String> {
+    EXAMPLES.iter().map(|(name, _)| name.to_string()).collect()
+}
+
+pub fn load_example(name: &str) -> Option<ExportDocument> {
+    let (_, data) = EXAMPLES.iter().find(|(example, _)| *example == name)?;
+    parse_document(data)
+}
+
+
+#[test]
+fn test_examples_load() {
+    use crate::draw_canvas::{CanvasWidget, Widget};
+
+    for name in example_names() {
+        let widgets = load_example(&name).unwrap().widgets;
+        assert!(!widgets.is_empty(), "{name}");
+        for widget in widgets.iter() {
+            assert!(!matches!(crate::app::import_widget(widget), CanvasWidget::None), "{name}");
+        }
+    }
+    let sketch = load_example("Freehand Sketch").unwrap().widgets;
+    assert!(sketch.iter().any(|widget| widget.name == Widget::FreeHand));
+    assert!(load_example("Nothing").is_none());
+}
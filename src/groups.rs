@@ -0,0 +1,102 @@
+//! groups
+//! Widgets grouped to be selected, moved, rotated and scaled as one.
+//! A group is the ids of its widgets, grouping widgets already in a
+//! group takes the whole group in, so the groups never overlap.
+//! Ungroup leaves the widgets where they are.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ids::WidgetId;
+
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Group {
+    pub widget_ids: Vec<WidgetId>,
+}
+
+pub fn find_group(groups: &[Group], id: &WidgetId) -> Option<usize> {
+    groups.iter().position(|group| group.widget_ids.contains(id))
+}
+
+// The ids with the rest of the groups they're in, in order,
+// each group taken in once however many of it are selected.
+pub fn expand_to_groups(groups: &[Group], ids: &[WidgetId]) -> Vec<WidgetId> {
+    let group_of: HashMap<WidgetId, usize> =
+        groups.iter()
+            .enumerate()
+            .flat_map(|(index, group)| group.widget_ids.iter().map(move |id| (*id, index)))
+            .collect();
+    let mut expanded_groups = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut expanded: Vec<WidgetId> = vec![];
+    for id in ids.iter() {
+        match group_of.get(id) {
+            Some(index) => {
+                if expanded_groups.insert(*index) {
+                    expanded.extend(groups[*index].widget_ids.iter().filter(|member| seen.insert(**member)));
+                }
+            },
+            None => {
+                if seen.insert(*id) {
+                    expanded.push(*id);
+                }
+            },
+        }
+    }
+    expanded
+}
+
+// A new group of the widgets and the groups they're in,
+// false for less than two widgets.
+pub fn group_widgets(groups: &mut Vec<Group>, ids: &[WidgetId]) -> bool {
+    let widget_ids = expand_to_groups(groups, ids);
+    if widget_ids.len() < 2 {
+        return false
+    }
+    let members: HashSet<&WidgetId> = widget_ids.iter().collect();
+    groups.retain(|group| !group.widget_ids.iter().any(|id| members.contains(id)));
+    groups.push(Group { widget_ids });
+    true
+}
+
+// Removes the groups of the widgets, false if none was grouped.
+pub fn ungroup_widgets(groups: &mut Vec<Group>, ids: &[WidgetId]) -> bool {
+    let count = groups.len();
+    groups.retain(|group| !group.widget_ids.iter().any(|id| ids.contains(id)));
+    groups.len() != count
+}
+
+// The deleted widgets leave their groups, a group
+// of one is no longer a group.
+pub fn remove_from_groups(groups: &mut Vec<Group>, ids: &[WidgetId]) {
+    for group in groups.iter_mut() {
+        group.widget_ids.retain(|id| !ids.contains(id));
+    }
+    groups.retain(|group| group.widget_ids.len() > 1);
+}
+
+
+#[test]
+fn test_groups() {
+    let ids: Vec<WidgetId> = (0..5).map(|_| WidgetId::unique()).collect();
+    let mut groups = vec![];
+    assert!(!group_widgets(&mut groups, &ids[0..1]));
+    assert!(group_widgets(&mut groups, &ids[0..2]));
+    assert_eq!(ids[0..2].to_vec(), expand_to_groups(&groups, &ids[1..2]));
+
+    // a group grouped again with another widget takes it in
+    assert!(group_widgets(&mut groups, &[ids[1], ids[2]]));
+    assert_eq!(1, groups.len());
+    assert_eq!(Some(0), find_group(&groups, &ids[2]));
+    assert_eq!(vec![ids[3]], expand_to_groups(&groups, &ids[3..4]));
+
+    remove_from_groups(&mut groups, &[ids[0]]);
+    assert_eq!(2, groups[0].widget_ids.len());
+    remove_from_groups(&mut groups, &[ids[1]]);
+    assert!(groups.is_empty());
+
+    assert!(group_widgets(&mut groups, &ids[3..5]));
+    assert!(!ungroup_widgets(&mut groups, &ids[0..1]));
+    assert!(ungroup_widgets(&mut groups, &ids[4..5]));
+    assert!(groups.is_empty());
+}
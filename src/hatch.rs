@@ -0,0 +1,271 @@
+//! hatch
+//! Pattern fills for the closed shapes, lines at an angle, cross hatch
+//! or dots, for the section drawings.  The pattern, spacing and angle
+//! are saved with the shape, the lines clipped to its outline are made
+//! when it is drawn or exported.  The lines are on a grid from the
+//! canvas corner so the hatch of shapes side by side lines up.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::ops::RangeInclusive;
+
+use iced::widget::canvas::{Frame, Path, Stroke};
+use iced::{Color, Point, Vector};
+use serde::{Deserialize, Serialize};
+
+use crate::draw_canvas::CanvasWidget;
+use crate::helpers::to_radians;
+use crate::ids::WidgetId;
+
+
+// the tag of the hatch lines in older drawings
+pub const HATCH_TAG: &str = "hatch";
+
+pub const SPACING_RANGE: RangeInclusive<f32> = 2.0..=50.0;
+pub const DEFAULT_SPACING: f32 = 8.0;
+pub const ANGLE_RANGE: RangeInclusive<f32> = 0.0..=180.0;
+pub const DEFAULT_ANGLE: f32 = 45.0;
+pub const HATCH_WIDTH: f32 = 1.0;
+pub const DOT_RADIUS: f32 = 1.5;
+const CIRCLE_SEGMENTS: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HatchPattern {
+    #[default]
+    Lines,
+    CrossHatch,
+    Dots,
+}
+
+impl HatchPattern {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            HatchPattern::Lines => Some("Hatch Lines".to_string()),
+            HatchPattern::CrossHatch => Some("Cross Hatch".to_string()),
+            HatchPattern::Dots => Some("Dots".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Cross Hatch" => HatchPattern::CrossHatch,
+            "Dots" => HatchPattern::Dots,
+            _ => HatchPattern::Lines,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Hatch Lines".to_string(), "Cross Hatch".to_string(), "Dots".to_string()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hatch {
+    pub pattern: HatchPattern,
+    pub spacing: f32,
+    // degrees from the horizontal
+    pub angle: f32,
+}
+
+// The lines and dots of a hatch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HatchMarks {
+    pub lines: Vec<(Point, Point)>,
+    pub dots: Vec<Point>,
+}
+
+// A circle as a closed outline.
+pub fn get_circle_outline(center: Point, radius: f32) -> Vec<Point> {
+    (0..=CIRCLE_SEGMENTS)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f32 / CIRCLE_SEGMENTS as f32;
+            center + Vector::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+// The outline with the first point last again, None when it
+// has too few points to hold anything.
+fn close_outline(mut outline: Vec<Point>) -> Option<Vec<Point>> {
+    if outline.len() < 3 {
+        return None
+    }
+    if outline.first() != outline.last() {
+        outline.push(outline[0]);
+    }
+    Some(outline)
+}
+
+// The outline of a closed shape, None for the open ones,
+// an open polyline or arc, lines and text.
+pub fn get_closed_outline(widget: &CanvasWidget) -> Option<Vec<Point>> {
+    widget.as_trait()
+        .and_then(|wt| wt.closed_outline())
+        .and_then(close_outline)
+}
+
+fn rotate(point: Point, (sin, cos): (f32, f32)) -> Point {
+    Point::new(point.x * cos - point.y * sin, point.x * sin + point.y * cos)
+}
+
+// The outline is turned so the hatch runs across, each row of the grid
+// has the spans inside the outline, crossings paired even-odd.
+fn get_spans(outline: &[Point], spacing: f32, radians: f32) -> Vec<(f32, Vec<(f32, f32)>)> {
+    let turned: Vec<Point> = outline.iter().map(|point| rotate(*point, (-radians).sin_cos())).collect();
+    let min_y = turned.iter().map(|point| point.y).fold(f32::INFINITY, f32::min);
+    let max_y = turned.iter().map(|point| point.y).fold(f32::NEG_INFINITY, f32::max);
+    let mut rows = vec![];
+    let mut row = (min_y / spacing).ceil() as i32;
+    while row as f32 * spacing < max_y {
+        let y = row as f32 * spacing;
+        let mut crossings: Vec<f32> =
+            turned.windows(2)
+                .filter(|side| (side[0].y <= y) != (side[1].y <= y))
+                .map(|side| side[0].x + (y - side[0].y) * (side[1].x - side[0].x) / (side[1].y - side[0].y))
+                .collect();
+        crossings.sort_by(f32::total_cmp);
+        let spans: Vec<(f32, f32)> =
+            crossings.chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .filter(|(left, right)| right - left > f32::EPSILON)
+                .collect();
+        rows.push((y, spans));
+        row += 1;
+    }
+    rows
+}
+
+// The hatch lines inside the outline at the angle in degrees.
+pub fn get_hatch_lines(outline: &[Point], spacing: f32, angle: f32) -> Vec<(Point, Point)> {
+    let radians = to_radians(&angle);
+    get_spans(outline, spacing, radians).into_iter()
+        .flat_map(|(y, spans)| {
+            spans.into_iter().map(move |(left, right)| {
+                (rotate(Point::new(left, y), radians.sin_cos()), rotate(Point::new(right, y), radians.sin_cos()))
+            })
+        })
+        .collect()
+}
+
+// The dots of the grid inside the outline, the rows at the angle.
+pub fn get_hatch_dots(outline: &[Point], spacing: f32, angle: f32) -> Vec<Point> {
+    let radians = to_radians(&angle);
+    let mut dots = vec![];
+    for (y, spans) in get_spans(outline, spacing, radians) {
+        for (left, right) in spans {
+            let mut column = (left / spacing).ceil() as i32;
+            while column as f32 * spacing < right {
+                dots.push(rotate(Point::new(column as f32 * spacing, y), radians.sin_cos()));
+                column += 1;
+            }
+        }
+    }
+    dots
+}
+
+// The hatch lines and dots of a shape, made from its outline as it
+// is now so they follow an edit, none without a hatch or an outline.
+pub fn get_hatch_marks(hatch: Option<Hatch>, outline: Option<Vec<Point>>) -> HatchMarks {
+    let (Some(hatch), Some(outline)) = (hatch, outline.and_then(close_outline)) else {
+        return HatchMarks::default()
+    };
+    match hatch.pattern {
+        HatchPattern::Lines => {
+            HatchMarks { lines: get_hatch_lines(&outline, hatch.spacing, hatch.angle), dots: vec![] }
+        },
+        HatchPattern::CrossHatch => {
+            let mut lines = get_hatch_lines(&outline, hatch.spacing, hatch.angle);
+            lines.extend(get_hatch_lines(&outline, hatch.spacing, hatch.angle + 90.0));
+            HatchMarks { lines, dots: vec![] }
+        },
+        HatchPattern::Dots => {
+            HatchMarks { lines: vec![], dots: get_hatch_dots(&outline, hatch.spacing, hatch.angle) }
+        },
+    }
+}
+
+pub fn get_widget_hatch_marks(widget: &CanvasWidget) -> HatchMarks {
+    widget.as_trait()
+        .map(|wt| get_hatch_marks(wt.hatch(), wt.closed_outline()))
+        .unwrap_or_default()
+}
+
+// The lines are one path and the dots another so a
+// hatch is two draws however many lines it has.
+pub fn draw_hatch(frame: &mut Frame, marks: &HatchMarks, color: Color) {
+    if !marks.lines.is_empty() {
+        let path = Path::new(|p| {
+            for (start, end) in marks.lines.iter() {
+                p.move_to(*start);
+                p.line_to(*end);
+            }
+        });
+        frame.stroke(&path, Stroke::default().with_width(HATCH_WIDTH).with_color(color));
+    }
+    if !marks.dots.is_empty() {
+        let path = Path::new(|p| {
+            for center in marks.dots.iter() {
+                p.circle(*center, DOT_RADIUS);
+            }
+        });
+        frame.fill(&path, color);
+    }
+}
+
+// The hatch lines of the drawings saved before the hatch was
+// part of the shape, Remove Hatch deletes them.
+pub fn is_hatch(tags: &HashMap<WidgetId, Vec<String>>, id: &WidgetId) -> bool {
+    tags.get(id).is_some_and(|widget_tags| widget_tags.iter().any(|tag| tag == HATCH_TAG))
+}
+
+
+#[test]
+fn test_hatch() {
+    // a 100 x 40 square outline from 0, 0
+    let square = [Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 40.0),
+        Point::new(0.0, 40.0), Point::new(0.0, 0.0)];
+    let lines = get_hatch_lines(&square, 10.0, 0.0);
+    // the rows at 0, 10, 20 and 30, the bottom edge is outside
+    assert_eq!(4, lines.len());
+    for (start, end) in lines.iter() {
+        assert!(start.x.abs() < 0.01 && (end.x - 100.0).abs() < 0.01);
+    }
+    let upright = get_hatch_lines(&square, 10.0, 90.0);
+    assert_eq!(10, upright.len());
+    assert!(upright.iter().all(|(start, end)| (start.x - end.x).abs() < 0.01 && start.distance(*end) > 39.9));
+    // a slanted line stays inside the outline
+    for (start, end) in get_hatch_lines(&square, 10.0, 45.0) {
+        for point in [start, end] {
+            assert!((-0.01..=100.01).contains(&point.x) && (-0.01..=40.01).contains(&point.y));
+        }
+    }
+    assert_eq!(40, get_hatch_dots(&square, 10.0, 0.0).len());
+
+    // a concave outline, the rows beside the notch are cut in two
+    let notched = [Point::new(0.0, 0.0), Point::new(30.0, 0.0), Point::new(30.0, 15.0), Point::new(60.0, 15.0),
+        Point::new(60.0, 0.0), Point::new(90.0, 0.0), Point::new(90.0, 30.0), Point::new(0.0, 30.0), Point::new(0.0, 0.0)];
+    let lines = get_hatch_lines(&notched, 10.0, 0.0);
+    assert_eq!(5, lines.len());
+    assert_eq!(4, lines.iter().filter(|(start, _)| start.y < 15.0).count());
+
+    use crate::draw_canvas::{set_widget_hatch, translate_widget, Circle};
+    use crate::test_widgets::circle;
+
+    let hatch = Hatch { pattern: HatchPattern::Lines, spacing: 10.0, angle: 0.0 };
+    let mut circle = CanvasWidget::Circle(Circle { hatch: Some(hatch), ..circle(Point::new(55.0, 55.0), 20.0) });
+    assert_eq!(4, get_widget_hatch_marks(&circle).lines.len());
+    assert!(set_widget_hatch(&mut circle, Some(Hatch { pattern: HatchPattern::CrossHatch, ..hatch })));
+    assert_eq!(8, get_widget_hatch_marks(&circle).lines.len());
+    // the hatch follows the moved circle
+    let mut circle = translate_widget(circle, Vector::new(3.0, 3.0));
+    for (start, end) in get_widget_hatch_marks(&circle).lines {
+        for point in [start, end] {
+            assert!((point.distance(Point::new(58.0, 58.0)) - 20.0).abs() < 0.5);
+        }
+    }
+    set_widget_hatch(&mut circle, Some(Hatch { pattern: HatchPattern::Dots, ..hatch }));
+    let marks = get_widget_hatch_marks(&circle);
+    assert!(marks.lines.is_empty() && !marks.dots.is_empty());
+    set_widget_hatch(&mut circle, None);
+    assert_eq!(HatchMarks::default(), get_widget_hatch_marks(&circle));
+}
@@ -3,12 +3,12 @@
 use std::f32::consts::PI;
 
 
-use iced::{alignment, Point, Radians};
+use iced::{alignment, Point, Radians, Vector};
 // use iced::{Font, Pixels};
 // use iced::widget::text::Shaping;
 // use iced::advanced::graphics::text::cosmic_text::{self, LayoutLine};
 // use iced::advanced::graphics::text;
-use crate::draw_canvas::{HTextAlignment, VTextAlignment, Widget};
+use crate::draw_canvas::{HTextAlignment, PolyLineCenter, VTextAlignment, Widget};
 
 
 pub fn build_polygon(mid_point: Point, pg_point: Point, poly_points: usize, mut degrees: f32) -> Vec<Point> {
@@ -33,57 +33,82 @@ pub fn get_mid_point(pt1: Point, pt2: Point) -> Point {
     Point {x: (pt1.x + pt2.x) / 2.0, y: (pt1.y + pt2.y) / 2.0 }
 }
 
-pub fn get_linear_regression(points: &[Point]) -> (f32, f32) {
-    let mut sx: f64 = 0.0;
-    let mut sy: f64 = 0.0;
-    let mut sxx: f64 = 0.0;
-    let mut sxy: f64 = 0.0;
-
+// The centroid weighs each segment by its length, so an L or a
+// vertical polyline is centered on its lines.  The bounds center
+// is the middle of the box around the points.
+pub fn get_polyline_center(points: &[Point], center: PolyLineCenter) -> Point {
+    if points.is_empty() {
+        return Point::ORIGIN
+    }
+    let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
     for point in points.iter() {
-        sx += point.x as f64;
-        sy += point.y as f64;
-        sxx += point.x as f64 * point.x as f64;
-        sxy += point.x as f64 * point.y as f64;
+        min_x = min_x.min(point.x);
+        min_y = min_y.min(point.y);
+        max_x = max_x.max(point.x);
+        max_y = max_y.max(point.y);
+    }
+    let bounds_center = Point::new((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    if center == PolyLineCenter::BoundsCenter {
+        return bounds_center
     }
 
-    let n = points.len() as f64;
-    let beta = (n*sxy-sx*sy) / (n*sxx - sx*sx);
-    let alpha = (1.0/n * sy) - (beta*1.0/n*sx);
-
-    (beta as f32, alpha as f32)
-
+    let mut length = 0.0;
+    let (mut x, mut y) = (0.0, 0.0);
+    for segment in points.windows(2) {
+        let segment_length = segment[0].distance(segment[1]);
+        let mid_point = get_mid_point(segment[0], segment[1]);
+        x += mid_point.x * segment_length;
+        y += mid_point.y * segment_length;
+        length += segment_length;
+    }
+    if length == 0.0 {
+        return bounds_center
+    }
+    Point::new(x / length, y / length)
 }
 
-pub fn get_line_from_slope_intercept(points: &[Point], 
-                                slope: f32, 
-                                intercept: f32,
-                                ) -> (Point, Point) {
-
-    let mut small_x = 1_000_000_f32;
-    let mut large_x = 0.0;
-    let mut small_y = 1_000_000_f32;
-    let mut large_y = 0.0;
-
-    for point in points.iter() {
-        if point.x < small_x {
-            small_x = point.x;
-        }
-        if point.x > large_x {
-            large_x = point.x;
+// Squares the three clicks into a right triangle, the right angle
+// going to the corner closest to one.  The last click is moved onto
+// the perpendicular, or for a corner at the last click, onto the
+// circle over the other two.  The corner is returned in the middle.
+pub fn square_right_triangle(clicks: [Point; 3]) -> [Point; 3] {
+    let cosine = |corner: Point, a: Point, b: Point| {
+        let (u, v) = (a - corner, b - corner);
+        let lengths = (u.x.hypot(u.y)) * (v.x.hypot(v.y));
+        if lengths == 0.0 {
+            return 1.0
         }
-        if point.y < small_y {
-            small_y = point.y;
+        ((u.x * v.x + u.y * v.y) / lengths).abs()
+    };
+    // the cursor onto the line through the corner square to the leg
+    let perpendicular = |corner: Point, leg: Point, cursor: Point| {
+        let leg = leg - corner;
+        let length = leg.x.hypot(leg.y);
+        if length == 0.0 {
+            return cursor
         }
-        if point.y > large_y {
-            large_y = point.y;
+        let normal = Vector::new(-leg.y / length, leg.x / length);
+        let along = (cursor.x - corner.x) * normal.x + (cursor.y - corner.y) * normal.y;
+        corner + normal * along
+    };
+
+    let [p0, p1, p2] = clicks;
+    let cosines = [cosine(p0, p1, p2), cosine(p1, p0, p2), cosine(p2, p0, p1)];
+    if cosines[0] < cosines[1] && cosines[0] < cosines[2] {
+        [p1, p0, perpendicular(p0, p1, p2)]
+    } else if cosines[2] < cosines[1] {
+        let center = get_mid_point(p0, p1);
+        let radius = p0.distance(p1) / 2.0;
+        let out = p2 - center;
+        let length = out.x.hypot(out.y);
+        if length == 0.0 {
+            return clicks
         }
+        [p0, center + out * (radius / length), p1]
+    } else {
+        [p0, p1, perpendicular(p1, p0, p2)]
     }
- 
-    let ys = slope*small_x + intercept;
-    let yl = slope*large_x + intercept; 
-    
-    (Point{x: small_x, y: ys}, Point{x: large_x, y: yl})  
-
 }
 
 pub fn translate_geometry(pts: &[Point], 
@@ -101,6 +126,13 @@ pub fn translate_geometry(pts: &[Point],
     new_pts
 }
 
+// Each point moved away from or toward the center by the factor.
+pub fn scale_geometry(pts: &[Point], center: Point, factor: f32) -> Vec<Point> {
+    pts.iter()
+        .map(|pt| Point::new(center.x + (pt.x - center.x) * factor, center.y + (pt.y - center.y) * factor))
+        .collect()
+}
+
 // The degrees are adjusted based on how degrees where calulated for each widget.
 pub fn rotate_geometry(
                     points: &[Point], 
@@ -174,6 +206,109 @@ pub fn get_angle_of_vectors(center: Point, p1: Point, p2: Point) -> Radians {
     Radians::from(new_angle)
 }
 
+// A line with the same direction and length as p1 -> p2 
+// with its mid point at the through point.
+pub fn get_parallel_line(p1: Point, p2: Point, through: Point) -> (Point, Point) {
+    let pts = 
+        translate_geometry(
+            &[p1, p2], 
+            through, 
+            get_mid_point(p1, p2),
+        );
+    (pts[0], pts[1])
+}
+
+// The point on the infinite line p1 -> p2 closest to the point.
+pub fn get_perpendicular_foot(p1: Point, p2: Point, point: Point) -> Point {
+    let dx = p2.x - p1.x;
+    let dy = p2.y - p1.y;
+    let len_sq = dx * dx + dy * dy;
+    if len_sq == 0.0 {
+        return p1
+    }
+    let t = ((point.x - p1.x) * dx + (point.y - p1.y) * dy) / len_sq;
+    Point::new(p1.x + t * dx, p1.y + t * dy)
+}
+
+// The points where the tangents from the point touch the circle,
+// none if the point is inside the circle.
+pub fn get_tangent_points(center: Point, radius: f32, point: Point) -> Vec<Point> {
+    let dist = center.distance(point);
+    if dist <= radius {
+        return vec![]
+    }
+    let angle = (point.y - center.y).atan2(point.x - center.x);
+    let offset = (radius / dist).acos();
+    vec![
+        Point::new(center.x + radius * (angle + offset).cos(), center.y + radius * (angle + offset).sin()),
+        Point::new(center.x + radius * (angle - offset).cos(), center.y + radius * (angle - offset).sin()),
+    ]
+}
+
+// Lazy brush, the brush only moves when the cursor is further
+// than the radius away and then trails the cursor by the radius.
+pub fn get_lazy_point(brush: Point, cursor: Point, radius: f32) -> Option<Point> {
+    let dist = brush.distance(cursor);
+    if dist <= radius {
+        return None
+    }
+    let t = (dist - radius) / dist;
+    Some(Point::new(brush.x + (cursor.x - brush.x) * t, brush.y + (cursor.y - brush.y) * t))
+}
+
+// The bezier control points of the Catmull-Rom curve from p1 to p2,
+// p0 and p3 are the points before and after.
+pub fn get_catmull_rom_controls(p0: Point, p1: Point, p2: Point, p3: Point) -> (Point, Point) {
+    (Point::new(p1.x + (p2.x - p0.x) / 6.0, p1.y + (p2.y - p0.y) / 6.0),
+    Point::new(p2.x - (p3.x - p1.x) / 6.0, p2.y - (p3.y - p1.y) / 6.0))
+}
+
+// Ramer-Douglas-Peucker, the indexes of the points kept when the
+// points closer than the tolerance to the simplified line are dropped.
+pub fn simplify_points(points: &[Point], tolerance: f32) -> Vec<usize> {
+    if points.len() < 3 {
+        return (0..points.len()).collect()
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let (mut farthest, mut farthest_dist) = (first, 0.0);
+        for index in first + 1..last {
+            let foot = get_perpendicular_foot(points[first], points[last], points[index]);
+            let dist = foot.distance(points[index]);
+            if dist > farthest_dist {
+                farthest = index;
+                farthest_dist = dist;
+            }
+        }
+        if farthest_dist > tolerance {
+            keep[farthest] = true;
+            ranges.push((first, farthest));
+            ranges.push((farthest, last));
+        }
+    }
+    (0..points.len()).filter(|index| keep[*index]).collect()
+}
+
+// The gauge scale starts at the lower left and goes
+// clockwise over the top to the lower right.
+pub const GAUGE_START_DEGREES: f32 = 135.0;
+pub const GAUGE_SWEEP_DEGREES: f32 = 270.0;
+
+// The angle in radians of the value on the gauge scale,
+// values outside of min and max stop at the ends.
+pub fn get_gauge_angle(min: f32, max: f32, value: f32) -> f32 {
+    let fraction = 
+        if max == min {
+            0.0
+        } else {
+            ((value - min) / (max - min)).clamp(0.0, 1.0)
+        };
+    to_radians(&(GAUGE_START_DEGREES + GAUGE_SWEEP_DEGREES * fraction))
+}
+
 pub fn to_degrees(radians_f32: &f32) -> f32 {
     radians_f32 * 180.0/PI
 }
@@ -182,6 +317,12 @@ pub fn to_radians(degrees: &f32) -> f32 {
     degrees * PI/180.0
 }
 
+// Rounded in f64 so six places of a large coordinate hold.
+pub fn round_to(value: f32, places: u8) -> f32 {
+    let factor = 10_f64.powi(places as i32);
+    ((value as f64 * factor).round() / factor) as f32
+}
+
 pub fn iced_h_text_alignment(align: HTextAlignment) -> alignment::Horizontal {
     match align {
         HTextAlignment::Left => alignment::Horizontal::Left,
@@ -222,39 +363,29 @@ pub fn iced_v_text_alignment(align: VTextAlignment) -> alignment::Vertical {
 
 
 #[test]
-fn test_get_linear_regression() {
-    let points: Vec<Point>= 
-    vec![
-    Point::new(1.47, 52.21),
-    Point::new(1.50, 53.12),
-    Point::new(1.52, 54.48),
-    Point::new(1.55, 55.84),
-    Point::new(1.57, 57.20),
-    Point::new(1.60, 58.57),
-    Point::new(1.63, 59.93),
-    Point::new(1.65, 61.29),
-    Point::new(1.68, 63.11),
-    Point::new(1.70, 64.47),
-    Point::new(1.73, 66.28),
-    Point::new(1.75, 68.10),
-    Point::new(1.78, 69.92),
-    Point::new(1.80, 72.19),
-    Point::new(1.83, 74.46),
-    ];
-
-    let (slope, intercept) = get_linear_regression(&points);
-
-    assert_eq!(61.27219, slope);
-    assert_eq!(-39.06196, intercept);
-
+fn test_get_polyline_center() {
+    // an L, the long side pulls the centroid to it
+    let points = vec![Point::new(0.0, 0.0), Point::new(0.0, 30.0), Point::new(10.0, 30.0)];
+    assert_eq!(Point::new(1.25, 18.75), get_polyline_center(&points, PolyLineCenter::Centroid));
+    assert_eq!(Point::new(5.0, 15.0), get_polyline_center(&points, PolyLineCenter::BoundsCenter));
+
+    // a vertical line has no regression, it is still centered
+    let points = vec![Point::new(5.0, 0.0), Point::new(5.0, 20.0), Point::new(5.0, 40.0)];
+    assert_eq!(Point::new(5.0, 20.0), get_polyline_center(&points, PolyLineCenter::Centroid));
+    assert_eq!(Point::new(5.0, 5.0), get_polyline_center(&[Point::new(5.0, 5.0); 2], PolyLineCenter::Centroid));
 }
 
 #[test]
-fn test_get_line_from_slope_intercept() {
-    let points = vec![Point::new(0.0, 100.0), Point::new(30.0, 30.0), Point::new(25.0, 50.0)];
-    let (slope, intercept) = get_linear_regression(&points);
-    let line_points = get_line_from_slope_intercept(&points, slope, intercept);
-    println!("{:?} {:?}, {:?}",slope, intercept, line_points );
+fn test_square_right_triangle() {
+    // the corner at the second click, the third is squared to the first leg
+    let pts = square_right_triangle([Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(12.0, -8.0)]);
+    assert_eq!([Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(10.0, -8.0)], pts);
+    // at the first click
+    let pts = square_right_triangle([Point::new(0.0, 0.0), Point::new(0.0, 10.0), Point::new(5.0, 1.0)]);
+    assert_eq!([Point::new(0.0, 10.0), Point::new(0.0, 0.0), Point::new(5.0, 0.0)], pts);
+    // at the last click, it is put on the circle over the first two
+    let pts = square_right_triangle([Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(5.0, 8.0)]);
+    assert_eq!([Point::new(0.0, 0.0), Point::new(5.0, 5.0), Point::new(10.0, 0.0)], pts);
 }
 
 #[test]
@@ -289,4 +420,66 @@ fn test_rotate_geometry() {
     }
 }
 
+#[test]
+fn test_get_perpendicular_foot() {
+    let foot = get_perpendicular_foot(Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(4.0, 7.0));
+    assert_eq!(Point::new(4.0, 0.0), foot);
+
+    let (p1, p2) = get_parallel_line(Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(5.0, 5.0));
+    assert_eq!((Point::new(0.0, 5.0), Point::new(10.0, 5.0)), (p1, p2));
+}
+
+#[test]
+fn test_get_tangent_points() {
+    let center = Point::new(0.0, 0.0);
+    assert!(get_tangent_points(center, 10.0, Point::new(5.0, 0.0)).is_empty());
+
+    let point = Point::new(20.0, 0.0);
+    let tangents = get_tangent_points(center, 10.0, point);
+    assert_eq!(2, tangents.len());
+    for tangent in tangents {
+        // radius and tangent line are perpendicular
+        let dot = tangent.x * (point.x - tangent.x) + tangent.y * (point.y - tangent.y);
+        assert!(dot.abs() < 1.0e-3);
+    }
+}
+
+#[test]
+fn test_get_lazy_point() {
+    let brush = Point::new(0.0, 0.0);
+    assert_eq!(None, get_lazy_point(brush, Point::new(5.0, 0.0), 10.0));
+    assert_eq!(Some(Point::new(5.0, 0.0)), get_lazy_point(brush, Point::new(15.0, 0.0), 10.0));
+}
+
+#[test]
+fn test_get_gauge_angle() {
+    assert!((get_gauge_angle(0.0, 100.0, 0.0) - to_radians(&135.0)).abs() < 1.0e-5);
+    assert!((get_gauge_angle(0.0, 100.0, 50.0) - to_radians(&270.0)).abs() < 1.0e-5);
+    assert!((get_gauge_angle(0.0, 100.0, 150.0) - to_radians(&405.0)).abs() < 1.0e-5);
+    assert!((get_gauge_angle(5.0, 5.0, 10.0) - to_radians(&135.0)).abs() < 1.0e-5);
+}
+
+#[test]
+fn test_simplify_points() {
+    let points = [Point::new(0.0, 0.0), Point::new(5.0, 0.1), Point::new(10.0, 0.0),
+                    Point::new(10.0, 10.0), Point::new(10.1, 20.0)];
+    assert_eq!(vec![0, 2, 4], simplify_points(&points, 0.5));
+    assert_eq!(vec![0, 1, 2, 3, 4], simplify_points(&points, 0.01));
+}
 
+#[test]
+fn test_get_catmull_rom_controls() {
+    // evenly spaced points on a line give controls on the line
+    let (c1, c2) = 
+        get_catmull_rom_controls(Point::new(0.0, 0.0), Point::new(3.0, 0.0), 
+            Point::new(6.0, 0.0), Point::new(9.0, 0.0));
+    assert_eq!(Point::new(4.0, 0.0), c1);
+    assert_eq!(Point::new(5.0, 0.0), c2);
+}
+
+#[test]
+fn test_scale_geometry() {
+    let center = Point::new(10.0, 10.0);
+    let pts = scale_geometry(&[Point::new(20.0, 10.0), Point::new(10.0, 0.0)], center, 1.5);
+    assert_eq!(vec![Point::new(25.0, 10.0), Point::new(10.0, -5.0)], pts);
+}
@@ -0,0 +1,33 @@
+//! ids
+//! The small copyable ids the widgets, tags, selection
+//! and instances refer to the widgets by.  The widgets stay
+//! in the map of their layer keyed by the id, not in one
+//! arena for the drawing, so a widget moved to another layer
+//! or loaded again keeps the id everything refers to it by.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WidgetId(u64);
+
+impl WidgetId {
+    // The id of CanvasWidget::None, never given out.
+    pub const NONE: WidgetId = WidgetId(0);
+
+    // A new id each time, like the iced WidgetId::unique.
+    pub fn unique() -> Self {
+        WidgetId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+
+#[test]
+fn test_unique() {
+    let first = WidgetId::unique();
+    let second = first;
+    assert_eq!(first, second);
+    assert_ne!(first, WidgetId::unique());
+}
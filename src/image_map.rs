@@ -0,0 +1,468 @@
+//! image_map
+//! Exports the drawing as an svg with an id on each element or
+//! as an html image map, so annotated drawings can be clickable
+//! on a web page.  The coordinates are the canvas coordinates
+//! so the html map matches a screenshot of the canvas.
+//!
+//! The tag name:xxx sets the element id, otherwise the component
+//! name is used.  The tag link:url makes the element a link.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use iced::{alignment, Color, Point};
+
+use crate::components::ComponentInstance;
+use crate::data_export::get_bounds;
+use crate::descriptions::describe_color;
+use crate::draw_canvas::{compare_widget_positions, get_widget_bounds, get_widget_color, Arc, ArcClosure, CanvasWidget, RoundedRect};
+use crate::hatch::{get_widget_hatch_marks, DOT_RADIUS, HATCH_WIDTH};
+use crate::helpers::{get_gauge_angle, to_degrees, to_radians, GAUGE_START_DEGREES, GAUGE_SWEEP_DEGREES};
+use crate::ids::WidgetId;
+use crate::intersections::get_rounded_rect_points;
+use crate::layers::{exportable_widgets, Layer};
+use crate::text_style::TextStyle;
+
+
+pub const NAME_TAG: &str = "name:";
+pub const LINK_TAG: &str = "link:";
+
+// Space around the drawing in the svg.
+const MARGIN: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    Svg,
+    Html,
+}
+
+impl MapFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MapFormat::Svg => "svg",
+            MapFormat::Html => "html",
+        }
+    }
+}
+
+// A widget with its element id and link.
+struct MapItem<'a> {
+    widget: &'a CanvasWidget,
+    id: String,
+    link: Option<String>,
+}
+
+pub fn export_image_map(curves: &HashMap<WidgetId, CanvasWidget>,
+                        instances: &[ComponentInstance],
+                        tags: &HashMap<WidgetId, Vec<String>>,
+                        background: Color,
+                        format: MapFormat,
+                        precision: u8,
+                        image: &str,
+                        ) -> String {
+    let items = get_map_items(curves, instances, tags);
+    match format {
+        MapFormat::Svg => items_to_svg(&items, Some(background), get_size(&items), precision as usize),
+        MapFormat::Html => items_to_html(&items, image),
+    }
+}
+
+// An svg for each layer checked for export with its file name, the
+// bottom layer first.  They all have the size of the whole drawing so
+// they line up when stacked, and no background so the layers below
+// show through.
+pub fn export_layer_svgs(layers: &[Layer],
+                        instances: &[ComponentInstance],
+                        tags: &HashMap<WidgetId, Vec<String>>,
+                        precision: u8,
+                        ) -> Vec<(String, String)> {
+    let layer_items: Vec<(usize, &Layer, Vec<MapItem>)> = 
+        layers.iter()
+            .enumerate()
+            .filter(|(_, layer)| layer.export)
+            .map(|(index, layer)| (index, layer, get_map_items(exportable_widgets([layer], tags), instances, tags)))
+            .collect();
+    let size = 
+        layer_items.iter()
+            .map(|(_, _, items)| get_size(items))
+            .fold((0.0_f32, 0.0_f32), |(width, height), size| (width.max(size.0), height.max(size.1)));
+    layer_items.iter()
+        .map(|(index, layer, items)| {
+            let file_name = format!("drawing-{}-{}.svg", index + 1, get_element_id(&layer.name));
+            (file_name, items_to_svg(items, None, size, precision as usize))
+        })
+        .collect()
+}
+
+fn get_map_items<'a>(curves: impl IntoIterator<Item = (&'a WidgetId, &'a CanvasWidget)>,
+                    instances: &[ComponentInstance],
+                    tags: &HashMap<WidgetId, Vec<String>>,
+                    ) -> Vec<MapItem<'a>> {
+    // text last so it is on top like on the canvas
+    let (mut texts, mut widgets): (Vec<(&WidgetId, &CanvasWidget)>, Vec<_>) =
+        curves.into_iter()
+            .filter(|(_, widget)| !matches!(widget, CanvasWidget::None))
+            .partition(|(_, widget)| matches!(widget, CanvasWidget::Text(_)));
+    widgets.sort_by(|a, b| compare_widget_positions(a.1, b.1));
+    texts.sort_by(|a, b| compare_widget_positions(a.1, b.1));
+    widgets.append(&mut texts);
+
+    let no_tags = vec![];
+    let mut used_ids: Vec<String> = vec![];
+    let mut items = vec![];
+    for (index, (id, widget)) in widgets.into_iter().enumerate() {
+        let widget_tags = tags.get(id).unwrap_or(&no_tags);
+        let name =
+            widget_tags.iter()
+                .find_map(|tag| tag.strip_prefix(NAME_TAG))
+                .map(|name| name.to_string())
+                .or_else(|| instances.iter()
+                    .find(|inst| inst.widget_ids.contains(id))
+                    .map(|inst| inst.name.clone()))
+                .unwrap_or_else(|| format!("widget-{}", index + 1));
+        let mut element_id = get_element_id(&name);
+        // the ids need to be unique
+        let mut count = 1;
+        while used_ids.contains(&element_id) {
+            count += 1;
+            element_id = format!("{}-{}", get_element_id(&name), count);
+        }
+        used_ids.push(element_id.clone());
+        let link =
+            widget_tags.iter()
+                .find_map(|tag| tag.strip_prefix(LINK_TAG))
+                .map(|link| link.to_string());
+        items.push(MapItem { widget, id: element_id, link });
+    }
+    items
+}
+
+// The coordinates have dp decimal places.
+fn items_to_svg(items: &[MapItem], background: Option<Color>, (width, height): (f32, f32), dp: usize) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.0} {:.0}\">\n",
+        width, height, width, height);
+    if let Some(background) = background {
+        svg.push_str(&format!("  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n", svg_color(background)));
+    }
+    for item in items.iter() {
+        let element = widget_to_svg(item.widget, &item.id, dp) + &hatch_to_svg(item.widget, dp);
+        if element.is_empty() {
+            continue
+        }
+        match &item.link {
+            Some(link) => {
+                svg.push_str(&format!("  <a href=\"{}\">\n    {}\n  </a>\n", escape_xml(link), element));
+            },
+            None => {
+                svg.push_str(&format!("  {}\n", element));
+            },
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Only the linked widgets are areas over the image, the svg of the
+// drawing.  The first area that contains the click is used so the
+// text comes first.
+fn items_to_html(items: &[MapItem], image: &str) -> String {
+    let mut areas = vec![];
+    for item in items.iter().rev() {
+        let Some(link) = &item.link else {
+            continue
+        };
+        let (shape, coords) = get_area(item.widget);
+        if coords.is_empty() {
+            continue
+        }
+        let coords: Vec<String> = coords.iter().map(|c| format!("{:.0}", c)).collect();
+        areas.push(format!("    <area id=\"{}\" shape=\"{}\" coords=\"{}\" href=\"{}\" alt=\"{}\">",
+            item.id, shape, coords.join(","), escape_xml(link), escape_xml(&item.id)));
+    }
+    format!("<img src=\"{}\" usemap=\"#drawing\" alt=\"drawing\">\n<map name=\"drawing\">\n{}\n</map>\n",
+        escape_xml(image), areas.join("\n"))
+}
+
+fn get_area(widget: &CanvasWidget) -> (&'static str, Vec<f32>) {
+    match widget {
+        CanvasWidget::Circle(cir) => ("circle", vec![cir.center.x, cir.center.y, cir.radius]),
+        CanvasWidget::Gauge(gauge) => ("circle", vec![gauge.center.x, gauge.center.y, gauge.radius]),
+        CanvasWidget::Polygon(pg) => ("poly", pg.points.iter().flat_map(|pt| [pt.x, pt.y]).collect()),
+        CanvasWidget::RightTriangle(tr) => ("poly", tr.points.iter().flat_map(|pt| [pt.x, pt.y]).collect()),
+        CanvasWidget::RoundedRect(rr) => ("poly", get_rounded_rect_points(rr).iter().flat_map(|pt| [pt.x, pt.y]).collect()),
+        _ => {
+            match get_widget_bounds(widget) {
+                Some(bounds) => {
+                    ("rect", vec![bounds.x, bounds.y, bounds.x + bounds.width, bounds.y + bounds.height])
+                },
+                None => ("rect", vec![]),
+            }
+        },
+    }
+}
+
+fn widget_to_svg(widget: &CanvasWidget, id: &str, dp: usize) -> String {
+    match widget {
+        CanvasWidget::None => String::new(),
+        CanvasWidget::Arc(arc) => {
+            format!("<path id=\"{}\" d=\"{}{}\" {}/>", id,
+                arc_path(arc.mid_point, arc.radius, arc.start_angle.0, arc.end_angle.0, dp),
+                arc_closure_path(arc, dp),
+                stroke(arc.color, arc.width))
+        },
+        CanvasWidget::Bezier(bz) => {
+            // drawn from points[0] to points[1] with points[2] as the control point
+            format!("<path id=\"{}\" d=\"M {:.dp$} {:.dp$} Q {:.dp$} {:.dp$} {:.dp$} {:.dp$}\" {}/>", id,
+                bz.points[0].x, bz.points[0].y, bz.points[2].x, bz.points[2].y,
+                bz.points[1].x, bz.points[1].y, stroke(bz.color, bz.width))
+        },
+        CanvasWidget::Circle(cir) => {
+            format!("<circle id=\"{}\" cx=\"{:.dp$}\" cy=\"{:.dp$}\" r=\"{:.dp$}\" {}/>", id,
+                cir.center.x, cir.center.y, cir.radius, stroke(cir.color, cir.width))
+        },
+        CanvasWidget::Ellipse(ell) => {
+            format!("<ellipse id=\"{}\" cx=\"{:.dp$}\" cy=\"{:.dp$}\" rx=\"{:.dp$}\" ry=\"{:.dp$}\" transform=\"rotate({:.dp$} {:.dp$} {:.dp$})\" {}/>", id,
+                ell.center.x, ell.center.y, ell.radii.x, ell.radii.y,
+                to_degrees(&ell.rotation.0), ell.center.x, ell.center.y, stroke(ell.color, ell.width))
+        },
+        CanvasWidget::Line(ln) => {
+            format!("<polyline id=\"{}\" points=\"{}\" {}/>", id, points(&ln.points, dp), stroke(ln.color, ln.width))
+        },
+        CanvasWidget::PolyLine(pl) => {
+            let element = if pl.closed { "polygon" } else { "polyline" };
+            format!("<{} id=\"{}\" points=\"{}\" {}/>", element, id, points(&pl.points, dp), stroke(pl.color, pl.width))
+        },
+        CanvasWidget::FreeHand(fh) => {
+            format!("<polyline id=\"{}\" points=\"{}\" stroke-linecap=\"round\" {}/>", id,
+                points(fh.points.as_slice(), dp), stroke(fh.color, fh.width))
+        },
+        CanvasWidget::Polygon(pg) => {
+            format!("<polygon id=\"{}\" points=\"{}\" {}/>", id, points(&pg.points, dp), stroke(pg.color, pg.width))
+        },
+        CanvasWidget::RightTriangle(tr) => {
+            format!("<polygon id=\"{}\" points=\"{}\" {}/>", id, points(&tr.points, dp), stroke(tr.color, tr.width))
+        },
+        CanvasWidget::RoundedRect(rr) => {
+            format!("<path id=\"{}\" d=\"{}\" {}/>", id, rounded_rect_path(rr, dp), stroke(rr.color, rr.width))
+        },
+        CanvasWidget::Gauge(gauge) => {
+            let start = to_radians(&GAUGE_START_DEGREES);
+            let end = to_radians(&(GAUGE_START_DEGREES + GAUGE_SWEEP_DEGREES));
+            let needle = get_gauge_angle(gauge.min, gauge.max, gauge.value);
+            let center = gauge.center;
+            let tip = Point::new(center.x + gauge.radius * 0.75 * needle.cos(),
+                                center.y + gauge.radius * 0.75 * needle.sin());
+            format!("<g id=\"{}\" {}><path d=\"{}\"/><line x1=\"{:.dp$}\" y1=\"{:.dp$}\" x2=\"{:.dp$}\" y2=\"{:.dp$}\"/></g>", id,
+                stroke(gauge.color, gauge.width), arc_path(center, gauge.radius, start, end, dp),
+                center.x, center.y, tip.x, tip.y)
+        },
+        CanvasWidget::Text(txt) => {
+            let style = TextStyle::from_text(txt);
+            let anchor =
+                match txt.horizontal_alignment {
+                    alignment::Horizontal::Left => "start",
+                    alignment::Horizontal::Center => "middle",
+                    alignment::Horizontal::Right => "end",
+                };
+            let baseline =
+                match txt.vertical_alignment {
+                    alignment::Vertical::Top => "hanging",
+                    alignment::Vertical::Center => "middle",
+                    alignment::Vertical::Bottom => "auto",
+                };
+            // each line is a tspan
+            let lines: String =
+                txt.content.split('\r')
+                    .enumerate()
+                    .map(|(i, line)| {
+                        let dy = if i == 0 { 0.0 } else { style.line_height };
+                        format!("<tspan x=\"{:.dp$}\" dy=\"{}em\">{}</tspan>", txt.position.x, dy, escape_xml(line))
+                    })
+                    .collect();
+            let (color, opacity) = get_color_opacity(txt.color);
+            let font = 
+                format!("font-family=\"{}\" font-weight=\"{}\" font-style=\"{}\"", 
+                    style.family.css(), 
+                    if style.bold { "bold" } else { "normal" }, 
+                    if style.italic { "italic" } else { "normal" });
+            format!("<text id=\"{}\" x=\"{:.dp$}\" y=\"{:.dp$}\" font-size=\"{:.1}\" {} text-anchor=\"{}\" dominant-baseline=\"{}\" fill=\"{}\" fill-opacity=\"{:.2}\" transform=\"rotate({:.dp$} {:.dp$} {:.dp$})\">{}</text>",
+                id, txt.position.x, txt.position.y, txt.size.0, font, anchor, baseline, color, opacity,
+                txt.degrees, txt.position.x, txt.position.y, lines)
+        },
+    }
+}
+
+// The hatch lines are one path after the shape, the dots circles.
+fn hatch_to_svg(widget: &CanvasWidget, dp: usize) -> String {
+    let marks = get_widget_hatch_marks(widget);
+    let color = get_widget_color(widget);
+    let mut svg = String::new();
+    if !marks.lines.is_empty() {
+        let d: Vec<String> =
+            marks.lines.iter()
+                .map(|(start, end)| format!("M {:.dp$} {:.dp$} L {:.dp$} {:.dp$}", start.x, start.y, end.x, end.y))
+                .collect();
+        svg.push_str(&format!("<path d=\"{}\" {}/>", d.join(" "), stroke(color, HATCH_WIDTH)));
+    }
+    let (fill, opacity) = get_color_opacity(color);
+    for center in marks.dots {
+        svg.push_str(&format!("<circle cx=\"{:.dp$}\" cy=\"{:.dp$}\" r=\"{:.dp$}\" fill=\"{}\" fill-opacity=\"{:.2}\"/>",
+            center.x, center.y, DOT_RADIUS, fill, opacity));
+    }
+    svg
+}
+
+// The angles are in radians, clockwise on the screen.
+fn arc_path(center: Point, radius: f32, start: f32, end: f32, dp: usize) -> String {
+    let sweep = end - start;
+    let large_arc = if sweep.abs() > PI { 1 } else { 0 };
+    let sweep_flag = if sweep >= 0.0 { 1 } else { 0 };
+    format!("M {:.dp$} {:.dp$} A {:.dp$} {:.dp$} 0 {} {} {:.dp$} {:.dp$}",
+        center.x + radius * start.cos(), center.y + radius * start.sin(),
+        radius, radius, large_arc, sweep_flag,
+        center.x + radius * end.cos(), center.y + radius * end.sin())
+}
+
+// Back across the chord or through the center for a pie.
+fn arc_closure_path(arc: &Arc, dp: usize) -> String {
+    match arc.closure {
+        ArcClosure::Open => String::new(),
+        ArcClosure::Chord => " Z".to_string(),
+        ArcClosure::Pie => format!(" L {:.dp$} {:.dp$} Z", arc.mid_point.x, arc.mid_point.y),
+    }
+}
+
+// A line along each side and an arc around each corner, the rectangle
+// may be turned so it isn't an svg rect.
+fn rounded_rect_path(rr: &RoundedRect, dp: usize) -> String {
+    let unit = |from: Point, to: Point| {
+        let length = from.distance(to);
+        if length > 0.0 { (to - from) * (1.0 / length) } else { to - from }
+    };
+    let mut d = vec![];
+    for i in 0..4 {
+        let corner = rr.points[i];
+        let prev = unit(corner, rr.points[(i + 3) % 4]);
+        let next = unit(corner, rr.points[i + 1]);
+        let start = corner + prev * rr.radius;
+        let end = corner + next * rr.radius;
+        // clockwise on the screen when the path turns right
+        let sweep_flag = if prev.x * next.y - prev.y * next.x < 0.0 { 1 } else { 0 };
+        let command = if i == 0 { "M" } else { "L" };
+        d.push(format!("{} {:.dp$} {:.dp$} A {:.dp$} {:.dp$} 0 0 {} {:.dp$} {:.dp$}",
+            command, start.x, start.y, rr.radius, rr.radius, sweep_flag, end.x, end.y));
+    }
+    d.push("Z".to_string());
+    d.join(" ")
+}
+
+fn points(points: &[Point], dp: usize) -> String {
+    points.iter()
+        .map(|pt| format!("{:.dp$},{:.dp$}", pt.x, pt.y))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn stroke(color: Color, width: f32) -> String {
+    let (color, opacity) = get_color_opacity(color);
+    format!("fill=\"none\" stroke=\"{}\" stroke-opacity=\"{:.2}\" stroke-width=\"{:.2}\"", color, opacity, width)
+}
+
+fn svg_color(color: Color) -> String {
+    get_color_opacity(color).0
+}
+
+// The svg takes the alpha as its own opacity.
+fn get_color_opacity(color: Color) -> (String, f32) {
+    (describe_color(Color { a: 1.0, ..color }), color.a)
+}
+
+// The svg reaches to the far edge of the widgets, the
+// canvas coordinates are kept so it starts at the origin.
+fn get_size(items: &[MapItem]) -> (f32, f32) {
+    let corners: Vec<Point> = 
+        items.iter()
+            .filter_map(|item| get_widget_bounds(item.widget))
+            .map(|bounds| Point::new(bounds.x + bounds.width, bounds.y + bounds.height))
+            .collect();
+    let (_, far) = get_bounds(&corners);
+    (far.x.max(0.0) + MARGIN, far.y.max(0.0) + MARGIN)
+}
+
+// Letters, digits, - and _ are kept.
+fn get_element_id(name: &str) -> String {
+    let id: String =
+        name.trim()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect();
+    // an id can not start with a digit
+    if id.starts_with(|c: char| c.is_ascii_digit()) || id.is_empty() {
+        format!("w-{}", id)
+    } else {
+        id
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+
+#[test]
+fn test_get_element_id() {
+    assert_eq!("main-valve", get_element_id("main valve"));
+    assert_eq!("w-2nd", get_element_id("2nd"));
+}
+
+#[test]
+fn test_arc_path() {
+    let path = arc_path(Point::new(0.0, 0.0), 10.0, 0.0, PI * 1.5, 2);
+    assert_eq!("M 10.00 0.00 A 10.00 10.00 0 1 1 0.00 -10.00", path);
+    // fewer places for a smaller file
+    let path = arc_path(Point::new(0.0, 0.0), 10.0, 0.0, PI * 1.5, 0);
+    assert_eq!("M 10 0 A 10 10 0 1 1 0 -10", path);
+}
+
+#[test]
+fn test_export_layer_svgs() {
+    use crate::layers::NO_EXPORT_TAG;
+    use crate::test_widgets::circle;
+
+    let mut layers = vec![Layer::new("Layer 1".to_string()), Layer::new("Top".to_string()), Layer::new("Notes".to_string())];
+    for (layer, (x, radius)) in layers.iter_mut().zip([(50.0, 10.0), (200.0, 30.0), (0.0, 5.0)]) {
+        let cir = circle(Point::new(x, 100.0), radius);
+        layer.insert(cir.id, CanvasWidget::Circle(cir));
+    }
+    layers[2].export = false;
+    // drawn but not exported
+    let cir = circle(Point::new(500.0, 500.0), 10.0);
+    let id = cir.id;
+    layers[0].insert(id, CanvasWidget::Circle(cir));
+    let tags = HashMap::from([(id, vec![NO_EXPORT_TAG.to_string()])]);
+
+    let svgs = export_layer_svgs(&layers, &[], &tags, 0);
+    assert_eq!(vec!["drawing-1-Layer-1.svg", "drawing-2-Top.svg"],
+        svgs.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>());
+    // the size of both layers together, with no background
+    for (_, svg) in svgs.iter() {
+        assert!(svg.contains("viewBox=\"0 0 251 151\""), "{}", svg);
+        assert!(!svg.contains("<rect"));
+        assert_eq!(1, svg.matches("<circle").count());
+    }
+
+    // the html map is over the svg written with it
+    let tags = HashMap::from([(id, vec![format!("{}https://example.com", LINK_TAG)])]);
+    let html = export_image_map(&layers[0].curves, &[], &tags, Color::WHITE, MapFormat::Html, 0, "drawing.svg");
+    assert!(html.starts_with("<img src=\"drawing.svg\""), "{}", html);
+    assert!(html.contains("shape=\"circle\" coords=\"500,500,10\""), "{}", html);
+}
+
+#[test]
+fn test_escape_xml() {
+    assert_eq!("a &lt;b&gt; &amp; &quot;c&quot;", escape_xml("a <b> & \"c\""));
+}
@@ -0,0 +1,355 @@
+//! intersections
+
+use std::f32::consts::PI;
+
+use iced::Point;
+
+use crate::draw_canvas::{Arc, Bezier, CanvasWidget, Ellipse, Gauge, RoundedRect};
+use crate::helpers::{get_gauge_angle, to_radians, GAUGE_START_DEGREES, GAUGE_SWEEP_DEGREES};
+
+
+// Curved widgets are approximated by this many segments
+// when looking for intersections.
+const CURVE_SEGMENTS: usize = 48;
+
+// Parameters this close to an end point are treated as the end point.
+pub const PARAM_EPSILON: f32 = 1.0e-4;
+
+// How close in pixels the cursor needs to be to snap to a point.
+pub const SNAP_DISTANCE: f32 = 10.0;
+
+// The geometry of a widget broken down into the
+// pieces that intersections can be calculated for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Primitive {
+    Segment(Point, Point),
+    Circle(Point, f32),
+}
+
+pub fn get_widget_primitives(widget: &CanvasWidget) -> Vec<Primitive> {
+    match widget {
+        CanvasWidget::None => vec![],
+        CanvasWidget::Arc(arc) => get_segments(&arc.outline()),
+        CanvasWidget::Bezier(bz) => get_segments(&get_bezier_points(bz)),
+        CanvasWidget::Circle(cir) => {
+            vec![Primitive::Circle(cir.center, cir.radius)]
+        },
+        CanvasWidget::Gauge(gauge) => get_gauge_primitives(gauge),
+        CanvasWidget::Ellipse(ell) => get_segments(&get_ellipse_points(ell)),
+        CanvasWidget::Line(line) => get_segments(&line.points),
+        CanvasWidget::PolyLine(pl) => get_segments(&pl.outline()),
+        // The polygon and right triangle points are already closed.
+        CanvasWidget::Polygon(pg) => get_segments(&pg.points),
+        CanvasWidget::RightTriangle(tr) => get_segments(&tr.points),
+        CanvasWidget::RoundedRect(rr) => get_segments(&get_rounded_rect_points(rr)),
+        CanvasWidget::FreeHand(fh) => get_segments(fh.points.as_slice()),
+        CanvasWidget::Text(_) => vec![],
+    }
+}
+
+pub fn get_arc_points(arc: &Arc) -> Vec<Point> {
+    let sweep = arc.end_angle.0 - arc.start_angle.0;
+    let mut pts = vec![];
+    for i in 0..=CURVE_SEGMENTS {
+        let angle = arc.start_angle.0 + sweep * i as f32 / CURVE_SEGMENTS as f32;
+        pts.push(Point::new(
+            arc.mid_point.x + arc.radius * angle.cos(),
+            arc.mid_point.y + arc.radius * angle.sin(),
+        ));
+    }
+    pts
+}
+
+// The bezier is drawn from points[0] to points[1]
+// with points[2] as the control point.
+pub fn get_bezier_points(bz: &Bezier) -> Vec<Point> {
+    let mut pts = vec![];
+    for i in 0..=CURVE_SEGMENTS {
+        let t = i as f32 / CURVE_SEGMENTS as f32;
+        let mt = 1.0 - t;
+        pts.push(Point::new(
+            mt*mt*bz.points[0].x + 2.0*mt*t*bz.points[2].x + t*t*bz.points[1].x,
+            mt*mt*bz.points[0].y + 2.0*mt*t*bz.points[2].y + t*t*bz.points[1].y,
+        ));
+    }
+    pts
+}
+
+// The scale arc and the needle.
+pub fn get_gauge_primitives(gauge: &Gauge) -> Vec<Primitive> {
+    let start = to_radians(&GAUGE_START_DEGREES);
+    let sweep = to_radians(&GAUGE_SWEEP_DEGREES);
+    let mut pts = vec![];
+    for i in 0..=CURVE_SEGMENTS {
+        let angle = start + sweep * i as f32 / CURVE_SEGMENTS as f32;
+        pts.push(Point::new(
+            gauge.center.x + gauge.radius * angle.cos(),
+            gauge.center.y + gauge.radius * angle.sin(),
+        ));
+    }
+    let mut primitives = get_segments(&pts);
+    let (sin, cos) = get_gauge_angle(gauge.min, gauge.max, gauge.value).sin_cos();
+    let needle = Point::new(
+        gauge.center.x + gauge.radius * 0.75 * cos,
+        gauge.center.y + gauge.radius * 0.75 * sin,
+    );
+    primitives.push(Primitive::Segment(gauge.center, needle));
+    primitives
+}
+
+pub fn get_ellipse_points(ell: &Ellipse) -> Vec<Point> {
+    let (sin, cos) = ell.rotation.0.sin_cos();
+    let mut pts = vec![];
+    for i in 0..=CURVE_SEGMENTS {
+        let angle = 2.0 * PI * i as f32 / CURVE_SEGMENTS as f32;
+        let x = ell.radii.x * angle.cos();
+        let y = ell.radii.y * angle.sin();
+        pts.push(Point::new(
+            ell.center.x + x*cos - y*sin,
+            ell.center.y + x*sin + y*cos,
+        ));
+    }
+    pts
+}
+
+// Each corner is a quarter circle, the sides join them
+// and the first point is repeated to close it.
+pub fn get_rounded_rect_points(rr: &RoundedRect) -> Vec<Point> {
+    let unit = |from: Point, to: Point| {
+        let side = to - from;
+        let length = side.x.hypot(side.y);
+        if length > 0.0 { side * (1.0 / length) } else { side }
+    };
+    let corner_segments = CURVE_SEGMENTS / 4;
+    let mut pts = vec![];
+    for i in 0..4 {
+        let corner = rr.points[i];
+        let prev = unit(corner, rr.points[(i + 3) % 4]);
+        let next = unit(corner, rr.points[i + 1]);
+        let center = corner + (prev + next) * rr.radius;
+        for j in 0..=corner_segments {
+            let angle = PI / 2.0 * j as f32 / corner_segments as f32;
+            pts.push(center - next * (rr.radius * angle.cos()) - prev * (rr.radius * angle.sin()));
+        }
+    }
+    pts.push(pts[0]);
+    pts
+}
+
+pub fn get_segments(points: &[Point]) -> Vec<Primitive> {
+    points.windows(2)
+        .map(|pts| Primitive::Segment(pts[0], pts[1]))
+        .collect()
+}
+
+fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    Point::new(a.x - b.x, a.y - b.y)
+}
+
+pub fn get_point_at_param(p1: Point, p2: Point, t: f32) -> Point {
+    Point::new(p1.x + (p2.x - p1.x) * t, p1.y + (p2.y - p1.y) * t)
+}
+
+// The parameter of the point on the infinite line p1 -> p2
+// closest to the point, 0.0 at p1 and 1.0 at p2.
+pub fn get_projection_param(p1: Point, p2: Point, point: Point) -> f32 {
+    let d = sub(p2, p1);
+    let len_sq = d.x * d.x + d.y * d.y;
+    if len_sq == 0.0 {
+        return 0.0
+    }
+    let w = sub(point, p1);
+    (w.x * d.x + w.y * d.y) / len_sq
+}
+
+pub fn get_distance_to_segment(p1: Point, p2: Point, point: Point) -> f32 {
+    let t = get_projection_param(p1, p2, point).clamp(0.0, 1.0);
+    point.distance(get_point_at_param(p1, p2, t))
+}
+
+pub fn get_distance_to_primitives(primitives: &[Primitive], point: Point) -> f32 {
+    let mut closest = f32::INFINITY;
+    for primitive in primitives.iter() {
+        let dist = match primitive {
+            Primitive::Segment(p1, p2) => get_distance_to_segment(*p1, *p2, point),
+            Primitive::Circle(center, radius) => (center.distance(point) - radius).abs(),
+        };
+        if dist < closest {
+            closest = dist;
+        }
+    }
+    closest
+}
+
+// Returns the parameters (t, u) of the infinite lines a1 -> a2 and b1 -> b2
+// where they cross, None if they are parallel.
+pub fn get_line_line_params(a1: Point, a2: Point, b1: Point, b2: Point) -> Option<(f32, f32)> {
+    let d = sub(a2, a1);
+    let e = sub(b2, b1);
+    let denom = cross(d, e);
+    if denom.abs() < f32::EPSILON {
+        return None
+    }
+    let w = sub(b1, a1);
+    Some((cross(w, e) / denom, cross(w, d) / denom))
+}
+
+// Returns the parameters along the infinite line p1 -> p2
+// where it crosses the circle.
+pub fn get_line_circle_params(p1: Point, p2: Point, center: Point, radius: f32) -> Vec<f32> {
+    let d = sub(p2, p1);
+    let f = sub(p1, center);
+    let a = d.x * d.x + d.y * d.y;
+    if a == 0.0 {
+        return vec![]
+    }
+    let b = 2.0 * (f.x * d.x + f.y * d.y);
+    let c = f.x * f.x + f.y * f.y - radius * radius;
+    let disc = b * b - 4.0 * a * c;
+    if disc < 0.0 {
+        vec![]
+    } else if disc == 0.0 {
+        vec![-b / (2.0 * a)]
+    } else {
+        let sqrt = disc.sqrt();
+        vec![(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+    }
+}
+
+pub fn get_circle_circle_intersections(c1: Point, r1: f32, c2: Point, r2: f32) -> Vec<Point> {
+    let dist = c1.distance(c2);
+    if dist == 0.0 || dist > r1 + r2 || dist < (r1 - r2).abs() {
+        return vec![]
+    }
+    let a = (r1 * r1 - r2 * r2 + dist * dist) / (2.0 * dist);
+    let h = (r1 * r1 - a * a).max(0.0).sqrt();
+    let mid = get_point_at_param(c1, c2, a / dist);
+    let ox = h * (c2.y - c1.y) / dist;
+    let oy = h * (c2.x - c1.x) / dist;
+    if h == 0.0 {
+        vec![mid]
+    } else {
+        vec![Point::new(mid.x + ox, mid.y - oy), Point::new(mid.x - ox, mid.y + oy)]
+    }
+}
+
+fn in_unit_range(t: f32) -> bool {
+    (-PARAM_EPSILON..=1.0 + PARAM_EPSILON).contains(&t)
+}
+
+// Parameters along the infinite line p1 -> p2 where it crosses
+// any of the primitives, which are treated as bounded.
+pub fn get_line_params(p1: Point, p2: Point, primitives: &[Primitive]) -> Vec<f32> {
+    let mut params = vec![];
+    for primitive in primitives.iter() {
+        match primitive {
+            Primitive::Segment(b1, b2) => {
+                if let Some((t, u)) = get_line_line_params(p1, p2, *b1, *b2) {
+                    if in_unit_range(u) {
+                        params.push(t);
+                    }
+                }
+            },
+            Primitive::Circle(center, radius) => {
+                params.extend(get_line_circle_params(p1, p2, *center, *radius));
+            },
+        }
+    }
+    params
+}
+
+pub fn get_primitive_intersections(a: &Primitive, b: &Primitive) -> Vec<Point> {
+    match (a, b) {
+        (Primitive::Segment(a1, a2), Primitive::Segment(b1, b2)) => {
+            match get_line_line_params(*a1, *a2, *b1, *b2) {
+                Some((t, u)) if in_unit_range(t) && in_unit_range(u) => {
+                    vec![get_point_at_param(*a1, *a2, t)]
+                },
+                _ => vec![],
+            }
+        },
+        (Primitive::Segment(p1, p2), Primitive::Circle(center, radius)) |
+        (Primitive::Circle(center, radius), Primitive::Segment(p1, p2)) => {
+            get_line_circle_params(*p1, *p2, *center, *radius)
+                .into_iter()
+                .filter(|t| in_unit_range(*t))
+                .map(|t| get_point_at_param(*p1, *p2, t))
+                .collect()
+        },
+        (Primitive::Circle(c1, r1), Primitive::Circle(c2, r2)) => {
+            get_circle_circle_intersections(*c1, *r1, *c2, *r2)
+        },
+    }
+}
+
+pub fn get_widget_intersections(a: &CanvasWidget, b: &CanvasWidget) -> Vec<Point> {
+    let a_primitives = get_widget_primitives(a);
+    let b_primitives = get_widget_primitives(b);
+    let mut points = vec![];
+    for pa in a_primitives.iter() {
+        for pb in b_primitives.iter() {
+            points.extend(get_primitive_intersections(pa, pb));
+        }
+    }
+    points
+}
+
+// Intersections between every pair of widgets.
+pub fn get_all_intersections(widgets: &[&CanvasWidget]) -> Vec<Point> {
+    let mut points = vec![];
+    for (idx, a) in widgets.iter().enumerate() {
+        for b in widgets.iter().skip(idx + 1) {
+            points.extend(get_widget_intersections(a, b));
+        }
+    }
+    points
+}
+
+pub fn get_closest_point(points: &[Point], cursor: Point, max_distance: f32) -> Option<Point> {
+    points.iter()
+        .copied()
+        .filter(|p| p.distance(cursor) <= max_distance)
+        .min_by(|a, b| a.distance(cursor).total_cmp(&b.distance(cursor)))
+}
+
+
+#[test]
+fn test_line_line_intersection() {
+    let a = Primitive::Segment(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+    let b = Primitive::Segment(Point::new(0.0, 10.0), Point::new(10.0, 0.0));
+    let points = get_primitive_intersections(&a, &b);
+    assert_eq!(vec![Point::new(5.0, 5.0)], points);
+
+    let c = Primitive::Segment(Point::new(20.0, 0.0), Point::new(30.0, 0.0));
+    assert!(get_primitive_intersections(&a, &c).is_empty());
+}
+
+#[test]
+fn test_line_circle_intersection() {
+    let a = Primitive::Segment(Point::new(-20.0, 0.0), Point::new(20.0, 0.0));
+    let b = Primitive::Circle(Point::new(0.0, 0.0), 10.0);
+    let points = get_primitive_intersections(&a, &b);
+    assert_eq!(vec![Point::new(-10.0, 0.0), Point::new(10.0, 0.0)], points);
+}
+
+#[test]
+fn test_circle_circle_intersection() {
+    let points = get_circle_circle_intersections(Point::new(0.0, 0.0), 10.0, Point::new(20.0, 0.0), 10.0);
+    assert_eq!(vec![Point::new(10.0, 0.0)], points);
+
+    let points = get_circle_circle_intersections(Point::new(0.0, 0.0), 10.0, Point::new(12.0, 0.0), 10.0);
+    assert_eq!(2, points.len());
+    assert!((points[0].x - 6.0).abs() < 1.0e-4);
+    assert!((points[0].y.abs() - 8.0).abs() < 1.0e-4);
+}
+
+#[test]
+fn test_get_closest_point() {
+    let points = vec![Point::new(0.0, 0.0), Point::new(5.0, 0.0), Point::new(50.0, 0.0)];
+    assert_eq!(Some(Point::new(5.0, 0.0)), get_closest_point(&points, Point::new(7.0, 0.0), SNAP_DISTANCE));
+    assert_eq!(None, get_closest_point(&points, Point::new(30.0, 0.0), SNAP_DISTANCE));
+}
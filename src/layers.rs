@@ -0,0 +1,163 @@
+//! layers
+//! Each layer holds its own widgets, the texts with the others
+//! in one collection.  The layers are drawn in order, the first
+//! layer is at the bottom.
+
+use std::collections::HashMap;
+
+use crate::draw_canvas::CanvasWidget;
+use crate::ids::WidgetId;
+
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub name: String,
+    pub visible: bool,
+    // included in the exports
+    pub export: bool,
+    pub curves: HashMap<WidgetId, CanvasWidget>,
+}
+
+impl Layer {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            visible: true,
+            export: true,
+            curves: HashMap::new(),
+        }
+    }
+
+    pub fn contains(&self, id: &WidgetId) -> bool {
+        self.curves.contains_key(id)
+    }
+
+    pub fn insert(&mut self, id: WidgetId, widget: CanvasWidget) {
+        if !matches!(widget, CanvasWidget::None) {
+            self.curves.insert(id, widget);
+        }
+    }
+
+    pub fn remove(&mut self, id: &WidgetId) {
+        self.curves.remove(id);
+    }
+
+    // The texts are drawn with a cache each, the others
+    // share the layer's cache.
+    pub fn texts(&self) -> impl Iterator<Item = (&WidgetId, &CanvasWidget)> {
+        self.curves.iter().filter(|(_, widget)| matches!(widget, CanvasWidget::Text(_)))
+    }
+}
+
+// The next unused name like Layer 3.
+pub fn get_new_layer_name(layers: &[Layer]) -> String {
+    let mut number = layers.len() + 1;
+    loop {
+        let name = format!("Layer {}", number);
+        if !layers.iter().any(|layer| layer.name == name) {
+            return name
+        }
+        number += 1;
+    }
+}
+
+// The index of the layer holding the widget.
+pub fn find_layer(layers: &[Layer], id: &WidgetId) -> Option<usize> {
+    layers.iter().position(|layer| layer.contains(id))
+}
+
+// Moves the layer up towards the top or down towards the
+// bottom and returns its new index.
+pub fn move_layer(layers: &mut [Layer], index: usize, up: bool) -> usize {
+    let other =
+        if up {
+            index + 1
+        } else {
+            match index.checked_sub(1) {
+                Some(other) => other,
+                None => return index,
+            }
+        };
+    if other >= layers.len() {
+        return index
+    }
+    layers.swap(index, other);
+    other
+}
+
+// The widgets tagged with it are drawn in the editor but
+// left out of every export, like a layer not checked.
+pub const NO_EXPORT_TAG: &str = "noexport";
+
+pub fn is_excluded(tags: &HashMap<WidgetId, Vec<String>>, id: &WidgetId) -> bool {
+    tags.get(id).is_some_and(|tags| tags.iter().any(|tag| tag == NO_EXPORT_TAG))
+}
+
+// The widgets every export writes, those on the layers checked for
+// export and not tagged noexport, the bottom layer first.
+pub fn exportable_widgets<'a>(layers: impl IntoIterator<Item = &'a Layer>,
+                            tags: &'a HashMap<WidgetId, Vec<String>>,
+                            ) -> impl Iterator<Item = (&'a WidgetId, &'a CanvasWidget)> {
+    layers.into_iter()
+        .filter(|layer| layer.export)
+        .flat_map(|layer| layer.curves.iter())
+        .filter(move |(id, _)| !is_excluded(tags, id))
+}
+
+// The exportable widgets together, for the exports
+// of the whole drawing.
+pub fn merge_exportable(layers: &[Layer], tags: &HashMap<WidgetId, Vec<String>>) -> HashMap<WidgetId, CanvasWidget> {
+    exportable_widgets(layers, tags)
+        .map(|(id, widget)| (*id, widget.clone()))
+        .collect()
+}
+
+// The widgets of the layers together, used when
+// exporting the whole drawing.
+pub fn merge_layers<'a>(layers: impl IntoIterator<Item = &'a Layer>) -> HashMap<WidgetId, CanvasWidget> {
+    let mut curves = HashMap::new();
+    for layer in layers {
+        curves.extend(layer.curves.iter().map(|(id, w)| (*id, w.clone())));
+    }
+    curves
+}
+
+
+#[test]
+fn test_move_layer() {
+    let mut layers = vec![Layer::new("a".to_string()), Layer::new("b".to_string())];
+    assert_eq!(1, move_layer(&mut layers, 0, true));
+    assert_eq!("a", layers[1].name);
+    assert_eq!(1, move_layer(&mut layers, 1, true));
+    assert_eq!(0, move_layer(&mut layers, 1, false));
+    assert_eq!(0, move_layer(&mut layers, 0, false));
+    assert_eq!("a", layers[0].name);
+}
+
+#[test]
+fn test_get_new_layer_name() {
+    let mut layers = vec![Layer::new("Layer 1".to_string()), Layer::new("Layer 3".to_string())];
+    assert_eq!("Layer 4", get_new_layer_name(&layers));
+    layers[1].name = "Other".to_string();
+    assert_eq!("Layer 3", get_new_layer_name(&layers));
+}
+
+#[test]
+fn test_exportable_widgets() {
+    use iced::Point;
+    use crate::draw_canvas::Line;
+    use crate::test_widgets::line;
+
+    let mut layers = vec![Layer::new("a".to_string()), Layer::new("b".to_string())];
+    let ids: Vec<WidgetId> = (0..3).map(|_| WidgetId::unique()).collect();
+    for (index, id) in ids.iter().enumerate() {
+        let line = CanvasWidget::Line(Line { id: *id, ..line(Point::new(0.0, 0.0), Point::new(10.0, 0.0)) });
+        layers[index / 2].insert(*id, line);
+    }
+    let tags = HashMap::from([(ids[1], vec![NO_EXPORT_TAG.to_string()])]);
+    assert_eq!(2, merge_exportable(&layers, &tags).len());
+    // a layer not checked leaves out all of its widgets
+    layers[1].export = false;
+    let curves = merge_exportable(&layers, &tags);
+    assert_eq!(vec![&ids[0]], curves.keys().collect::<Vec<&WidgetId>>());
+}
@@ -0,0 +1,210 @@
+//! legend
+//! A block of swatches and labels generated from the colors
+//! or the tags used in the drawing.  The legend widgets are
+//! tagged legend so they can be found and replaced.
+
+use std::collections::HashMap;
+
+use iced::widget::text::{LineHeight, Shaping};
+use iced::{alignment, Color, Font, Pixels, Point};
+
+use crate::descriptions::describe_color;
+use crate::draw_canvas::{compare_widget_positions, get_widget_color, get_widget_id, get_widget_points,
+    CanvasWidget, DrawMode, DrawStatus, Polygon, Text};
+use crate::hatch::HATCH_TAG;
+use crate::helpers::{build_polygon, get_horizontal_angle_of_vector};
+use crate::ids::WidgetId;
+use crate::image_map::{LINK_TAG, NAME_TAG};
+use crate::layers::NO_EXPORT_TAG;
+use crate::numbering::NUMBER_TAG;
+use crate::title_block::{TITLE_BLOCK_TAG, TITLE_FIELD_TAG};
+use crate::text_edit::TextCursor;
+
+
+pub const LEGEND_TAG: &str = "legend";
+
+const SWATCH_SIZE: f32 = 12.0;
+const ROW_HEIGHT: f32 = 22.0;
+const TEXT_SIZE: f32 = 14.0;
+// Space between the drawing and the legend.
+const LEGEND_MARGIN: f32 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegendSource {
+    Colors,
+    Tags,
+}
+
+// The live data keys, the live feature may not be built.
+const LIVE_KEY: &str = "live:";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendEntry {
+    pub color: Color,
+    pub label: String,
+}
+
+// The distinct colors of the shapes, or each tag with the color
+// of its first shape.  Text and the old legend are skipped.
+pub fn get_legend_entries(curves: &HashMap<WidgetId, CanvasWidget>,
+                        tags: &HashMap<WidgetId, Vec<String>>,
+                        source: LegendSource,
+                        ) -> Vec<LegendEntry> {
+    let no_tags: Vec<String> = vec![];
+    let mut widgets: Vec<(&CanvasWidget, &Vec<String>)> =
+        curves.iter()
+            .filter(|(_, widget)| !matches!(widget, CanvasWidget::None | CanvasWidget::Text(_)))
+            .map(|(id, widget)| (widget, tags.get(id).unwrap_or(&no_tags)))
+            .filter(|(_, widget_tags)| !widget_tags.iter().any(|tag| tag == LEGEND_TAG))
+            .collect();
+    // the hashmap order changes, sort so the legend does not
+    widgets.sort_by(|a, b| compare_widget_positions(a.0, b.0));
+
+    let mut entries: Vec<LegendEntry> = vec![];
+    for (widget, widget_tags) in widgets {
+        let color = get_widget_color(widget);
+        match source {
+            LegendSource::Colors => {
+                if !entries.iter().any(|entry| entry.color == color) {
+                    entries.push(LegendEntry { color, label: describe_color(color) });
+                }
+            },
+            LegendSource::Tags => {
+                for tag in widget_tags.iter().filter(|tag| !is_internal_tag(tag)) {
+                    if !entries.iter().any(|entry| &entry.label == tag) {
+                        entries.push(LegendEntry { color, label: tag.clone() });
+                    }
+                }
+            },
+        }
+    }
+    if source == LegendSource::Tags {
+        entries.sort_by(|a, b| a.label.cmp(&b.label));
+    }
+    entries
+}
+
+// The tags the program sets or reads, the markers and the keys
+// with a value, are not labels.
+fn is_internal_tag(tag: &str) -> bool {
+    [LEGEND_TAG, NO_EXPORT_TAG, HATCH_TAG, TITLE_BLOCK_TAG, NUMBER_TAG].contains(&tag) ||
+        [LIVE_KEY, NAME_TAG, LINK_TAG, TITLE_FIELD_TAG].iter().any(|key| tag.starts_with(key))
+}
+
+// The legend starts to the right of the drawing.
+pub fn get_legend_origin(curves: &HashMap<WidgetId, CanvasWidget>) -> Point {
+    let points: Vec<Point> = curves.values().flat_map(get_widget_points).collect();
+    if points.is_empty() {
+        return Point::new(LEGEND_MARGIN, LEGEND_MARGIN)
+    }
+    let max_x = points.iter().map(|pt| pt.x).fold(f32::NEG_INFINITY, f32::max);
+    let min_y = points.iter().map(|pt| pt.y).fold(f32::INFINITY, f32::min);
+    Point::new(max_x + LEGEND_MARGIN, min_y)
+}
+
+// A square swatch and a label for each entry.
+pub fn build_legend(entries: &[LegendEntry], origin: Point, text_color: Color) -> Vec<CanvasWidget> {
+    let mut widgets = vec![];
+    for (index, entry) in entries.iter().enumerate() {
+        let center = Point::new(origin.x + SWATCH_SIZE / 2.0, origin.y + ROW_HEIGHT * index as f32);
+        widgets.push(new_swatch(center, entry.color));
+        let position = Point::new(origin.x + SWATCH_SIZE * 2.0, center.y);
+        widgets.push(new_label(&entry.label, position, text_color));
+    }
+    widgets
+}
+
+// The ids of the widgets with the legend tag.
+pub fn get_legend_ids(tags: &HashMap<WidgetId, Vec<String>>) -> Vec<WidgetId> {
+    tags.iter()
+        .filter(|(_, widget_tags)| widget_tags.iter().any(|tag| tag == LEGEND_TAG))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+pub fn get_legend_tags(widgets: &[CanvasWidget]) -> HashMap<WidgetId, Vec<String>> {
+    widgets.iter()
+        .map(|widget| (get_widget_id(widget), vec![LEGEND_TAG.to_string()]))
+        .collect()
+}
+
+// A square polygon, the stroke is as wide as
+// the square so it looks filled.
+fn new_swatch(center: Point, color: Color) -> CanvasWidget {
+    let half = SWATCH_SIZE / 4.0;
+    let pg_point = Point::new(center.x + half, center.y + half);
+    let degrees = get_horizontal_angle_of_vector(center, pg_point);
+    CanvasWidget::Polygon(
+        Polygon {
+            id: WidgetId::unique(),
+            points: build_polygon(center, pg_point, 4, degrees),
+            poly_points: 4,
+            mid_point: center,
+            pg_point,
+            color,
+            width: SWATCH_SIZE / 2.0,
+            hatch: None,
+            degrees,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+fn new_label(content: &str, position: Point, color: Color) -> CanvasWidget {
+    CanvasWidget::Text(
+        Text {
+            id: WidgetId::unique(),
+            content: content.to_string(),
+            position,
+            color,
+            size: Pixels(TEXT_SIZE),
+            line_height: LineHeight::Relative(1.2),
+            font: Font::default(),
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Center,
+            shaping: Shaping::Basic,
+            degrees: 0.0,
+            cursor: TextCursor::default(),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+
+#[test]
+fn test_get_legend_entries() {
+    let red = Color::from_rgb(1.0, 0.0, 0.0);
+    let blue = Color::from_rgb(0.0, 0.0, 1.0);
+    let widgets = [new_swatch(Point::new(10.0, 10.0), red),
+                    new_swatch(Point::new(10.0, 50.0), blue),
+                    new_swatch(Point::new(10.0, 90.0), red)];
+    let mut curves = HashMap::new();
+    let mut tags = HashMap::new();
+    for (widget, tag) in widgets.iter().zip(["valve", "pump", "valve"]) {
+        let id = get_widget_id(widget);
+        tags.insert(id, [tag, "live:temp", NO_EXPORT_TAG, HATCH_TAG, "name:inlet", "title:project"]
+            .map(str::to_string).to_vec());
+        curves.insert(id, widget.clone());
+    }
+
+    let entries = get_legend_entries(&curves, &tags, LegendSource::Colors);
+    assert_eq!(vec!["#FF0000", "#0000FF"], entries.iter().map(|e| e.label.as_str()).collect::<Vec<_>>());
+
+    let entries = get_legend_entries(&curves, &tags, LegendSource::Tags);
+    assert_eq!(vec!["pump", "valve"], entries.iter().map(|e| e.label.as_str()).collect::<Vec<_>>());
+    assert_eq!(blue, entries[0].color);
+}
+
+#[test]
+fn test_new_swatch() {
+    // the square is not turned
+    let CanvasWidget::Polygon(pg) = new_swatch(Point::new(10.0, 10.0), Color::BLACK) else {
+        panic!("not a polygon")
+    };
+    for point in pg.points.iter() {
+        assert!(((point.x - 10.0).abs() - SWATCH_SIZE / 4.0).abs() < 0.01);
+        assert!(((point.y - 10.0).abs() - SWATCH_SIZE / 4.0).abs() < 0.01);
+    }
+}
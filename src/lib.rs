@@ -0,0 +1,68 @@
+//! draw
+//! The drawing canvas as a library.  CanvasDraw in app is the whole
+//! application, draw_canvas has the CanvasState, its CanvasWidgets and
+//! the canvas Program, DrawPending, for embedding the canvas alone in
+//! another iced application.
+
+pub mod app;
+pub mod draw_canvas;
+mod colors;
+mod path_builds;
+mod helpers;
+mod intersections;
+mod descriptions;
+mod palettes;
+mod pressure;
+mod settings;
+mod components;
+mod query;
+mod data_export;
+mod svg_import;
+pub mod layers;
+mod legend;
+mod numbering;
+mod image_map;
+mod optimize;
+mod stroke;
+pub mod ids;
+mod widget_trait;
+mod transform;
+mod constraints;
+mod resize;
+mod text_style;
+mod text_edit;
+mod numeric_input;
+mod spatial_index;
+mod render_cache;
+mod canvas_update;
+mod nodes;
+mod object_snap;
+mod preview;
+mod groups;
+mod split_join;
+mod replace_color;
+mod style_presets;
+mod templates;
+mod themes;
+mod document;
+mod title_block;
+mod hatch;
+mod drawing_file;
+mod examples;
+mod rulers;
+#[cfg(test)]
+mod simulate;
+#[cfg(test)]
+mod round_trip;
+#[cfg(test)]
+mod test_widgets;
+#[cfg(feature = "control")]
+mod control;
+#[cfg(feature = "live")]
+mod live;
+#[cfg(feature = "image")]
+mod background;
+
+pub use app::{CanvasDraw, Message};
+pub use canvas_update::CanvasEvent;
+pub use draw_canvas::{CanvasMessage, CanvasState, CanvasWidget, DrawPending};
@@ -0,0 +1,123 @@
+//! live
+//! Updates the widgets tagged live:key from the values received
+//! on a websocket, only built with the live feature.
+//!
+//! The messages are text, either key=value or
+//! {"key": "temp", "value": "21.5"}
+
+use std::thread;
+use std::time::Duration;
+
+use iced::futures::channel::mpsc;
+use iced::futures::executor::block_on;
+use iced::futures::{SinkExt, Stream};
+use iced::{Point, Radians};
+use serde::Deserialize;
+
+use crate::draw_canvas::CanvasWidget;
+use crate::helpers::{to_radians, translate_geometry};
+
+
+pub const LIVE_TAG: &str = "live:";
+
+// Time to wait before connecting again after the socket closes.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LiveValue {
+    pub key: String,
+    pub value: String,
+}
+
+pub fn listen(url: String) -> impl Stream<Item = LiveValue> {
+    iced::stream::channel(100, |output| async move {
+        thread::spawn(move || receive(url, output));
+        // the thread sends the values, keep the stream open
+        std::future::pending::<()>().await
+    })
+}
+
+fn receive(url: String, mut output: mpsc::Sender<LiveValue>) {
+    loop {
+        if let Ok((mut socket, _)) = tungstenite::connect(url.as_str()) {
+            while let Ok(message) = socket.read() {
+                let tungstenite::Message::Text(text) = message else {
+                    continue
+                };
+                if let Some(value) = parse_value(&text) {
+                    // the subscription was dropped
+                    if block_on(output.send(value)).is_err() {
+                        return
+                    }
+                }
+            }
+        }
+        if output.is_closed() {
+            return
+        }
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+fn parse_value(text: &str) -> Option<LiveValue> {
+    let text = text.trim();
+    if text.starts_with('{') {
+        return serde_json::from_str(text).ok()
+    }
+    let (key, value) = text.split_once('=')?;
+    Some(LiveValue { key: key.trim().to_string(), value: value.trim().to_string() })
+}
+
+// The key of the first live tag.
+pub fn get_live_key(tags: &[String]) -> Option<&str> {
+    tags.iter().find_map(|tag| tag.strip_prefix(LIVE_TAG))
+}
+
+// A Text shows the value, a Gauge moves its needle and an Arc
+// uses it as the sweep in degrees from the start angle.
+// Returns true if changed.
+pub fn apply_value(widget: &mut CanvasWidget, value: &str) -> bool {
+    match widget {
+        CanvasWidget::Text(txt) => {
+            txt.content = value.to_string();
+            true
+        },
+        CanvasWidget::Arc(arc) => {
+            let Ok(degrees) = value.parse::<f32>() else {
+                return false
+            };
+            arc.end_angle = arc.start_angle + Radians(to_radians(&degrees));
+            // calc the end_angle point
+            let r = arc.radius;
+            let b = arc.end_angle.0;
+            let point_b = Point::new(r*b.cos(), r*b.sin());
+            if arc.points.len() == 3 {
+                arc.points[2] = translate_geometry(&[point_b], arc.mid_point, Point::default())[0];
+            }
+            true
+        },
+        CanvasWidget::Gauge(gauge) => {
+            let Ok(value) = value.parse::<f32>() else {
+                return false
+            };
+            gauge.value = value;
+            true
+        },
+        _ => false,
+    }
+}
+
+
+#[test]
+fn test_parse_value() {
+    let expected = Some(LiveValue { key: "temp".to_string(), value: "21.5".to_string() });
+    assert_eq!(expected, parse_value("temp = 21.5"));
+    assert_eq!(expected, parse_value(r#"{"key": "temp", "value": "21.5"}"#));
+    assert_eq!(None, parse_value("temp"));
+}
+
+#[test]
+fn test_get_live_key() {
+    let tags = vec!["valve".to_string(), "live:temp".to_string()];
+    assert_eq!(Some("temp"), get_live_key(&tags));
+}
@@ -1,4 +0,0 @@
-
-mod draw_canvas;
-mod path_builds;
-mod helpers;
@@ -0,0 +1,161 @@
+//! nodes
+//! Adding and removing the nodes of a polyline or freehand while it's
+//! edited.  A node is added on the segment where it's clicked so the
+//! shape doesn't change, a removed node joins its neighbours.
+
+use iced::Point;
+
+use crate::draw_canvas::{CanvasWidget, PolyLine};
+use crate::helpers::get_horizontal_angle_of_vector;
+use crate::intersections::{get_distance_to_segment, get_point_at_param, get_projection_param};
+
+
+// A line needs its two ends, a closed shape three corners.
+const MIN_NODES: usize = 2;
+const MIN_CLOSED_NODES: usize = 3;
+
+// Adds a node on the segment within the distance of the
+// point, false when there's none or it isn't a path.
+// A closed polyline's side back to the first point is one
+// of its segments, a node there goes in at the end.
+pub fn insert_node(widget: &mut CanvasWidget, point: Point, distance: f32) -> bool {
+    let points = match widget {
+        CanvasWidget::PolyLine(pl) => pl.outline(),
+        CanvasWidget::FreeHand(fh) => fh.points.to_vec(),
+        _ => return false,
+    };
+    let Some((index, node)) = find_segment(&points, point, distance) else {
+        return false
+    };
+    match widget {
+        CanvasWidget::PolyLine(pl) => {
+            pl.points.insert(index, node);
+            update_polyline(pl);
+        },
+        CanvasWidget::FreeHand(fh) => {
+            fh.points.insert(index, node);
+            // the width between its neighbours
+            if index <= fh.widths.len() && index > 0 {
+                let next = fh.widths.get(index).copied().unwrap_or(fh.widths[index - 1]);
+                fh.widths.insert(index, (fh.widths[index - 1] + next) / 2.0);
+            }
+        },
+        _ => (),
+    }
+    true
+}
+
+// Removes the node within the distance of the point, false when
+// there's none or only the two ends are left.  A closed polyline
+// keeps three so its first and last nodes still close it.
+pub fn remove_node(widget: &mut CanvasWidget, point: Point, distance: f32) -> bool {
+    let (points, min_nodes) = match widget {
+        CanvasWidget::PolyLine(pl) if pl.closed => (pl.points.clone(), MIN_CLOSED_NODES),
+        CanvasWidget::PolyLine(pl) => (pl.points.clone(), MIN_NODES),
+        CanvasWidget::FreeHand(fh) => (fh.points.to_vec(), MIN_NODES),
+        _ => return false,
+    };
+    if points.len() <= min_nodes {
+        return false
+    }
+    let Some(index) = find_node(&points, point, distance) else {
+        return false
+    };
+    match widget {
+        CanvasWidget::PolyLine(pl) => {
+            pl.points.remove(index);
+            update_polyline(pl);
+        },
+        CanvasWidget::FreeHand(fh) => {
+            fh.points.remove(index);
+            if index < fh.widths.len() {
+                fh.widths.remove(index);
+            }
+        },
+        _ => (),
+    }
+    true
+}
+
+// The index the node goes in at and the point on the
+// closest segment.
+fn find_segment(points: &[Point], point: Point, distance: f32) -> Option<(usize, Point)> {
+    points.windows(2)
+        .enumerate()
+        .map(|(index, ends)| (index, get_distance_to_segment(ends[0], ends[1], point)))
+        .filter(|(_, dist)| *dist <= distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| {
+            let (start, end) = (points[index], points[index + 1]);
+            let t = get_projection_param(start, end, point).clamp(0.0, 1.0);
+            (index + 1, get_point_at_param(start, end, t))
+        })
+}
+
+fn find_node(points: &[Point], point: Point, distance: f32) -> Option<usize> {
+    points.iter()
+        .enumerate()
+        .map(|(index, node)| (index, node.distance(point)))
+        .filter(|(_, dist)| *dist <= distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+// The mid point follows the nodes and the
+// degrees are read from the rotation handle.
+pub fn update_polyline(pl: &mut PolyLine) {
+    pl.poly_points = pl.points.len();
+    pl.recenter();
+    pl.degrees = get_horizontal_angle_of_vector(pl.mid_point, pl.pl_point);
+}
+
+
+#[test]
+fn test_polyline_nodes() {
+    use crate::draw_canvas::{DrawMode, DrawStatus};
+    use crate::test_widgets::polyline;
+
+    let mut widget = CanvasWidget::PolyLine(PolyLine {
+        draw_mode: DrawMode::Edit,
+        status: DrawStatus::Inprogress,
+        ..polyline(vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 100.0)], false)
+    });
+    // too far from a segment
+    assert!(!insert_node(&mut widget, Point::new(50.0, 20.0), 5.0));
+    assert!(insert_node(&mut widget, Point::new(50.0, 3.0), 5.0));
+    let CanvasWidget::PolyLine(pl) = &widget else { unreachable!() };
+    assert_eq!(Point::new(50.0, 0.0), pl.points[1]);
+    assert_eq!((4, 4), (pl.points.len(), pl.poly_points));
+    // on the path the centroid stays
+    assert_eq!(Point::new(75.0, 25.0), pl.mid_point);
+
+    assert!(remove_node(&mut widget, Point::new(98.0, 2.0), 5.0));
+    assert!(remove_node(&mut widget, Point::new(100.0, 100.0), 5.0));
+    // the two ends are kept
+    assert!(!remove_node(&mut widget, Point::new(0.0, 0.0), 5.0));
+    let CanvasWidget::PolyLine(pl) = &widget else { unreachable!() };
+    assert_eq!(vec![Point::new(0.0, 0.0), Point::new(50.0, 0.0)], pl.points);
+    assert_eq!(Point::new(25.0, 0.0), pl.mid_point);
+    assert_eq!(2, pl.poly_points);
+}
+
+#[test]
+fn test_closed_polyline_nodes() {
+    use crate::test_widgets::polyline;
+
+    let mut widget = CanvasWidget::PolyLine(
+        polyline(vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 100.0)], true));
+    // on the side from the last corner back to the first
+    assert!(insert_node(&mut widget, Point::new(48.0, 52.0), 5.0));
+    let CanvasWidget::PolyLine(pl) = &widget else { unreachable!() };
+    assert_eq!(Point::new(50.0, 50.0), pl.points[3]);
+    assert_eq!(4, pl.poly_points);
+
+    // the closing side now joins the new first and last nodes
+    assert!(remove_node(&mut widget, Point::new(0.0, 0.0), 5.0));
+    let CanvasWidget::PolyLine(pl) = &widget else { unreachable!() };
+    assert_eq!(vec![Point::new(100.0, 0.0), Point::new(100.0, 100.0), Point::new(50.0, 50.0), Point::new(100.0, 0.0)],
+        pl.outline());
+    // three corners are left to close it
+    assert!(!remove_node(&mut widget, Point::new(50.0, 50.0), 5.0));
+}
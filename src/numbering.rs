@@ -0,0 +1,170 @@
+//! numbering
+//! Labels placed with a click that count up, 1, 2, 3 or A, B, C,
+//! for step by step annotations.  The labels are tagged number
+//! so they can be renumbered in reading order.
+
+use std::collections::HashMap;
+
+use iced::widget::text::{LineHeight, Shaping};
+use iced::{alignment, Color, Font, Pixels, Point};
+
+use crate::draw_canvas::{CanvasWidget, DrawMode, DrawStatus, Text};
+use crate::ids::WidgetId;
+use crate::text_edit::TextCursor;
+
+
+pub const NUMBER_TAG: &str = "number";
+
+const NUMBER_SIZE: f32 = 20.0;
+// Labels closer than this in y are read as one row.
+const ROW_HEIGHT: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    Numbers,
+    Letters,
+}
+
+impl NumberStyle {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            NumberStyle::Numbers => Some("1, 2, 3".to_string()),
+            NumberStyle::Letters => Some("A, B, C".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "A, B, C" => NumberStyle::Letters,
+            _ => NumberStyle::Numbers,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["1, 2, 3".to_string(), "A, B, C".to_string()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Numbering {
+    pub style: NumberStyle,
+    pub next: usize,
+    pub next_str: String,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl Default for Numbering {
+    fn default() -> Self {
+        Self {
+            style: NumberStyle::Numbers,
+            next: 1,
+            next_str: String::new(),
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+}
+
+impl Numbering {
+    pub fn label(&self, number: usize) -> String {
+        let number =
+            match self.style {
+                NumberStyle::Numbers => number.to_string(),
+                NumberStyle::Letters => to_letters(number),
+            };
+        format!("{}{}{}", self.prefix, number, self.suffix)
+    }
+
+    // The next label, the count goes up.
+    pub fn new_label(&mut self, position: Point, color: Color) -> CanvasWidget {
+        let content = self.label(self.next);
+        self.next += 1;
+        self.next_str = String::new();
+        new_number(&content, position, color)
+    }
+}
+
+// 1 is A, 26 is Z, 27 is AA like spreadsheet columns.
+pub fn to_letters(mut number: usize) -> String {
+    let mut letters = vec![];
+    while number > 0 {
+        number -= 1;
+        letters.push((b'A' + (number % 26) as u8) as char);
+        number /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+// The labels are numbered again from 1 in reading order,
+// top to bottom then left to right.  Returns the next number.
+pub fn renumber(text_curves: &mut [&mut CanvasWidget], numbering: &Numbering) -> usize {
+    let mut labels: Vec<&mut Text> =
+        text_curves.iter_mut()
+            .filter_map(|widget| match widget {
+                CanvasWidget::Text(txt) => Some(txt),
+                _ => None,
+            })
+            .collect();
+    labels.sort_by(|a, b| {
+        let row_a = (a.position.y / ROW_HEIGHT).floor();
+        let row_b = (b.position.y / ROW_HEIGHT).floor();
+        row_a.total_cmp(&row_b).then(a.position.x.total_cmp(&b.position.x))
+    });
+    for (index, txt) in labels.iter_mut().enumerate() {
+        txt.content = numbering.label(index + 1);
+    }
+    labels.len() + 1
+}
+
+pub fn is_number(tags: &HashMap<WidgetId, Vec<String>>, id: &WidgetId) -> bool {
+    tags.get(id).is_some_and(|tags| tags.iter().any(|tag| tag == NUMBER_TAG))
+}
+
+fn new_number(content: &str, position: Point, color: Color) -> CanvasWidget {
+    CanvasWidget::Text(
+        Text {
+            id: WidgetId::unique(),
+            content: content.to_string(),
+            position,
+            color,
+            size: Pixels(NUMBER_SIZE),
+            line_height: LineHeight::Relative(1.2),
+            font: Font::default(),
+            horizontal_alignment: alignment::Horizontal::Center,
+            vertical_alignment: alignment::Vertical::Center,
+            shaping: Shaping::Basic,
+            degrees: 0.0,
+            cursor: TextCursor::default(),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+
+#[test]
+fn test_to_letters() {
+    assert_eq!("A", to_letters(1));
+    assert_eq!("Z", to_letters(26));
+    assert_eq!("AA", to_letters(27));
+    assert_eq!("AZ", to_letters(52));
+}
+
+#[test]
+fn test_renumber() {
+    let mut numbering = Numbering { prefix: "(".to_string(), suffix: ")".to_string(), ..Default::default() };
+    let mut second = numbering.new_label(Point::new(50.0, 100.0), Color::BLACK);
+    let mut first = numbering.new_label(Point::new(80.0, 10.0), Color::BLACK);
+    let mut third = numbering.new_label(Point::new(90.0, 105.0), Color::BLACK);
+    numbering.style = NumberStyle::Letters;
+
+    let next = renumber(&mut [&mut second, &mut first, &mut third], &numbering);
+    assert_eq!(4, next);
+    for (widget, expected) in [(first, "(A)"), (second, "(B)"), (third, "(C)")] {
+        let CanvasWidget::Text(txt) = widget else {
+            panic!("not a text")
+        };
+        assert_eq!(expected, txt.content);
+    }
+}
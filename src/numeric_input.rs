@@ -0,0 +1,64 @@
+//! numeric_input
+//! The numbers typed into the side panel.  An entry that isn't a
+//! number keeps the last good value, one out of range is clamped, and
+//! either way the input is drawn in the danger color until it's fixed.
+
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use iced::widget::text_input;
+use iced::Theme;
+
+
+pub const POLY_POINTS_RANGE: RangeInclusive<usize> = 3..=100;
+pub const DEFAULT_POLY_POINTS: usize = 3;
+pub const WIDTH_RANGE: RangeInclusive<f32> = 0.1..=100.0;
+pub const DEFAULT_WIDTH: f32 = 2.0;
+pub const TEXT_SIZE_RANGE: RangeInclusive<f32> = 4.0..=400.0;
+pub const DEFAULT_TEXT_SIZE: f32 = 16.0;
+pub const LINE_HEIGHT_RANGE: RangeInclusive<f32> = 0.5..=5.0;
+pub const DEFAULT_LINE_HEIGHT: f32 = 1.2;
+
+// The value for the entry and whether it's in error.  An empty
+// entry is the default, not an error.
+pub fn parse_input<T>(input: &str, last: T, default: T, range: RangeInclusive<T>) -> (T, bool)
+where
+    T: FromStr + PartialOrd + Copy,
+{
+    let input = input.trim();
+    if input.is_empty() {
+        return (default, false)
+    }
+    match input.parse::<T>() {
+        Ok(value) if value < *range.start() => (*range.start(), true),
+        Ok(value) if value > *range.end() => (*range.end(), true),
+        // NaN isn't in any range
+        Ok(value) if range.contains(&value) => (value, false),
+        _ => (last, true),
+    }
+}
+
+pub fn input_style(theme: &Theme, status: text_input::Status, error: bool) -> text_input::Style {
+    let mut style = text_input::default(theme, status);
+    if error {
+        style.border.color = theme.palette().danger;
+        style.border.width = 2.0;
+    }
+    style
+}
+
+
+#[test]
+fn test_parse_input() {
+    assert_eq!((5, false), parse_input("5", 4, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE));
+    assert_eq!((3, false), parse_input(" ", 4, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE));
+    // not a count, the last one stays
+    assert_eq!((4, true), parse_input("abc", 4, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE));
+    assert_eq!((4, true), parse_input("1.5", 4, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE));
+    assert_eq!((3, true), parse_input("1", 4, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE));
+    assert_eq!((100, true), parse_input("1000", 4, DEFAULT_POLY_POINTS, POLY_POINTS_RANGE));
+
+    assert_eq!((1.5, false), parse_input("1.5", 2.0, DEFAULT_WIDTH, WIDTH_RANGE));
+    assert_eq!((0.1, true), parse_input("0", 2.0, DEFAULT_WIDTH, WIDTH_RANGE));
+    assert_eq!((2.5, true), parse_input("NaN", 2.5, DEFAULT_WIDTH, WIDTH_RANGE));
+}
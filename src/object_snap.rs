@@ -0,0 +1,165 @@
+//! object_snap
+//! The points of the widgets a click snaps to while drawing or editing,
+//! the ends and middles of their sides and the centers of the round
+//! and closed shapes, so the lines of a diagram meet exactly.  Each
+//! kind has its own marker by the cursor.
+
+use iced::widget::canvas::Path;
+use iced::{Point, Size, Vector};
+
+use crate::draw_canvas::CanvasWidget;
+use crate::helpers::get_mid_point;
+use crate::intersections::{get_arc_points, get_bezier_points};
+
+
+const MARKER_SIZE: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapKind {
+    Endpoint,
+    Midpoint,
+    Center,
+    Intersection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapPoint {
+    pub point: Point,
+    pub kind: SnapKind,
+}
+
+impl SnapPoint {
+    pub fn new(point: Point, kind: SnapKind) -> Self {
+        SnapPoint { point, kind }
+    }
+}
+
+pub fn get_snap_points(widget: &CanvasWidget) -> Vec<SnapPoint> {
+    let center = |point: Point| vec![SnapPoint::new(point, SnapKind::Center)];
+    match widget {
+        CanvasWidget::None | CanvasWidget::Text(_) => vec![],
+        CanvasWidget::Arc(arc) => {
+            let points = get_arc_points(arc);
+            vec![
+                SnapPoint::new(points[0], SnapKind::Endpoint),
+                SnapPoint::new(points[points.len() - 1], SnapKind::Endpoint),
+                SnapPoint::new(points[points.len() / 2], SnapKind::Midpoint),
+                SnapPoint::new(arc.mid_point, SnapKind::Center),
+            ]
+        },
+        CanvasWidget::Bezier(bz) => {
+            // the middle of the curve, not of its ends
+            let points = get_bezier_points(bz);
+            vec![
+                SnapPoint::new(bz.points[0], SnapKind::Endpoint),
+                SnapPoint::new(bz.points[1], SnapKind::Endpoint),
+                SnapPoint::new(points[points.len() / 2], SnapKind::Midpoint),
+            ]
+        },
+        CanvasWidget::Circle(cir) => center(cir.center),
+        CanvasWidget::Ellipse(ell) => center(ell.center),
+        CanvasWidget::Gauge(gauge) => center(gauge.center),
+        CanvasWidget::RoundedRect(rr) => center(rr.mid_point),
+        CanvasWidget::Line(line) => get_side_points(&line.points),
+        CanvasWidget::PolyLine(pl) => {
+            let mut snaps = get_side_points(&pl.outline());
+            if pl.closed {
+                snaps.extend(center(pl.mid_point));
+            }
+            snaps
+        },
+        CanvasWidget::Polygon(pg) => {
+            let mut snaps = get_side_points(&pg.points);
+            snaps.extend(center(pg.mid_point));
+            snaps
+        },
+        CanvasWidget::RightTriangle(tr) => {
+            let mut snaps = get_side_points(&tr.points);
+            snaps.extend(center(tr.mid_point));
+            snaps
+        },
+        CanvasWidget::FreeHand(fh) => {
+            fh.points.first().into_iter()
+                .chain(fh.points.last())
+                .map(|point| SnapPoint::new(point, SnapKind::Endpoint))
+                .collect()
+        },
+    }
+}
+
+// The corners and the middle of each side, a closed outline
+// repeats its first point at the end.
+fn get_side_points(points: &[Point]) -> Vec<SnapPoint> {
+    let mut snaps = vec![];
+    for (index, point) in points.iter().enumerate() {
+        if index == 0 || *point != points[0] {
+            snaps.push(SnapPoint::new(*point, SnapKind::Endpoint));
+        }
+    }
+    for side in points.windows(2) {
+        snaps.push(SnapPoint::new(get_mid_point(side[0], side[1]), SnapKind::Midpoint));
+    }
+    snaps
+}
+
+pub fn get_closest_snap(snaps: &[SnapPoint], cursor: Point, max_distance: f32) -> Option<SnapPoint> {
+    snaps.iter()
+        .copied()
+        .filter(|snap| snap.point.distance(cursor) <= max_distance)
+        .min_by(|a, b| a.point.distance(cursor).total_cmp(&b.point.distance(cursor)))
+}
+
+// A square on an end, a triangle on a middle, a circle with
+// a cross on a center and a circle on an intersection.
+pub fn build_snap_marker(snap: &SnapPoint) -> Path {
+    let Point { x, y } = snap.point;
+    let size = MARKER_SIZE;
+    Path::new(|builder| {
+        match snap.kind {
+            SnapKind::Endpoint => {
+                builder.rectangle(snap.point - Vector::new(size, size), Size::new(2.0*size, 2.0*size));
+            },
+            SnapKind::Midpoint => {
+                builder.move_to(Point::new(x, y - size));
+                builder.line_to(Point::new(x + size, y + size));
+                builder.line_to(Point::new(x - size, y + size));
+                builder.close();
+            },
+            SnapKind::Center => {
+                builder.circle(snap.point, size);
+                builder.move_to(Point::new(x - size, y));
+                builder.line_to(Point::new(x + size, y));
+                builder.move_to(Point::new(x, y - size));
+                builder.line_to(Point::new(x, y + size));
+            },
+            SnapKind::Intersection => {
+                builder.circle(snap.point, size);
+            },
+        }
+    })
+}
+
+
+#[test]
+fn test_snap_points() {
+    use crate::test_widgets::line;
+
+    let line = CanvasWidget::Line(line(Point::new(0.0, 0.0), Point::new(100.0, 0.0)));
+    let snaps = get_snap_points(&line);
+    assert_eq!(
+        vec![
+            SnapPoint::new(Point::new(0.0, 0.0), SnapKind::Endpoint),
+            SnapPoint::new(Point::new(100.0, 0.0), SnapKind::Endpoint),
+            SnapPoint::new(Point::new(50.0, 0.0), SnapKind::Midpoint),
+        ],
+        snaps,
+    );
+    assert_eq!(Some(snaps[2]), get_closest_snap(&snaps, Point::new(54.0, 3.0), 10.0));
+    assert_eq!(None, get_closest_snap(&snaps, Point::new(75.0, 0.0), 10.0));
+
+    // a closed outline has its first corner once
+    let square = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0), (0.0, 0.0)].map(|(x, y)| Point::new(x, y));
+    let snaps = get_side_points(&square);
+    assert_eq!(4, snaps.iter().filter(|snap| snap.kind == SnapKind::Endpoint).count());
+    assert_eq!(4, snaps.iter().filter(|snap| snap.kind == SnapKind::Midpoint).count());
+}
@@ -0,0 +1,137 @@
+//! optimize
+//! Removes the repeated points that long freehand strokes collect,
+//! done on load and save when Optimize is checked.  The coordinates
+//! are rounded to the export precision on save when Round is checked.
+
+use crate::draw_canvas::Widget;
+use crate::app::ExportWidget;
+use crate::helpers::round_to;
+
+
+// Points closer than this are the same point.
+const SAME_POINT: f32 = 0.01;
+
+// Drops the repeated points of the polylines and freehands and
+// the widgets with no length left.  Returns the points removed.
+pub fn optimize_widgets(widgets: &mut Vec<ExportWidget>) -> usize {
+    let mut removed = 0;
+    widgets.retain_mut(|widget| {
+        let before = widget.points.len();
+        let keep =
+            match widget.name {
+                Widget::Line => {
+                    widget.points.len() != 2 ||
+                        widget.points[0].distance(widget.points[1]) > SAME_POINT
+                },
+                Widget::PolyLine => {
+                    remove_repeated_points(widget);
+                    widget.poly_points = widget.points.len();
+                    widget.points.len() > 1
+                },
+                Widget::FreeHand => {
+                    remove_repeated_points(widget);
+                    widget.points.len() > 1
+                },
+                // the other shapes have a fixed number of points
+                _ => true,
+            };
+        removed += if keep { before - widget.points.len() } else { before };
+        keep
+    });
+    removed
+}
+
+// The points, radius and widths to the decimal places, the
+// shorter numbers are most of the size of a freehand stroke.
+pub fn round_widgets(widgets: &mut [ExportWidget], places: u8) {
+    for widget in widgets.iter_mut() {
+        for point in widget.points.iter_mut() {
+            *point = point.round(places);
+        }
+        widget.mid_point = widget.mid_point.round(places);
+        widget.other_point = widget.other_point.round(places);
+        widget.radius = round_to(widget.radius, places);
+        for width in widget.widths.iter_mut() {
+            *width = round_to(*width, places);
+        }
+        if let Some(instance) = &mut widget.instance {
+            instance.origin = instance.origin.round(places);
+        }
+    }
+}
+
+// Keeps the first of each run of points, the widths
+// are kept with their points.
+fn remove_repeated_points(widget: &mut ExportWidget) {
+    let with_widths = widget.widths.len() == widget.points.len();
+    let mut index = 1;
+    while index < widget.points.len() {
+        if widget.points[index].distance(widget.points[index - 1]) <= SAME_POINT {
+            widget.points.remove(index);
+            if with_widths {
+                widget.widths.remove(index);
+            }
+        } else {
+            index += 1;
+        }
+    }
+}
+
+
+#[test]
+fn test_optimize_widgets() {
+    use iced::{Color, Point};
+    use crate::app::convert_widget_to_export;
+    use crate::draw_canvas::{CanvasWidget, DrawMode, DrawStatus, FreeHand};
+    use crate::ids::WidgetId;
+    use crate::test_widgets::line;
+
+    // a repeated start, a point moved less than SAME_POINT and a line with no length
+    let points = [(0.0, 0.0), (0.0, 0.0), (5.0, 5.0), (5.0, 5.001), (10.0, 5.0)];
+    let fh = FreeHand {
+        id: WidgetId::unique(),
+        points: points.iter().map(|(x, y)| Point::new(*x, *y)).collect(),
+        color: Color::BLACK,
+        width: 2.0,
+        widths: vec![1.0, 2.0, 3.0, 4.0, 5.0],
+        smooth: false,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+        completed: true,
+    };
+    let ln = line(Point::new(3.0, 3.0), Point::new(3.0, 3.0));
+    let mut widgets = vec![convert_widget_to_export(&CanvasWidget::FreeHand(fh)),
+                            convert_widget_to_export(&CanvasWidget::Line(ln))];
+
+    assert_eq!(4, optimize_widgets(&mut widgets));
+    assert_eq!(1, widgets.len());
+    assert_eq!(3, widgets[0].points.len());
+    assert_eq!(vec![1.0, 3.0, 5.0], widgets[0].widths);
+}
+
+#[test]
+fn test_round_widgets() {
+    use iced::{Color, Point};
+    use crate::app::convert_widget_to_export;
+    use crate::draw_canvas::{CanvasWidget, DrawMode, DrawStatus, FreeHand};
+    use crate::ids::WidgetId;
+
+    let fh = FreeHand {
+        id: WidgetId::unique(),
+        points: [Point::new(10.126, 20.0), Point::new(10.124, 20.0), Point::new(30.5, 7.25)].into_iter().collect(),
+        color: Color::BLACK,
+        width: 2.0,
+        widths: vec![1.3333, 2.0, 2.6667],
+        smooth: false,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+        completed: true,
+    };
+    let mut widgets = vec![convert_widget_to_export(&CanvasWidget::FreeHand(fh))];
+    round_widgets(&mut widgets, 1);
+    assert_eq!(vec![1.3, 2.0, 2.7], widgets[0].widths);
+    assert_eq!("[{\"x\":10.1,\"y\":20.0},{\"x\":10.1,\"y\":20.0},{\"x\":30.5,\"y\":7.3}]", 
+        serde_json::to_string(&widgets[0].points).unwrap());
+    // the points rounded together are then one
+    assert_eq!(1, optimize_widgets(&mut widgets));
+}
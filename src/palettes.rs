@@ -0,0 +1,210 @@
+//! palettes
+//! Swatch palettes that stay distinguishable for the common
+//! color vision deficiencies and a simulation of how colors
+//! are seen with each deficiency.
+//!
+//! Thanks to:
+//! * [Okabe and Ito](https://jfly.uni-koeln.de/color/)
+//! * [Machado et al. 2009](https://www.inf.ufrgs.br/~oliveira/pubs_files/CVD_Simulation/CVD_Simulation.html)
+
+use iced::Color;
+
+
+// The recent colors kept in the settings.
+pub const MAX_RECENT_COLORS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorVision {
+    Normal,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl ColorVision {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            ColorVision::Normal => Some("Normal".to_string()),
+            ColorVision::Deuteranopia => Some("Deuteranopia".to_string()),
+            ColorVision::Protanopia => Some("Protanopia".to_string()),
+            ColorVision::Tritanopia => Some("Tritanopia".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Normal" => ColorVision::Normal,
+            "Deuteranopia" => ColorVision::Deuteranopia,
+            "Protanopia" => ColorVision::Protanopia,
+            "Tritanopia" => ColorVision::Tritanopia,
+            _ => ColorVision::Normal,
+        }
+    }
+    pub fn options() -> Vec<String> {
+        vec!["Normal".to_string(), "Deuteranopia".to_string(),
+            "Protanopia".to_string(), "Tritanopia".to_string()]
+    }
+}
+
+pub fn get_palette(vision: ColorVision) -> Vec<Color> {
+    let hex = match vision {
+        ColorVision::Normal => {
+            vec![0xFFFFFF, 0x000000, 0xFF0000, 0x00A000, 0x0000FF, 0xFFFF00, 0xFFA500, 0x800080]
+        },
+        // IBM design library palette
+        ColorVision::Deuteranopia => {
+            vec![0xFFFFFF, 0x000000, 0x648FFF, 0x785EF0, 0xDC267F, 0xFE6100, 0xFFB000, 0x808080]
+        },
+        // Okabe-Ito palette
+        ColorVision::Protanopia => {
+            vec![0x000000, 0xE69F00, 0x56B4E9, 0x009E73, 0xF0E442, 0x0072B2, 0xD55E00, 0xCC79A7]
+        },
+        // The red-green axis is kept for tritanopia so reds and teals are used.
+        ColorVision::Tritanopia => {
+            vec![0xFFFFFF, 0x000000, 0xC00000, 0xFF8080, 0x008080, 0x80E0E0, 0x606060, 0xC0C0C0]
+        },
+    };
+
+    hex.into_iter()
+        .map(|h: u32| Color::from_rgb8((h >> 16) as u8, (h >> 8) as u8, h as u8))
+        .collect()
+}
+
+// The color first, taken out of where it was, and the
+// oldest ones dropped past the most kept.
+pub fn add_recent_color(recent: &mut Vec<Color>, color: Color) {
+    recent.retain(|c| *c != color);
+    recent.insert(0, color);
+    recent.truncate(MAX_RECENT_COLORS);
+}
+
+// Shows how the color is seen with the deficiency.
+pub fn simulate_color_vision(color: Color, vision: ColorVision) -> Color {
+    let matrix = match vision {
+        ColorVision::Normal => return color,
+        ColorVision::Deuteranopia => [
+            [0.367322, 0.860646, -0.227968],
+            [0.280085, 0.672501, 0.047413],
+            [-0.011820, 0.042940, 0.968881],
+        ],
+        ColorVision::Protanopia => [
+            [0.152286, 1.052583, -0.204868],
+            [0.114503, 0.786281, 0.099216],
+            [-0.003882, -0.048116, 1.051998],
+        ],
+        ColorVision::Tritanopia => [
+            [1.255528, -0.076749, -0.178779],
+            [-0.078411, 0.930809, 0.147602],
+            [0.004733, 0.691367, 0.303900],
+        ],
+    };
+
+    // The matrices work on linear rgb.
+    let rgb = [to_linear(color.r), to_linear(color.g), to_linear(color.b)];
+    let mut sim = [0.0; 3];
+    for (row, value) in matrix.iter().zip(sim.iter_mut()) {
+        *value = to_srgb(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+    }
+
+    Color::from_rgba(sim[0], sim[1], sim[2], color.a)
+}
+
+// WCAG AA minimum contrast for normal text.
+pub const CONTRAST_THRESHOLD: f32 = 4.5;
+
+pub fn get_relative_luminance(color: Color) -> f32 {
+    0.2126 * to_linear(color.r) + 0.7152 * to_linear(color.g) + 0.0722 * to_linear(color.b)
+}
+
+// WCAG contrast ratio from 1.0 to 21.0
+pub fn get_contrast_ratio(c1: Color, c2: Color) -> f32 {
+    let l1 = get_relative_luminance(c1);
+    let l2 = get_relative_luminance(c2);
+    (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+}
+
+// Darkens or lightens the color, whichever contrasts more with the
+// background, until the contrast reaches the threshold.
+pub fn fix_contrast(color: Color, background: Color) -> Color {
+    let target = if get_contrast_ratio(Color::BLACK, background) > 
+                    get_contrast_ratio(Color::WHITE, background) {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    };
+
+    let mut fixed = color;
+    let mut step = 0.0;
+    while get_contrast_ratio(fixed, background) < CONTRAST_THRESHOLD && step < 1.0 {
+        step = (step + 0.05_f32).min(1.0);
+        fixed = Color::from_rgba(
+            color.r + (target.r - color.r) * step,
+            color.g + (target.g - color.g) * step,
+            color.b + (target.b - color.b) * step,
+            color.a,
+        );
+    }
+    fixed
+}
+
+fn to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_srgb(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+
+#[test]
+fn test_simulate_color_vision() {
+    // greys are not changed
+    let grey = Color::from_rgb(0.5, 0.5, 0.5);
+    for vision in [ColorVision::Deuteranopia, ColorVision::Protanopia, ColorVision::Tritanopia] {
+        let sim = simulate_color_vision(grey, vision);
+        assert!((sim.r - 0.5).abs() < 0.01);
+        assert!((sim.g - 0.5).abs() < 0.01);
+        assert!((sim.b - 0.5).abs() < 0.01);
+    }
+
+    // red and green are hard to tell apart with deuteranopia
+    let red = simulate_color_vision(Color::from_rgb(1.0, 0.0, 0.0), ColorVision::Deuteranopia);
+    assert!(red.g > 0.5);
+}
+
+#[test]
+fn test_add_recent_color() {
+    let mut recent = vec![];
+    for i in 0..10 {
+        add_recent_color(&mut recent, Color::from_rgb(i as f32 / 10.0, 0.0, 0.0));
+    }
+    assert_eq!(MAX_RECENT_COLORS, recent.len());
+    assert_eq!(Color::from_rgb(0.9, 0.0, 0.0), recent[0]);
+
+    // used again it moves to the front
+    add_recent_color(&mut recent, Color::from_rgb(0.5, 0.0, 0.0));
+    assert_eq!(MAX_RECENT_COLORS, recent.len());
+    assert_eq!(Color::from_rgb(0.5, 0.0, 0.0), recent[0]);
+    assert_eq!(Color::from_rgb(0.9, 0.0, 0.0), recent[1]);
+}
+
+#[test]
+fn test_contrast_ratio() {
+    let ratio = get_contrast_ratio(Color::BLACK, Color::WHITE);
+    assert!((ratio - 21.0).abs() < 0.01);
+
+    let background = Color::from_rgb(0.0, 0.502, 0.502);
+    let text = Color::from_rgb(0.0, 0.6, 0.6);
+    assert!(get_contrast_ratio(text, background) < CONTRAST_THRESHOLD);
+    let fixed = fix_contrast(text, background);
+    assert!(get_contrast_ratio(fixed, background) >= CONTRAST_THRESHOLD);
+}
@@ -4,16 +4,62 @@ use std::f32::consts::PI;
 
 use iced::{widget::canvas::{self, path::arc::Elliptical, Path}, Point, Radians, Vector};
 
-use crate::{draw_canvas::{get_mid_geometry, Arc, Bezier, Circle, DrawMode, Ellipse, FreeHand, 
-    Line, PolyLine, Polygon, RightTriangle, Text, Widget}, helpers::{build_polygon, 
-    get_angle_of_vectors, get_horizontal_angle_of_vector, get_mid_point, rotate_geometry, 
-    to_degrees, translate_geometry}};
+use crate::intersections::Primitive;
+use crate::text_edit::byte_offset;
+use crate::{draw_canvas::{Arc, ArcClosure, Bezier, Circle, DrawMode, Ellipse, FreeHand, 
+    Gauge, Line, PolyLine, Polygon, RightTriangle, RoundedRect, Text, Widget}, helpers::{build_polygon, 
+    get_angle_of_vectors, get_catmull_rom_controls, get_gauge_angle, get_horizontal_angle_of_vector, get_mid_point, get_polyline_center, rotate_geometry, square_right_triangle, 
+    to_degrees, to_radians, translate_geometry, GAUGE_START_DEGREES, GAUGE_SWEEP_DEGREES}};
+
+// The size of the tool preview by the cursor.
+const TOOL_PREVIEW_RADIUS: f32 = 8.0;
+
+// How the path of a widget is built, the finished widget or one
+// that is new, edited or rotated following the cursor.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PathOptions {
+    pub draw_mode: DrawMode,
+    pub cursor: Option<Point>,
+    // the dragged point, named the way find_closest_point_index does
+    pub edit_point_index: Option<usize>,
+    pub edit_mid_point: bool,
+    pub edit_other_point: bool,
+    // the angle of a rotation not yet finished
+    pub degrees: Option<f32>,
+}
+
+impl PathOptions {
+    pub fn new(draw_mode: DrawMode) -> Self {
+        Self { draw_mode, ..Self::default() }
+    }
+}
+
+// The path with the angles shown while it is drawn,
+// left of or above the mid point.
+pub struct WidgetPath {
+    pub path: Path,
+    pub mid_point: Point,
+    pub degrees_left: Option<f32>,
+    pub degrees_center: Option<f32>,
+}
+
+impl WidgetPath {
+    pub fn new(path: Path) -> Self {
+        Self { path, mid_point: Point::ORIGIN, degrees_left: None, degrees_center: None }
+    }
+
+    // The angle shown above the mid point.
+    pub fn with_degrees(path: Path, mid_point: Point, degrees: Option<f32>) -> Self {
+        Self { path, mid_point, degrees_left: None, degrees_center: degrees }
+    }
+}
 
 pub fn build_arc_path(arc: &Arc, 
                     draw_mode: DrawMode, 
                     pending_cursor: Option<Point>,
                     edit_point_index: Option<usize>, 
                     edit_mid_point: bool,
+                    edit_other_point: bool,
                     ) -> (Path, Point, Radians, Radians, Option<f32>, Option<f32>) {
 
     let mut pts = arc.points.clone();
@@ -26,7 +72,9 @@ pub fn build_arc_path(arc: &Arc,
 
     let path = Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 let new_arc = 
                     canvas::path::Arc{ 
                         center: arc.mid_point, 
@@ -35,61 +83,40 @@ pub fn build_arc_path(arc: &Arc,
                         end_angle: arc.end_angle, 
                     };
                 p.arc(new_arc);
+                close_arc(p, arc.mid_point, arc.closure);
             },
             DrawMode::Edit => {
-                pts = arc.points.clone();
+                // each handle changes one thing, the readouts
+                // are drawn by the handles
                 let cursor = pending_cursor.unwrap();
-
+                let mut edited = arc.clone();
                 if edit_mid_point {
-                    pts = translate_geometry(
-                        &pts, 
-                        cursor,
-                        mid_point, 
-                        );
-                    mid_point = cursor;
-                } 
-                if edit_point_index.is_some() && edit_point_index != Some(0) {
-                    pts[edit_point_index.unwrap()] = cursor;
-                    if edit_point_index == Some(1) {
-                        radius = mid_point.distance(cursor);
-
-                        start_angle = get_angle_of_vectors(
-                            mid_point, 
-                            Point::new(-mid_point.x, mid_point.y), 
-                            cursor) + Radians::PI;
-                    
-                        end_angle = 
-                            get_angle_of_vectors(
-                                mid_point, 
-                                cursor, 
-                                pts[2]) + start_angle;
-                        degrees_left = 
-                            Some(get_horizontal_angle_of_vector(
-                                mid_point, 
-                                cursor, 
-                            ));
-                    } else if edit_point_index == Some(2) {
-                        end_angle = 
-                            get_angle_of_vectors(
-                                mid_point, 
-                                pts[1], 
-                                cursor) + start_angle;
-                        degrees_center = Some(to_degrees(&(end_angle.0-start_angle.0)));
-                    }
-
-                    // calc the end_angle point        
-                    let r = radius;
-                    let b = end_angle.0;
-                    let point_b = Point::new(r*b.cos(), r*b.sin());
-                    pts[2] = translate_geometry(&[point_b], mid_point, Point::default())[0];
-                    
+                    edited.points = translate_geometry(&edited.points, cursor, edited.mid_point);
+                    edited.mid_point = cursor;
+                }
+                if edit_other_point {
+                    edited.set_radius(edited.mid_point.distance(cursor));
+                }
+                match edit_point_index {
+                    Some(1) => edited.set_start_angle(cursor),
+                    Some(2) => edited.set_end_angle(cursor),
+                    _ => (),
                 }
+                pts = edited.points.clone();
+                mid_point = edited.mid_point;
+                radius = edited.radius;
+                start_angle = edited.start_angle;
+                end_angle = edited.end_angle;
+
                 p.move_to(pts[0]);
                 p.line_to(pts[1]);
+                p.move_to(pts[0]);
+                p.line_to(pts[2]);
                 
                 p.circle(mid_point, 3.0);
                 p.circle(pts[1], 3.0);
                 p.circle(pts[2], 3.0);
+                p.circle(edited.radius_point(), 3.0);
                 
                 let edit_arc = canvas::path::Arc{ 
                                             center: mid_point, 
@@ -98,6 +125,7 @@ pub fn build_arc_path(arc: &Arc,
                                             end_angle, 
                                         };
                 p.arc(edit_arc);
+                close_arc(p, mid_point, arc.closure);
             },
             DrawMode::New => {
                 let cursor = pending_cursor.unwrap();
@@ -134,6 +162,7 @@ pub fn build_arc_path(arc: &Arc,
                                             end_angle, 
                                         };
                     p.arc(new_arc);
+                    close_arc(p, arc.points[0], arc.closure);
 
                     degrees_left = 
                         Some(get_horizontal_angle_of_vector(
@@ -159,6 +188,7 @@ pub fn build_arc_path(arc: &Arc,
                     ));
 
                 p.arc(rotated_arc);
+                close_arc(p, arc.points[0], arc.closure);
                 p.move_to(arc.points[0]);
                 p.line_to(arc.points[1]);
                 p.move_to(arc.points[0]);
@@ -172,6 +202,19 @@ pub fn build_arc_path(arc: &Arc,
 
 }
 
+// Back across the chord or through the center for a pie,
+// the arc was just added to the path.
+fn close_arc(p: &mut canvas::path::Builder, center: Point, closure: ArcClosure) {
+    match closure {
+        ArcClosure::Open => (),
+        ArcClosure::Chord => p.close(),
+        ArcClosure::Pie => {
+            p.line_to(center);
+            p.close();
+        },
+    }
+}
+
 pub fn build_bezier_path(bz: &Bezier, 
                     draw_mode: DrawMode, 
                     pending_cursor: Option<Point>,
@@ -188,7 +231,9 @@ pub fn build_bezier_path(bz: &Bezier,
 
     let path = Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 p.move_to(bz.points[0]);
                 p.quadratic_curve_to(bz.points[2], bz.points[1]);
             },
@@ -265,7 +310,9 @@ pub fn build_circle_path(cir: &Circle,
                 ) -> Path {
     Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 p.circle(cir.center, cir.radius);
             },
             DrawMode::Edit => {
@@ -305,6 +352,77 @@ pub fn build_circle_path(cir: &Circle,
     })
 }
 
+pub fn build_gauge_path(gauge: &Gauge, 
+                    draw_mode: DrawMode, 
+                    pending_cursor: Option<Point>,
+                    edit_point_index: Option<usize>, 
+                    edit_mid_point: bool,
+                ) -> Path {
+    Path::new(|p| {
+        match draw_mode {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize | DrawMode::Rotate => {
+                add_gauge_to_path(p, gauge, gauge.center, gauge.radius);
+            },
+            DrawMode::Edit => {
+                let mut center = gauge.center;
+                let mut gauge_point = gauge.gauge_point;
+                let mut radius = gauge.radius;
+
+                if edit_mid_point {
+                    gauge_point = translate_geometry(
+                        &[gauge_point], 
+                        pending_cursor.unwrap(),
+                        center,
+                    )[0];
+                    center = pending_cursor.unwrap();
+                }
+
+                if edit_point_index.is_some() {
+                    gauge_point = pending_cursor.unwrap();
+                    radius = center.distance(gauge_point);
+                }
+
+                add_gauge_to_path(p, gauge, center, radius);
+                p.circle(center, 3.0);
+                p.circle(gauge_point, 3.0);
+            },
+            DrawMode::New => {
+                let gauge_point = pending_cursor.unwrap();
+                let radius = gauge.center.distance(gauge_point);
+                p.move_to(gauge.center);
+                p.line_to(gauge_point);
+                add_gauge_to_path(p, gauge, gauge.center, radius);
+            },
+        }
+    })
+}
+
+// The scale arc with a tick at each tenth and the needle.
+fn add_gauge_to_path(p: &mut canvas::path::Builder, gauge: &Gauge, center: Point, radius: f32) {
+    let start_angle = to_radians(&GAUGE_START_DEGREES);
+    let end_angle = to_radians(&(GAUGE_START_DEGREES + GAUGE_SWEEP_DEGREES));
+    p.arc(canvas::path::Arc {
+        center,
+        radius,
+        start_angle: Radians(start_angle),
+        end_angle: Radians(end_angle),
+    });
+
+    for i in 0..=10 {
+        let angle = start_angle + (end_angle - start_angle) * i as f32 / 10.0;
+        let (sin, cos) = angle.sin_cos();
+        p.move_to(Point::new(center.x + radius * 0.85 * cos, center.y + radius * 0.85 * sin));
+        p.line_to(Point::new(center.x + radius * cos, center.y + radius * sin));
+    }
+
+    let (sin, cos) = get_gauge_angle(gauge.min, gauge.max, gauge.value).sin_cos();
+    p.move_to(center);
+    p.line_to(Point::new(center.x + radius * 0.75 * cos, center.y + radius * 0.75 * sin));
+    p.circle(center, radius * 0.05);
+}
+
 pub fn build_ellipse_path(ell: &Ellipse, 
                         draw_mode: DrawMode, 
                         pending_cursor: Option<Point>,
@@ -313,7 +431,9 @@ pub fn build_ellipse_path(ell: &Ellipse,
                     ) -> Path {
     Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 p.ellipse(Elliptical{ 
                     center: ell.center, 
                     radii: ell.radii, 
@@ -339,19 +459,12 @@ pub fn build_ellipse_path(ell: &Ellipse,
                     p2 = points[2];
                 }
 
-                if edit_point_index.is_some() {
-                    let cursor = pending_cursor.unwrap();
-                    if edit_point_index == Some(1) {
-                        let vx = cursor.distance(center);
-                        let vy = ell.points[2].distance(center);
-                        p1 = Point::new(cursor.x, center.y);
-                        radii = Vector{x: vx, y: vy};
-                    } else {
-                        let vx = ell.points[1].distance(center);
-                        let vy = cursor.distance(center);
-                        p2 = Point::new(center.x, cursor.y);
-                        radii = Vector{x: vx, y: vy};
-                    }
+                if let Some(index) = edit_point_index {
+                    let mut edited = ell.clone();
+                    edited.set_axis_point(index, pending_cursor.unwrap());
+                    p1 = edited.points[1];
+                    p2 = edited.points[2];
+                    radii = edited.radii;
                 }
 
                 p.circle(center, 3.0);
@@ -425,7 +538,9 @@ pub fn build_line_path(line: &Line,
 
     let path = Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 p.move_to(line.points[0]);
                 p.line_to(line.points[1]);
             },
@@ -497,7 +612,9 @@ pub fn build_polygon_path(pg: &Polygon,
 
     let path = Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 let points = &pg.points;
                 for (index, point) in points.iter().enumerate() {
                     if index == 0 {
@@ -602,7 +719,9 @@ pub fn build_polyline_path(pl: &PolyLine,
 
     let path = Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 for (index, point) in pl.points.iter().enumerate() {
                     if index == 0 {
                         p.move_to(*point);
@@ -610,6 +729,9 @@ pub fn build_polyline_path(pl: &PolyLine,
                         p.line_to(*point);
                     }
                 }
+                if pl.closed {
+                    p.close();
+                }
             },
             DrawMode::Edit => {
                 if edit_mid_point {
@@ -624,7 +746,7 @@ pub fn build_polyline_path(pl: &PolyLine,
                 } 
                 if edit_point_index.is_some() {
                     pts[edit_point_index.unwrap()] = pending_cursor.unwrap();
-                    mid_point = get_mid_geometry(&pts, Widget::PolyLine);
+                    mid_point = get_polyline_center(&pts, pl.center);
                     pl_point = translate_geometry(
                                     &[pl_point], 
                                     mid_point, 
@@ -646,6 +768,9 @@ pub fn build_polyline_path(pl: &PolyLine,
                         p.line_to(*point);
                     }
                 }
+                if pl.closed {
+                    p.close();
+                }
                 for pt in pts.iter() {
                     p.circle(*pt, 3.0);
                 }
@@ -663,6 +788,10 @@ pub fn build_polyline_path(pl: &PolyLine,
                     }
                 }
                 p.line_to(pending_cursor.unwrap());
+                // the closing side follows the cursor too
+                if pl.closed {
+                    p.close();
+                }
             },
             DrawMode::Rotate => {
                 for (index, point) in pl.points.iter().enumerate() {
@@ -672,10 +801,9 @@ pub fn build_polyline_path(pl: &PolyLine,
                         p.line_to(*point);
                     }
                 }
-                // let (slope, intercept) = get_linear_regression(&pl.points);
-                // let(p1, p2) = get_line_from_slope_intercept(&pl.points, slope, intercept);
-                // mid_point = get_mid_point(p1, p2);
-                // degrees = get_vertical_angle_of_vector(mid_point, p2);
+                if pl.closed {
+                    p.close();
+                }
                 p.move_to(pl.mid_point);
                 p.line_to(pl.pl_point);
                 p.circle(mid_point, 3.0);
@@ -706,7 +834,9 @@ pub fn build_right_triangle_path(tr: &RightTriangle,
 
     let path = Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
                 p.move_to(tr.points[0]);
                 p.line_to(tr.points[1]);
                 p.line_to(tr.points[2]);
@@ -723,24 +853,12 @@ pub fn build_right_triangle_path(tr: &RightTriangle,
                     tr_point = pts.pop().unwrap();
                     mid_point = pending_cursor.unwrap();
                 } 
-                if edit_point_index.is_some() {
-                    let index = edit_point_index.unwrap();
-                    let cursor = pending_cursor.unwrap();
-                    if index == 0 {
-                        pts[0].y = cursor.y
-                    }
-                    if index == 1 {
-                        pts[1].y = cursor.y;
-                        pts[2].y = cursor.y;
-                    }
-                    if index == 2 {
-                        pts[2].x = cursor.x;
-                }
-                    let mid = get_mid_point(pts[1], pts[2]);
-                    let dist_b_mid = Point::new(mid.x-pts[2].x, mid.y-pts[2].y);
-                    tr_point = Point::new(
-                                    tr.points[2].x+dist_b_mid.x, 
-                                    tr.points[2].y+dist_b_mid.y);
+                if let Some(index) = edit_point_index {
+                    let mut edited = tr.clone();
+                    edited.set_leg_point(index, pending_cursor.unwrap());
+                    pts = edited.points;
+                    mid_point = edited.mid_point;
+                    tr_point = edited.tr_point;
                 }
                 if edit_other_point {
                     degrees = 
@@ -772,15 +890,17 @@ pub fn build_right_triangle_path(tr: &RightTriangle,
                 p.circle(tr_point, 3.0);
             },
             DrawMode::New => {
-                let mut cursor = pending_cursor.unwrap();
-                p.move_to(tr.points[0]);
+                let cursor = pending_cursor.unwrap();
                 if tr.points.len() == 1 {
-                    cursor.x = tr.points[0].x;
+                    p.move_to(tr.points[0]);
                     p.line_to(cursor);
                 } else if tr.points.len() == 2 {
-                    cursor.y = tr.points[1].y;
-                    p.line_to(tr.points[1]);
-                    p.line_to(cursor);
+                    // as it will be squared on the click
+                    let pts = square_right_triangle([tr.points[0], tr.points[1], cursor]);
+                    p.move_to(pts[0]);
+                    p.line_to(pts[1]);
+                    p.line_to(pts[2]);
+                    p.line_to(pts[0]);
                 }
             },
             DrawMode::Rotate => {
@@ -802,6 +922,77 @@ pub fn build_right_triangle_path(tr: &RightTriangle,
 
 }
 
+// The corners are rounded with arcs of the radius, a line from the
+// middle of the last side starts it so every corner is an arc_to.
+fn rounded_rect_outline(p: &mut canvas::path::Builder, points: &[Point], radius: f32) {
+    p.move_to(get_mid_point(points[3], points[0]));
+    for i in 0..4 {
+        p.arc_to(points[i], points[i + 1], radius);
+    }
+    p.close();
+}
+
+pub fn build_rounded_rect_path(rr: &RoundedRect, 
+                            draw_mode: DrawMode, 
+                            pending_cursor: Option<Point>,
+                            edit_point_index: Option<usize>, 
+                            edit_mid_point: bool,
+                            edit_other_point: bool,
+                            degrees: Option<f32>,
+                        ) -> (Path, f32, Point) {
+
+    let mut edited = rr.clone();
+    let degrees = match degrees {
+        Some(d) => d,
+        None => rr.degrees,
+    };
+
+    let path = Path::new(|p| {
+        match draw_mode {
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
+                rounded_rect_outline(p, &rr.points, rr.radius);
+            },
+            DrawMode::Edit => {
+                let cursor = pending_cursor.unwrap();
+                if edit_mid_point {
+                    edited.points = translate_geometry(&rr.points, cursor, rr.mid_point);
+                    edited.mid_point = cursor;
+                }
+                if let Some(index) = edit_point_index {
+                    edited.set_corner(index, cursor);
+                }
+                if edit_other_point {
+                    edited.set_radius_point(cursor);
+                }
+                rounded_rect_outline(p, &edited.points, edited.radius);
+
+                p.circle(edited.points[0], 3.0);
+                p.circle(edited.points[2], 3.0);
+                p.circle(edited.mid_point, 3.0);
+                p.circle(edited.radius_point(), 3.0);
+            },
+            DrawMode::New => {
+                // the upright rectangle it will be on the click
+                let cursor = pending_cursor.unwrap();
+                if let Some(first) = rr.points.first() {
+                    edited.set_corners(*first, cursor);
+                    edited.set_radius(edited.max_radius() / 2.0);
+                    rounded_rect_outline(p, &edited.points, edited.radius);
+                }
+            },
+            DrawMode::Rotate => {
+                rounded_rect_outline(p, &rr.points, rr.radius);
+                p.circle(rr.mid_point, 3.0);
+            },
+        }
+    });
+
+    (path, degrees, edited.mid_point)
+
+}
+
 
 pub fn build_free_hand_path(fh: &FreeHand, 
                         draw_mode: DrawMode, 
@@ -809,31 +1000,21 @@ pub fn build_free_hand_path(fh: &FreeHand,
                         edit_point_index: Option<usize>,
                         ) -> Path {
 
-    let mut pts = fh.points.clone();
-
     Path::new(|p| {
         match draw_mode {
-            DrawMode::DrawAll => {
-                for (index, point) in fh.points.iter().enumerate() {
-                    if index == 0 {
-                        p.move_to(*point);
-                    } else {
-                        p.line_to(*point);
-                    }
-                }
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => {
+                add_free_hand_to_path(p, fh.points.as_slice(), fh.smooth);
             },
             DrawMode::Edit => {
+                // only the stroke being edited is copied
+                let mut pts = fh.points.to_vec();
                 if edit_point_index.is_some() {
                     pts[edit_point_index.unwrap()] = pending_cursor.unwrap();
                 }
                 
-                for (index, point) in pts.iter().enumerate() {
-                    if index == 0 {
-                        p.move_to(*point);
-                    } else {
-                        p.line_to(*point);
-                    }
-                }
+                add_free_hand_to_path(p, &pts, fh.smooth);
                 for pt in pts.iter() {
                     p.circle(*pt, 3.0);
                 }
@@ -841,9 +1022,9 @@ pub fn build_free_hand_path(fh: &FreeHand,
             DrawMode::New => {
                 for (index, point) in fh.points.iter().enumerate() {
                     if index == 0 {
-                        p.move_to(*point);
+                        p.move_to(point);
                     } else {
-                        p.line_to(*point);
+                        p.line_to(point);
                     }
                 }
                 p.line_to(pending_cursor.unwrap());
@@ -856,6 +1037,24 @@ pub fn build_free_hand_path(fh: &FreeHand,
 
 }
 
+// A smooth stroke is a Catmull-Rom curve through the points.
+fn add_free_hand_to_path(p: &mut canvas::path::Builder, points: &[Point], smooth: bool) {
+    let Some(first) = points.first() else {
+        return
+    };
+    p.move_to(*first);
+    for index in 1..points.len() {
+        if smooth {
+            let before = points[index.saturating_sub(2)];
+            let after = points[(index + 1).min(points.len() - 1)];
+            let (c1, c2) = get_catmull_rom_controls(before, points[index - 1], points[index], after);
+            p.bezier_curve_to(c1, c2, points[index]);
+        } else {
+            p.line_to(points[index]);
+        }
+    }
+}
+
 pub fn build_text_path (txt: &Text, 
                     draw_mode: DrawMode, 
                     blink: bool,
@@ -873,7 +1072,9 @@ pub fn build_text_path (txt: &Text,
                     shaping: txt.shaping,
                 };
         match draw_mode {
-            DrawMode::DrawAll => { 
+            DrawMode::DrawAll | DrawMode::Trim | DrawMode::Extend | 
+            DrawMode::Parallel | DrawMode::Perpendicular | DrawMode::Tangent | 
+            DrawMode::Select | DrawMode::Place | DrawMode::Number | DrawMode::Delete | DrawMode::Split | DrawMode::Join | DrawMode::Resize => { 
                 (text, None)
             },
             DrawMode::Edit => {
@@ -883,9 +1084,17 @@ pub fn build_text_path (txt: &Text,
                 (text, path)
             },
             DrawMode::New => {
+                // the selection is bracketed, else the cursor blinks
                 let mut text_cont = txt.content.clone();
-                if blink {
-                    text_cont.push('|');
+                match txt.cursor.selection() {
+                    Some((start, end)) => {
+                        text_cont.insert(byte_offset(&text_cont, end), ']');
+                        text_cont.insert(byte_offset(&text_cont, start), '[');
+                    },
+                    None if blink => {
+                        text_cont.insert(byte_offset(&text_cont, txt.cursor.index), '|');
+                    },
+                    None => (),
                 }
                 text.content = text_cont;
                 (text, None)
@@ -900,3 +1109,115 @@ pub fn build_text_path (txt: &Text,
 
                   
 }
+
+pub fn build_primitives_path(primitives: &[Primitive]) -> Path {
+    Path::new(|p| {
+        for primitive in primitives.iter() {
+            match primitive {
+                Primitive::Segment(p1, p2) => {
+                    p.move_to(*p1);
+                    p.line_to(*p2);
+                },
+                Primitive::Circle(center, radius) => {
+                    p.circle(*center, *radius);
+                },
+            }
+        }
+    })
+}
+
+// A small sketch of the tool's shape about the center, drawn by
+// the cursor in New mode before the first click.
+pub fn build_tool_preview_path(widget: Widget, center: Point, poly_points: usize) -> Path {
+    let r = TOOL_PREVIEW_RADIUS;
+    let at = |x: f32, y: f32| Point::new(center.x + x*r, center.y + y*r);
+    Path::new(|p| {
+        match widget {
+            Widget::None => (),
+            Widget::Arc => {
+                p.arc(canvas::path::Arc { 
+                    center, 
+                    radius: r, 
+                    start_angle: Radians(PI), 
+                    end_angle: Radians(1.75*PI), 
+                });
+                p.move_to(center);
+                p.line_to(at(-1.0, 0.0));
+            },
+            Widget::Bezier => {
+                p.move_to(at(-1.0, 0.5));
+                p.quadratic_curve_to(at(0.0, -1.5), at(1.0, 0.5));
+            },
+            Widget::Circle => p.circle(center, r),
+            Widget::Ellipse => {
+                p.ellipse(Elliptical { 
+                    center, 
+                    radii: Vector::new(r, 0.6*r), 
+                    rotation: Radians(0.0), 
+                    start_angle: Radians(0.0), 
+                    end_angle: Radians(2.0*PI), 
+                });
+            },
+            Widget::Line => {
+                p.move_to(at(-1.0, 1.0));
+                p.line_to(at(1.0, -1.0));
+            },
+            Widget::PolyLine => {
+                p.move_to(at(-1.0, 0.5));
+                p.line_to(at(-0.3, -0.5));
+                p.line_to(at(0.3, 0.5));
+                p.line_to(at(1.0, -0.5));
+            },
+            Widget::Polygon => {
+                let points = build_polygon(center, at(1.0, 0.0), poly_points.max(3), 0.0);
+                p.move_to(points[0]);
+                for point in points.iter().skip(1) {
+                    p.line_to(*point);
+                }
+            },
+            Widget::RightTriangle => {
+                p.move_to(at(-1.0, -1.0));
+                p.line_to(at(-1.0, 1.0));
+                p.line_to(at(1.0, 1.0));
+                p.close();
+            },
+            Widget::RoundedRect => {
+                let points = [at(-1.0, -0.7), at(1.0, -0.7), at(1.0, 0.7), at(-1.0, 0.7), at(-1.0, -0.7)];
+                rounded_rect_outline(p, &points, 0.3*r);
+            },
+            Widget::Text => {
+                p.move_to(at(-0.8, -1.0));
+                p.line_to(at(0.8, -1.0));
+                p.move_to(at(0.0, -1.0));
+                p.line_to(at(0.0, 1.0));
+            },
+            Widget::FreeHand => {
+                p.move_to(at(-1.0, 0.0));
+                p.bezier_curve_to(at(-0.5, -1.0), at(0.0, 1.0), at(0.5, 0.0));
+                p.quadratic_curve_to(at(0.8, -0.5), at(1.0, 0.0));
+            },
+            Widget::Gauge => {
+                p.arc(canvas::path::Arc { 
+                    center, 
+                    radius: r, 
+                    start_angle: Radians(0.75*PI), 
+                    end_angle: Radians(2.25*PI), 
+                });
+                p.move_to(center);
+                p.line_to(at(0.5, -0.5));
+            },
+        }
+    })
+}
+
+// Small crosses used to mark points of interest.
+pub fn build_markers_path(points: &[Point]) -> Path {
+    Path::new(|p| {
+        for point in points.iter() {
+            p.move_to(Point::new(point.x - 4.0, point.y - 4.0));
+            p.line_to(Point::new(point.x + 4.0, point.y + 4.0));
+            p.move_to(Point::new(point.x - 4.0, point.y + 4.0));
+            p.line_to(Point::new(point.x + 4.0, point.y - 4.0));
+        }
+    })
+}
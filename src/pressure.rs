@@ -0,0 +1,177 @@
+//! pressure
+//! The pressure to width curve applied to the freehand strokes.
+
+use iced::widget::canvas::{self, event, Event, Frame, Geometry, Path, Stroke};
+use iced::{mouse, Point, Rectangle, Renderer, Theme};
+use serde::{Deserialize, Serialize};
+
+
+// The width factors at evenly spaced pressures from 0.0 to 1.0
+pub const CURVE_POINTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PressurePreset {
+    Linear,
+    Soft,
+    Firm,
+    Constant,
+    Custom,
+}
+
+impl PressurePreset {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            PressurePreset::Linear => Some("Linear".to_string()),
+            PressurePreset::Soft => Some("Soft".to_string()),
+            PressurePreset::Firm => Some("Firm".to_string()),
+            PressurePreset::Constant => Some("Constant".to_string()),
+            PressurePreset::Custom => Some("Custom".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Linear" => PressurePreset::Linear,
+            "Soft" => PressurePreset::Soft,
+            "Firm" => PressurePreset::Firm,
+            "Constant" => PressurePreset::Constant,
+            "Custom" => PressurePreset::Custom,
+            _ => PressurePreset::Linear,
+        }
+    }
+    pub fn options() -> Vec<String> {
+        vec!["Linear".to_string(), "Soft".to_string(), "Firm".to_string(),
+            "Constant".to_string(), "Custom".to_string()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PressureCurve {
+    pub preset: PressurePreset,
+    pub factors: [f32; CURVE_POINTS],
+}
+
+impl Default for PressureCurve {
+    fn default() -> Self {
+        PressureCurve::from_preset(PressurePreset::Constant)
+    }
+}
+
+impl PressureCurve {
+    // Custom keeps the current factors.
+    pub fn from_preset(preset: PressurePreset) -> Self {
+        let factors = match preset {
+            PressurePreset::Linear => [0.1, 0.325, 0.55, 0.775, 1.0],
+            PressurePreset::Soft => [0.1, 0.55, 0.8, 0.93, 1.0],
+            PressurePreset::Firm => [0.1, 0.17, 0.3, 0.55, 1.0],
+            PressurePreset::Constant | PressurePreset::Custom => [1.0; CURVE_POINTS],
+        };
+        PressureCurve { preset, factors }
+    }
+
+    // The width factor for a pressure from 0.0 to 1.0
+    pub fn get_factor(&self, pressure: f32) -> f32 {
+        let pos = pressure.clamp(0.0, 1.0) * (CURVE_POINTS - 1) as f32;
+        let idx = (pos.floor() as usize).min(CURVE_POINTS - 2);
+        let t = pos - idx as f32;
+        self.factors[idx] + (self.factors[idx + 1] - self.factors[idx]) * t
+    }
+}
+
+// iced does not report the pen pressure yet, so
+// full pressure is used for every point.
+pub fn get_pen_pressure() -> f32 {
+    1.0
+}
+
+// A small canvas showing the curve, clicking moves the
+// closest curve point to the cursor height.
+pub struct PressureCurveEditor {
+    pub curve: PressureCurve,
+}
+
+impl canvas::Program<PressureCurve> for PressureCurveEditor {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<PressureCurve>) {
+        let Some(cursor_position) = cursor.position_in(bounds) else {
+            return (event::Status::Ignored, None);
+        };
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let step = bounds.width / (CURVE_POINTS - 1) as f32;
+                let idx = ((cursor_position.x / step).round() as usize).min(CURVE_POINTS - 1);
+                let factor = (1.0 - cursor_position.y / bounds.height).clamp(0.0, 1.0);
+
+                let mut curve = self.curve;
+                curve.preset = PressurePreset::Custom;
+                curve.factors[idx] = factor;
+                (event::Status::Captured, Some(curve))
+            },
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let step = bounds.width / (CURVE_POINTS - 1) as f32;
+        let points: Vec<Point> =
+            self.curve.factors.iter()
+                .enumerate()
+                .map(|(idx, factor)| {
+                    Point::new(idx as f32 * step, (1.0 - factor) * bounds.height)
+                })
+                .collect();
+
+        frame.stroke(
+            &Path::rectangle(Point::ORIGIN, frame.size()),
+            Stroke::default()
+                .with_width(1.0)
+                .with_color(theme.palette().text),
+        );
+
+        let curve = Path::new(|p| {
+            p.move_to(points[0]);
+            for point in points.iter().skip(1) {
+                p.line_to(*point);
+            }
+            for point in points.iter() {
+                p.circle(*point, 3.0);
+            }
+        });
+        frame.stroke(
+            &curve,
+            Stroke::default()
+                .with_width(2.0)
+                .with_color(theme.palette().primary),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
+
+
+#[test]
+fn test_pressure_curve_factor() {
+    let curve = PressureCurve::from_preset(PressurePreset::Linear);
+    assert_eq!(0.1, curve.get_factor(0.0));
+    assert_eq!(1.0, curve.get_factor(1.0));
+    assert!((curve.get_factor(0.125) - 0.2125).abs() < 1.0e-5);
+
+    let curve = PressureCurve::default();
+    assert_eq!(1.0, curve.get_factor(0.3));
+}
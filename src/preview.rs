@@ -0,0 +1,138 @@
+//! preview
+//! While a widget is dragged or rotated its preview is drawn from every
+//! few of its points, so a freehand stroke or polyline of thousands of
+//! points follows the cursor smoothly.  The widget itself keeps all its
+//! points and is drawn in full again when it's put down.
+
+use serde::{Deserialize, Serialize};
+
+use crate::draw_canvas::{CanvasWidget, Pending};
+use crate::stroke::StrokePoints;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PreviewQuality {
+    Full,
+    #[default]
+    Balanced,
+    Fast,
+}
+
+impl PreviewQuality {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            PreviewQuality::Full => Some("Full".to_string()),
+            PreviewQuality::Balanced => Some("Balanced".to_string()),
+            PreviewQuality::Fast => Some("Fast".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Full" => PreviewQuality::Full,
+            "Fast" => PreviewQuality::Fast,
+            _ => PreviewQuality::Balanced,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Full".to_string(), "Balanced".to_string(), "Fast".to_string()]
+    }
+
+    // The most points a preview is drawn with, None for all of them.
+    pub fn max_points(&self) -> Option<usize> {
+        match self {
+            PreviewQuality::Full => None,
+            PreviewQuality::Balanced => Some(2000),
+            PreviewQuality::Fast => Some(500),
+        }
+    }
+}
+
+// The pending drag or rotation with fewer points, None when
+// it's drawn as it is.
+pub fn get_preview_pending(pending: &Pending, quality: PreviewQuality) -> Option<Pending> {
+    let max_points = quality.max_points()?;
+    let mut preview = pending.clone();
+    let widget = match &mut preview {
+        Pending::Rotate { widget, .. } | Pending::Drag { widget, .. } => widget,
+        _ => return None,
+    };
+    *widget = decimate_widget(widget, max_points)?;
+    Some(preview)
+}
+
+// Every nth point and the last one, None when the widget has no
+// more than the points.
+pub fn decimate_widget(widget: &CanvasWidget, max_points: usize) -> Option<CanvasWidget> {
+    match widget {
+        CanvasWidget::FreeHand(fh) if fh.points.len() > max_points => {
+            let keep = get_kept_indices(fh.points.len(), max_points);
+            let points = fh.points.as_slice();
+            let mut decimated = StrokePoints::default();
+            for index in keep.iter() {
+                decimated.push(points[*index]);
+            }
+            let mut fh = fh.clone();
+            if fh.widths.len() == points.len() {
+                fh.widths = keep.iter().map(|index| fh.widths[*index]).collect();
+            }
+            fh.points = decimated;
+            Some(CanvasWidget::FreeHand(fh))
+        },
+        CanvasWidget::PolyLine(pl) if pl.points.len() > max_points => {
+            let keep = get_kept_indices(pl.points.len(), max_points);
+            let mut pl = pl.clone();
+            pl.points = keep.iter().map(|index| pl.points[*index]).collect();
+            pl.poly_points = pl.points.len();
+            Some(CanvasWidget::PolyLine(pl))
+        },
+        _ => None,
+    }
+}
+
+fn get_kept_indices(count: usize, max_points: usize) -> Vec<usize> {
+    let step = count.div_ceil(max_points.max(2) - 1);
+    let mut keep: Vec<usize> = (0..count).step_by(step).collect();
+    if keep.last() != Some(&(count - 1)) {
+        keep.push(count - 1);
+    }
+    keep
+}
+
+
+#[test]
+fn test_decimate_widget() {
+    use iced::{Color, Point};
+    use crate::draw_canvas::{DrawMode, DrawStatus, FreeHand};
+    use crate::ids::WidgetId;
+
+    assert_eq!(vec![0, 4, 8, 9], get_kept_indices(10, 4));
+    assert_eq!(vec![0, 2], get_kept_indices(3, 2));
+
+    let mut points = StrokePoints::default();
+    for i in 0..5000 {
+        points.push(Point::new(i as f32 * 0.5, (i % 7) as f32));
+    }
+    let fh = CanvasWidget::FreeHand(FreeHand {
+        id: WidgetId::unique(),
+        points,
+        color: Color::BLACK,
+        width: 2.0,
+        widths: (0..5000).map(|i| i as f32).collect(),
+        smooth: false,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+        completed: true,
+    });
+    let Some(CanvasWidget::FreeHand(preview)) = decimate_widget(&fh, 500) else {
+        panic!("not decimated")
+    };
+    assert!(preview.points.len() <= 500);
+    assert_eq!(preview.points.len(), preview.widths.len());
+    // the stroke still ends where it did
+    assert_eq!(Some(Point::new(2499.5, 1.0)), preview.points.last());
+    assert_eq!(Some(&4999.0), preview.widths.last());
+
+    assert!(decimate_widget(&fh, 5000).is_none());
+}
@@ -0,0 +1,163 @@
+//! query
+//! Finds the widgets matching a tag expression like
+//! type:circle AND tag:valve OR text:pump OR width:2
+
+use std::collections::HashMap;
+
+use crate::draw_canvas::{get_widget_type, get_widget_width, CanvasWidget};
+use crate::ids::WidgetId;
+
+// Stroke widths this close are the same.
+pub const WIDTH_TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Type(String),
+    Tag(String),
+    Text(String),
+    Width(f32),
+}
+
+// A term and whether it is negated by NOT.
+type Condition = (bool, Term);
+
+// AND binds tighter than OR so the query is
+// a list of OR'd groups of AND'd conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    groups: Vec<Vec<Condition>>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, String> {
+        let mut groups = vec![];
+        let mut conditions = vec![];
+        let mut negate = false;
+        let mut expect_term = true;
+
+        for word in input.split_whitespace() {
+            match word.to_uppercase().as_str() {
+                "AND" if !expect_term => expect_term = true,
+                "OR" if !expect_term => {
+                    groups.push(std::mem::take(&mut conditions));
+                    expect_term = true;
+                },
+                "NOT" if expect_term => negate = !negate,
+                _ => {
+                    if !expect_term {
+                        return Err(format!("Expected AND or OR before {}", word))
+                    }
+                    conditions.push((negate, parse_term(word)?));
+                    negate = false;
+                    expect_term = false;
+                },
+            }
+        }
+
+        if expect_term {
+            return Err("Incomplete query".to_string())
+        }
+        groups.push(conditions);
+
+        Ok(Query { groups })
+    }
+
+    pub fn matches(&self, widget: &CanvasWidget, tags: &[String]) -> bool {
+        self.groups.iter().any(|group| {
+            group.iter().all(|(negate, term)| term_matches(term, widget, tags) != *negate)
+        })
+    }
+}
+
+fn parse_term(word: &str) -> Result<Term, String> {
+    let Some((key, value)) = word.split_once(':') else {
+        return Err(format!("Expected key:value, found {}", word))
+    };
+    let value = value.to_lowercase();
+    match key.to_lowercase().as_str() {
+        "type" => Ok(Term::Type(value)),
+        "tag" => Ok(Term::Tag(value)),
+        "text" => Ok(Term::Text(value)),
+        "width" => {
+            value.parse::<f32>()
+                .map(Term::Width)
+                .map_err(|_| format!("Expected a number for width, found {}", value))
+        },
+        _ => Err(format!("Unknown key {}, use type, tag, text or width", key)),
+    }
+}
+
+fn term_matches(term: &Term, widget: &CanvasWidget, tags: &[String]) -> bool {
+    match term {
+        Term::Type(value) => {
+            format!("{:?}", get_widget_type(widget)).to_lowercase() == *value
+        },
+        Term::Tag(value) => {
+            tags.iter().any(|tag| tag.to_lowercase() == *value)
+        },
+        Term::Text(value) => {
+            match widget {
+                CanvasWidget::Text(txt) => txt.content.to_lowercase().contains(value.as_str()),
+                _ => false,
+            }
+        },
+        Term::Width(value) => is_same_width(widget, *value),
+    }
+}
+
+// A text has no stroke to compare.
+fn is_same_width(widget: &CanvasWidget, width: f32) -> bool {
+    !matches!(widget, CanvasWidget::Text(_)) && 
+        (get_widget_width(widget) - width).abs() <= WIDTH_TOLERANCE
+}
+
+// The ids of the widgets drawn with about the width.
+pub fn find_same_width(curves: &HashMap<WidgetId, CanvasWidget>, width: f32) -> Vec<WidgetId> {
+    curves.iter()
+        .filter(|(_, widget)| is_same_width(widget, width))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+// The ids of all widgets matching the query.
+pub fn find_widgets(query: &Query,
+                    curves: &HashMap<WidgetId, CanvasWidget>,
+                    tags: &HashMap<WidgetId, Vec<String>>,
+                    ) -> Vec<WidgetId> {
+    curves.iter()
+        .filter(|(id, widget)| {
+            let widget_tags = tags.get(*id).map(|t| t.as_slice()).unwrap_or(&[]);
+            query.matches(widget, widget_tags)
+        })
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+// Tags are entered separated by commas.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    input.split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+
+#[test]
+fn test_query_parse() {
+    let query = Query::parse("type:circle AND tag:valve OR NOT text:pump").unwrap();
+    assert_eq!(2, query.groups.len());
+    assert_eq!(vec![(false, Term::Type("circle".to_string())), (false, Term::Tag("valve".to_string()))],
+        query.groups[0]);
+    assert_eq!(vec![(true, Term::Text("pump".to_string()))], query.groups[1]);
+
+    assert!(Query::parse("type:circle AND").is_err());
+    assert!(Query::parse("type:circle tag:valve").is_err());
+    assert!(Query::parse("size:10").is_err());
+    assert_eq!(vec![vec![(false, Term::Width(2.5))]], Query::parse("width:2.5").unwrap().groups);
+    assert!(Query::parse("width:wide").is_err());
+}
+
+#[test]
+fn test_parse_tags() {
+    assert_eq!(vec!["valve".to_string(), "inlet".to_string()], parse_tags(" valve, ,inlet "));
+}
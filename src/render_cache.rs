@@ -0,0 +1,108 @@
+//! render_cache
+//! The canvas caches of the drawing.  The background is at the bottom,
+//! then a cache for each layer's curves, a cache for each text, the
+//! markers over them and the rulers on top.  A change only clears the
+//! cache it's in, the others are drawn from their geometry.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use iced::widget::canvas::{self, Frame, Geometry};
+use iced::{Renderer, Size};
+
+use crate::ids::WidgetId;
+
+
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    background: canvas::Cache,
+    // by layer index, made when first drawn
+    layers: RefCell<Vec<canvas::Cache>>,
+    // by text id, made when first drawn
+    texts: RefCell<HashMap<WidgetId, canvas::Cache>>,
+    overlay: canvas::Cache,
+    // redrawn when the canvas is resized
+    rulers: canvas::Cache,
+}
+
+impl RenderCache {
+    // Everything but the texts, also when the layers
+    // are added, removed or moved.
+    pub fn invalidate_drawing(&mut self) {
+        self.background.clear();
+        self.layers.get_mut().clear();
+        self.overlay.clear();
+        self.rulers.clear();
+    }
+
+    // Only the canvas color or image changed.
+    pub fn invalidate_background(&mut self) {
+        self.background.clear();
+    }
+
+    // The layer's curves, the markers over them may have changed too.
+    pub fn invalidate_layer(&mut self, layer: usize) {
+        if let Some(cache) = self.layers.get_mut().get(layer) {
+            cache.clear();
+        }
+        self.overlay.clear();
+    }
+
+    // The curves share their layer's cache, only a text has its own.
+    pub fn invalidate(&mut self, id: &WidgetId) {
+        self.texts.get_mut().remove(id);
+    }
+
+    pub fn invalidate_texts(&mut self) {
+        self.texts.get_mut().clear();
+    }
+
+    pub fn draw_background(&self, renderer: &Renderer, size: Size, draw: impl FnOnce(&mut Frame)) -> Geometry {
+        self.background.draw(renderer, size, draw)
+    }
+
+    pub fn draw_layer(&self, layer: usize, renderer: &Renderer, size: Size, draw: impl FnOnce(&mut Frame)) -> Geometry {
+        let mut layers = self.layers.borrow_mut();
+        if layers.len() <= layer {
+            layers.resize_with(layer + 1, canvas::Cache::new);
+        }
+        layers[layer].draw(renderer, size, draw)
+    }
+
+    pub fn draw_text(&self, id: WidgetId, renderer: &Renderer, size: Size, draw: impl FnOnce(&mut Frame)) -> Geometry {
+        self.texts.borrow_mut().entry(id).or_default().draw(renderer, size, draw)
+    }
+
+    pub fn draw_overlay(&self, renderer: &Renderer, size: Size, draw: impl FnOnce(&mut Frame)) -> Geometry {
+        self.overlay.draw(renderer, size, draw)
+    }
+
+    pub fn draw_rulers(&self, renderer: &Renderer, size: Size, draw: impl FnOnce(&mut Frame)) -> Geometry {
+        self.rulers.draw(renderer, size, draw)
+    }
+
+    // Drops the caches of the texts deleted or hidden.
+    pub fn retain_texts(&self, keep: impl Fn(&WidgetId) -> bool) {
+        self.texts.borrow_mut().retain(|id, _| keep(id));
+    }
+}
+
+
+#[test]
+fn test_render_cache_invalidate() {
+    let mut cache = RenderCache::default();
+    let ids = [WidgetId::unique(), WidgetId::unique()];
+    for id in ids.iter() {
+        cache.texts.get_mut().insert(*id, canvas::Cache::new());
+    }
+    cache.invalidate(&ids[0]);
+    assert_eq!(1, cache.texts.borrow().len());
+    cache.retain_texts(|id| *id != ids[1]);
+    assert_eq!(0, cache.texts.borrow().len());
+
+    cache.layers.get_mut().resize_with(3, canvas::Cache::new);
+    cache.invalidate_layer(5);
+    assert_eq!(3, cache.layers.borrow().len());
+    cache.invalidate_drawing();
+    assert!(cache.layers.borrow().is_empty());
+}
@@ -0,0 +1,183 @@
+//! replace_color
+//! Rebranding a drawing, every widget drawn in a color near the
+//! source color takes the target color, on all of the layers.  The
+//! colors replaced are kept so the whole replace is undone at once.
+//! The colors of the whole drawing can also be remapped together,
+//! each to the closest color of another palette.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use iced::Color;
+
+use crate::draw_canvas::{get_widget_color, set_widget_color, CanvasWidget};
+use crate::ids::WidgetId;
+use crate::layers::Layer;
+
+
+pub const TOLERANCE_RANGE: RangeInclusive<f32> = 0.0..=0.5;
+pub const DEFAULT_TOLERANCE: f32 = 0.05;
+// the remap palette of the recent draw colors
+pub const RECENT_COLORS: &str = "Recent Colors";
+
+// How far apart two colors are, the largest difference of a channel.
+pub fn get_color_difference(c1: Color, c2: Color) -> f32 {
+    [c1.r - c2.r, c1.g - c2.g, c1.b - c2.b, c1.a - c2.a]
+        .into_iter()
+        .fold(0.0, |max, diff| max.max(diff.abs()))
+}
+
+// The widgets within the tolerance of the color are given the new
+// color, the ids and the colors they had are returned for the undo.
+pub fn replace_color(layers: &mut [Layer], from: Color, to: Color, tolerance: f32) -> Vec<(WidgetId, Color)> {
+    let mut replaced = vec![];
+    for layer in layers.iter_mut() {
+        for (id, widget) in layer.curves.iter_mut() {
+            let color = get_widget_color(widget);
+            if get_color_difference(color, from) > tolerance {
+                continue
+            }
+            *widget = set_widget_color(widget.clone(), to);
+            replaced.push((*id, color));
+        }
+    }
+    replaced
+}
+
+// The colors back as they were, widgets deleted since are skipped.
+pub fn restore_colors(layers: &mut [Layer], replaced: &[(WidgetId, Color)]) {
+    for (id, color) in replaced.iter() {
+        for layer in layers.iter_mut() {
+            if let Some(widget) = layer.curves.get_mut(id) {
+                *widget = set_widget_color(widget.clone(), *color);
+            }
+        }
+    }
+}
+
+// Every color the widgets are drawn in, the most used first.
+pub fn get_document_colors(layers: &[Layer]) -> Vec<Color> {
+    let mut counts: Vec<(Color, usize)> = vec![];
+    for widget in layers.iter().flat_map(|layer| layer.curves.values()) {
+        let color = get_widget_color(widget);
+        match counts.iter_mut().find(|(c, _)| *c == color) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((color, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts.into_iter().map(|(color, _)| color).collect()
+}
+
+// Each color with the closest color of the palette, the alpha is kept.
+pub fn get_palette_remap(colors: &[Color], palette: &[Color]) -> Vec<(Color, Color)> {
+    let distance = |c1: Color, c2: Color| {
+        (c1.r - c2.r).powi(2) + (c1.g - c2.g).powi(2) + (c1.b - c2.b).powi(2)
+    };
+    colors.iter()
+        .filter_map(|&color| {
+            palette.iter()
+                .min_by(|a, b| distance(**a, color).total_cmp(&distance(**b, color)))
+                .map(|&closest| (color, Color { a: color.a, ..closest }))
+        })
+        .collect()
+}
+
+// A color not in the remap stays as it is.
+pub fn get_remapped_color(remap: &[(Color, Color)], color: Color) -> Color {
+    remap.iter()
+        .find(|(from, _)| *from == color)
+        .map_or(color, |(_, to)| *to)
+}
+
+// The widgets as they would be remapped, for the preview.
+pub fn get_remapped_curves(curves: &HashMap<WidgetId, CanvasWidget>, remap: &[(Color, Color)]) -> HashMap<WidgetId, CanvasWidget> {
+    curves.iter()
+        .map(|(id, widget)| {
+            let color = get_remapped_color(remap, get_widget_color(widget));
+            (*id, set_widget_color(widget.clone(), color))
+        })
+        .collect()
+}
+
+// Remaps every widget, the ids and the colors they had are returned for the undo.
+pub fn remap_colors(layers: &mut [Layer], remap: &[(Color, Color)]) -> Vec<(WidgetId, Color)> {
+    let mut replaced = vec![];
+    for layer in layers.iter_mut() {
+        for (id, widget) in layer.curves.iter_mut() {
+            let color = get_widget_color(widget);
+            let remapped = get_remapped_color(remap, color);
+            if remapped != color {
+                *widget = set_widget_color(widget.clone(), remapped);
+                replaced.push((*id, color));
+            }
+        }
+    }
+    replaced
+}
+
+
+#[test]
+fn test_replace_color() {
+    use iced::Point;
+    use crate::draw_canvas::Line;
+    use crate::test_widgets::line;
+
+    let line = |color: Color| {
+        let ln = Line { color, ..line(Point::new(0.0, 0.0), Point::new(10.0, 0.0)) };
+        (ln.id, CanvasWidget::Line(ln))
+    };
+    let red = Color::from_rgb(1.0, 0.0, 0.0);
+    let mut layers = vec![Layer::new("Layer 1".to_string()), Layer::new("Layer 2".to_string())];
+    let (near, widget) = line(Color::from_rgb(0.98, 0.02, 0.0));
+    layers[0].insert(near, widget);
+    let (far, widget) = line(Color::from_rgb(0.8, 0.0, 0.0));
+    layers[0].insert(far, widget);
+    let (other, widget) = line(red);
+    layers[1].insert(other, widget);
+
+    let replaced = replace_color(&mut layers, red, Color::BLACK, DEFAULT_TOLERANCE);
+    assert_eq!(2, replaced.len());
+    assert_eq!(Color::BLACK, get_widget_color(&layers[0].curves[&near]));
+    assert_eq!(Color::BLACK, get_widget_color(&layers[1].curves[&other]));
+    assert_eq!(Color::from_rgb(0.8, 0.0, 0.0), get_widget_color(&layers[0].curves[&far]));
+
+    restore_colors(&mut layers, &replaced);
+    assert_eq!(Color::from_rgb(0.98, 0.02, 0.0), get_widget_color(&layers[0].curves[&near]));
+    assert_eq!(red, get_widget_color(&layers[1].curves[&other]));
+}
+
+#[test]
+fn test_remap_palette() {
+    use iced::Point;
+    use crate::draw_canvas::Circle;
+    use crate::test_widgets::circle;
+
+    let circle = |color: Color| {
+        let cir = Circle { color, ..circle(Point::new(0.0, 0.0), 10.0) };
+        (cir.id, CanvasWidget::Circle(cir))
+    };
+    let dark_red = Color::from_rgb(0.7, 0.1, 0.1);
+    let light_blue = Color { a: 0.5, ..Color::from_rgb(0.6, 0.6, 1.0) };
+    let mut layers = vec![Layer::new("Layer 1".to_string())];
+    for color in [light_blue, dark_red, dark_red] {
+        let (id, widget) = circle(color);
+        layers[0].insert(id, widget);
+    }
+    assert_eq!(vec![dark_red, light_blue], get_document_colors(&layers));
+
+    let palette = [Color::from_rgb(1.0, 0.0, 0.0), Color::from_rgb(0.0, 0.0, 1.0)];
+    let remap = get_palette_remap(&get_document_colors(&layers), &palette);
+    assert_eq!(Color::from_rgb(1.0, 0.0, 0.0), get_remapped_color(&remap, dark_red));
+    assert_eq!(Color::from_rgba(0.0, 0.0, 1.0, 0.5), get_remapped_color(&remap, light_blue));
+    assert_eq!(Color::BLACK, get_remapped_color(&remap, Color::BLACK));
+
+    let preview = get_remapped_curves(&layers[0].curves, &remap);
+    assert_eq!(vec![Color::from_rgb(1.0, 0.0, 0.0), Color::from_rgba(0.0, 0.0, 1.0, 0.5)],
+        get_document_colors(&[Layer { curves: preview, ..Layer::new("Preview".to_string()) }]));
+
+    let replaced = remap_colors(&mut layers, &remap);
+    assert_eq!(3, replaced.len());
+    restore_colors(&mut layers, &replaced);
+    assert_eq!(vec![dark_red, light_blue], get_document_colors(&layers));
+}
@@ -0,0 +1,139 @@
+//! resize
+//! Scales the whole drawing for a different output size, by a factor
+//! or to fit an artboard.  Everything is scaled about the top left
+//! corner of the canvas, the stroke widths included, so the drawing
+//! looks the same, only bigger or smaller.  The stroke widths can also
+//! be scaled alone, thickened for print without moving anything.
+
+use iced::{Point, Rectangle, Size};
+
+use crate::draw_canvas::get_widget_bounds;
+use crate::ids::WidgetId;
+use crate::layers::Layer;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resize {
+    Factor(f32),
+    // fits the drawing into an artboard of this size
+    Fit(Size),
+}
+
+impl Resize {
+    // "1.5" is a factor, "800x600" is an artboard.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim().to_lowercase();
+        match input.split_once('x') {
+            Some((width, height)) => Ok(Resize::Fit(Size::new(parse_factor(width)?, parse_factor(height)?))),
+            None => Ok(Resize::Factor(parse_factor(&input)?)),
+        }
+    }
+
+    // The factor for a drawing with these bounds.  The space
+    // between the canvas corner and the drawing is kept in the fit.
+    pub fn factor(&self, bounds: Rectangle) -> f32 {
+        match self {
+            Resize::Factor(factor) => *factor,
+            Resize::Fit(size) => {
+                let right = bounds.x + bounds.width;
+                let bottom = bounds.y + bounds.height;
+                if right <= 0.0 || bottom <= 0.0 {
+                    return 1.0
+                }
+                (size.width / right).min(size.height / bottom)
+            },
+        }
+    }
+}
+
+pub fn parse_factor(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(value) if value > 0.0 && value.is_finite() => Ok(value),
+        _ => Err(format!("{} is not a positive number", value.trim())),
+    }
+}
+
+// The box around every widget of every layer, None when empty.
+pub fn get_drawing_bounds(layers: &[Layer]) -> Option<Rectangle> {
+    layers.iter()
+        .flat_map(|layer| layer.curves.values())
+        .filter_map(get_widget_bounds)
+        .reduce(|bounds, next| bounds.union(&next))
+}
+
+// The drawing bounds after the resize.
+pub fn get_resized_bounds(bounds: Rectangle, factor: f32) -> Rectangle {
+    Rectangle::new(
+        Point::new(bounds.x * factor, bounds.y * factor),
+        bounds.size() * factor,
+    )
+}
+
+pub fn resize_layers(layers: &mut [Layer], factor: f32) {
+    for layer in layers.iter_mut() {
+        for widget in layer.curves.values_mut() {
+            if let Some(wt) = widget.as_trait_mut() {
+                wt.scale(Point::ORIGIN, factor);
+                wt.scale_width(factor);
+            }
+        }
+    }
+}
+
+// Only the stroke widths of the widgets, of all of them when no ids
+// are given.  A freehand's pressure widths are scaled along.
+pub fn scale_widths(layers: &mut [Layer], ids: &[WidgetId], factor: f32) {
+    for layer in layers.iter_mut() {
+        for (id, widget) in layer.curves.iter_mut() {
+            if !ids.is_empty() && !ids.contains(id) {
+                continue
+            }
+            if let Some(wt) = widget.as_trait_mut() {
+                wt.scale_width(factor);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn test_resize() {
+    assert_eq!(Ok(Resize::Factor(2.0)), Resize::parse(" 2 "));
+    assert_eq!(Ok(Resize::Fit(Size::new(800.0, 600.0))), Resize::parse("800 X 600"));
+    assert!(Resize::parse("0").is_err());
+    assert!(Resize::parse("800x").is_err());
+
+    let bounds = Rectangle::new(Point::new(10.0, 10.0), Size::new(190.0, 90.0));
+    // 200 wide and 100 high to the corner, the width limits it
+    assert_eq!(2.0, Resize::Fit(Size::new(400.0, 400.0)).factor(bounds));
+    assert_eq!(Rectangle::new(Point::new(20.0, 20.0), Size::new(380.0, 180.0)), get_resized_bounds(bounds, 2.0));
+
+    use crate::draw_canvas::{get_widget_width, CanvasWidget};
+    use crate::test_widgets::line;
+
+    let line = line(Point::new(10.0, 10.0), Point::new(30.0, 10.0));
+    let id = line.id;
+    let mut layer = Layer::new("Layer 1".to_string());
+    layer.insert(id, CanvasWidget::Line(line));
+    let mut layers = vec![layer];
+    resize_layers(&mut layers, 1.5);
+    let Some(CanvasWidget::Line(line)) = layers[0].curves.get(&id) else {
+        panic!("the line is missing")
+    };
+    assert_eq!(vec![Point::new(15.0, 15.0), Point::new(45.0, 15.0)], line.points);
+    assert_eq!(3.0, get_widget_width(&layers[0].curves[&id]));
+    // half the stroke width is in the bounds
+    assert_eq!(Some(Rectangle::new(Point::new(13.5, 13.5), Size::new(33.0, 3.0))), get_drawing_bounds(&layers));
+
+    // the widths alone, the points stay
+    scale_widths(&mut layers, &[], 2.0);
+    assert_eq!(6.0, get_widget_width(&layers[0].curves[&id]));
+    scale_widths(&mut layers, &[WidgetId::unique()], 2.0);
+    assert_eq!(6.0, get_widget_width(&layers[0].curves[&id]));
+    let Some(CanvasWidget::Line(line)) = layers[0].curves.get(&id) else {
+        panic!("the line is missing")
+    };
+    assert_eq!(vec![Point::new(15.0, 15.0), Point::new(45.0, 15.0)], line.points);
+    assert_eq!(Ok(1.5), parse_factor(" 1.5"));
+    assert!(parse_factor("-1").is_err());
+}
@@ -0,0 +1,302 @@
+//! round_trip
+//! Property tests that every widget comes back from a save and load
+//! with the same geometry, only off by the float error of the json.
+
+use iced::widget::text::{LineHeight, Shaping};
+use iced::{alignment, Color, Pixels, Point, Radians, Vector};
+use proptest::prelude::*;
+
+use crate::draw_canvas::{get_widget_color, get_widget_type, get_widget_width, Arc, ArcClosure, Bezier, CanvasWidget,
+    Circle, DrawMode, DrawStatus, Ellipse, FreeHand, Gauge, Line, PolyLine, PolyLineCenter, Polygon, RightTriangle, RoundedRect, Text};
+use crate::ids::WidgetId;
+use crate::text_edit::TextCursor;
+use crate::text_style::{FontFamily, TextStyle};
+use crate::app::{convert_widget_to_export, import_widget, ExportWidget};
+
+
+// Relative to the size of the number, json keeps
+// a float to about one in ten million.
+const TOLERANCE: f32 = 1.0e-6;
+
+// Saved and loaded the way a drawing is.
+fn round_trip(widget: &CanvasWidget) -> CanvasWidget {
+    let data = serde_json::to_string(&vec![convert_widget_to_export(widget)]).unwrap();
+    let widgets: Vec<ExportWidget> = serde_json::from_str(&data).unwrap();
+    import_widget(&widgets[0])
+}
+
+// Every number that shapes the widget.
+fn numbers(widget: &CanvasWidget) -> Vec<f32> {
+    let xy = |pts: &[Point]| pts.iter().flat_map(|pt| [pt.x, pt.y]).collect::<Vec<f32>>();
+    let color = get_widget_color(widget);
+    let mut numbers = vec![color.r, color.g, color.b, color.a, get_widget_width(widget)];
+    match widget {
+        CanvasWidget::None => (),
+        CanvasWidget::Arc(arc) => {
+            numbers.extend(xy(&arc.points));
+            numbers.extend([arc.mid_point.x, arc.mid_point.y, arc.radius, arc.start_angle.0, arc.end_angle.0]);
+        },
+        CanvasWidget::Bezier(bz) => {
+            numbers.extend(xy(&bz.points));
+            numbers.extend([bz.mid_point.x, bz.mid_point.y, bz.degrees]);
+        },
+        CanvasWidget::Circle(cir) => {
+            numbers.extend(xy(&[cir.center, cir.circle_point]));
+            numbers.push(cir.radius);
+        },
+        CanvasWidget::Ellipse(ell) => {
+            numbers.extend(xy(&ell.points));
+            numbers.extend([ell.center.x, ell.center.y, ell.radii.x, ell.radii.y, ell.rotation.0]);
+        },
+        CanvasWidget::Line(ln) => {
+            numbers.extend(xy(&ln.points));
+            numbers.extend([ln.mid_point.x, ln.mid_point.y, ln.degrees]);
+        },
+        CanvasWidget::Polygon(pg) => {
+            numbers.extend(xy(&pg.points));
+            numbers.extend(xy(&[pg.mid_point, pg.pg_point]));
+            numbers.extend([pg.degrees, pg.poly_points as f32]);
+        },
+        CanvasWidget::PolyLine(pl) => {
+            numbers.extend(xy(&pl.points));
+            numbers.extend(xy(&[pl.mid_point, pl.pl_point]));
+            numbers.extend([pl.degrees, pl.poly_points as f32]);
+        },
+        CanvasWidget::RightTriangle(tr) => {
+            numbers.extend(xy(&tr.points));
+            numbers.extend(xy(&[tr.mid_point, tr.tr_point]));
+            numbers.push(tr.degrees);
+        },
+        CanvasWidget::RoundedRect(rr) => {
+            numbers.extend(xy(&rr.points));
+            numbers.extend([rr.mid_point.x, rr.mid_point.y, rr.radius, rr.degrees]);
+        },
+        CanvasWidget::FreeHand(fh) => {
+            numbers.extend(xy(fh.points.as_slice()));
+            numbers.extend(fh.widths.iter());
+        },
+        CanvasWidget::Gauge(gauge) => {
+            numbers.extend(xy(&[gauge.center, gauge.gauge_point]));
+            numbers.extend([gauge.radius, gauge.min, gauge.max, gauge.value]);
+        },
+        CanvasWidget::Text(txt) => {
+            let style = TextStyle::from_text(txt);
+            numbers.extend([txt.position.x, txt.position.y, txt.degrees, style.size, style.line_height]);
+        },
+    }
+    numbers
+}
+
+// What isn't a number, the text and its style.
+fn labels(widget: &CanvasWidget) -> String {
+    match widget {
+        CanvasWidget::Text(txt) => {
+            let style = TextStyle::from_text(txt);
+            format!("{:?} {:?} {:?} {:?} {} {}", txt.content, txt.horizontal_alignment, txt.vertical_alignment,
+                style.family, style.bold, style.italic)
+        },
+        CanvasWidget::FreeHand(fh) => format!("{}", fh.smooth),
+        CanvasWidget::PolyLine(pl) => format!("{:?} {}", pl.center, pl.closed),
+        CanvasWidget::Arc(arc) => format!("{:?}", arc.closure),
+        _ => String::new(),
+    }
+}
+
+fn assert_same_widget(saved: &CanvasWidget, loaded: &CanvasWidget) {
+    assert_eq!(get_widget_type(saved), get_widget_type(loaded));
+    assert_eq!(labels(saved), labels(loaded));
+    let (before, after) = (numbers(saved), numbers(loaded));
+    assert_eq!(before.len(), after.len());
+    for (a, b) in before.iter().zip(after.iter()) {
+        assert!((a - b).abs() <= TOLERANCE * a.abs().max(1.0), "{} was loaded as {} in {:?}", a, b, loaded);
+    }
+}
+
+fn coordinate() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        -2000.0f32..2000.0,
+        Just(0.0f32),
+        -1.0e30f32..1.0e30,
+    ]
+}
+
+fn point() -> impl Strategy<Value = Point> {
+    (coordinate(), coordinate()).prop_map(|(x, y)| Point::new(x, y))
+}
+
+fn points(count: std::ops::Range<usize>) -> impl Strategy<Value = Vec<Point>> {
+    prop::collection::vec(point(), count)
+}
+
+fn radius() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        Just(0.0f32),
+        0.0f32..2000.0,
+        0.0f32..1.0e30,
+    ]
+}
+
+// Negative angles and more than a turn are kept as they are.
+fn angle() -> impl Strategy<Value = f32> {
+    -1000.0f32..1000.0
+}
+
+fn color() -> impl Strategy<Value = Color> {
+    (0.0f32..=1.0, 0.0f32..=1.0, 0.0f32..=1.0, 0.0f32..=1.0).prop_map(|(r, g, b, a)| Color::from_rgba(r, g, b, a))
+}
+
+fn width() -> impl Strategy<Value = f32> {
+    0.0f32..50.0
+}
+
+fn closure() -> impl Strategy<Value = ArcClosure> {
+    prop_oneof![Just(ArcClosure::Open), Just(ArcClosure::Chord), Just(ArcClosure::Pie)]
+}
+
+fn widget() -> impl Strategy<Value = CanvasWidget> {
+    prop_oneof![
+        (point(), radius(), angle(), angle(), color(), width(), closure())
+            .prop_map(|(center, radius, start, end, color, width, closure)| {
+                let mut arc = new_arc(center, radius, start, end, color, width);
+                if let CanvasWidget::Arc(arc) = &mut arc {
+                    arc.closure = closure;
+                }
+                arc
+            }),
+        (points(3..4), angle(), color(), width()).prop_map(|(points, degrees, color, width)| {
+            CanvasWidget::Bezier(Bezier { id: WidgetId::unique(), mid_point: points[1], points, color, width, degrees,
+                draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (point(), radius(), color(), width()).prop_map(|(center, radius, color, width)| {
+            CanvasWidget::Circle(Circle { id: WidgetId::unique(), center, circle_point: center + Vector::new(radius, 0.0),
+                radius, color, width, hatch: None, draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (point(), radius(), radius(), angle(), color(), width()).prop_map(|(center, rx, ry, rotation, color, width)| {
+            new_ellipse(center, Vector::new(rx, ry), rotation, color, width)
+        }),
+        (points(2..3), angle(), color(), width()).prop_map(|(points, degrees, color, width)| {
+            CanvasWidget::Line(Line { id: WidgetId::unique(), mid_point: points[0], points, color, width, degrees,
+                draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (points(3..9), point(), point(), angle(), color(), width()).prop_map(|(points, mid_point, pg_point, degrees, color, width)| {
+            CanvasWidget::Polygon(Polygon { id: WidgetId::unique(), poly_points: points.len(), points, mid_point, pg_point,
+                color, width, hatch: None, degrees, draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (points(2..9), point(), point(), any::<bool>(), any::<bool>(), angle(), color(), width())
+            .prop_map(|(points, mid_point, pl_point, bounds, closed, degrees, color, width)| {
+            let center = if bounds { PolyLineCenter::BoundsCenter } else { PolyLineCenter::Centroid };
+            CanvasWidget::PolyLine(PolyLine { id: WidgetId::unique(), poly_points: points.len(), points, mid_point, pl_point,
+                center, closed, color, width, hatch: None, degrees, draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (points(3..4), point(), point(), angle(), color(), width()).prop_map(|(points, mid_point, tr_point, degrees, color, width)| {
+            CanvasWidget::RightTriangle(RightTriangle { id: WidgetId::unique(), points, mid_point, tr_point,
+                color, width, hatch: None, degrees, draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (points(5..6), radius(), angle(), color(), width()).prop_map(|(points, radius, degrees, color, width)| {
+            CanvasWidget::RoundedRect(RoundedRect { id: WidgetId::unique(), mid_point: points[0], points, radius,
+                color, width, hatch: None, degrees, draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed })
+        }),
+        (points(2..30), any::<bool>(), color(), width()).prop_map(|(points, smooth, color, width)| {
+            let widths = points.iter().enumerate().map(|(i, _)| width * (i % 3 + 1) as f32).collect();
+            CanvasWidget::FreeHand(FreeHand { id: WidgetId::unique(), points: points.into_iter().collect(), color, width,
+                widths, smooth, draw_mode: DrawMode::DrawAll, status: DrawStatus::Completed, completed: true })
+        }),
+        (point(), radius(), -1000.0f32..1000.0, 0.0f32..1000.0, any::<f32>(), color(), width())
+            .prop_filter("a finite value", |(.., value, _, _)| value.is_finite())
+            .prop_map(|(center, radius, min, range, value, color, width)| {
+                CanvasWidget::Gauge(Gauge { id: WidgetId::unique(), center, gauge_point: center + Vector::new(0.0, radius),
+                    radius, min, max: min + range, value, color, width, draw_mode: DrawMode::DrawAll,
+                    status: DrawStatus::Completed })
+            }),
+        ("[a-zA-Z0-9 \r]{0,20}", point(), angle(), color(), text_style(), 0usize..3, 0usize..3)
+            .prop_map(|(content, position, degrees, color, style, h, v)| new_text(content, position, degrees, color, style, h, v)),
+    ]
+}
+
+fn text_style() -> impl Strategy<Value = TextStyle> {
+    (0usize..3, 0.0f32..200.0, 0.5f32..3.0, any::<bool>(), any::<bool>()).prop_map(|(family, size, line_height, bold, italic)| {
+        let family = [FontFamily::SansSerif, FontFamily::Serif, FontFamily::Monospace][family];
+        TextStyle { family, size, line_height, bold, italic }
+    })
+}
+
+fn new_arc(center: Point, radius: f32, start: f32, end: f32, color: Color, width: f32) -> CanvasWidget {
+    let on_arc = |angle: f32| center + Vector::new(radius * angle.cos(), radius * angle.sin());
+    CanvasWidget::Arc(Arc {
+        id: WidgetId::unique(),
+        points: vec![center, on_arc(start), on_arc(end)],
+        mid_point: center,
+        radius,
+        color,
+        width,
+        hatch: None,
+        start_angle: Radians(start),
+        end_angle: Radians(end),
+        closure: ArcClosure::Open,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    })
+}
+
+// The points are the center and the ends of the turned axes.
+fn new_ellipse(center: Point, radii: Vector, rotation: f32, color: Color, width: f32) -> CanvasWidget {
+    let (sin, cos) = rotation.sin_cos();
+    CanvasWidget::Ellipse(Ellipse {
+        id: WidgetId::unique(),
+        points: vec![center, center + Vector::new(radii.x * cos, radii.x * sin), center + Vector::new(-radii.y * sin, radii.y * cos)],
+        center,
+        radii,
+        rotation: Radians(rotation),
+        color,
+        width,
+        hatch: None,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    })
+}
+
+fn new_text(content: String, position: Point, degrees: f32, color: Color, style: TextStyle, h: usize, v: usize) -> CanvasWidget {
+    let mut txt = Text {
+        id: WidgetId::unique(),
+        content,
+        position,
+        color,
+        size: Pixels(16.0),
+        line_height: LineHeight::Relative(1.2),
+        font: Default::default(),
+        horizontal_alignment: [alignment::Horizontal::Left, alignment::Horizontal::Center, alignment::Horizontal::Right][h],
+        vertical_alignment: [alignment::Vertical::Top, alignment::Vertical::Center, alignment::Vertical::Bottom][v],
+        shaping: Shaping::Basic,
+        degrees,
+        cursor: TextCursor::default(),
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    };
+    style.apply(&mut txt);
+    CanvasWidget::Text(txt)
+}
+
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn test_round_trip(widget in widget()) {
+        assert_same_widget(&widget, &round_trip(&widget));
+    }
+}
+
+#[test]
+fn test_round_trip_edge_cases() {
+    let far = Point::new(1.0e30, -1.0e30);
+    let widgets = [
+        new_arc(Point::ORIGIN, 0.0, -3.0, -0.5, Color::BLACK, 2.0),
+        // a small ellipse far out has no room between its points
+        new_ellipse(far, Vector::new(5.0, 3.0), -0.5, Color::BLACK, 2.0),
+        new_ellipse(Point::ORIGIN, Vector::new(0.0, 0.0), 0.0, Color::BLACK, 0.0),
+        new_text(String::new(), far, -725.0, Color::TRANSPARENT, TextStyle::default(), 0, 2),
+    ];
+    for widget in widgets.iter() {
+        assert_same_widget(widget, &round_trip(widget));
+    }
+}
@@ -0,0 +1,137 @@
+//! rulers
+//! The rulers along the top and left edges of the canvas and the
+//! cursor position readout in the bottom right corner.  The rulers
+//! only change with the canvas size so they have their own cache, the
+//! readout and the cursor marks on the rulers follow every move.
+//! The canvas has no zoom or pan, a frame point is a drawing point.
+
+use iced::widget::canvas::{self, Frame, Path, Stroke};
+use iced::{alignment, Color, Point, Size, Theme};
+
+
+pub const RULER_SIZE: f32 = 20.0;
+// A short tick every 10 pixels, a longer one every 50
+// and a numbered one every 100.
+const TICK_SPACING: f32 = 10.0;
+const LABEL_SPACING: usize = 10;
+const READOUT_SIZE: f32 = 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tick {
+    Short,
+    Middle,
+    Labeled,
+}
+
+impl Tick {
+    fn length(&self) -> f32 {
+        match self {
+            Tick::Short => RULER_SIZE * 0.25,
+            Tick::Middle => RULER_SIZE * 0.5,
+            Tick::Labeled => RULER_SIZE,
+        }
+    }
+}
+
+// The ticks along a ruler of the length, past the corner
+// where the rulers meet.
+pub fn get_ticks(length: f32) -> Vec<(f32, Tick)> {
+    let count = (length / TICK_SPACING).floor() as usize;
+    (1..=count)
+        .map(|index| {
+            let tick =
+                if index % LABEL_SPACING == 0 {
+                    Tick::Labeled
+                } else if index % (LABEL_SPACING / 2) == 0 {
+                    Tick::Middle
+                } else {
+                    Tick::Short
+                };
+            (index as f32 * TICK_SPACING, tick)
+        })
+        .filter(|(position, _)| *position > RULER_SIZE)
+        .collect()
+}
+
+pub fn format_position(point: Point) -> String {
+    format!("x {:.0}  y {:.0}", point.x, point.y)
+}
+
+pub fn draw_rulers(frame: &mut Frame, theme: &Theme) {
+    let size = frame.size();
+    let band = Color { a: 0.85, ..theme.palette().background };
+    let color = theme.palette().text;
+    frame.fill_rectangle(Point::ORIGIN, Size::new(size.width, RULER_SIZE), band);
+    frame.fill_rectangle(Point::ORIGIN, Size::new(RULER_SIZE, size.height), band);
+
+    let stroke = Stroke::default().with_width(1.0).with_color(color);
+    let ticks = Path::new(|builder| {
+        for (x, tick) in get_ticks(size.width) {
+            builder.move_to(Point::new(x, RULER_SIZE));
+            builder.line_to(Point::new(x, RULER_SIZE - tick.length()));
+        }
+        for (y, tick) in get_ticks(size.height) {
+            builder.move_to(Point::new(RULER_SIZE, y));
+            builder.line_to(Point::new(RULER_SIZE - tick.length(), y));
+        }
+        builder.move_to(Point::new(RULER_SIZE, size.height));
+        builder.line_to(Point::new(RULER_SIZE, RULER_SIZE));
+        builder.line_to(Point::new(size.width, RULER_SIZE));
+    });
+    frame.stroke(&ticks, stroke);
+
+    // the numbers beside the long ticks, the left ones turned up
+    let labels = |position: f32| canvas::Text {
+        content: format!("{position:.0}"),
+        color,
+        size: iced::Pixels(9.0),
+        ..canvas::Text::default()
+    };
+    for (x, _) in get_ticks(size.width).into_iter().filter(|(_, tick)| *tick == Tick::Labeled) {
+        frame.fill_text(canvas::Text { position: Point::new(x + 2.0, 1.0), ..labels(x) });
+    }
+    for (y, _) in get_ticks(size.height).into_iter().filter(|(_, tick)| *tick == Tick::Labeled) {
+        frame.with_save(|frame| {
+            frame.translate(iced::Vector::new(1.0, y - 2.0));
+            frame.rotate(-std::f32::consts::FRAC_PI_2);
+            frame.fill_text(labels(y));
+        });
+    }
+}
+
+// The cursor marks on the rulers and its position in the corner.
+pub fn draw_cursor_readout(frame: &mut Frame, cursor: Point, theme: &Theme) {
+    let size = frame.size();
+    let marks = Path::new(|builder| {
+        builder.move_to(Point::new(cursor.x, 0.0));
+        builder.line_to(Point::new(cursor.x, RULER_SIZE));
+        builder.move_to(Point::new(0.0, cursor.y));
+        builder.line_to(Point::new(RULER_SIZE, cursor.y));
+    });
+    frame.stroke(&marks, Stroke::default().with_width(1.0).with_color(theme.palette().danger));
+
+    frame.fill_text(canvas::Text {
+        content: format_position(cursor),
+        position: Point::new(size.width - 6.0, size.height - 6.0),
+        color: theme.palette().text,
+        size: iced::Pixels(READOUT_SIZE),
+        horizontal_alignment: alignment::Horizontal::Right,
+        vertical_alignment: alignment::Vertical::Bottom,
+        ..canvas::Text::default()
+    });
+}
+
+
+#[test]
+fn test_ruler_ticks() {
+    let ticks = get_ticks(205.0);
+    // the ticks under the corner are left out
+    assert_eq!(Some(&(30.0, Tick::Short)), ticks.first());
+    assert_eq!(Some(&(200.0, Tick::Labeled)), ticks.last());
+    assert!(ticks.contains(&(50.0, Tick::Middle)));
+    assert!(ticks.contains(&(100.0, Tick::Labeled)));
+    assert_eq!(18, ticks.len());
+    assert!(get_ticks(15.0).is_empty());
+
+    assert_eq!("x 120  y 46", format_position(Point::new(120.4, 45.6)));
+}
@@ -0,0 +1,87 @@
+//! settings
+//! User settings that are kept between sessions.
+
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::drawing_file::SaveFormat;
+use crate::pressure::PressureCurve;
+use crate::preview::PreviewQuality;
+use crate::themes::AppTheme;
+
+
+pub const SETTINGS_PATH: &str = "./resources/settings.json";
+pub const GRAB_RADIUS_RANGE: RangeInclusive<f32> = 4.0..=30.0;
+pub const PRECISION_RANGE: RangeInclusive<u8> = 0..=6;
+pub const BACKUPS_RANGE: RangeInclusive<u8> = 0..=10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub pressure_curve: PressureCurve,
+    pub stabilizer: bool,
+    pub stabilizer_strength: f32,
+    pub smoothing: bool,
+    pub smoothing_strength: f32,
+    pub live_url: String,
+    pub optimize: bool,
+    // the separate Edit and Rotate modes beside the Select tool
+    pub classic_modes: bool,
+    // how near in pixels a click grabs a widget or handle
+    pub grab_radius: f32,
+    // the decimal places of the exported coordinates
+    pub precision: u8,
+    // the saved json coordinates are rounded to the precision too
+    pub round_coordinates: bool,
+    pub save_format: SaveFormat,
+    // the older saves kept beside a drawing saved over
+    pub backups: u8,
+    // how many points a dragged or rotated widget is drawn with
+    pub preview_quality: PreviewQuality,
+    // the Resize mode scales the stroke width along with the widget
+    pub resize_widths: bool,
+    // the draw colors last picked, newest first, as rgba
+    pub recent_colors: Vec<[f32; 4]>,
+    // the first draw and canvas colors come from the theme
+    pub theme: AppTheme,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            pressure_curve: PressureCurve::default(),
+            stabilizer: false,
+            stabilizer_strength: 20.0,
+            smoothing: false,
+            smoothing_strength: 2.0,
+            live_url: "ws://127.0.0.1:9001".to_string(),
+            optimize: false,
+            classic_modes: false,
+            grab_radius: 10.0,
+            precision: 2,
+            round_coordinates: false,
+            save_format: SaveFormat::Pretty,
+            backups: 3,
+            preview_quality: PreviewQuality::Balanced,
+            resize_widths: false,
+            recent_colors: vec![],
+            theme: AppTheme::default(),
+        }
+    }
+}
+
+// A missing or unreadable file gives the default settings.
+pub fn load_settings() -> Settings {
+    fs::read_to_string(Path::new(SETTINGS_PATH))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(settings: &Settings) -> std::io::Result<()> {
+    let data = serde_json::to_string_pretty(settings)?;
+    fs::write(Path::new(SETTINGS_PATH), data)
+}
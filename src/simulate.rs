@@ -0,0 +1,1271 @@
+//! simulate
+//! Plays scripted mouse and key events through the canvas program the
+//! way iced does and hands the widgets it returns to the app update,
+//! so the click flows of each mode are tested from end to end.
+
+use iced::keyboard::{self, key, Key, Modifiers};
+use iced::widget::canvas::{Event, Program};
+use iced::{mouse, Color, Point, Rectangle, Size, Vector};
+
+use crate::draw_canvas::{get_draw_mode_and_status, get_rotate_handle, get_widget_color, get_widget_hatch, get_widget_id, get_widget_width, Arc, CanvasWidget, DrawMode, DrawPending, DrawStatus, Pending, Widget};
+use crate::app::{convert_widget_to_export, import_widget, CanvasDraw, ExportLayer, Message};
+use crate::hatch::{get_widget_hatch_marks, Hatch, HatchPattern};
+use crate::helpers::get_polyline_center;
+use crate::settings::Settings;
+use crate::title_block::TitleField;
+
+
+// Large enough that every scripted point is on the canvas.
+const CANVAS: Size = Size::new(1000.0, 1000.0);
+
+#[derive(Default)]
+pub struct Simulation {
+    pub app: CanvasDraw,
+    pub pending: Option<Pending>,
+}
+
+impl Simulation {
+    // Ready to draw the widget in New mode, without the
+    // stabilizer, smoothing or grab radius of the saved settings,
+    // and with the classic Edit and Rotate modes.
+    pub fn new(widget: Widget) -> Self {
+        let mut simulation = Simulation::default();
+        simulation.app.canvas_state.stabilizer = false;
+        simulation.app.canvas_state.smoothing = false;
+        simulation.app.canvas_state.grab_radius = Settings::default().grab_radius;
+        simulation.app.canvas_state.classic_modes = true;
+        simulation.send(Message::RadioSelected(widget));
+        simulation.set_mode(DrawMode::New);
+        simulation
+    }
+
+    pub fn set_mode(&mut self, mode: DrawMode) {
+        self.pending = None;
+        self.send(Message::ModeSelected(mode.string().unwrap_or_default()));
+    }
+
+    pub fn send(&mut self, message: Message) {
+        let _ = self.app.update(message);
+    }
+
+    pub fn click(&mut self, x: f32, y: f32) {
+        self.press(x, y);
+        self.release(x, y);
+    }
+
+    pub fn press(&mut self, x: f32, y: f32) {
+        self.event(Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)), Point::new(x, y));
+    }
+
+    pub fn release(&mut self, x: f32, y: f32) {
+        self.event(Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)), Point::new(x, y));
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        let position = Point::new(x, y);
+        self.event(Event::Mouse(mouse::Event::CursorMoved { position }), position);
+    }
+
+    pub fn scroll(&mut self, x: f32, y: f32, lines: f32) {
+        let delta = mouse::ScrollDelta::Lines { x: 0.0, y: lines };
+        self.event(Event::Mouse(mouse::Event::WheelScrolled { delta }), Point::new(x, y));
+    }
+
+    pub fn key(&mut self, key: Key) {
+        self.key_with(key, Modifiers::default());
+    }
+
+    pub fn key_with(&mut self, key: Key, modifiers: Modifiers) {
+        self.key_event(key, modifiers, Point::ORIGIN);
+    }
+
+    // A key pressed with the cursor over the point.
+    pub fn key_at(&mut self, x: f32, y: f32, key: Key) {
+        self.key_event(key, Modifiers::default(), Point::new(x, y));
+    }
+
+    fn key_event(&mut self, key: Key, modifiers: Modifiers, cursor: Point) {
+        let event =
+            keyboard::Event::KeyPressed {
+                key: key.clone(),
+                modified_key: key,
+                physical_key: key::Physical::Unidentified(key::NativeCode::Unidentified),
+                location: keyboard::Location::Standard,
+                modifiers,
+                text: None,
+            };
+        self.event(Event::Keyboard(event), cursor);
+    }
+
+    pub fn modifiers(&mut self, modifiers: Modifiers) {
+        self.event(Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)), Point::ORIGIN);
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key(Key::Character(c.to_string().into()));
+        }
+    }
+
+    // The widgets of the active layer, the text last.
+    pub fn widgets(&self) -> Vec<CanvasWidget> {
+        let state = &self.app.canvas_state;
+        let (texts, mut widgets): (Vec<CanvasWidget>, Vec<CanvasWidget>) = 
+            state.curves().values()
+                .cloned()
+                .partition(|widget| matches!(widget, CanvasWidget::Text(_)));
+        widgets.extend(texts);
+        widgets
+    }
+
+    pub fn only_widget(&self) -> CanvasWidget {
+        let widgets = self.widgets();
+        assert_eq!(1, widgets.len(), "expected a single widget");
+        widgets[0].clone()
+    }
+
+    fn event(&mut self, event: Event, cursor: Point) {
+        let state = &self.app.canvas_state;
+        let program =
+            DrawPending {
+                state,
+                curves: state.curves(),
+            };
+        let (_, message) =
+            program.update(
+                &mut self.pending,
+                event,
+                Rectangle::new(Point::ORIGIN, CANVAS),
+                mouse::Cursor::Available(cursor),
+            );
+        if let Some(message) = message {
+            self.send(Message::Canvas(message));
+        }
+    }
+}
+
+fn assert_points(expected: &[(f32, f32)], points: &[Point]) {
+    assert_eq!(expected.len(), points.len());
+    for ((x, y), point) in expected.iter().zip(points.iter()) {
+        assert!(point.distance(Point::new(*x, *y)) < 1.0e-3, "{:?} is not at ({}, {})", point, x, y);
+    }
+}
+
+
+#[test]
+fn test_simulate_new_widgets() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    assert!(sim.widgets().is_empty());
+    sim.click(200.0, 100.0);
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert_points(&[(100.0, 100.0), (200.0, 100.0)], &line.points);
+    assert_eq!(Point::new(150.0, 100.0), line.mid_point);
+    assert!(sim.pending.is_none());
+
+    let mut sim = Simulation::new(Widget::Circle);
+    sim.click(100.0, 100.0);
+    sim.click(130.0, 140.0);
+    let CanvasWidget::Circle(cir) = sim.only_widget() else {
+        panic!("not a circle")
+    };
+    assert_eq!(Point::new(100.0, 100.0), cir.center);
+    assert_eq!(50.0, cir.radius);
+}
+
+#[test]
+fn test_simulate_right_triangle() {
+    // the right angle up and to the left, then the second leg lengthened
+    let mut sim = Simulation::new(Widget::RightTriangle);
+    sim.click(200.0, 200.0);
+    sim.click(100.0, 200.0);
+    sim.click(98.0, 150.0);
+    let CanvasWidget::RightTriangle(tr) = sim.only_widget() else {
+        panic!("not a right triangle")
+    };
+    assert_points(&[(200.0, 200.0), (100.0, 200.0), (100.0, 150.0), (200.0, 200.0)], &tr.points);
+    assert_eq!(Point::new(100.0, 200.0), tr.mid_point);
+
+    sim.set_mode(DrawMode::Edit);
+    sim.click(150.0, 199.0);
+    sim.click(100.0, 150.0);
+    sim.click(90.0, 120.0);
+    let CanvasWidget::RightTriangle(tr) = sim.only_widget() else {
+        panic!("not a right triangle")
+    };
+    assert_points(&[(200.0, 200.0), (100.0, 200.0), (100.0, 120.0), (200.0, 200.0)], &tr.points);
+}
+
+#[test]
+fn test_simulate_rounded_rect() {
+    let mut sim = Simulation::new(Widget::RoundedRect);
+    sim.click(100.0, 100.0);
+    sim.click(300.0, 200.0);
+    let CanvasWidget::RoundedRect(rr) = sim.only_widget() else {
+        panic!("not a rounded rectangle")
+    };
+    assert_points(&[(100.0, 100.0), (300.0, 100.0), (300.0, 200.0), (100.0, 200.0), (100.0, 100.0)], &rr.points);
+    assert_eq!(25.0, rr.radius);
+
+    // the radius handle is held to half the shorter side, a capsule
+    sim.set_mode(DrawMode::Edit);
+    sim.click(200.0, 101.0);
+    sim.click(125.0, 100.0);
+    sim.click(160.0, 130.0);
+    let CanvasWidget::RoundedRect(rr) = sim.only_widget() else {
+        panic!("not a rounded rectangle")
+    };
+    assert_eq!(50.0, rr.radius);
+
+    // the opposite corner stays put
+    sim.click(200.0, 101.0);
+    sim.click(300.0, 200.0);
+    sim.click(400.0, 250.0);
+    let CanvasWidget::RoundedRect(rr) = sim.only_widget() else {
+        panic!("not a rounded rectangle")
+    };
+    assert_points(&[(100.0, 100.0), (400.0, 100.0), (400.0, 250.0), (100.0, 250.0), (100.0, 100.0)], &rr.points);
+    assert_eq!(Point::new(250.0, 175.0), rr.mid_point);
+    assert_eq!(50.0, rr.radius);
+}
+
+#[test]
+fn test_simulate_poly_points() {
+    // two more sides with the keys, one less with the wheel
+    let mut sim = Simulation::new(Widget::Polygon);
+    sim.click(200.0, 200.0);
+    sim.key(Key::Character("+".into()));
+    sim.key(Key::Character("=".into()));
+    sim.scroll(200.0, 200.0, -1.0);
+    sim.click(200.0, 100.0);
+    let CanvasWidget::Polygon(pg) = sim.only_widget() else {
+        panic!("not a polygon")
+    };
+    assert_eq!(4, pg.poly_points);
+    assert_eq!("4", sim.app.canvas_state.selected_poly_points_str);
+
+    // not fewer points than clicked and one to go
+    let mut sim = Simulation::new(Widget::PolyLine);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    sim.key(Key::Character("-".into()));
+    sim.key(Key::Character("-".into()));
+    sim.scroll(200.0, 100.0, 1.0);
+    sim.click(200.0, 200.0);
+    assert!(sim.widgets().is_empty());
+    sim.click(100.0, 200.0);
+    let CanvasWidget::PolyLine(pl) = sim.only_widget() else {
+        panic!("not a polyline")
+    };
+    assert_eq!(4, pl.points.len());
+}
+
+#[test]
+fn test_simulate_polyline_nodes() {
+    let mut sim = Simulation::new(Widget::PolyLine);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    sim.click(200.0, 200.0);
+    let polyline = |sim: &Simulation| match sim.only_widget() {
+        CanvasWidget::PolyLine(pl) => pl,
+        _ => panic!("not a polyline"),
+    };
+
+    // an alt click on a segment adds a node on it
+    sim.set_mode(DrawMode::Edit);
+    sim.click(150.0, 101.0);
+    sim.modifiers(Modifiers::ALT);
+    sim.click(150.0, 102.0);
+    sim.modifiers(Modifiers::default());
+    let pl = polyline(&sim);
+    assert_points(&[(100.0, 100.0), (150.0, 100.0), (200.0, 100.0), (200.0, 200.0)], &pl.points);
+    assert_eq!(4, pl.poly_points);
+
+    // Delete over a node removes only the node
+    sim.key_at(201.0, 100.0, Key::Named(key::Named::Delete));
+    let pl = polyline(&sim);
+    assert_points(&[(100.0, 100.0), (150.0, 100.0), (200.0, 200.0)], &pl.points);
+    assert_eq!(pl.mid_point, get_polyline_center(&pl.points, pl.center));
+
+    // and the new node is edited like the others
+    sim.click(150.0, 101.0);
+    sim.click(200.0, 100.0);
+    assert!(sim.pending.is_none());
+    assert_points(&[(100.0, 100.0), (200.0, 100.0), (200.0, 200.0)], &polyline(&sim).points);
+}
+
+#[test]
+fn test_simulate_edit_line() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let id = get_widget_id(&sim.only_widget());
+
+    // select, pick the end point and put it down
+    sim.set_mode(DrawMode::Edit);
+    sim.click(150.0, 101.0);
+    sim.click(199.0, 100.0);
+    sim.click(200.0, 200.0);
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert_eq!(id, line.id);
+    assert_points(&[(100.0, 100.0), (200.0, 200.0)], &line.points);
+    assert_eq!(Point::new(150.0, 150.0), line.mid_point);
+
+    // the mid point moves the whole line
+    sim.click(150.0, 151.0);
+    sim.click(150.0, 150.0);
+    sim.click(170.0, 180.0);
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert_points(&[(120.0, 130.0), (220.0, 230.0)], &line.points);
+    assert_eq!(DrawMode::DrawAll, line.draw_mode);
+
+    // delete while selected
+    sim.click(170.0, 180.0);
+    sim.key(Key::Named(key::Named::Delete));
+    assert!(sim.widgets().is_empty());
+}
+
+#[test]
+fn test_simulate_cycle_selection() {
+    let mut sim = Simulation::new(Widget::Circle);
+    sim.click(100.0, 100.0);
+    sim.click(150.0, 100.0);
+    sim.click(120.0, 100.0);
+    sim.click(170.0, 100.0);
+    let circle = |sim: &Simulation, x: f32| {
+        sim.widgets().into_iter()
+            .find_map(|widget| match widget {
+                CanvasWidget::Circle(cir) if cir.circle_point.x == x => Some(cir),
+                _ => None,
+            })
+            .expect("the circle is missing")
+    };
+
+    // inside both, the closer outline is the second circle,
+    // Tab goes on to the first and a point of it is edited
+    sim.set_mode(DrawMode::Edit);
+    sim.click(105.0, 100.0);
+    sim.key(Key::Named(key::Named::Tab));
+    sim.click(150.0, 100.0);
+    sim.click(160.0, 100.0);
+    assert_eq!(60.0, circle(&sim, 160.0).radius);
+    let second = circle(&sim, 170.0);
+    assert_eq!(50.0, second.radius);
+    assert_eq!((DrawMode::DrawAll, DrawStatus::Completed), (second.draw_mode, second.status));
+
+    // an alt click does the same and lets the first circle be moved
+    sim.click(105.0, 100.0);
+    sim.modifiers(Modifiers::ALT);
+    sim.click(105.0, 100.0);
+    sim.modifiers(Modifiers::default());
+    sim.click(100.0, 100.0);
+    sim.click(90.0, 100.0);
+    assert_eq!(Point::new(90.0, 100.0), circle(&sim, 150.0).center);
+    assert_eq!(Point::new(120.0, 100.0), circle(&sim, 170.0).center);
+}
+
+#[test]
+fn test_simulate_select_tool() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let id = get_widget_id(&sim.only_widget());
+    let line_points = |sim: &Simulation| match sim.only_widget() {
+        CanvasWidget::Line(line) => line.points,
+        _ => panic!("not a line"),
+    };
+
+    // without the classic modes Edit isn't offered
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Edit);
+    assert_eq!(DrawMode::New, sim.app.canvas_state.draw_mode);
+    sim.set_mode(DrawMode::Select);
+
+    // a drag on the body selects and moves it
+    sim.press(120.0, 100.0);
+    sim.move_to(120.0, 130.0);
+    sim.release(120.0, 130.0);
+    assert_eq!(vec![id], sim.app.canvas_state.selected_ids);
+    assert_points(&[(100.0, 130.0), (200.0, 130.0)], &line_points(&sim));
+
+    // the rotation handle is turned a quarter about the mid point
+    let handle = get_rotate_handle(&sim.only_widget()).expect("a line turns");
+    sim.press(handle.x, handle.y);
+    sim.move_to(200.0, 130.0);
+    sim.release(200.0, 130.0);
+    assert_points(&[(150.0, 80.0), (150.0, 180.0)], &line_points(&sim));
+
+    // and the wheel over it turns it another quarter
+    sim.scroll(150.0, 100.0, 90.0 / sim.app.canvas_state.selected_step_degrees);
+    assert_points(&[(200.0, 130.0), (100.0, 130.0)], &line_points(&sim));
+
+    // a drag on a vertex edits it
+    sim.press(100.0, 130.0);
+    sim.move_to(100.0, 160.0);
+    sim.release(100.0, 160.0);
+    assert_points(&[(200.0, 130.0), (100.0, 160.0)], &line_points(&sim));
+    let (draw_mode, status) = get_draw_mode_and_status(&sim.only_widget());
+    assert_eq!((DrawMode::DrawAll, DrawStatus::Completed), (draw_mode, status));
+    assert_eq!(DrawMode::Select, sim.app.canvas_state.draw_mode);
+    assert!(sim.pending.is_none());
+
+    // shift adds or removes, a click on nothing clears
+    sim.modifiers(Modifiers::SHIFT);
+    sim.click(150.0, 145.0);
+    assert!(sim.app.canvas_state.selected_ids.is_empty());
+    sim.modifiers(Modifiers::default());
+    sim.click(150.0, 145.0);
+    assert_eq!(vec![id], sim.app.canvas_state.selected_ids);
+    sim.click(500.0, 500.0);
+    assert!(sim.app.canvas_state.selected_ids.is_empty());
+
+    // a wider grab radius reaches a click further from the line
+    sim.click(150.0, 160.0);
+    assert!(sim.app.canvas_state.selected_ids.is_empty());
+    sim.app.canvas_state.grab_radius = 20.0;
+    sim.click(150.0, 160.0);
+    assert_eq!(vec![id], sim.app.canvas_state.selected_ids);
+}
+
+#[test]
+fn test_simulate_rubber_band() {
+    // a grid of short lines, ten by ten
+    let mut sim = Simulation::new(Widget::Line);
+    for row in 0..10 {
+        for col in 0..10 {
+            let (x, y) = (col as f32 * 50.0 + 10.0, row as f32 * 50.0 + 10.0);
+            sim.click(x, y);
+            sim.click(x + 20.0, y);
+        }
+    }
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+
+    // the box holds the whole of four lines and cuts two more
+    sim.press(85.0, 85.0);
+    sim.move_to(215.0, 170.0);
+    sim.release(215.0, 170.0);
+    assert_eq!(4, sim.app.canvas_state.selected_ids.len());
+
+    // shift adds a second box, a click on nothing clears
+    sim.modifiers(Modifiers::SHIFT);
+    sim.press(385.0, 385.0);
+    sim.release(500.0, 500.0);
+    sim.modifiers(Modifiers::default());
+    assert_eq!(8, sim.app.canvas_state.selected_ids.len());
+    sim.click(40.0, 40.0);
+    assert!(sim.app.canvas_state.selected_ids.is_empty());
+
+    // a moved line is found where it was put
+    sim.press(470.0, 460.0);
+    sim.move_to(470.0, 480.0);
+    sim.release(470.0, 480.0);
+    sim.set_mode(DrawMode::Delete);
+    sim.click(470.0, 460.0);
+    assert_eq!(100, sim.widgets().len());
+    sim.click(470.0, 480.0);
+    assert_eq!(99, sim.widgets().len());
+}
+
+#[test]
+fn test_simulate_rotate_selection() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let first = get_widget_id(&sim.only_widget());
+    sim.click(100.0, 200.0);
+    sim.click(200.0, 200.0);
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+    sim.press(80.0, 80.0);
+    sim.release(220.0, 220.0);
+    assert_eq!(2, sim.app.canvas_state.selected_ids.len());
+    let line_points = |sim: &Simulation, first_line: bool| {
+        sim.widgets().into_iter()
+            .find_map(|widget| match widget {
+                CanvasWidget::Line(line) if (line.id == first) == first_line => Some(line.points),
+                _ => None,
+            })
+            .expect("the line is missing")
+    };
+    let quarter = 90.0 / sim.app.canvas_state.selected_step_degrees;
+
+    // both turn a quarter about the center between them
+    sim.scroll(150.0, 100.0, quarter);
+    assert_points(&[(200.0, 100.0), (200.0, 200.0)], &line_points(&sim, true));
+    assert_points(&[(100.0, 100.0), (100.0, 200.0)], &line_points(&sim, false));
+
+    // with ctrl about the cursor
+    sim.modifiers(Modifiers::CTRL);
+    sim.scroll(200.0, 150.0, quarter);
+    sim.modifiers(Modifiers::default());
+    assert_points(&[(250.0, 150.0), (150.0, 150.0)], &line_points(&sim, true));
+    assert_points(&[(250.0, 50.0), (150.0, 50.0)], &line_points(&sim, false));
+
+    // off the selection the wheel does nothing
+    sim.scroll(500.0, 500.0, quarter);
+    assert_points(&[(250.0, 50.0), (150.0, 50.0)], &line_points(&sim, false));
+}
+
+#[test]
+fn test_simulate_style_selection() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let style = |sim: &Simulation| {
+        let widget = sim.only_widget();
+        (get_widget_width(&widget), get_widget_color(&widget))
+    };
+
+    // the width given while the point is picked stays after the edit
+    sim.set_mode(DrawMode::Edit);
+    sim.click(150.0, 100.0);
+    sim.send(Message::WidthInput("5".to_string()));
+    assert_eq!(5.0, style(&sim).0);
+    sim.click(200.0, 100.0);
+    sim.click(200.0, 150.0);
+    sim.send(Message::SubmitDrawColor(Color::from_rgb(1.0, 0.0, 0.0)));
+    assert_eq!((5.0, Color::from_rgb(1.0, 0.0, 0.0)), style(&sim));
+
+    // clearing the entry to retype it leaves the widget alone
+    sim.send(Message::WidthInput(String::new()));
+    assert_eq!(5.0, style(&sim).0);
+
+    // in New mode only the next widget is styled
+    sim.set_mode(DrawMode::New);
+    sim.send(Message::WidthInput("1".to_string()));
+    assert_eq!(5.0, style(&sim).0);
+}
+
+#[test]
+fn test_simulate_clear() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.send(Message::WidthInput("5".to_string()));
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+
+    // nothing goes until Yes
+    sim.send(Message::Clear);
+    sim.send(Message::CancelClear);
+    assert_eq!(1, sim.widgets().len());
+    sim.send(Message::Clear);
+    sim.send(Message::ClearDrawing);
+    assert!(sim.widgets().is_empty());
+    // the tool settings are kept
+    assert_eq!(Widget::Line, sim.app.canvas_state.selected_radio_widget.unwrap());
+    assert_eq!(5.0, sim.app.canvas_state.selected_width);
+    assert_eq!(DrawMode::New, sim.app.canvas_state.draw_mode);
+    sim.send(Message::UndoClear);
+    assert_eq!(1, sim.widgets().len());
+
+    // the undo is gone once something is drawn again
+    sim.send(Message::ClearDrawing);
+    sim.click(100.0, 200.0);
+    sim.click(200.0, 200.0);
+    assert!(!sim.app.canvas_state.can_undo_clear());
+    sim.send(Message::UndoClear);
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert_eq!(Point::new(150.0, 200.0), line.mid_point);
+
+    // and the tool settings are reset apart from the drawing and the document
+    sim.app.canvas_state.grab_radius = 20.0;
+    sim.send(Message::PresetName("Thick".to_string()));
+    sim.send(Message::SavePreset);
+    sim.send(Message::Metadata(TitleField::Project, "Pump House".to_string()));
+    sim.app.canvas_state.read_only = true;
+    sim.send(Message::ResetToolSettings);
+    assert_eq!(1, sim.widgets().len());
+    assert_eq!(1, sim.app.canvas_state.style_presets.len());
+    assert_eq!("Pump House", sim.app.canvas_state.metadata.project);
+    assert!(sim.app.canvas_state.read_only);
+    assert_eq!(2.0, sim.app.canvas_state.selected_width);
+    assert_eq!(None, sim.app.canvas_state.selected_radio_widget);
+    assert_eq!(DrawMode::DrawAll, sim.app.canvas_state.draw_mode);
+    assert_eq!(20.0, sim.app.canvas_state.grab_radius);
+}
+
+#[test]
+fn test_simulate_missed_click() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+
+    // a click away from every widget picks nothing
+    sim.set_mode(DrawMode::Delete);
+    sim.click(150.0, 130.0);
+    assert_eq!(1, sim.widgets().len());
+    sim.set_mode(DrawMode::Edit);
+    sim.click(150.0, 130.0);
+    assert!(sim.pending.is_none());
+    // nor a line to construct from
+    sim.set_mode(DrawMode::Parallel);
+    sim.click(150.0, 130.0);
+    assert!(sim.pending.is_none());
+
+    // inside a filled shape is a hit however far from the outline
+    sim.set_mode(DrawMode::New);
+    sim.send(Message::RadioSelected(Widget::Circle));
+    sim.click(400.0, 400.0);
+    sim.click(400.0, 500.0);
+    // the tangents need a click on the circle itself
+    sim.set_mode(DrawMode::Tangent);
+    sim.click(400.0, 560.0);
+    assert!(sim.pending.is_none());
+    sim.click(400.0, 505.0);
+    assert!(sim.pending.is_some());
+    sim.set_mode(DrawMode::Delete);
+    sim.click(400.0, 400.0);
+    sim.click(150.0, 101.0);
+    assert!(sim.widgets().is_empty());
+}
+
+#[test]
+fn test_simulate_trim() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(300.0, 100.0);
+    sim.click(200.0, 50.0);
+    sim.click(200.0, 150.0);
+    let lengths = |sim: &Simulation| {
+        let mut lengths: Vec<f32> = sim.widgets().iter()
+            .map(|widget| match widget {
+                CanvasWidget::Line(line) => line.points[0].distance(line.points[1]),
+                _ => panic!("not a line"),
+            })
+            .collect();
+        lengths.sort_by(f32::total_cmp);
+        lengths
+    };
+
+    // far from both lines, neither is cut
+    sim.set_mode(DrawMode::Trim);
+    sim.click(260.0, 300.0);
+    assert_eq!(vec![100.0, 200.0], lengths(&sim));
+    // the right side of the long line is removed
+    sim.click(260.0, 101.0);
+    assert_eq!(vec![100.0, 100.0], lengths(&sim));
+}
+
+#[test]
+fn test_simulate_rotate_line() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+
+    // each line of the wheel turns by the step degrees
+    sim.set_mode(DrawMode::Rotate);
+    sim.click(150.0, 100.0);
+    sim.scroll(150.0, 100.0, 90.0 / sim.app.canvas_state.selected_step_degrees);
+    sim.click(150.0, 100.0);
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert_points(&[(150.0, 50.0), (150.0, 150.0)], &line.points);
+    assert!(sim.pending.is_none());
+}
+
+#[test]
+fn test_simulate_rotate_typed() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+
+    // the typed angle, then Enter
+    sim.set_mode(DrawMode::Rotate);
+    sim.click(150.0, 100.0);
+    sim.type_text("9");
+    sim.type_text("05");
+    sim.key(Key::Named(key::Named::Backspace));
+    sim.key(Key::Named(key::Named::Enter));
+    assert!(sim.pending.is_none());
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert!((line.degrees - 90.0).abs() < 1.0e-3);
+    assert_points(&[(150.0, 150.0), (150.0, 50.0)], &line.points);
+
+    // Escape puts it back
+    sim.click(150.0, 100.0);
+    sim.type_text("30");
+    sim.key(Key::Named(key::Named::Escape));
+    assert!(sim.pending.is_none());
+    let CanvasWidget::Line(line) = sim.only_widget() else {
+        panic!("not a line")
+    };
+    assert_points(&[(150.0, 150.0), (150.0, 50.0)], &line.points);
+    assert_eq!(DrawStatus::Completed, get_draw_mode_and_status(&sim.only_widget()).1);
+}
+
+#[test]
+fn test_simulate_freehand_and_text() {
+    // dragged with the button held
+    let mut sim = Simulation::new(Widget::FreeHand);
+    sim.press(10.0, 10.0);
+    sim.move_to(20.0, 10.0);
+    sim.move_to(20.0, 10.0);
+    sim.move_to(30.0, 15.0);
+    sim.release(30.0, 15.0);
+    let CanvasWidget::FreeHand(fh) = sim.only_widget() else {
+        panic!("not a freehand")
+    };
+    assert_points(&[(10.0, 10.0), (20.0, 10.0), (30.0, 15.0)], fh.points.as_slice());
+
+    // placed, typed into and finished with a click
+    let mut sim = Simulation::new(Widget::Text);
+    sim.click(100.0, 100.0);
+    sim.type_text("Hi");
+    sim.key(Key::Named(key::Named::Backspace));
+    sim.type_text("ello");
+    sim.click(300.0, 300.0);
+    let CanvasWidget::Text(txt) = sim.only_widget() else {
+        panic!("not a text")
+    };
+    assert_eq!("Hello", txt.content);
+    assert_eq!(Point::new(100.0, 100.0), txt.position);
+}
+
+#[test]
+fn test_simulate_delete_text() {
+    let mut sim = Simulation::new(Widget::Text);
+    sim.click(100.0, 100.0);
+    sim.type_text("Hi");
+    sim.click(300.0, 300.0);
+    // a finished text leaves New mode
+    sim.set_mode(DrawMode::New);
+    sim.click(100.0, 300.0);
+    sim.type_text("There");
+    sim.click(300.0, 300.0);
+    assert_eq!(2, sim.widgets().len());
+
+    // picked in Edit mode like any curve
+    sim.set_mode(DrawMode::Edit);
+    sim.click(100.0, 300.0);
+    sim.key(Key::Named(key::Named::Delete));
+    assert_eq!(1, sim.widgets().len());
+
+    // or selected and deleted with the selection
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+    sim.click(100.0, 100.0);
+    assert_eq!(1, sim.app.canvas_state.selected_ids.len());
+    sim.key(Key::Named(key::Named::Delete));
+    assert!(sim.widgets().is_empty());
+    assert!(sim.app.canvas_state.selected_ids.is_empty());
+}
+
+#[test]
+fn test_simulate_text_with_curves() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    sim.app.canvas_state.selected_radio_widget = Some(Widget::Text);
+    sim.click(100.0, 200.0);
+    sim.type_text("Hi");
+    sim.click(300.0, 300.0);
+
+    // one collection, the texts found in it
+    let layer = &sim.app.canvas_state.layers[0];
+    assert_eq!(2, layer.curves.len());
+    assert_eq!(1, layer.texts().count());
+
+    // the rubber band picks both
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+    sim.press(50.0, 50.0);
+    sim.release(250.0, 250.0);
+    assert_eq!(2, sim.app.canvas_state.selected_ids.len());
+}
+
+#[test]
+fn test_simulate_text_cursor() {
+    let mut sim = Simulation::new(Widget::Text);
+    sim.click(100.0, 100.0);
+    sim.type_text("Hlo");
+    sim.key(Key::Named(key::Named::ArrowLeft));
+    sim.type_text("l");
+    sim.key(Key::Named(key::Named::Home));
+    sim.key(Key::Named(key::Named::ArrowRight));
+    sim.type_text("e");
+    // shift selects to the end, the typing replaces it
+    sim.key_with(Key::Named(key::Named::End), Modifiers::SHIFT);
+    sim.type_text("llo!");
+    sim.key(Key::Named(key::Named::Home));
+    sim.key(Key::Named(key::Named::Delete));
+    sim.type_text("J");
+    sim.click(300.0, 300.0);
+    let CanvasWidget::Text(txt) = sim.only_widget() else {
+        panic!("not a text")
+    };
+    assert_eq!("Jello!", txt.content);
+}
+
+#[test]
+fn test_simulate_retype_text() {
+    let mut sim = Simulation::new(Widget::Text);
+    sim.click(100.0, 100.0);
+    sim.type_text("Hi");
+    sim.click(300.0, 300.0);
+
+    // a double click in the edit types at the end again
+    sim.set_mode(DrawMode::Edit);
+    sim.click(100.0, 100.0);
+    sim.click(100.0, 100.0);
+    assert!(sim.app.canvas_state.timer_event_enabled);
+    sim.type_text("!");
+    sim.click(300.0, 300.0);
+    let CanvasWidget::Text(txt) = sim.only_widget() else {
+        panic!("not a text")
+    };
+    assert_eq!("Hi!", txt.content);
+    assert_eq!(DrawMode::DrawAll, txt.draw_mode);
+    assert!(!sim.app.canvas_state.timer_event_enabled);
+}
+
+#[test]
+fn test_simulate_text_size_input() {
+    // only a whole size in range restyles the selected text
+    let mut sim = Simulation::new(Widget::Text);
+    sim.click(100.0, 100.0);
+    sim.type_text("Hi");
+    sim.click(300.0, 300.0);
+    let id = get_widget_id(&sim.only_widget());
+    sim.app.canvas_state.selected_ids = vec![id];
+    let size = |sim: &Simulation| match sim.only_widget() {
+        CanvasWidget::Text(txt) => txt.size.0,
+        _ => panic!("not a text"),
+    };
+    sim.send(Message::TextSize("24".to_string()));
+    assert_eq!(24.0, size(&sim));
+    for input in ["", "2", "2x", "1000"] {
+        sim.send(Message::TextSize(input.to_string()));
+        assert_eq!(24.0, size(&sim), "{input}");
+    }
+    assert!(sim.app.canvas_state.text_size_error);
+    sim.send(Message::TextSize("32".to_string()));
+    assert_eq!(32.0, size(&sim));
+    assert!(!sim.app.canvas_state.text_size_error);
+}
+
+#[test]
+fn test_simulate_circle_radius() {
+    let mut sim = Simulation::new(Widget::Circle);
+    sim.click(100.0, 100.0);
+    sim.click(130.0, 140.0);
+
+    // typed into the panel while the circle is selected
+    sim.set_mode(DrawMode::Edit);
+    sim.click(100.0, 100.0);
+    sim.send(Message::CircleRadius("a".to_string()));
+    sim.send(Message::CircleRadius("100".to_string()));
+    let CanvasWidget::Circle(cir) = sim.only_widget() else {
+        panic!("not a circle")
+    };
+    assert_eq!(Point::new(100.0, 100.0), cir.center);
+    assert_eq!(100.0, cir.radius);
+    assert_points(&[(160.0, 180.0)], &[cir.circle_point]);
+}
+
+#[test]
+fn test_simulate_ellipse() {
+    // the second radius is the height of the last click
+    let mut sim = Simulation::new(Widget::Ellipse);
+    sim.click(100.0, 100.0);
+    sim.click(150.0, 120.0);
+    sim.click(130.0, 130.0);
+    let CanvasWidget::Ellipse(ell) = sim.only_widget() else {
+        panic!("not an ellipse")
+    };
+    assert_eq!(Point::new(100.0, 100.0), ell.center);
+    assert_eq!(Vector::new(50.0, 30.0), ell.radii);
+
+    // shift locks it to a circle
+    let mut sim = Simulation::new(Widget::Ellipse);
+    sim.click(100.0, 100.0);
+    sim.click(150.0, 120.0);
+    sim.modifiers(Modifiers::SHIFT);
+    sim.click(130.0, 300.0);
+    let CanvasWidget::Ellipse(ell) = sim.only_widget() else {
+        panic!("not an ellipse")
+    };
+    assert_eq!(Vector::new(50.0, 50.0), ell.radii);
+    assert_points(&[(100.0, 100.0), (150.0, 100.0), (100.0, 150.0)], &ell.points);
+}
+
+#[test]
+fn test_simulate_example_read_only() {
+    // the canvas ignores the clicks until the example is duplicated
+    let mut sim = Simulation::new(Widget::Line);
+    sim.send(Message::Example("Floor Plan".to_string()));
+    assert!(sim.app.canvas_state.read_only);
+    let count = sim.widgets().len();
+    assert!(count > 0);
+    sim.click(800.0, 800.0);
+    sim.click(900.0, 800.0);
+    assert_eq!(count, sim.widgets().len());
+
+    sim.send(Message::DuplicateExample);
+    assert!(!sim.app.canvas_state.read_only);
+    sim.click(800.0, 800.0);
+    sim.click(900.0, 800.0);
+    assert_eq!(count + 1, sim.widgets().len());
+}
+
+#[test]
+fn test_simulate_load_error() {
+    // a missing or broken file is reported and the drawing kept
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let path = std::env::temp_dir().join("draw_test_broken.json");
+    std::fs::write(&path, "[{\"name\": ").unwrap();
+    for path in [path, std::env::temp_dir().join("draw_test_missing.json")] {
+        sim.send(Message::LoadFrom(Some(path)));
+        assert!(sim.app.canvas_state.load_error.is_some());
+        assert_eq!(1, sim.widgets().len());
+    }
+    sim.send(Message::DismissLoadError);
+    assert!(sim.app.canvas_state.load_error.is_none());
+
+    // a save that fails is reported and the drawing keeps its path
+    let data_path = sim.app.canvas_state.data_path.clone();
+    sim.send(Message::SaveTo(Some(std::env::temp_dir().join("draw_test_missing_dir").join("drawing.json"))));
+    assert!(sim.app.canvas_state.load_error.is_some());
+    assert_eq!(data_path, sim.app.canvas_state.data_path);
+}
+
+#[test]
+fn test_simulate_import_svg() {
+    let mut sim = Simulation::new(Widget::Line);
+    let dir = std::env::temp_dir().join(format!("draw_test_import_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let svg = dir.join("import.svg");
+    let broken = dir.join("broken.svg");
+    std::fs::write(&svg, r#"<svg xmlns="http://www.w3.org/2000/svg"><line x1="0" y1="0" x2="10" y2="0"/></svg>"#).unwrap();
+    std::fs::write(&broken, "<svg><line").unwrap();
+
+    sim.send(Message::ImportSvgFrom(Some(svg)));
+    assert!(sim.app.canvas_state.load_error.is_none());
+    assert_eq!(1, sim.widgets().len());
+    // a missing or broken file is reported and nothing is added
+    for path in [broken, dir.join("missing.svg")] {
+        sim.send(Message::DismissLoadError);
+        sim.send(Message::ImportSvgFrom(Some(path)));
+        assert!(sim.app.canvas_state.load_error.is_some());
+        assert_eq!(1, sim.widgets().len());
+    }
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_simulate_export_description() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let path = std::env::temp_dir().join(format!("draw_test_description_{}.txt", std::process::id()));
+    sim.send(Message::ExportDescriptionTo(Some(path.clone())));
+    assert!(sim.app.canvas_state.load_error.is_none());
+    assert!(std::fs::read_to_string(&path).unwrap().contains("Line"));
+    let _ = std::fs::remove_file(path);
+
+    // a folder that isn't there is reported
+    sim.send(Message::ExportDescriptionTo(Some(std::env::temp_dir().join("draw_test_missing_dir").join("description.txt"))));
+    assert!(sim.app.canvas_state.load_error.is_some());
+}
+
+#[test]
+fn test_simulate_export_data() {
+    use crate::data_export::DataFormat;
+
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let dir = std::env::temp_dir().join(format!("draw_test_data_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // the table is written next to the drawing
+    sim.app.canvas_state.data_path = dir.join("drawing.json");
+    sim.send(Message::ExportData(DataFormat::Csv));
+    assert!(sim.app.canvas_state.load_error.is_none());
+    assert!(dir.join("widgets.csv").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+
+    sim.send(Message::ExportData(DataFormat::Json));
+    assert!(sim.app.canvas_state.load_error.is_some());
+}
+
+#[test]
+fn test_simulate_export_map() {
+    use crate::image_map::MapFormat;
+
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let dir = std::env::temp_dir().join(format!("draw_test_map_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    sim.app.canvas_state.data_path = dir.join("drawing.json");
+    sim.send(Message::ExportMap(MapFormat::Html));
+    assert!(sim.app.canvas_state.load_error.is_none());
+    assert!(dir.join("drawing.svg").exists() && dir.join("drawing.html").exists());
+
+    // the svg can't be written, so no map points at it
+    for name in ["drawing.svg", "drawing.html"] {
+        std::fs::remove_file(dir.join(name)).unwrap();
+    }
+    std::fs::create_dir(dir.join("drawing.svg")).unwrap();
+    sim.send(Message::ExportMap(MapFormat::Html));
+    assert!(sim.app.canvas_state.load_error.is_some());
+    assert!(!dir.join("drawing.html").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_simulate_export_layer_svgs() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    sim.send(Message::NewLayer);
+    sim.click(100.0, 200.0);
+    sim.click(200.0, 200.0);
+    let dir = std::env::temp_dir().join(format!("draw_test_layer_svgs_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    sim.app.canvas_state.data_path = dir.join("drawing.json");
+    // a file for each layer, next to the drawing
+    sim.send(Message::ExportLayerSvgs);
+    assert!(sim.app.canvas_state.load_error.is_none());
+    assert_eq!(2, std::fs::read_dir(&dir).unwrap().count());
+
+    // with the folder gone each layer's file is reported
+    let _ = std::fs::remove_dir_all(&dir);
+    sim.send(Message::ExportLayerSvgs);
+    assert_eq!(Some(2), sim.app.canvas_state.load_error.as_ref().map(|error| error.lines().count()));
+}
+
+#[test]
+fn test_simulate_load_layers() {
+    // the layer indices of a file edited by hand are renumbered
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let mut widget = convert_widget_to_export(&sim.only_widget());
+    widget.layer = Some(ExportLayer { layer: 4_000_000_000, name: "Far".to_string(), visible: true, export: true });
+    let path = std::env::temp_dir().join(format!("draw_test_layers_{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string(&vec![widget]).unwrap()).unwrap();
+    sim.send(Message::LoadFrom(Some(path.clone())));
+    let _ = std::fs::remove_file(path);
+    assert!(sim.app.canvas_state.load_error.is_none());
+    let names: Vec<&str> = sim.app.canvas_state.layers.iter().map(|layer| layer.name.as_str()).collect();
+    assert_eq!(vec!["Far"], names);
+    assert_eq!(1, sim.widgets().len());
+}
+
+#[test]
+fn test_simulate_delete_layer() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    sim.send(Message::NewLayer);
+    sim.click(100.0, 200.0);
+    sim.click(200.0, 200.0);
+    sim.click(100.0, 300.0);
+    sim.click(200.0, 300.0);
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+    sim.press(80.0, 180.0);
+    sim.release(220.0, 320.0);
+    sim.send(Message::GroupSelection);
+    assert_eq!(1, sim.app.canvas_state.groups.len());
+    sim.send(Message::ConstraintKind("Horizontal".to_string()));
+    sim.send(Message::AddConstraint);
+    assert_eq!(2, sim.app.canvas_state.constraints.len());
+
+    // the group and constraints go with the widgets, they aren't saved
+    sim.send(Message::DeleteLayer);
+    assert_eq!(1, sim.app.canvas_state.layers.len());
+    assert_eq!(1, sim.widgets().len());
+    assert!(sim.app.canvas_state.groups.is_empty());
+    assert!(sim.app.canvas_state.constraints.is_empty());
+}
+
+#[test]
+fn test_simulate_hatch() {
+    // the hatch is part of the square and follows it when it is edited
+    let mut sim = Simulation::new(Widget::RoundedRect);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 140.0);
+    let square = get_widget_id(&sim.only_widget());
+    sim.app.canvas_state.selected_ids = vec![square];
+    sim.send(Message::HatchAngle(0.0));
+    sim.send(Message::HatchSpacing(10.0));
+    sim.send(Message::HatchSelection);
+    let hatch = Hatch { pattern: HatchPattern::Lines, spacing: 10.0, angle: 0.0 };
+    assert_eq!(Some(hatch), get_widget_hatch(&sim.only_widget()));
+    // the rows at 100 to 130, the bottom edge is outside
+    assert_eq!(4, get_widget_hatch_marks(&sim.only_widget()).lines.len());
+    let loaded = import_widget(&convert_widget_to_export(&sim.only_widget()));
+    assert_eq!(Some(hatch), get_widget_hatch(&loaded));
+
+    sim.send(Message::HatchPattern("Cross Hatch".to_string()));
+    sim.send(Message::HatchSelection);
+    assert_eq!(1, sim.widgets().len());
+    // and the ten upright lines
+    assert_eq!(14, get_widget_hatch_marks(&sim.only_widget()).lines.len());
+    sim.send(Message::RemoveHatch);
+    assert_eq!(None, get_widget_hatch(&sim.only_widget()));
+}
+
+#[test]
+fn test_simulate_object_snap() {
+    // the second line starts on the end of the first and ends on its middle
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let first = get_widget_id(&sim.only_widget());
+    sim.send(Message::SnapObjects(true));
+    sim.click(204.0, 103.0);
+    sim.click(147.0, 96.0);
+    let second = 
+        sim.widgets().into_iter()
+            .find(|widget| get_widget_id(widget) != first)
+            .unwrap();
+    let CanvasWidget::Line(line) = second else {
+        panic!("not a line")
+    };
+    assert_points(&[(200.0, 100.0), (150.0, 100.0)], &line.points);
+}
+
+#[test]
+fn test_simulate_group() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let first = get_widget_id(&sim.only_widget());
+    sim.click(100.0, 200.0);
+    sim.click(200.0, 200.0);
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+    sim.press(80.0, 80.0);
+    sim.release(220.0, 220.0);
+    sim.send(Message::GroupSelection);
+    assert_eq!(1, sim.app.canvas_state.groups.len());
+    let line_points = |sim: &Simulation, first_line: bool| {
+        sim.widgets().into_iter()
+            .find_map(|widget| match widget {
+                CanvasWidget::Line(line) if (line.id == first) == first_line => Some(line.points),
+                _ => None,
+            })
+            .expect("the line is missing")
+    };
+
+    // a click on one line selects the group, a drag moves both
+    sim.click(500.0, 500.0);
+    sim.click(150.0, 100.0);
+    assert_eq!(2, sim.app.canvas_state.selected_ids.len());
+    sim.press(150.0, 100.0);
+    sim.move_to(170.0, 130.0);
+    sim.release(170.0, 130.0);
+    assert_points(&[(120.0, 130.0), (220.0, 130.0)], &line_points(&sim, true));
+    assert_points(&[(120.0, 230.0), (220.0, 230.0)], &line_points(&sim, false));
+
+    // ungrouped each line is on its own again
+    sim.send(Message::UngroupSelection);
+    assert!(sim.app.canvas_state.groups.is_empty());
+    sim.click(500.0, 500.0);
+    sim.click(170.0, 130.0);
+    assert_eq!(vec![first], sim.app.canvas_state.selected_ids);
+}
+
+#[test]
+fn test_simulate_split_join() {
+    let mut sim = Simulation::new(Widget::FreeHand);
+    sim.press(10.0, 10.0);
+    sim.move_to(50.0, 10.0);
+    sim.move_to(90.0, 10.0);
+    sim.release(90.0, 10.0);
+
+    // a click away from the stroke cuts nothing
+    sim.set_mode(DrawMode::Split);
+    sim.click(30.0, 60.0);
+    assert_eq!(1, sim.widgets().len());
+    // cut in the middle of the first segment
+    sim.click(30.0, 12.0);
+    assert_eq!(2, sim.widgets().len());
+
+    // the stroke kept and the stroke joined onto it
+    sim.set_mode(DrawMode::Join);
+    sim.click(20.0, 10.0);
+    sim.click(70.0, 10.0);
+    let CanvasWidget::FreeHand(fh) = sim.only_widget() else {
+        panic!("not a freehand")
+    };
+    assert_points(&[(10.0, 10.0), (30.0, 10.0), (50.0, 10.0), (90.0, 10.0)], fh.points.as_slice());
+}
+
+#[test]
+fn test_simulate_resize() {
+    let mut sim = Simulation::new(Widget::Line);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    let line = |sim: &Simulation| match sim.only_widget() {
+        CanvasWidget::Line(line) => line,
+        _ => panic!("not a line"),
+    };
+
+    // dragged from 40 to 80 away from the mid point doubles it
+    sim.set_mode(DrawMode::Resize);
+    sim.press(190.0, 100.0);
+    sim.move_to(230.0, 100.0);
+    sim.release(230.0, 100.0);
+    assert_points(&[(50.0, 100.0), (250.0, 100.0)], &line(&sim).points);
+    assert_eq!(2.0, line(&sim).width);
+
+    // the wheel scales a step, with the width when asked
+    sim.app.canvas_state.resize_widths = true;
+    sim.scroll(150.0, 100.0, -1.0);
+    let line = line(&sim);
+    assert!((line.points[0].distance(line.points[1]) - 200.0 / 1.1).abs() < 1.0e-3);
+    assert!((line.width - 2.0 / 1.1).abs() < 1.0e-4);
+}
+
+#[test]
+fn test_simulate_arc_handles() {
+    // the center, the start and the end
+    let mut sim = Simulation::new(Widget::Arc);
+    sim.click(100.0, 100.0);
+    sim.click(200.0, 100.0);
+    sim.click(100.0, 200.0);
+    let arc = |sim: &Simulation| match sim.only_widget() {
+        CanvasWidget::Arc(arc) => arc,
+        _ => panic!("not an arc"),
+    };
+    sim.app.canvas_state.classic_modes = false;
+    sim.set_mode(DrawMode::Select);
+    sim.click(170.7, 170.7);
+
+    // the end goes round to the left, the start and radius stay
+    sim.press(100.0, 200.0);
+    sim.move_to(50.0, 100.0);
+    sim.release(50.0, 100.0);
+    let edited = arc(&sim);
+    // half way round from the start
+    let sweep = |arc: &Arc| arc.end_angle.0 - arc.start_angle.0;
+    assert!((sweep(&edited) - std::f32::consts::PI).abs() < 1.0e-4);
+    assert_eq!(100.0, edited.radius);
+    assert_points(&[(100.0, 100.0), (200.0, 100.0), (0.0, 100.0)], &edited.points);
+
+    // the radius handle in the middle of the arc keeps the angles
+    let handle = edited.radius_point();
+    sim.press(handle.x, handle.y);
+    sim.move_to(handle.x, handle.y + 50.0);
+    sim.release(handle.x, handle.y + 50.0);
+    let edited = arc(&sim);
+    assert!((edited.radius - 150.0).abs() < 1.0e-3);
+    assert!((sweep(&edited) - std::f32::consts::PI).abs() < 1.0e-4);
+    assert_points(&[(100.0, 100.0), (250.0, 100.0), (-50.0, 100.0)], &edited.points);
+}
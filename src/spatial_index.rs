@@ -0,0 +1,187 @@
+//! spatial_index
+//! A quadtree of the widget bounds of a layer, so finding the widgets
+//! under the cursor or inside a box tests only the nearby ones instead
+//! of every widget of a large drawing.  A bound that straddles the
+//! quarters of a node stays in that node.  A widget added, edited or
+//! deleted is put in or taken out on its own, the tree isn't built
+//! again.
+
+use std::collections::HashMap;
+
+use iced::{Point, Rectangle, Size};
+
+use crate::ids::WidgetId;
+
+
+// A node splits into quarters when it holds more than this.
+const MAX_ENTRIES: usize = 8;
+const MAX_DEPTH: usize = 10;
+
+#[derive(Debug)]
+pub struct SpatialIndex {
+    // the layer it was built from
+    pub layer: usize,
+    root: Node,
+    // the bounds each widget was put in with, to find it again
+    bounds: HashMap<WidgetId, Rectangle>,
+}
+
+#[derive(Debug)]
+struct Node {
+    bounds: Rectangle,
+    entries: Vec<(WidgetId, Rectangle)>,
+    children: Vec<Node>,
+}
+
+impl SpatialIndex {
+    pub fn new(layer: usize, entries: Vec<(WidgetId, Rectangle)>) -> Self {
+        let bounds =
+            entries.iter()
+                .map(|(_, rect)| *rect)
+                .reduce(|a, b| a.union(&b))
+                .unwrap_or(Rectangle::new(Point::ORIGIN, Size::new(1.0, 1.0)));
+        let mut index = SpatialIndex { layer, root: Node::new(bounds), bounds: HashMap::new() };
+        for (id, rect) in entries {
+            index.insert(id, rect);
+        }
+        index
+    }
+
+    // A bound outside the first ones stays in the root.
+    pub fn insert(&mut self, id: WidgetId, rect: Rectangle) {
+        self.remove(&id);
+        self.bounds.insert(id, rect);
+        self.root.insert(id, rect, 0);
+    }
+
+    pub fn remove(&mut self, id: &WidgetId) {
+        if let Some(rect) = self.bounds.remove(id) {
+            self.root.remove(id, &rect);
+        }
+    }
+
+    // The widget moved or changed size.
+    pub fn update(&mut self, id: WidgetId, rect: Rectangle) {
+        self.insert(id, rect);
+    }
+
+    // The widgets whose bounds are within the distance of the point.
+    pub fn query_point(&self, point: Point, distance: f32) -> Vec<WidgetId> {
+        let area =
+            Rectangle::new(
+                Point::new(point.x - distance, point.y - distance),
+                Size::new(2.0*distance, 2.0*distance),
+            );
+        self.query_rect(area)
+    }
+
+    // The widgets whose bounds touch the area.
+    pub fn query_rect(&self, area: Rectangle) -> Vec<WidgetId> {
+        let mut ids = vec![];
+        self.root.query(&area, &mut ids);
+        ids
+    }
+}
+
+impl Node {
+    fn new(bounds: Rectangle) -> Self {
+        Node { bounds, entries: vec![], children: vec![] }
+    }
+
+    fn insert(&mut self, id: WidgetId, rect: Rectangle, depth: usize) {
+        if let Some(child) = self.children.iter_mut().find(|child| encloses(&child.bounds, &rect)) {
+            child.insert(id, rect, depth + 1);
+            return
+        }
+        self.entries.push((id, rect));
+        if self.children.is_empty() && self.entries.len() > MAX_ENTRIES && depth < MAX_DEPTH {
+            self.split(depth);
+        }
+    }
+
+    // The same way down as it was inserted.
+    fn remove(&mut self, id: &WidgetId, rect: &Rectangle) {
+        if let Some(child) = self.children.iter_mut().find(|child| encloses(&child.bounds, rect)) {
+            child.remove(id, rect);
+            return
+        }
+        self.entries.retain(|(entry, _)| entry != id);
+    }
+
+    fn split(&mut self, depth: usize) {
+        let Rectangle { x, y, width, height } = self.bounds;
+        let size = Size::new(width / 2.0, height / 2.0);
+        self.children =
+            [(x, y), (x + size.width, y), (x, y + size.height), (x + size.width, y + size.height)]
+                .into_iter()
+                .map(|(x, y)| Node::new(Rectangle::new(Point::new(x, y), size)))
+                .collect();
+        for (id, rect) in std::mem::take(&mut self.entries) {
+            self.insert(id, rect, depth);
+        }
+    }
+
+    fn query(&self, area: &Rectangle, ids: &mut Vec<WidgetId>) {
+        ids.extend(self.entries.iter()
+            .filter(|(_, rect)| touches(rect, area))
+            .map(|(id, _)| *id));
+        for child in self.children.iter() {
+            if touches(&child.bounds, area) {
+                child.query(area, ids);
+            }
+        }
+    }
+}
+
+fn encloses(outer: &Rectangle, inner: &Rectangle) -> bool {
+    outer.x <= inner.x && inner.x + inner.width <= outer.x + outer.width &&
+        outer.y <= inner.y && inner.y + inner.height <= outer.y + outer.height
+}
+
+// Unlike Rectangle::intersects, edges that meet and a line's
+// bounds without height count.
+fn touches(a: &Rectangle, b: &Rectangle) -> bool {
+    a.x <= b.x + b.width && b.x <= a.x + a.width &&
+        a.y <= b.y + b.height && b.y <= a.y + a.height
+}
+
+
+#[test]
+fn test_spatial_index() {
+    // a row of small boxes that splits the root
+    let ids: Vec<WidgetId> = (0..100).map(|_| WidgetId::unique()).collect();
+    let boxes = |count: usize| 
+        ids.iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, id)| (*id, Rectangle::new(Point::new(i as f32 * 10.0, 0.0), Size::new(5.0, 5.0))))
+            .collect::<Vec<_>>();
+    let index = SpatialIndex::new(0, boxes(100));
+    assert!(!index.root.children.is_empty());
+
+    assert_eq!(vec![ids[42]], index.query_point(Point::new(422.0, 2.0), 1.0));
+    assert!(index.query_point(Point::new(428.0, 2.0), 1.0).is_empty());
+    let mut found = index.query_point(Point::new(428.0, 2.0), 3.0);
+    found.sort();
+    assert_eq!(vec![ids[42], ids[43]], found);
+
+    let mut found = index.query_rect(Rectangle::new(Point::new(95.0, -10.0), Size::new(20.0, 20.0)));
+    found.sort();
+    assert_eq!(ids[9..12].to_vec(), found);
+
+    // a bound across the whole row stays in the root
+    let long = WidgetId::unique();
+    let mut entries = boxes(20);
+    entries.push((long, Rectangle::new(Point::ORIGIN, Size::new(995.0, 5.0))));
+    let mut index = SpatialIndex::new(0, entries);
+    assert!(index.query_point(Point::new(900.0, 2.0), 1.0).contains(&long));
+
+    // moved, taken out and put in without building it again
+    index.update(ids[3], Rectangle::new(Point::new(500.0, 0.0), Size::new(5.0, 5.0)));
+    assert!(!index.query_point(Point::new(32.0, 2.0), 1.0).contains(&ids[3]));
+    assert!(index.query_point(Point::new(502.0, 2.0), 1.0).contains(&ids[3]));
+    index.remove(&long);
+    assert!(index.query_point(Point::new(900.0, 2.0), 1.0).is_empty());
+    index.insert(ids[50], Rectangle::new(Point::new(2000.0, 2000.0), Size::new(5.0, 5.0)));
+    assert_eq!(vec![ids[50]], index.query_point(Point::new(2002.0, 2002.0), 1.0));
+}
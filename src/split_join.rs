@@ -0,0 +1,217 @@
+//! split_join
+//! Cleaning up traced strokes, a freehand or open polyline is split in
+//! two where it's clicked and two strokes of the same kind whose ends
+//! are near each other are joined into one.  The first stroke keeps its
+//! id and style, the split off part is a new widget.
+
+use iced::Point;
+
+use crate::draw_canvas::{get_widget_id, CanvasWidget};
+use crate::ids::WidgetId;
+use crate::intersections::{get_distance_to_segment, get_point_at_param, get_projection_param};
+use crate::nodes::update_polyline;
+
+
+// How far apart the ends of two strokes can be joined.
+pub const JOIN_DISTANCE: f32 = 20.0;
+
+// The stroke up to the point on it within the distance and a new
+// stroke from there on, None when the point is off it or on an end.
+pub fn split_widget(widget: &CanvasWidget, point: Point, distance: f32) -> Option<(CanvasWidget, CanvasWidget)> {
+    let points = match widget {
+        CanvasWidget::PolyLine(pl) if !pl.closed => pl.points.clone(),
+        CanvasWidget::FreeHand(fh) => fh.points.to_vec(),
+        _ => return None,
+    };
+    let (index, t) = find_split(&points, point, distance)?;
+    let cut = get_point_at_param(points[index], points[index + 1], t);
+    if cut == points[0] || cut == points[points.len() - 1] {
+        return None
+    }
+    let mut first_points = points[..=index].to_vec();
+    let mut second_points = points[index + 1..].to_vec();
+    if first_points.last() != Some(&cut) {
+        first_points.push(cut);
+    }
+    if second_points.first() != Some(&cut) {
+        second_points.insert(0, cut);
+    }
+
+    match widget {
+        CanvasWidget::PolyLine(pl) => {
+            let mut first = pl.clone();
+            first.points = first_points;
+            update_polyline(&mut first);
+            let mut second = pl.clone();
+            second.id = WidgetId::unique();
+            second.points = second_points;
+            update_polyline(&mut second);
+            Some((CanvasWidget::PolyLine(first), CanvasWidget::PolyLine(second)))
+        },
+        CanvasWidget::FreeHand(fh) => {
+            let mut first = fh.clone();
+            let mut second = fh.clone();
+            second.id = WidgetId::unique();
+            // the width at the cut between the widths on either side
+            if fh.widths.len() == points.len() {
+                let (start, end) = (fh.widths[index], fh.widths[index + 1]);
+                let width = start + (end - start) * t;
+                first.widths = fh.widths[..first_points.len() - 1].to_vec();
+                first.widths.push(width);
+                second.widths = fh.widths[points.len() + 1 - second_points.len()..].to_vec();
+                second.widths.insert(0, width);
+            }
+            first.points = first_points.into_iter().collect();
+            second.points = second_points.into_iter().collect();
+            Some((CanvasWidget::FreeHand(first), CanvasWidget::FreeHand(second)))
+        },
+        _ => None,
+    }
+}
+
+// The two strokes as one, the second turned around if need be so the
+// closest ends meet.  None when they aren't the same kind of open
+// stroke or the ends are further apart than the distance.
+pub fn join_widgets(first: &CanvasWidget, second: &CanvasWidget, distance: f32) -> Option<CanvasWidget> {
+    if get_widget_id(first) == get_widget_id(second) {
+        return None
+    }
+    match (first, second) {
+        (CanvasWidget::PolyLine(pl), CanvasWidget::PolyLine(other)) if !pl.closed && !other.closed => {
+            let (points, _) = join_points(&pl.points, &other.points, &[], &[], distance)?;
+            let mut joined = pl.clone();
+            joined.points = points;
+            update_polyline(&mut joined);
+            Some(CanvasWidget::PolyLine(joined))
+        },
+        (CanvasWidget::FreeHand(fh), CanvasWidget::FreeHand(other)) => {
+            let (points, widths) =
+                join_points(fh.points.as_slice(), other.points.as_slice(), &fh.widths, &other.widths, distance)?;
+            let mut joined = fh.clone();
+            joined.points = points.into_iter().collect();
+            joined.widths = widths;
+            Some(CanvasWidget::FreeHand(joined))
+        },
+        _ => None,
+    }
+}
+
+// The segment the point is closest to and how far along it.
+fn find_split(points: &[Point], point: Point, distance: f32) -> Option<(usize, f32)> {
+    points.windows(2)
+        .enumerate()
+        .map(|(index, ends)| (index, get_distance_to_segment(ends[0], ends[1], point)))
+        .filter(|(_, dist)| *dist <= distance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| {
+            (index, get_projection_param(points[index], points[index + 1], point).clamp(0.0, 1.0))
+        })
+}
+
+// The points running through the closest pair of ends, a point the
+// two have in common once.  The widths are kept only when both
+// strokes have one for each point.
+fn join_points(first: &[Point], second: &[Point],
+                first_widths: &[f32], second_widths: &[f32],
+                distance: f32) -> Option<(Vec<Point>, Vec<f32>)> {
+    let (&first_start, &first_end) = (first.first()?, first.last()?);
+    let (&second_start, &second_end) = (second.first()?, second.last()?);
+    // which ends meet, the first reversed and the second reversed
+    let (reverse_first, reverse_second) =
+        [
+            (first_end.distance(second_start), false, false),
+            (first_end.distance(second_end), false, true),
+            (first_start.distance(second_start), true, false),
+            (first_start.distance(second_end), true, true),
+        ]
+        .into_iter()
+        .filter(|(gap, _, _)| *gap <= distance)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, first, second)| (first, second))?;
+
+    let has_widths = first_widths.len() == first.len() && second_widths.len() == second.len();
+    let mut points = first.to_vec();
+    let mut widths = if has_widths { first_widths.to_vec() } else { vec![] };
+    if reverse_first {
+        points.reverse();
+        widths.reverse();
+    }
+    let mut more_points = second.to_vec();
+    let mut more_widths = if has_widths { second_widths.to_vec() } else { vec![] };
+    if reverse_second {
+        more_points.reverse();
+        more_widths.reverse();
+    }
+    if points.last() == more_points.first() {
+        more_points.remove(0);
+        if has_widths {
+            more_widths.remove(0);
+        }
+    }
+    points.extend(more_points);
+    widths.extend(more_widths);
+    Some((points, widths))
+}
+
+
+#[test]
+fn test_split_join() {
+    use iced::Color;
+    use crate::draw_canvas::{DrawMode, DrawStatus, FreeHand};
+    use crate::test_widgets::polyline;
+
+    let pl = CanvasWidget::PolyLine(polyline(vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 100.0)], false));
+    // off the stroke or on an end
+    assert!(split_widget(&pl, Point::new(50.0, 30.0), 5.0).is_none());
+    assert!(split_widget(&pl, Point::new(-2.0, 1.0), 5.0).is_none());
+
+    let Some((CanvasWidget::PolyLine(first), CanvasWidget::PolyLine(second))) =
+        split_widget(&pl, Point::new(50.0, 3.0), 5.0) else {
+        panic!("not split")
+    };
+    assert_eq!(vec![Point::new(0.0, 0.0), Point::new(50.0, 0.0)], first.points);
+    assert_eq!(vec![Point::new(50.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 100.0)], second.points);
+    assert_eq!((2, Point::new(25.0, 0.0)), (first.poly_points, first.mid_point));
+    assert_ne!(first.id, second.id);
+
+    // joined again from either end
+    let second = CanvasWidget::PolyLine(second);
+    let Some(CanvasWidget::PolyLine(joined)) = join_widgets(&second, &CanvasWidget::PolyLine(first), JOIN_DISTANCE) else {
+        panic!("not joined")
+    };
+    assert_eq!(vec![Point::new(100.0, 100.0), Point::new(100.0, 0.0), Point::new(50.0, 0.0), Point::new(0.0, 0.0)], joined.points);
+    assert_eq!(4, joined.poly_points);
+
+    // a freehand splits its widths at the cut
+    let fh = CanvasWidget::FreeHand(FreeHand {
+        id: WidgetId::unique(),
+        points: [(0.0, 0.0), (10.0, 0.0), (20.0, 0.0)].map(|(x, y)| Point::new(x, y)).into_iter().collect(),
+        color: Color::BLACK,
+        width: 2.0,
+        widths: vec![1.0, 2.0, 3.0],
+        smooth: false,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+        completed: true,
+    });
+    let Some((CanvasWidget::FreeHand(first), CanvasWidget::FreeHand(second))) =
+        split_widget(&fh, Point::new(15.0, 1.0), 5.0) else {
+        panic!("not split")
+    };
+    assert_eq!(vec![1.0, 2.0, 2.5], first.widths);
+    assert_eq!(vec![2.5, 3.0], second.widths);
+    assert_eq!(Some(Point::new(15.0, 0.0)), second.points.first());
+
+    // too far apart, then near
+    let mut far = second.clone();
+    far.points = [(60.0, 0.0), (80.0, 0.0)].map(|(x, y)| Point::new(x, y)).into_iter().collect();
+    assert!(join_widgets(&CanvasWidget::FreeHand(first.clone()), &CanvasWidget::FreeHand(far), JOIN_DISTANCE).is_none());
+    let Some(CanvasWidget::FreeHand(joined)) =
+        join_widgets(&CanvasWidget::FreeHand(first), &CanvasWidget::FreeHand(second), JOIN_DISTANCE) else {
+        panic!("not joined")
+    };
+    assert_eq!(4, joined.points.len());
+    assert_eq!(vec![1.0, 2.0, 2.5, 3.0], joined.widths);
+    // a polyline doesn't join a freehand
+    assert!(join_widgets(&pl, &fh, JOIN_DISTANCE).is_none());
+}
@@ -0,0 +1,102 @@
+//! stroke
+//! The freehand points in one flat buffer, edited in place by
+//! index and drawn from a slice, so a long stroke isn't copied
+//! for each point added or each time it is drawn.
+
+use iced::Point;
+
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrokePoints {
+    points: Vec<Point>,
+}
+
+impl StrokePoints {
+    pub fn push(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn first(&self) -> Option<Point> {
+        self.points.first().copied()
+    }
+
+    pub fn last(&self) -> Option<Point> {
+        self.points.last().copied()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Point> {
+        self.points.get(index).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.points.iter().copied()
+    }
+
+    pub fn as_slice(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn to_vec(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    pub fn set(&mut self, index: usize, point: Point) {
+        if let Some(pt) = self.points.get_mut(index) {
+            *pt = point;
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, point: Point) {
+        if index <= self.points.len() {
+            self.points.insert(index, point);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.points.len() {
+            self.points.remove(index);
+        }
+    }
+}
+
+impl FromIterator<Point> for StrokePoints {
+    fn from_iter<I: IntoIterator<Item = Point>>(points: I) -> Self {
+        StrokePoints { points: points.into_iter().collect() }
+    }
+}
+
+
+#[test]
+fn test_stroke_points() {
+    // the points are kept as they are, not rounded
+    let points = vec![Point::new(10.0, 20.0), Point::new(10.51, 19.253),
+                    Point::new(9000.0, 20.0), Point::new(9001.1, -3.0)];
+    let mut stroke: StrokePoints = points.iter().copied().collect();
+    assert_eq!(4, stroke.len());
+    assert_eq!(points, stroke.as_slice());
+    assert_eq!(Some(Point::new(9001.1, -3.0)), stroke.last());
+
+    // an edited point doesn't move the others
+    for _ in 0..3 {
+        stroke.set(1, Point::new(11.03, 21.07));
+    }
+    assert_eq!(Some(Point::new(11.03, 21.07)), stroke.get(1));
+    assert_eq!(points[2..], stroke.as_slice()[2..]);
+    assert_eq!(Some(Point::new(10.0, 20.0)), stroke.first());
+    stroke.insert(1, Point::new(10.0, 30.0));
+    stroke.remove(0);
+    assert_eq!(vec![Point::new(10.0, 30.0), Point::new(11.03, 21.07)], stroke.as_slice()[..2].to_vec());
+    assert_eq!(4, stroke.len());
+    // out of range is left alone
+    stroke.set(9, Point::ORIGIN);
+    stroke.remove(9);
+    assert_eq!(4, stroke.len());
+}
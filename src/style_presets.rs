@@ -0,0 +1,102 @@
+//! style_presets
+//! Named styles kept with a drawing, picking one sets the draw color
+//! and width for the new widgets and restyles the selection.  The
+//! widgets have no dash styles or fills yet so a preset is the color
+//! and width.  The presets are saved next to the drawing, data.json
+//! has its presets in data.styles.json.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StylePreset {
+    pub name: String,
+    // rgba
+    pub color: [f32; 4],
+    pub width: f32,
+}
+
+impl StylePreset {
+    pub fn new(name: &str, color: Color, width: f32) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            color: [color.r, color.g, color.b, color.a],
+            width,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        let [r, g, b, a] = self.color;
+        Color::from_rgba(r, g, b, a)
+    }
+}
+
+// A preset saved under a name already used takes its place.
+pub fn add_preset(presets: &mut Vec<StylePreset>, preset: StylePreset) {
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(p) => *p = preset,
+        None => presets.push(preset),
+    }
+}
+
+pub fn find_preset<'a>(presets: &'a [StylePreset], name: &str) -> Option<&'a StylePreset> {
+    presets.iter().find(|p| p.name == name)
+}
+
+pub fn get_preset_names(presets: &[StylePreset]) -> Vec<String> {
+    presets.iter().map(|p| p.name.clone()).collect()
+}
+
+// data.json and data.json.gz both have data.styles.json.
+pub fn get_styles_path(path: &Path) -> PathBuf {
+    let path = if path.extension().is_some_and(|ext| ext == "gz") { path.with_extension("") } else { path.to_path_buf() };
+    path.with_extension("styles.json")
+}
+
+// A drawing without presets leaves no file.
+pub fn save_presets(path: &Path, presets: &[StylePreset]) -> io::Result<()> {
+    let styles_path = get_styles_path(path);
+    if presets.is_empty() {
+        if styles_path.exists() {
+            fs::remove_file(styles_path)?;
+        }
+        return Ok(())
+    }
+    let data = serde_json::to_string_pretty(presets)?;
+    fs::write(styles_path, data)
+}
+
+// A missing or unreadable file has no presets.
+pub fn load_presets(path: &Path) -> Vec<StylePreset> {
+    fs::read_to_string(get_styles_path(path))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+
+#[test]
+fn test_style_presets() {
+    assert_eq!(PathBuf::from("dir/data.styles.json"), get_styles_path(Path::new("dir/data.json")));
+    assert_eq!(PathBuf::from("dir/data.styles.json"), get_styles_path(Path::new("dir/data.json.gz")));
+
+    let mut presets = vec![];
+    add_preset(&mut presets, StylePreset::new(" Outline ", Color::BLACK, 2.0));
+    add_preset(&mut presets, StylePreset::new("Highlight", Color::from_rgba(1.0, 1.0, 0.0, 0.5), 8.0));
+    // saved again under the same name
+    add_preset(&mut presets, StylePreset::new("Outline", Color::WHITE, 3.0));
+    assert_eq!(vec!["Outline".to_string(), "Highlight".to_string()], get_preset_names(&presets));
+    assert_eq!(Some(Color::WHITE), find_preset(&presets, "Outline").map(StylePreset::color));
+
+    let path = std::env::temp_dir().join("draw_test_presets.json");
+    save_presets(&path, &presets).unwrap();
+    assert_eq!(presets, load_presets(&path));
+    save_presets(&path, &[]).unwrap();
+    assert!(!get_styles_path(&path).exists());
+    assert!(load_presets(&path).is_empty());
+}
@@ -0,0 +1,633 @@
+//! svg_import
+//! Converts a subset of svg, paths, circles, ellipses, lines,
+//! polylines, polygons, rects and text, into widgets.
+//! Transforms, styles sheets and gradients are not supported.
+
+use std::f32::consts::PI;
+
+use iced::widget::text::{LineHeight, Shaping};
+use iced::{alignment, Color, Font, Pixels, Point, Radians, Vector};
+
+use crate::draw_canvas::{new_line, Bezier, CanvasWidget, Circle, DrawMode, DrawStatus, Ellipse, FreeHand, PolyLine, PolyLineCenter, Text};
+use crate::helpers::{get_horizontal_angle_of_vector, get_mid_point, get_polyline_center, to_radians};
+use crate::ids::WidgetId;
+use crate::text_edit::TextCursor;
+
+
+// Number of points used for each cubic curve or arc
+// when a path is converted to a FreeHand.
+const CURVE_STEPS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Segment {
+    Line(Point),
+    Quadratic(Point, Point),
+    Cubic(Point, Point, Point),
+}
+
+// The start point and the segments of a path up to the next move.
+#[derive(Debug, Clone, PartialEq)]
+struct SubPath {
+    start: Point,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+// The stroke is used for the color, if none, then the fill.
+// Elements without either use the draw color.
+pub fn import_svg(data: &str, draw_color: Color, draw_width: f32) -> Result<Vec<CanvasWidget>, String> {
+    let doc = roxmltree::Document::parse(data).map_err(|e| e.to_string())?;
+    let mut widgets = vec![];
+
+    for node in doc.descendants().filter(|n| n.is_element()) {
+        let color = get_color(&node).unwrap_or(draw_color);
+        let width = get_number(&node, "stroke-width").unwrap_or(draw_width);
+
+        match node.tag_name().name() {
+            "path" => {
+                let Some(d) = node.attribute("d") else {
+                    continue
+                };
+                // a path that can't be read is left out, not the whole file
+                let Ok(sub_paths) = parse_path(d) else {
+                    continue
+                };
+                for sub_path in sub_paths {
+                    if let Some(widget) = convert_sub_path(&sub_path, color, width) {
+                        widgets.push(widget);
+                    }
+                }
+            },
+            "circle" => {
+                let center = Point::new(get_number(&node, "cx").unwrap_or(0.0), get_number(&node, "cy").unwrap_or(0.0));
+                let radius = get_number(&node, "r").unwrap_or(0.0);
+                if radius > 0.0 {
+                    widgets.push(new_circle(center, radius, color, width));
+                }
+            },
+            "ellipse" => {
+                let center = Point::new(get_number(&node, "cx").unwrap_or(0.0), get_number(&node, "cy").unwrap_or(0.0));
+                let radii = Vector::new(get_number(&node, "rx").unwrap_or(0.0), get_number(&node, "ry").unwrap_or(0.0));
+                if radii.x > 0.0 && radii.y > 0.0 {
+                    widgets.push(new_ellipse(center, radii, color, width));
+                }
+            },
+            "line" => {
+                let points = vec![
+                    Point::new(get_number(&node, "x1").unwrap_or(0.0), get_number(&node, "y1").unwrap_or(0.0)),
+                    Point::new(get_number(&node, "x2").unwrap_or(0.0), get_number(&node, "y2").unwrap_or(0.0)),
+                ];
+                widgets.push(new_line(points, color, width));
+            },
+            "polyline" | "polygon" => {
+                let points = parse_points(node.attribute("points").unwrap_or(""));
+                if points.len() > 1 {
+                    let closed = node.tag_name().name() == "polygon";
+                    widgets.push(new_poly_line(points, color, width, closed));
+                }
+            },
+            "rect" => {
+                let x = get_number(&node, "x").unwrap_or(0.0);
+                let y = get_number(&node, "y").unwrap_or(0.0);
+                let w = get_number(&node, "width").unwrap_or(0.0);
+                let h = get_number(&node, "height").unwrap_or(0.0);
+                if w > 0.0 && h > 0.0 {
+                    let points = vec![
+                        Point::new(x, y),
+                        Point::new(x + w, y),
+                        Point::new(x + w, y + h),
+                        Point::new(x, y + h),
+                    ];
+                    widgets.push(new_poly_line(points, color, width, true));
+                }
+            },
+            "text" => {
+                let content: String =
+                    node.descendants()
+                        .filter(|n| n.is_text())
+                        .filter_map(|n| n.text())
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+                let content = content.trim();
+                if content.is_empty() {
+                    continue
+                }
+                let position = Point::new(get_number(&node, "x").unwrap_or(0.0), get_number(&node, "y").unwrap_or(0.0));
+                let size = get_number(&node, "font-size").unwrap_or(16.0);
+                let anchor = get_style(&node, "text-anchor").unwrap_or_default();
+                widgets.push(new_text(content, position, size, &anchor, color));
+            },
+            _ => (),
+        }
+    }
+
+    Ok(widgets)
+}
+
+// Attributes may also be given in the style attribute.
+fn get_style(node: &roxmltree::Node, name: &str) -> Option<String> {
+    if let Some(value) = node.attribute(name) {
+        return Some(value.trim().to_string())
+    }
+    node.attribute("style")?
+        .split(';')
+        .filter_map(|item| item.split_once(':'))
+        .find(|(key, _)| key.trim() == name)
+        .map(|(_, value)| value.trim().to_string())
+}
+
+// Only user units, the px and pt suffixes are dropped.
+fn get_number(node: &roxmltree::Node, name: &str) -> Option<f32> {
+    get_style(node, name)?
+        .trim_end_matches("px")
+        .trim_end_matches("pt")
+        .parse()
+        .ok()
+}
+
+fn get_color(node: &roxmltree::Node) -> Option<Color> {
+    ["stroke", "fill"].iter()
+        .filter_map(|name| get_style(node, name))
+        .find_map(|value| parse_color(&value))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "none" | "transparent" => None,
+        "black" => Some(Color::BLACK),
+        "white" => Some(Color::WHITE),
+        "red" => Some(Color::from_rgb8(255, 0, 0)),
+        "green" => Some(Color::from_rgb8(0, 128, 0)),
+        "blue" => Some(Color::from_rgb8(0, 0, 255)),
+        "yellow" => Some(Color::from_rgb8(255, 255, 0)),
+        "gray" | "grey" => Some(Color::from_rgb8(128, 128, 128)),
+        _ => {
+            if let Some(rgb) = value.strip_prefix("rgb(").and_then(|v| v.strip_suffix(')')) {
+                let channels: Vec<u8> = rgb.split(',').filter_map(|c| c.trim().parse().ok()).collect();
+                if channels.len() == 3 {
+                    return Some(Color::from_rgb8(channels[0], channels[1], channels[2]))
+                }
+                return None
+            }
+            let hex = value.strip_prefix('#')?;
+            // short form #rgb
+            if hex.len() == 3 {
+                let long: String = hex.chars().flat_map(|c| [c, c]).collect();
+                return Color::parse(&long)
+            }
+            Color::parse(hex)
+        },
+    }
+}
+
+fn parse_numbers(data: &str) -> Vec<f32> {
+    let mut numbers = vec![];
+    let mut current = String::new();
+    for c in data.chars() {
+        let starts_new =
+            (c == '-' && !current.is_empty() && !current.ends_with(['e', 'E'])) ||
+            (c == '.' && current.contains('.'));
+        if starts_new || c.is_whitespace() || c == ',' {
+            if let Ok(number) = current.parse() {
+                numbers.push(number);
+            }
+            current.clear();
+        }
+        if !(c.is_whitespace() || c == ',') {
+            current.push(c);
+        }
+    }
+    if let Ok(number) = current.parse() {
+        numbers.push(number);
+    }
+    numbers
+}
+
+fn parse_points(data: &str) -> Vec<Point> {
+    parse_numbers(data)
+        .chunks_exact(2)
+        .map(|xy| Point::new(xy[0], xy[1]))
+        .collect()
+}
+
+// The smooth curves reflect the control point before them,
+// arcs become cubic curves of a quarter turn or less.
+fn parse_path(data: &str) -> Result<Vec<SubPath>, String> {
+    // split the data into the commands and their numbers
+    let mut commands: Vec<(char, Vec<f32>)> = vec![];
+    let mut start = 0;
+    for (index, c) in data.char_indices() {
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            if let Some((_, numbers)) = commands.last_mut() {
+                *numbers = parse_numbers(&data[start..index]);
+            }
+            commands.push((c, vec![]));
+            start = index + c.len_utf8();
+        }
+    }
+    if let Some((_, numbers)) = commands.last_mut() {
+        *numbers = parse_numbers(&data[start..]);
+    }
+
+    let mut sub_paths: Vec<SubPath> = vec![];
+    let mut current = Point::ORIGIN;
+    let mut start_point = Point::ORIGIN;
+    // the control points a following S or T reflects
+    let mut last_cubic: Option<Point> = None;
+    let mut last_quadratic: Option<Point> = None;
+
+    for (command, numbers) in commands.iter() {
+        let relative = command.is_ascii_lowercase();
+        let offset = |current: Point| if relative { Vector::new(current.x, current.y) } else { Vector::new(0.0, 0.0) };
+        let size =
+            match command.to_ascii_uppercase() {
+                'M' | 'L' | 'T' => 2,
+                'H' | 'V' => 1,
+                'Q' | 'S' => 4,
+                'C' => 6,
+                'A' => 7,
+                'Z' => 0,
+                _ => return Err(format!("Unsupported path command {}", command)),
+            };
+
+        if size == 0 {
+            if let Some(sub_path) = sub_paths.last_mut() {
+                sub_path.closed = true;
+                if current != start_point {
+                    sub_path.segments.push(Segment::Line(start_point));
+                }
+            }
+            current = start_point;
+            (last_cubic, last_quadratic) = (None, None);
+            continue
+        }
+
+        for (index, n) in numbers.chunks_exact(size).enumerate() {
+            let off = offset(current);
+            let (mut cubic, mut quadratic) = (None, None);
+            match command.to_ascii_uppercase() {
+                'M' if index == 0 => {
+                    current = Point::new(n[0], n[1]) + off;
+                    start_point = current;
+                    sub_paths.push(SubPath { start: current, segments: vec![], closed: false });
+                },
+                // extra pairs after a move are lines
+                'M' | 'L' => {
+                    current = Point::new(n[0], n[1]) + off;
+                    push_segment(&mut sub_paths, start_point, Segment::Line(current));
+                },
+                'H' => {
+                    current = Point::new(n[0] + off.x, current.y);
+                    push_segment(&mut sub_paths, start_point, Segment::Line(current));
+                },
+                'V' => {
+                    current = Point::new(current.x, n[0] + off.y);
+                    push_segment(&mut sub_paths, start_point, Segment::Line(current));
+                },
+                'A' => {
+                    let end = Point::new(n[5], n[6]) + off;
+                    for segment in get_arc_segments(current, Vector::new(n[0], n[1]), n[2], n[3] != 0.0, n[4] != 0.0, end) {
+                        push_segment(&mut sub_paths, start_point, segment);
+                    }
+                    current = end;
+                },
+                'Q' | 'T' => {
+                    let control = 
+                        if n.len() == 4 {
+                            Point::new(n[0], n[1]) + off
+                        } else {
+                            reflect(last_quadratic, current)
+                        };
+                    current = Point::new(n[n.len() - 2], n[n.len() - 1]) + off;
+                    push_segment(&mut sub_paths, start_point, Segment::Quadratic(control, current));
+                    quadratic = Some(control);
+                },
+                'C' | 'S' => {
+                    let (c1, c2) = 
+                        if n.len() == 6 {
+                            (Point::new(n[0], n[1]) + off, Point::new(n[2], n[3]) + off)
+                        } else {
+                            (reflect(last_cubic, current), Point::new(n[0], n[1]) + off)
+                        };
+                    current = Point::new(n[n.len() - 2], n[n.len() - 1]) + off;
+                    push_segment(&mut sub_paths, start_point, Segment::Cubic(c1, c2, current));
+                    cubic = Some(c2);
+                },
+                _ => (),
+            }
+            (last_cubic, last_quadratic) = (cubic, quadratic);
+        }
+    }
+
+    Ok(sub_paths.into_iter().filter(|sp| !sp.segments.is_empty()).collect())
+}
+
+// The control point before turned about the current
+// point, the current point when there is none.
+fn reflect(control: Option<Point>, current: Point) -> Point {
+    control.map_or(current, |control| current + (current - control))
+}
+
+// The svg arc from its end points to its center, then split into
+// cubic curves of a quarter turn or less.  A radius of zero is a line.
+fn get_arc_segments(from: Point, radii: Vector, x_rotation: f32, large_arc: bool, sweep: bool, to: Point) -> Vec<Segment> {
+    if from == to {
+        return vec![]
+    }
+    let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+    if rx == 0.0 || ry == 0.0 {
+        return vec![Segment::Line(to)]
+    }
+    let (sin, cos) = to_radians(&x_rotation).sin_cos();
+    let half = (from - to) * 0.5;
+    let x1 = cos * half.x + sin * half.y;
+    let y1 = -sin * half.x + cos * half.y;
+    // radii too small to reach are scaled up
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coef = sign * (numerator / denominator).max(0.0).sqrt();
+    let (cx1, cy1) = (coef * rx * y1 / ry, -coef * ry * x1 / rx);
+    let center = Point::new(
+        cos * cx1 - sin * cy1 + (from.x + to.x) / 2.0,
+        sin * cx1 + cos * cy1 + (from.y + to.y) / 2.0,
+    );
+    let angle = |x: f32, y: f32| y.atan2(x);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut sweep_angle = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - start;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += 2.0 * PI;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= 2.0 * PI;
+    }
+
+    let point = |t: f32| Point::new(
+        center.x + rx * t.cos() * cos - ry * t.sin() * sin,
+        center.y + rx * t.cos() * sin + ry * t.sin() * cos,
+    );
+    let tangent = |t: f32| Vector::new(
+        -rx * t.sin() * cos - ry * t.cos() * sin,
+        -rx * t.sin() * sin + ry * t.cos() * cos,
+    );
+    let pieces = (sweep_angle.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let step = sweep_angle / pieces as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    (0..pieces)
+        .map(|piece| {
+            let (t0, t1) = (start + step * piece as f32, start + step * (piece + 1) as f32);
+            let end = if piece + 1 == pieces { to } else { point(t1) };
+            Segment::Cubic(point(t0) + tangent(t0) * k, end - tangent(t1) * k, end)
+        })
+        .collect()
+}
+
+// A path without a move starts at the last start point.
+fn push_segment(sub_paths: &mut Vec<SubPath>, start: Point, segment: Segment) {
+    if sub_paths.is_empty() {
+        sub_paths.push(SubPath { start, segments: vec![], closed: false });
+    }
+    if let Some(sub_path) = sub_paths.last_mut() {
+        sub_path.segments.push(segment);
+    }
+}
+
+// A single line or quadratic keeps its type, only lines become
+// a PolyLine and anything with curves becomes a FreeHand.
+fn convert_sub_path(sub_path: &SubPath, color: Color, width: f32) -> Option<CanvasWidget> {
+    let start = sub_path.start;
+    match sub_path.segments.as_slice() {
+        [] => None,
+        [Segment::Line(end)] => Some(new_line(vec![start, *end], color, width)),
+        [Segment::Quadratic(control, end)] => Some(new_bezier(start, *end, *control, color, width)),
+        segments => {
+            let mut points = vec![start];
+            let mut curved = false;
+            for segment in segments.iter() {
+                let from = *points.last().unwrap();
+                match *segment {
+                    Segment::Line(end) => points.push(end),
+                    Segment::Quadratic(control, end) => {
+                        curved = true;
+                        for step in 1..=CURVE_STEPS {
+                            let t = step as f32 / CURVE_STEPS as f32;
+                            points.push(get_quadratic_point(from, control, end, t));
+                        }
+                    },
+                    Segment::Cubic(c1, c2, end) => {
+                        curved = true;
+                        for step in 1..=CURVE_STEPS {
+                            let t = step as f32 / CURVE_STEPS as f32;
+                            points.push(get_cubic_point(from, c1, c2, end, t));
+                        }
+                    },
+                }
+            }
+            if curved {
+                Some(new_free_hand(points, color, width))
+            } else {
+                Some(new_poly_line(points, color, width, false))
+            }
+        },
+    }
+}
+
+fn get_quadratic_point(p0: Point, p1: Point, p2: Point, t: f32) -> Point {
+    let u = 1.0 - t;
+    Point::new(
+        u * u * p0.x + 2.0 * u * t * p1.x + t * t * p2.x,
+        u * u * p0.y + 2.0 * u * t * p1.y + t * t * p2.y,
+    )
+}
+
+fn get_cubic_point(p0: Point, p1: Point, p2: Point, p3: Point, t: f32) -> Point {
+    let u = 1.0 - t;
+    Point::new(
+        u * u * u * p0.x + 3.0 * u * u * t * p1.x + 3.0 * u * t * t * p2.x + t * t * t * p3.x,
+        u * u * u * p0.y + 3.0 * u * u * t * p1.y + 3.0 * u * t * t * p2.y + t * t * t * p3.y,
+    )
+}
+
+fn new_circle(center: Point, radius: f32, color: Color, width: f32) -> CanvasWidget {
+    CanvasWidget::Circle(
+        Circle {
+            id: WidgetId::unique(),
+            center,
+            circle_point: Point::new(center.x + radius, center.y),
+            radius,
+            color,
+            width,
+            hatch: None,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+fn new_ellipse(center: Point, radii: Vector, color: Color, width: f32) -> CanvasWidget {
+    CanvasWidget::Ellipse(
+        Ellipse {
+            id: WidgetId::unique(),
+            points: vec![
+                center,
+                Point::new(center.x + radii.x, center.y),
+                Point::new(center.x, center.y + radii.y),
+            ],
+            center,
+            radii,
+            rotation: Radians(0.0),
+            color,
+            width,
+            hatch: None,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+fn new_bezier(start: Point, end: Point, control: Point, color: Color, width: f32) -> CanvasWidget {
+    CanvasWidget::Bezier(
+        Bezier {
+            id: WidgetId::unique(),
+            points: vec![start, end, control],
+            mid_point: get_mid_point(start, end),
+            color,
+            width,
+            degrees: 0.0,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+// The mid point and degrees are found the same way
+// as when a PolyLine is drawn.
+fn new_poly_line(points: Vec<Point>, color: Color, width: f32, closed: bool) -> CanvasWidget {
+    let mid_point = get_polyline_center(&points, PolyLineCenter::default());
+    let pl_point = Point::new(mid_point.x + 100.0, mid_point.y);
+    CanvasWidget::PolyLine(
+        PolyLine {
+            id: WidgetId::unique(),
+            poly_points: points.len(),
+            points,
+            mid_point,
+            pl_point,
+            center: PolyLineCenter::default(),
+            closed,
+            color,
+            width,
+            hatch: None,
+            degrees: get_horizontal_angle_of_vector(mid_point, pl_point),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+fn new_free_hand(points: Vec<Point>, color: Color, width: f32) -> CanvasWidget {
+    CanvasWidget::FreeHand(
+        FreeHand {
+            id: WidgetId::unique(),
+            points: points.into_iter().collect(),
+            color,
+            width,
+            widths: vec![],
+            smooth: false,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+            completed: true,
+        }
+    )
+}
+
+// The svg position is on the baseline.
+fn new_text(content: &str, position: Point, size: f32, anchor: &str, color: Color) -> CanvasWidget {
+    let horizontal_alignment =
+        match anchor {
+            "middle" => alignment::Horizontal::Center,
+            "end" => alignment::Horizontal::Right,
+            _ => alignment::Horizontal::Left,
+        };
+    CanvasWidget::Text(
+        Text {
+            id: WidgetId::unique(),
+            content: content.to_string(),
+            position,
+            color,
+            size: Pixels(size),
+            line_height: LineHeight::Relative(1.2),
+            font: Font::default(),
+            horizontal_alignment,
+            vertical_alignment: alignment::Vertical::Bottom,
+            shaping: Shaping::Basic,
+            degrees: 0.0,
+            cursor: TextCursor::default(),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+
+#[test]
+fn test_parse_numbers() {
+    assert_eq!(vec![10.0, -5.5, 0.5, 0.25, 1e2], parse_numbers("10-5.5.5,0.25 1e2"));
+}
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(Some(Color::from_rgb8(255, 0, 0)), parse_color("#f00"));
+    assert_eq!(Some(Color::from_rgb8(0, 128, 255)), parse_color("rgb(0, 128, 255)"));
+    assert_eq!(None, parse_color("none"));
+}
+
+#[test]
+fn test_parse_path() {
+    let sub_paths = parse_path("M10 10 h20 v20 z m5 5 q5 -5 10 0").unwrap();
+    assert_eq!(2, sub_paths.len());
+    assert!(sub_paths[0].closed);
+    assert_eq!(
+        vec![
+            Segment::Line(Point::new(30.0, 10.0)),
+            Segment::Line(Point::new(30.0, 30.0)),
+            Segment::Line(Point::new(10.0, 10.0)),
+        ],
+        sub_paths[0].segments);
+    assert_eq!(Point::new(15.0, 15.0), sub_paths[1].start);
+    assert_eq!(vec![Segment::Quadratic(Point::new(20.0, 10.0), Point::new(25.0, 15.0))], sub_paths[1].segments);
+
+    // the smooth curves reflect the control point before them
+    let sub_paths = parse_path("M0 0 C0 10 10 10 10 0 S20 -10 20 0 M0 0 Q5 5 10 0 t10 0").unwrap();
+    assert_eq!(Segment::Cubic(Point::new(10.0, -10.0), Point::new(20.0, -10.0), Point::new(20.0, 0.0)), sub_paths[0].segments[1]);
+    assert_eq!(Segment::Quadratic(Point::new(15.0, -5.0), Point::new(20.0, 0.0)), sub_paths[1].segments[1]);
+
+    // a half circle is two quarters through the top
+    let sub_paths = parse_path("M0 0 A10 10 0 0 1 20 0").unwrap();
+    let Segment::Cubic(_, _, quarter) = sub_paths[0].segments[0] else {
+        panic!("not a curve")
+    };
+    assert!(quarter.distance(Point::new(10.0, -10.0)) < 0.01);
+    assert_eq!(2, sub_paths[0].segments.len());
+    assert!(matches!(sub_paths[0].segments[1], Segment::Cubic(_, _, end) if end == Point::new(20.0, 0.0)));
+}
+
+#[test]
+fn test_import_svg() {
+    let svg = r##"<svg xmlns="http://www.w3.org/2000/svg">
+        <circle cx="50" cy="50" r="20" stroke="#000"/>
+        <line x1="0" y1="0" x2="10" y2="10"/>
+        <text x="5" y="5">Valve</text>
+        <path d="M0 0 B10 10"/>
+    </svg>"##;
+    // the path it can't read is left out
+    let widgets = import_svg(svg, Color::WHITE, 2.0).unwrap();
+    assert_eq!(3, widgets.len());
+    assert!(matches!(&widgets[0], CanvasWidget::Circle(cir) if cir.color == Color::BLACK && cir.radius == 20.0));
+    assert!(matches!(&widgets[1], CanvasWidget::Line(ln) if ln.color == Color::WHITE));
+    assert!(matches!(&widgets[2], CanvasWidget::Text(txt) if txt.content == "Valve"));
+}
@@ -0,0 +1,75 @@
+//! templates
+//! Any drawing can be saved as a template in the templates folder,
+//! with its style presets.  A new drawing from a template starts with
+//! its title blocks, grids and frames, and is saved where Save asks
+//! so the template isn't saved over.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::document::{read_document, ExportDocument};
+
+
+pub const TEMPLATES_DIR: &str = "./resources/templates";
+
+pub fn get_template_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name.trim()))
+}
+
+// A name is used as the file name, so no folders.
+pub fn is_template_name(name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty() && !name.contains(['/', '\\', '.'])
+}
+
+pub fn list_templates(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> =
+        match fs::read_dir(dir) {
+            Ok(entries) => {
+                entries.filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                    // the style presets saved beside a template
+                    .filter(|name| !name.ends_with(".styles"))
+                    .collect()
+            },
+            Err(_) => vec![],
+        };
+    names.sort();
+    names
+}
+
+pub fn save_template(dir: &Path, name: &str, document: &ExportDocument) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = get_template_path(dir, name);
+    fs::write(&path, serde_json::to_string_pretty(document)?)?;
+    Ok(path)
+}
+
+pub fn load_template(dir: &Path, name: &str) -> Option<ExportDocument> {
+    read_document(&get_template_path(dir, name)).ok()
+}
+
+
+#[test]
+fn test_templates() {
+    use crate::examples::load_example;
+    use crate::style_presets::{save_presets, StylePreset};
+
+    let dir = std::env::temp_dir().join("draw_test_templates");
+    let _ = fs::remove_dir_all(&dir);
+    assert!(list_templates(&dir).is_empty());
+    assert!(is_template_name(" Title Block "));
+    assert!(!is_template_name("../data"));
+    assert!(!is_template_name(" "));
+
+    let document = load_example("Flowchart").unwrap();
+    let path = save_template(&dir, "Title Block", &document).unwrap();
+    save_template(&dir, "A4 Frame", &ExportDocument::new(vec![])).unwrap();
+    save_presets(&path, &[StylePreset::new("Frame", iced::Color::BLACK, 3.0)]).unwrap();
+    assert_eq!(vec!["A4 Frame".to_string(), "Title Block".to_string()], list_templates(&dir));
+    assert_eq!(Some(document.widgets.len()), load_template(&dir, "Title Block").map(|template| template.widgets.len()));
+    assert!(load_template(&dir, "Nothing").is_none());
+}
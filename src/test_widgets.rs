@@ -0,0 +1,49 @@
+//! test_widgets
+//! Completed widgets for the tests, black and 2 wide. They are the
+//! structs, so a test can change a field with `..line(p0, p1)`.
+
+use iced::{Color, Point, Vector};
+
+use crate::draw_canvas::{new_line, CanvasWidget, Circle, DrawMode, DrawStatus, Line, PolyLine, PolyLineCenter};
+use crate::helpers::get_polyline_center;
+use crate::ids::WidgetId;
+
+pub fn line(start: Point, end: Point) -> Line {
+    let CanvasWidget::Line(line) = new_line(vec![start, end], Color::BLACK, 2.0) else {
+        unreachable!()
+    };
+    line
+}
+
+pub fn circle(center: Point, radius: f32) -> Circle {
+    Circle {
+        id: WidgetId::unique(),
+        center,
+        circle_point: center + Vector::new(radius, 0.0),
+        radius,
+        color: Color::BLACK,
+        width: 2.0,
+        hatch: None,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    }
+}
+
+pub fn polyline(points: Vec<Point>, closed: bool) -> PolyLine {
+    let mid_point = get_polyline_center(&points, PolyLineCenter::Centroid);
+    PolyLine {
+        id: WidgetId::unique(),
+        poly_points: points.len(),
+        points,
+        mid_point,
+        pl_point: Point::new(mid_point.x + 100.0, mid_point.y),
+        center: PolyLineCenter::Centroid,
+        closed,
+        color: Color::BLACK,
+        width: 2.0,
+        hatch: None,
+        degrees: 0.0,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    }
+}
@@ -0,0 +1,140 @@
+//! text_edit
+//! The cursor and selection of the Text being typed.  The indexes
+//! count chars, not bytes, and the lines are split by '\r'.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCursor {
+    pub index: usize,
+    // where a shift selection started, it runs to the index
+    pub anchor: Option<usize>,
+}
+
+impl TextCursor {
+    // The selected chars, the start first.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.anchor
+            .filter(|anchor| *anchor != self.index)
+            .map(|anchor| (anchor.min(self.index), anchor.max(self.index)))
+    }
+
+    // A shift move starts or keeps the selection, any other drops it.
+    fn move_to(&mut self, index: usize, shift: bool) {
+        if !shift {
+            self.anchor = None;
+        } else if self.anchor.is_none() {
+            self.anchor = Some(self.index);
+        }
+        self.index = index;
+    }
+
+    pub fn left(&mut self, shift: bool) {
+        match self.selection() {
+            Some((start, _)) if !shift => self.move_to(start, false),
+            _ => self.move_to(self.index.saturating_sub(1), shift),
+        }
+    }
+
+    pub fn right(&mut self, content: &str, shift: bool) {
+        match self.selection() {
+            Some((_, end)) if !shift => self.move_to(end, false),
+            _ => self.move_to((self.index + 1).min(content.chars().count()), shift),
+        }
+    }
+
+    // The start of the line the cursor is on.
+    pub fn home(&mut self, content: &str, shift: bool) {
+        let start =
+            content.chars()
+                .take(self.index)
+                .collect::<Vec<char>>()
+                .iter()
+                .rposition(|c| *c == '\r')
+                .map_or(0, |newline| newline + 1);
+        self.move_to(start, shift);
+    }
+
+    pub fn end(&mut self, content: &str, shift: bool) {
+        let end =
+            content.chars()
+                .skip(self.index)
+                .position(|c| c == '\r')
+                .map_or(content.chars().count(), |newline| self.index + newline);
+        self.move_to(end, shift);
+    }
+
+    // Typed text replaces the selection.
+    pub fn insert(&mut self, content: &mut String, text: &str) {
+        self.delete_selection(content);
+        content.insert_str(byte_offset(content, self.index), text);
+        self.index += text.chars().count();
+    }
+
+    pub fn backspace(&mut self, content: &mut String) {
+        if !self.delete_selection(content) && self.index > 0 {
+            self.index -= 1;
+            content.remove(byte_offset(content, self.index));
+        }
+    }
+
+    pub fn delete(&mut self, content: &mut String) {
+        if !self.delete_selection(content) && self.index < content.chars().count() {
+            content.remove(byte_offset(content, self.index));
+        }
+    }
+
+    fn delete_selection(&mut self, content: &mut String) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+        let Some((start, end)) = selection else {
+            return false
+        };
+        content.replace_range(byte_offset(content, start)..byte_offset(content, end), "");
+        self.index = start;
+        true
+    }
+}
+
+// Where the char at the index starts in the string.
+pub fn byte_offset(content: &str, index: usize) -> usize {
+    content.char_indices().nth(index).map_or(content.len(), |(offset, _)| offset)
+}
+
+
+#[test]
+fn test_text_cursor() {
+    let mut content = String::new();
+    let mut cursor = TextCursor::default();
+    cursor.insert(&mut content, "Hllo");
+    cursor.home(&content, false);
+    cursor.right(&content, false);
+    cursor.insert(&mut content, "e");
+    assert_eq!("Hello", content);
+    assert_eq!(2, cursor.index);
+
+    // a second line, then select its first word back to the line start
+    cursor.end(&content, false);
+    cursor.insert(&mut content, "\rgrüne Welt");
+    cursor.left(false);
+    cursor.home(&content, true);
+    assert_eq!(Some((6, 15)), cursor.selection());
+    cursor.end(&content, false);
+    cursor.home(&content, false);
+    for _ in 0..5 {
+        cursor.right(&content, true);
+    }
+    cursor.insert(&mut content, "green");
+    assert_eq!("Hello\rgreen Welt", content);
+
+    // a selection is deleted whole, otherwise a char at a time
+    cursor.left(true);
+    cursor.left(true);
+    cursor.backspace(&mut content);
+    assert_eq!("Hello\rgre Welt", content);
+    cursor.delete(&mut content);
+    cursor.backspace(&mut content);
+    assert_eq!("Hello\rgrWelt", content);
+    assert_eq!(None, cursor.selection());
+    cursor.end(&content, false);
+    cursor.delete(&mut content);
+    assert_eq!(12, cursor.index);
+}
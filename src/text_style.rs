@@ -0,0 +1,150 @@
+//! text_style
+//! The font, size and line height of the Text widget, set from the
+//! side panel for new text and for the selected text, and saved
+//! with the drawing.
+
+use iced::font::{Family, Style, Weight};
+use iced::widget::text::LineHeight;
+use iced::{Font, Pixels};
+use serde::{Deserialize, Serialize};
+
+use crate::draw_canvas::Text;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FontFamily {
+    SansSerif,
+    Serif,
+    Monospace,
+}
+
+impl FontFamily {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            FontFamily::SansSerif => Some("Sans Serif".to_string()),
+            FontFamily::Serif => Some("Serif".to_string()),
+            FontFamily::Monospace => Some("Monospace".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Serif" => FontFamily::Serif,
+            "Monospace" => FontFamily::Monospace,
+            _ => FontFamily::SansSerif,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Sans Serif".to_string(), "Serif".to_string(), "Monospace".to_string()]
+    }
+
+    // The name used in the svg export.
+    pub fn css(&self) -> &'static str {
+        match self {
+            FontFamily::SansSerif => "sans-serif",
+            FontFamily::Serif => "serif",
+            FontFamily::Monospace => "monospace",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TextStyle {
+    pub family: FontFamily,
+    pub size: f32,
+    // a multiple of the size
+    pub line_height: f32,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            family: FontFamily::SansSerif,
+            size: 16.0,
+            line_height: 1.2,
+            bold: false,
+            italic: false,
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn from_text(txt: &Text) -> Self {
+        let family =
+            match txt.font.family {
+                Family::Serif => FontFamily::Serif,
+                Family::Monospace => FontFamily::Monospace,
+                _ => FontFamily::SansSerif,
+            };
+        let line_height =
+            match txt.line_height {
+                LineHeight::Relative(height) => height,
+                LineHeight::Absolute(height) if txt.size.0 > 0.0 => height.0 / txt.size.0,
+                LineHeight::Absolute(_) => 1.2,
+            };
+        TextStyle {
+            family,
+            size: txt.size.0,
+            line_height,
+            bold: txt.font.weight == Weight::Bold,
+            italic: txt.font.style == Style::Italic,
+        }
+    }
+
+    pub fn font(&self) -> Font {
+        let family =
+            match self.family {
+                FontFamily::SansSerif => Family::SansSerif,
+                FontFamily::Serif => Family::Serif,
+                FontFamily::Monospace => Family::Monospace,
+            };
+        Font {
+            family,
+            weight: if self.bold { Weight::Bold } else { Weight::Normal },
+            style: if self.italic { Style::Italic } else { Style::Normal },
+            ..Font::default()
+        }
+    }
+
+    pub fn apply(&self, txt: &mut Text) {
+        txt.size = Pixels(self.size);
+        txt.line_height = LineHeight::Relative(self.line_height);
+        txt.font = self.font();
+    }
+}
+
+
+#[test]
+fn test_text_style() {
+    use iced::{alignment, Color, Point};
+    use iced::widget::text::Shaping;
+    use crate::draw_canvas::{DrawMode, DrawStatus};
+    use crate::ids::WidgetId;
+    use crate::text_edit::TextCursor;
+
+    let mut txt = Text {
+        id: WidgetId::unique(),
+        content: "Label".to_string(),
+        position: Point::new(10.0, 10.0),
+        color: Color::BLACK,
+        size: Pixels(16.0),
+        line_height: LineHeight::Relative(1.2),
+        font: Font::default(),
+        horizontal_alignment: alignment::Horizontal::Center,
+        vertical_alignment: alignment::Vertical::Center,
+        shaping: Shaping::Basic,
+        degrees: 0.0,
+        cursor: TextCursor::default(),
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    };
+    assert_eq!(TextStyle::default(), TextStyle::from_text(&txt));
+
+    let style = TextStyle { family: FontFamily::Serif, size: 24.0, line_height: 1.5, bold: true, italic: true };
+    style.apply(&mut txt);
+    assert_eq!(style, TextStyle::from_text(&txt));
+    assert_eq!(Some("Serif".to_string()), FontFamily::to_enum("Serif".to_string()).string());
+}
@@ -0,0 +1,83 @@
+//! themes
+//! The themes offered in the side panel.  The first draw and canvas
+//! colors come from the theme's palette, the canvas a shade off the
+//! background and the draw color its text, so a new drawing is
+//! readable in every theme.
+
+use iced::{Color, Theme};
+use serde::{Deserialize, Serialize};
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppTheme {
+    Light,
+    Dark,
+    CatppuccinLatte,
+    CatppuccinFrappe,
+    CatppuccinMacchiato,
+    #[default]
+    CatppuccinMocha,
+}
+
+impl AppTheme {
+    pub fn string(&self) -> Option<String> {
+        match &self {
+            AppTheme::Light => Some("Light".to_string()),
+            AppTheme::Dark => Some("Dark".to_string()),
+            AppTheme::CatppuccinLatte => Some("Catppuccin Latte".to_string()),
+            AppTheme::CatppuccinFrappe => Some("Catppuccin Frappe".to_string()),
+            AppTheme::CatppuccinMacchiato => Some("Catppuccin Macchiato".to_string()),
+            AppTheme::CatppuccinMocha => Some("Catppuccin Mocha".to_string()),
+        }
+    }
+
+    pub fn to_enum(s: String) -> Self {
+        match s.as_str() {
+            "Light" => AppTheme::Light,
+            "Dark" => AppTheme::Dark,
+            "Catppuccin Latte" => AppTheme::CatppuccinLatte,
+            "Catppuccin Frappe" => AppTheme::CatppuccinFrappe,
+            "Catppuccin Macchiato" => AppTheme::CatppuccinMacchiato,
+            _ => AppTheme::CatppuccinMocha,
+        }
+    }
+
+    pub fn options() -> Vec<String> {
+        vec!["Light".to_string(), "Dark".to_string(), "Catppuccin Latte".to_string(),
+            "Catppuccin Frappe".to_string(), "Catppuccin Macchiato".to_string(), "Catppuccin Mocha".to_string()]
+    }
+
+    pub fn theme(&self) -> Theme {
+        match self {
+            AppTheme::Light => Theme::Light,
+            AppTheme::Dark => Theme::Dark,
+            AppTheme::CatppuccinLatte => Theme::CatppuccinLatte,
+            AppTheme::CatppuccinFrappe => Theme::CatppuccinFrappe,
+            AppTheme::CatppuccinMacchiato => Theme::CatppuccinMacchiato,
+            AppTheme::CatppuccinMocha => Theme::CatppuccinMocha,
+        }
+    }
+
+    // The draw color and the canvas color.
+    pub fn default_colors(&self) -> (Color, Color) {
+        let theme = self.theme();
+        let palette = theme.extended_palette();
+        (palette.background.base.text, palette.background.weak.color)
+    }
+}
+
+
+#[test]
+fn test_theme_colors() {
+    for name in AppTheme::options() {
+        let theme = AppTheme::to_enum(name.clone());
+        assert_eq!(Some(name), theme.string());
+        let (draw, canvas) = theme.default_colors();
+        assert_ne!(draw, canvas);
+    }
+    // dark lines on a light canvas and the other way round
+    let (draw, canvas) = AppTheme::Light.default_colors();
+    assert!(draw.r < canvas.r);
+    let (draw, canvas) = AppTheme::Dark.default_colors();
+    assert!(draw.r > canvas.r);
+}
@@ -0,0 +1,244 @@
+//! title_block
+//! A frame around the artboard with a title block in its bottom right
+//! corner, the project, author, date and scale of the document.  Each
+//! field's text is tagged with it so an edited field changes the text,
+//! and the whole block is tagged title block so a new one replaces it.
+
+use std::collections::HashMap;
+
+use iced::widget::text::{LineHeight, Shaping};
+use iced::{alignment, Color, Font, Pixels, Point, Size};
+
+use crate::document::DocumentMetadata;
+use crate::draw_canvas::{get_widget_id, CanvasWidget, DrawMode, DrawStatus, RoundedRect, Text};
+use crate::ids::WidgetId;
+use crate::layers::Layer;
+use crate::resize::{get_drawing_bounds, parse_factor};
+use crate::text_edit::TextCursor;
+
+
+pub const TITLE_BLOCK_TAG: &str = "title block";
+// the prefix of the field tags, title:project and so on
+pub const TITLE_FIELD_TAG: &str = "title:";
+
+// The artboard of an empty drawing.
+pub const DEFAULT_FRAME_SIZE: Size = Size::new(800.0, 600.0);
+// Space between the artboard edge and the frame.
+const FRAME_MARGIN: f32 = 20.0;
+const FRAME_WIDTH: f32 = 2.0;
+const BLOCK_WIDTH: f32 = 260.0;
+const ROW_HEIGHT: f32 = 24.0;
+const TEXT_SIZE: f32 = 12.0;
+const TEXT_PADDING: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleField {
+    Project,
+    Author,
+    Date,
+    Scale,
+}
+
+impl TitleField {
+    pub fn all() -> [TitleField; 4] {
+        [TitleField::Project, TitleField::Author, TitleField::Date, TitleField::Scale]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TitleField::Project => "Project",
+            TitleField::Author => "Author",
+            TitleField::Date => "Date",
+            TitleField::Scale => "Scale",
+        }
+    }
+
+    // The tag of the text showing the field.
+    pub fn tag(&self) -> String {
+        format!("{}{}", TITLE_FIELD_TAG, self.label().to_lowercase())
+    }
+
+    pub fn value<'a>(&self, metadata: &'a DocumentMetadata) -> &'a str {
+        match self {
+            TitleField::Project => &metadata.project,
+            TitleField::Author => &metadata.author,
+            TitleField::Date => &metadata.date,
+            TitleField::Scale => &metadata.scale,
+        }
+    }
+
+    pub fn set(&self, metadata: &mut DocumentMetadata, value: String) {
+        match self {
+            TitleField::Project => metadata.project = value,
+            TitleField::Author => metadata.author = value,
+            TitleField::Date => metadata.date = value,
+            TitleField::Scale => metadata.scale = value,
+        }
+    }
+
+    pub fn content(&self, metadata: &DocumentMetadata) -> String {
+        format!("{}: {}", self.label(), self.value(metadata).trim())
+    }
+}
+
+// "800x600", an empty input is the artboard around the drawing.
+pub fn parse_frame_size(input: &str) -> Result<Option<Size>, String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Ok(None)
+    }
+    match input.split_once('x') {
+        Some((width, height)) => Ok(Some(Size::new(parse_factor(width)?, parse_factor(height)?))),
+        None => Err(format!("{} is not a width x height", input)),
+    }
+}
+
+// The drawing's right and bottom edges with room for the frame,
+// the old title block left out so it doesn't grow each time.
+pub fn get_frame_size(layers: &[Layer], tags: &HashMap<WidgetId, Vec<String>>) -> Size {
+    let old_ids = get_title_block_ids(tags);
+    let layers: Vec<Layer> =
+        layers.iter()
+            .map(|layer| {
+                let mut layer = layer.clone();
+                for id in old_ids.iter() {
+                    layer.remove(id);
+                }
+                layer
+            })
+            .collect();
+    match get_drawing_bounds(&layers) {
+        Some(bounds) => Size::new(
+            (bounds.x + bounds.width + FRAME_MARGIN * 2.0).max(BLOCK_WIDTH + FRAME_MARGIN * 3.0),
+            bounds.y + bounds.height + ROW_HEIGHT * 4.0 + FRAME_MARGIN * 2.0,
+        ),
+        None => DEFAULT_FRAME_SIZE,
+    }
+}
+
+// The frame, the rows of the block and their texts, with their tags.
+pub fn build_title_block(size: Size, metadata: &DocumentMetadata, color: Color)
+    -> (Vec<CanvasWidget>, HashMap<WidgetId, Vec<String>>) {
+    let right = size.width - FRAME_MARGIN;
+    let bottom = size.height - FRAME_MARGIN;
+    let mut widgets = vec![new_rectangle(Point::new(FRAME_MARGIN, FRAME_MARGIN), Point::new(right, bottom), color)];
+    let mut tags = HashMap::new();
+    tags.insert(get_widget_id(&widgets[0]), vec![TITLE_BLOCK_TAG.to_string()]);
+
+    let fields = TitleField::all();
+    let left = right - BLOCK_WIDTH;
+    let top = bottom - ROW_HEIGHT * fields.len() as f32;
+    for (index, field) in fields.iter().enumerate() {
+        let row_top = top + ROW_HEIGHT * index as f32;
+        let row = new_rectangle(Point::new(left, row_top), Point::new(right, row_top + ROW_HEIGHT), color);
+        tags.insert(get_widget_id(&row), vec![TITLE_BLOCK_TAG.to_string()]);
+        widgets.push(row);
+        let position = Point::new(left + TEXT_PADDING, row_top + ROW_HEIGHT / 2.0);
+        let label = new_label(&field.content(metadata), position, color);
+        tags.insert(get_widget_id(&label), vec![TITLE_BLOCK_TAG.to_string(), field.tag()]);
+        widgets.push(label);
+    }
+    (widgets, tags)
+}
+
+// The ids of the widgets with the title block tag.
+pub fn get_title_block_ids(tags: &HashMap<WidgetId, Vec<String>>) -> Vec<WidgetId> {
+    tags.iter()
+        .filter(|(_, widget_tags)| widget_tags.iter().any(|tag| tag == TITLE_BLOCK_TAG))
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+// The texts of the field take its new value, their ids are returned.
+pub fn update_title_field(layers: &mut [Layer],
+                        tags: &HashMap<WidgetId, Vec<String>>,
+                        field: TitleField,
+                        metadata: &DocumentMetadata,
+                        ) -> Vec<WidgetId> {
+    let field_tag = field.tag();
+    let mut ids = vec![];
+    for layer in layers.iter_mut() {
+        for (id, widget) in layer.curves.iter_mut() {
+            let CanvasWidget::Text(txt) = widget else {
+                continue
+            };
+            if tags.get(id).is_some_and(|widget_tags| widget_tags.contains(&field_tag)) {
+                txt.content = field.content(metadata);
+                ids.push(*id);
+            }
+        }
+    }
+    ids
+}
+
+// An upright rectangle with square corners.
+fn new_rectangle(first: Point, opposite: Point, color: Color) -> CanvasWidget {
+    let mut rect = RoundedRect {
+        id: WidgetId::unique(),
+        points: vec![],
+        mid_point: Point::ORIGIN,
+        radius: 0.0,
+        color,
+        width: FRAME_WIDTH,
+        hatch: None,
+        degrees: 0.0,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    };
+    rect.set_corners(first, opposite);
+    CanvasWidget::RoundedRect(rect)
+}
+
+fn new_label(content: &str, position: Point, color: Color) -> CanvasWidget {
+    CanvasWidget::Text(
+        Text {
+            id: WidgetId::unique(),
+            content: content.to_string(),
+            position,
+            color,
+            size: Pixels(TEXT_SIZE),
+            line_height: LineHeight::Relative(1.2),
+            font: Font::default(),
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Center,
+            shaping: Shaping::Basic,
+            degrees: 0.0,
+            cursor: TextCursor::default(),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    )
+}
+
+
+#[test]
+fn test_title_block() {
+    assert_eq!(Ok(Some(Size::new(1200.0, 800.0))), parse_frame_size(" 1200x800 "));
+    assert_eq!(Ok(None), parse_frame_size(""));
+    assert!(parse_frame_size("1200").is_err());
+
+    let mut metadata = DocumentMetadata { project: "Pump House".to_string(), ..DocumentMetadata::default() };
+    let (widgets, tags) = build_title_block(DEFAULT_FRAME_SIZE, &metadata, Color::BLACK);
+    // the frame and a row and a text for each field
+    assert_eq!(9, widgets.len());
+    assert_eq!(9, get_title_block_ids(&tags).len());
+    let mut layer = Layer::new("Layer 1".to_string());
+    for widget in widgets {
+        layer.insert(get_widget_id(&widget), widget);
+    }
+    let bounds = get_drawing_bounds(std::slice::from_ref(&layer)).unwrap();
+    assert!(bounds.x + bounds.width <= DEFAULT_FRAME_SIZE.width);
+    assert!(bounds.y + bounds.height <= DEFAULT_FRAME_SIZE.height);
+
+    let mut layers = vec![layer];
+    // the block alone is not the drawing
+    assert_eq!(DEFAULT_FRAME_SIZE, get_frame_size(&layers, &tags));
+
+    TitleField::Author.set(&mut metadata, "Ana".to_string());
+    let ids = update_title_field(&mut layers, &tags, TitleField::Author, &metadata);
+    assert_eq!(1, ids.len());
+    let CanvasWidget::Text(txt) = &layers[0].curves[&ids[0]] else {
+        panic!("not a text")
+    };
+    assert_eq!("Author: Ana", txt.content);
+}
@@ -0,0 +1,92 @@
+//! transform
+//! A 2D affine transform so moving, turning and scaling are done
+//! the same way for every widget instead of each shape working
+//! out its new points.
+
+use iced::{Point, Vector};
+
+use crate::helpers::{to_degrees, to_radians};
+
+
+// x' = a*x + c*y + tx
+// y' = b*x + d*y + ty
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    tx: f32,
+    ty: f32,
+}
+
+impl Transform2D {
+    pub fn translate(offset: Vector) -> Self {
+        Self { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx: offset.x, ty: offset.y }
+    }
+
+    // Turns by the degrees about the center.
+    pub fn rotate(center: Point, degrees: f32) -> Self {
+        let (sin, cos) = to_radians(&degrees).sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            tx: center.x - cos*center.x + sin*center.y,
+            ty: center.y - sin*center.x - cos*center.y,
+        }
+    }
+
+    // Scales evenly about the center.
+    pub fn scale(center: Point, factor: f32) -> Self {
+        Self {
+            a: factor,
+            b: 0.0,
+            c: 0.0,
+            d: factor,
+            tx: center.x * (1.0 - factor),
+            ty: center.y * (1.0 - factor),
+        }
+    }
+
+    pub fn apply(&self, pt: Point) -> Point {
+        Point::new(self.a*pt.x + self.c*pt.y + self.tx, self.b*pt.x + self.d*pt.y + self.ty)
+    }
+
+    pub fn apply_all(&self, pts: &mut [Point]) {
+        pts.iter_mut().for_each(|pt| *pt = self.apply(*pt));
+    }
+
+    // The turn, the widgets are only scaled evenly
+    // so the x axis gives it.
+    pub fn radians(&self) -> f32 {
+        self.b.atan2(self.a)
+    }
+
+    pub fn degrees(&self) -> f32 {
+        to_degrees(&self.radians())
+    }
+
+    // Multiplies the radii and text sizes.
+    pub fn scale_factor(&self) -> f32 {
+        (self.a*self.d - self.b*self.c).abs().sqrt()
+    }
+}
+
+
+#[test]
+fn test_transform() {
+    let center = Point::new(10.0, 10.0);
+    let pt = Transform2D::rotate(center, 90.0).apply(Point::new(20.0, 10.0));
+    assert!(pt.distance(Point::new(10.0, 20.0)) < 1.0e-4);
+
+    let scale = Transform2D::scale(center, 2.0);
+    assert_eq!(Point::new(30.0, 10.0), scale.apply(Point::new(20.0, 10.0)));
+    assert_eq!(2.0, scale.scale_factor());
+
+    let turn = Transform2D::rotate(center, 30.0);
+    assert!((turn.degrees() - 30.0).abs() < 1.0e-4);
+    assert!((turn.scale_factor() - 1.0).abs() < 1.0e-6);
+    assert_eq!(Point::new(5.0, -2.0), Transform2D::translate(Vector::new(5.0, -2.0)).apply(Point::ORIGIN));
+}
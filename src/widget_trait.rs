@@ -0,0 +1,2075 @@
+//! widget_trait
+//! What every shape can do, implemented once for each shape, its
+//! drawing, editing and saving too.  The functions like get_widget_id,
+//! translate_widget and set_widget_point go through the trait, so a
+//! new shape needs its impl here instead of an arm in each.
+
+use iced::widget::canvas::{Frame, LineCap, Path, Stroke};
+use iced::widget::text::{LineHeight, Shaping};
+use iced::{alignment, Color, Font, Pixels, Point, Radians, Rectangle, Theme, Vector};
+
+use crate::app::{convert_to_export_horizontal, convert_to_export_vertical, convert_to_iced_horizontal,
+    convert_to_iced_vertical, ExportPoint, ExportWidget};
+use crate::draw_canvas::{draw_arc_readouts, get_mid_geometry, Arc, ArcClosure, Bezier, CanvasWidget, Circle, DrawMode,
+    DrawStatus, Ellipse, FreeHand, Gauge, Line, PolyLine, Polygon, RightTriangle, RoundedRect, Text, Widget};
+use crate::hatch::{draw_hatch, get_circle_outline, get_hatch_marks, Hatch};
+use crate::helpers::{build_polygon, get_angle_of_vectors, get_catmull_rom_controls, get_horizontal_angle_of_vector,
+    get_mid_point, get_polyline_center, square_right_triangle, to_degrees, to_radians, translate_geometry};
+use crate::palettes::{simulate_color_vision, ColorVision};
+use crate::path_builds::{build_arc_path, build_bezier_path, build_circle_path, build_ellipse_path, build_free_hand_path,
+    build_gauge_path, build_line_path, build_polygon_path, build_polyline_path, build_right_triangle_path,
+    build_rounded_rect_path, build_text_path, PathOptions, WidgetPath};
+use crate::pressure::PressureCurve;
+use crate::text_edit::TextCursor;
+use crate::ids::WidgetId;
+use crate::text_style::TextStyle;
+use crate::transform::Transform2D;
+use crate::intersections::{get_bezier_points, get_distance_to_primitives, 
+    get_ellipse_points, get_gauge_primitives, get_rounded_rect_points, get_segments};
+
+
+// The cursor within this many pixels of a stroke is on it.
+const HIT_DISTANCE: f32 = 3.0;
+
+pub trait WidgetTrait: HitTest {
+    fn id(&self) -> WidgetId;
+    fn widget_type(&self) -> Widget;
+    fn color(&self) -> Color;
+    fn set_color(&mut self, color: Color);
+    fn width(&self) -> f32;
+    fn scale_width(&mut self, factor: f32);
+    // All of the defining points of the widget.
+    fn points(&self) -> Vec<Point>;
+    // None when the widget can't be rotated.
+    fn degrees(&self) -> Option<f32>;
+    // The box around the drawn widget, with the
+    // rotation and half the stroke width included.
+    fn bounds(&self) -> Rectangle;
+    fn mode_and_status(&self) -> (DrawMode, DrawStatus);
+    fn set_mode_or_status(&mut self, mode: Option<DrawMode>, status: Option<DrawStatus>);
+    // The point the widget is moved and turned by.
+    fn mid_point(&self) -> Point;
+    // The pattern fill, only the closed shapes have one.
+    fn hatch(&self) -> Option<Hatch> {
+        None
+    }
+    // False when the widget can't have a hatch.
+    fn set_hatch(&mut self, _hatch: Option<Hatch>) -> bool {
+        false
+    }
+    // Every point, radius, angle and text size of the
+    // widget follows the transform, the stroke width doesn't.
+    fn transform(&mut self, transform: &Transform2D);
+
+    fn translate(&mut self, offset: Vector) {
+        self.transform(&Transform2D::translate(offset));
+    }
+
+    fn scale(&mut self, center: Point, factor: f32) {
+        self.transform(&Transform2D::scale(center, factor));
+    }
+
+    // A freehand keeps the widths its pressure gave it.
+    fn set_width(&mut self, width: f32) {
+        if self.width() > 0.0 {
+            self.scale_width(width / self.width());
+        }
+    }
+
+    // Turns about the mid point and returns the degrees shown.
+    fn rotate(&mut self, step_degrees: f32) -> f32 {
+        self.transform(&Transform2D::rotate(self.mid_point(), step_degrees));
+        self.degrees().unwrap_or(0.0)
+    }
+
+    // The path of the finished widget, or of one being drawn,
+    // edited or rotated with the angles shown by it.
+    fn build_path(&self, options: &PathOptions) -> WidgetPath;
+
+    // The finished widget and its hatch.
+    fn draw(&self, frame: &mut Frame, vision: ColorVision) {
+        let color = simulate_color_vision(self.color(), vision);
+        let (draw_mode, _) = self.mode_and_status();
+        let path = self.build_path(&PathOptions::new(draw_mode)).path;
+        frame.stroke(&path, Stroke::default().with_width(self.width()).with_color(color));
+        draw_hatch(frame, &get_hatch_marks(self.hatch(), self.closed_outline()), color);
+    }
+
+    // The widget following the cursor, anything shown
+    // besides its path is drawn on the frame.
+    fn draw_pending(&self, _frame: &mut Frame, options: &PathOptions, _theme: &Theme) -> WidgetPath {
+        self.build_path(options)
+    }
+
+    // The size shown by the cursor while the widget is drawn or edited.
+    fn cursor_label(&self, _options: &PathOptions) -> Option<String> {
+        None
+    }
+
+    // Where the next point goes with shift held.
+    fn locked_cursor(&self, cursor: Point) -> Point {
+        cursor
+    }
+
+    // Adds a clicked point, true when the widget is finished.
+    fn set_point(&mut self, cursor: Point, pressure_curve: &PressureCurve) -> bool;
+
+    // Moves the point named by closest_point to the cursor,
+    // the mid point is moved with translate.
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, other_point: bool);
+
+    // The point an edit drags, an index into the points,
+    // the mid point or the other point.
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool);
+
+    // The points an edit moves and the other point,
+    // a rotation or radius handle.
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (self.points(), None)
+    }
+
+    // Where the point named by closest_point is, the mid point aside.
+    fn handle_point(&self, index: Option<usize>, other_point: bool) -> Option<Point> {
+        if other_point {
+            self.edit_handles().1
+        } else {
+            index.and_then(|index| self.points().get(index).copied())
+        }
+    }
+
+    // The outline a hatch fills, None for the open shapes.
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        None
+    }
+
+    fn to_export(&self) -> ExportWidget;
+
+    fn from_export(widget: &ExportWidget) -> Self where Self: Sized;
+}
+
+// How the cursor finds a widget when selecting, editing,
+// rotating or deleting.
+pub trait HitTest {
+    // The distance to the outline of the widget.
+    fn distance_to(&self, cursor: Point) -> f32;
+    // On the stroke, or inside the fill of a closed shape.
+    fn contains(&self, point: Point) -> bool;
+}
+
+// The methods that only read or set the fields every shape has.
+macro_rules! shape_fields {
+    ($widget:ident) => {
+        fn id(&self) -> WidgetId {
+            self.id
+        }
+
+        fn widget_type(&self) -> Widget {
+            Widget::$widget
+        }
+
+        fn color(&self) -> Color {
+            self.color
+        }
+
+        fn set_color(&mut self, color: Color) {
+            self.color = color;
+        }
+
+        fn mode_and_status(&self) -> (DrawMode, DrawStatus) {
+            (self.draw_mode, self.status)
+        }
+
+        fn set_mode_or_status(&mut self, mode: Option<DrawMode>, status: Option<DrawStatus>) {
+            if let Some(mode) = mode {
+                self.draw_mode = mode;
+            }
+            if let Some(status) = status {
+                self.status = status;
+            }
+        }
+    };
+}
+
+// The hatch of the shapes that can be filled.
+macro_rules! hatch_field {
+    () => {
+        fn hatch(&self) -> Option<Hatch> {
+            self.hatch
+        }
+
+        fn set_hatch(&mut self, hatch: Option<Hatch>) -> bool {
+            self.hatch = hatch;
+            true
+        }
+    };
+}
+
+fn on_stroke(distance: f32, width: f32) -> bool {
+    distance <= width / 2.0 + HIT_DISTANCE
+}
+
+fn distance_to_points(points: &[Point], cursor: Point) -> f32 {
+    get_distance_to_primitives(&get_segments(points), cursor)
+}
+
+// Even-odd test, the points are already closed.
+fn inside_polygon(points: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    for pts in points.windows(2) {
+        let (a, b) = (pts[0], pts[1]);
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+// The box around the points grown by half the width.
+pub(crate) fn get_points_bounds(points: &[Point], width: f32) -> Rectangle {
+    let Some(first) = points.first() else {
+        return Rectangle::default()
+    };
+    let mut min = *first;
+    let mut max = *first;
+    for pt in points.iter() {
+        min.x = min.x.min(pt.x);
+        min.y = min.y.min(pt.y);
+        max.x = max.x.max(pt.x);
+        max.y = max.y.max(pt.y);
+    }
+    let half = width / 2.0;
+    Rectangle::new(
+        Point::new(min.x - half, min.y - half),
+        iced::Size::new(max.x - min.x + width, max.y - min.y + width),
+    )
+}
+
+// Roughly the size of the text before it is rotated.
+pub fn get_text_box(txt: &Text) -> Rectangle {
+    let lines: Vec<&str> = txt.content.split('\r').collect();
+    let chars = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as f32;
+    let width = chars * txt.size.0 * 0.6;
+    let height = lines.len() as f32 * txt.size.0 * TextStyle::from_text(txt).line_height;
+    let left =
+        match txt.horizontal_alignment {
+            alignment::Horizontal::Left => txt.position.x,
+            alignment::Horizontal::Center => txt.position.x - width / 2.0,
+            alignment::Horizontal::Right => txt.position.x - width,
+        };
+    let top =
+        match txt.vertical_alignment {
+            alignment::Vertical::Top => txt.position.y,
+            alignment::Vertical::Center => txt.position.y - height / 2.0,
+            alignment::Vertical::Bottom => txt.position.y - height,
+        };
+    Rectangle::new(Point::new(left, top), iced::Size::new(width, height))
+}
+
+// The corners of the square around a circle.
+// The index of the point closest to the cursor and its distance.
+fn nearest_point(points: impl IntoIterator<Item = (usize, Point)>, cursor: Point) -> (usize, f32) {
+    let mut nearest = (0, f32::INFINITY);
+    for (index, point) in points {
+        let dist = cursor.distance(point);
+        if dist < nearest.1 {
+            nearest = (index, dist);
+        }
+    }
+    nearest
+}
+
+// The angle is left of the mid point while the shape
+// is drawn, above it while it is edited or turned.
+fn shape_path(path: Path, mid_point: Point, degrees: f32, draw_mode: DrawMode) -> WidgetPath {
+    match draw_mode {
+        DrawMode::New => WidgetPath { path, mid_point, degrees_left: Some(degrees), degrees_center: None },
+        _ => WidgetPath::with_degrees(path, mid_point, Some(degrees)),
+    }
+}
+
+fn export_points(points: &[Point]) -> Vec<ExportPoint> {
+    points.iter().map(ExportPoint::convert).collect()
+}
+
+fn bounding_points(center: Point, r: f32) -> Vec<Point> {
+    vec![Point::new(center.x - r, center.y - r),
+        Point::new(center.x + r, center.y + r)]
+}
+
+impl WidgetTrait for Arc {
+    shape_fields!(Arc);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(Radians::into(self.start_angle))
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&self.outline(), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.radius *= transform.scale_factor();
+        self.start_angle = Radians(self.start_angle.0 + transform.radians());
+        self.end_angle = Radians(self.end_angle.0 + transform.radians());
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, mid_point, _, _, degrees_left, degrees_center) = 
+            build_arc_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point, 
+                options.edit_other_point,
+            );
+        WidgetPath { path, mid_point, degrees_left, degrees_center }
+    }
+
+    // The handle dragged shows what it changes.
+    fn draw_pending(&self, frame: &mut Frame, options: &PathOptions, theme: &Theme) -> WidgetPath {
+        let dragged = options.edit_point_index.is_some() || options.edit_mid_point || options.edit_other_point;
+        if let (DrawMode::Edit, Some(cursor), true) = (options.draw_mode, options.cursor, dragged) {
+            let mut edited = self.clone();
+            if options.edit_mid_point {
+                edited.translate(cursor - edited.mid_point);
+            }
+            edited.edit_point(cursor, options.edit_point_index, options.edit_other_point);
+            draw_arc_readouts(frame, &edited, theme);
+        }
+        self.build_path(options)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        self.points.push(cursor);
+
+        match self.points.len() {
+            1 => {
+                self.mid_point = self.points[0];
+                false
+            },
+            2 => {
+                self.radius = self.points[0].distance(self.points[1]);
+                self.start_angle = 
+                    get_angle_of_vectors(
+                        self.points[0], 
+                        Point::new(-self.points[0].x, self.points[0].y), 
+                        self.points[1]) + Radians::PI;
+                false
+            },
+            3 => {
+                self.end_angle = 
+                    get_angle_of_vectors(
+                        self.points[0], 
+                        self.points[1], 
+                        cursor) + self.start_angle;
+                // calc the end_angle point        
+                let r = self.radius;
+                let b = self.end_angle.0;
+                let point_b = Point::new(r*b.cos(), r*b.sin());
+                self.points[2] = translate_geometry(&[point_b], self.mid_point, Point::default())[0];
+                true
+            },
+            _ => false
+        }
+    }
+
+    // the start, end and radius handles each change one thing
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, other_point: bool) {
+        if other_point {
+            self.set_radius(self.mid_point.distance(cursor));
+        }
+        match index {
+            Some(1) => self.set_start_angle(cursor),
+            Some(2) => self.set_end_angle(cursor),
+            _ => (),
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        // skip first point since its a mid_point too.
+        let (point_index, point_dist) = nearest_point(self.points.iter().copied().enumerate().skip(1), cursor);
+        let mid_dist = self.mid_point.distance(cursor);
+        let radius_dist = self.radius_point().distance(cursor);
+
+        if radius_dist < point_dist && radius_dist < mid_dist {
+            (None, false, true)
+        } else if mid_dist < point_dist {
+            (None, true, false)
+        } else {
+            (Some(point_index), false, false)
+        }
+    }
+
+    // the first point is the mid point
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (self.points[1..].to_vec(), Some(self.radius_point()))
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        (self.closure != ArcClosure::Open).then(|| self.outline())
+    }
+
+    // the angles are saved in the other point
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            mid_point: ExportPoint::convert(&self.mid_point),
+            other_point: ExportPoint::convert(&Point::new(self.start_angle.0, self.end_angle.0)),
+            radius: self.radius,
+            arc_closure: Some(self.closure),
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::Arc, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        let angles = widget.get_other_point();
+        Arc {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            mid_point: widget.get_mid_point(),
+            radius: widget.radius,
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            start_angle: Radians(angles.x),
+            end_angle: Radians(angles.y),
+            closure: widget.arc_closure.unwrap_or_default(),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for Bezier {
+    shape_fields!(Bezier);
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&get_bezier_points(self), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.points[1]);
+    }
+
+    // The angle is shown by the first point while the curve is drawn.
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, degrees, mid_point) = 
+            build_bezier_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point, 
+                options.degrees,
+            );
+        let mid_point = 
+            match options.draw_mode {
+                DrawMode::New => self.points.first().copied().unwrap_or(mid_point),
+                _ => mid_point,
+            };
+        WidgetPath::with_degrees(path, mid_point, Some(degrees))
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        self.points.push(cursor);
+        if self.points.len() == 2 {
+            self.degrees = get_horizontal_angle_of_vector(self.points[0], self.points[1]);
+        }
+        self.points.len() == 3
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, _other_point: bool) {
+        if let Some(index) = index {
+            self.points[index] = cursor;
+            self.mid_point = get_mid_point(self.points[0], self.points[1]);
+        }
+        self.degrees = get_horizontal_angle_of_vector(self.points[0], self.points[1]);
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let (point_index, point_dist) = nearest_point(self.points.iter().copied().enumerate(), cursor);
+        if self.mid_point.distance(cursor) < point_dist {
+            (None, true, false)
+        } else {
+            (Some(point_index), false, false)
+        }
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            mid_point: ExportPoint::convert(&self.mid_point),
+            rotation: self.degrees,
+            ..ExportWidget::new(Widget::Bezier, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        Bezier {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            mid_point: widget.get_mid_point(),
+            color: widget.get_color(),
+            width: widget.width,
+            degrees: widget.rotation,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for Circle {
+    shape_fields!(Circle);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        bounding_points(self.center, self.radius)
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(0.0)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&bounding_points(self.center, self.radius), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.center
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        self.center = transform.apply(self.center);
+        self.circle_point = transform.apply(self.circle_point);
+        self.radius *= transform.scale_factor();
+    }
+
+    // Turning a circle changes nothing drawn.
+    fn rotate(&mut self, _step_degrees: f32) -> f32 {
+        0.0
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let path = 
+            build_circle_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point,
+            );
+        WidgetPath::with_degrees(path, self.center, None)
+    }
+
+    // the radius to the cursor while it is drawn or its point dragged
+    fn cursor_label(&self, options: &PathOptions) -> Option<String> {
+        let cursor = options.cursor?;
+        let radius = 
+            if options.draw_mode == DrawMode::New || options.edit_point_index.is_some() {
+                self.center.distance(cursor)
+            } else {
+                self.radius
+            };
+        Some(format!("r {:.1}  d {:.1}", radius, radius * 2.0))
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        if self.center == Point::default() {
+            self.center = cursor;
+            false
+        } else {
+            self.radius = self.center.distance(cursor);
+            self.circle_point = cursor;
+            true
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, _other_point: bool) {
+        if index.is_some() {
+            self.circle_point = cursor;
+            self.radius = self.center.distance(cursor);
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        if cursor.distance(self.center) < cursor.distance(self.circle_point) {
+            (None, true, false)
+        } else {
+            (Some(1), false, false)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (vec![self.circle_point], None)
+    }
+
+    fn handle_point(&self, index: Option<usize>, _other_point: bool) -> Option<Point> {
+        index.map(|_| self.circle_point)
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        Some(get_circle_outline(self.center, self.radius))
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&[self.circle_point]),
+            mid_point: ExportPoint::convert(&self.center),
+            other_point: ExportPoint::convert(&self.circle_point),
+            radius: self.radius,
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::Circle, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        Circle {
+            id: WidgetId::unique(),
+            center: widget.get_mid_point(),
+            circle_point: widget.get_points()[0],
+            radius: widget.radius,
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for Ellipse {
+    shape_fields!(Ellipse);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(0.0)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&get_ellipse_points(self), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.center
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.center = transform.apply(self.center);
+        self.radii = self.radii * transform.scale_factor();
+        self.rotation = Radians(self.rotation.0 + transform.radians());
+    }
+
+    // The ellipse keeps its own rotation.
+    fn rotate(&mut self, step_degrees: f32) -> f32 {
+        self.transform(&Transform2D::rotate(self.center, step_degrees));
+        to_degrees(&self.rotation.0)
+    }
+
+    // The turn of a rotated ellipse is shown over it.
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let path = 
+            build_ellipse_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point,
+            );
+        let degrees = 
+            match options.draw_mode {
+                DrawMode::Rotate => Some(to_degrees(&self.rotation.0)),
+                DrawMode::Edit if self.rotation.0 != 0.0 => Some(to_degrees(&self.rotation.0)),
+                _ => None,
+            };
+        WidgetPath::with_degrees(path, self.center, degrees)
+    }
+
+    // the radii to the cursor while it is drawn or an axis dragged
+    fn cursor_label(&self, options: &PathOptions) -> Option<String> {
+        let cursor = options.cursor?;
+        if options.draw_mode == DrawMode::New {
+            let first = *self.points.first()?;
+            return match self.points.get(1) {
+                Some(p1) => Some(format!("rx {:.1}  ry {:.1}", p1.distance(first), (cursor.y - first.y).abs())),
+                None => Some(format!("rx {:.1}", (cursor.x - first.x).abs())),
+            }
+        }
+        let mut ell = self.clone();
+        if let Some(index) = options.edit_point_index {
+            ell.set_axis_point(index, cursor);
+        }
+        Some(format!("rx {:.1}  ry {:.1}", ell.radii.x, ell.radii.y))
+    }
+
+    // With shift held, the last point is put at the
+    // first radius so it is drawn as a circle.
+    fn locked_cursor(&self, cursor: Point) -> Point {
+        if self.points.len() != 2 {
+            return cursor
+        }
+        let rx = self.points[1].distance(self.points[0]);
+        let sign = if cursor.y < self.points[0].y { -1.0 } else { 1.0 };
+        Point::new(self.points[0].x, self.points[0].y + sign * rx)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        match self.points.len() {
+            0 => {
+                self.points.push(cursor);
+                false
+            },
+            1 => {
+                self.points.push(Point::new(cursor.x, self.points[0].y));
+                false
+            },
+            2 => {
+                self.points.push(Point::new(self.points[0].x, cursor.y));
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, _other_point: bool) {
+        if let Some(index) = index {
+            self.set_axis_point(index, cursor);
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let center_dist = cursor.distance(self.center);
+        let point_1_dist = cursor.distance(self.points[1]);
+        let point_2_dist = cursor.distance(self.points[2]);
+        if center_dist < point_1_dist && center_dist < point_2_dist {
+            (None, true, false)
+        } else if point_1_dist < point_2_dist {
+            (Some(1), false, false)
+        } else {
+            (Some(2), false, false)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (self.points[1..3].to_vec(), None)
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        Some(get_ellipse_points(self))
+    }
+
+    // the radii are saved in the other_point, a small ellipse
+    // far out can't get them back from its points
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            mid_point: ExportPoint::convert(&self.center),
+            other_point: ExportPoint::convert(&Point::new(self.radii.x, self.radii.y)),
+            rotation: self.rotation.0,
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::Ellipse, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        let points = widget.get_points();
+        let center = widget.get_mid_point();
+        let other_point = widget.get_other_point();
+        // older files have no radii saved
+        let radii = 
+            if other_point == Point::ORIGIN && points.len() == 3 {
+                Vector { x: points[1].distance(center), y: points[2].distance(center) }
+            } else {
+                Vector { x: other_point.x, y: other_point.y }
+            };
+        Ellipse {
+            id: WidgetId::unique(),
+            points,
+            center,
+            radii,
+            rotation: Radians(widget.rotation),
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for Line {
+    shape_fields!(Line);
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&self.points, self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.points[1]);
+    }
+
+    // The angle is by the first point while the line is drawn.
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, degrees, mid_point) = 
+            build_line_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point, 
+                options.degrees,
+            );
+        let mid_point = 
+            match options.draw_mode {
+                DrawMode::New => self.points.first().copied().unwrap_or(mid_point),
+                _ => mid_point,
+            };
+        shape_path(path, mid_point, degrees, options.draw_mode)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        self.points.push(cursor);
+        if self.points.len() == 2 {
+            self.mid_point = get_mid_point(self.points[0], self.points[1]);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, _other_point: bool) {
+        if let Some(index) = index {
+            self.points[index] = cursor;
+            self.mid_point = get_mid_point(self.points[0], self.points[1]);
+        }
+        self.degrees = get_horizontal_angle_of_vector(self.points[0], self.points[1]);
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let (point_index, point_dist) = nearest_point(self.points.iter().copied().enumerate(), cursor);
+        if cursor.distance(self.mid_point) < point_dist {
+            (None, true, false)
+        } else {
+            (Some(point_index), false, false)
+        }
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            mid_point: ExportPoint::convert(&self.mid_point),
+            rotation: self.degrees,
+            ..ExportWidget::new(Widget::Line, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        Line {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            mid_point: widget.get_mid_point(),
+            color: widget.get_color(),
+            width: widget.width,
+            degrees: widget.rotation,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for PolyLine {
+    shape_fields!(PolyLine);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&self.points, self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.pl_point = transform.apply(self.pl_point);
+        self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.pl_point);
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, degrees, mid_point) = 
+            build_polyline_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point, 
+                options.edit_other_point, 
+                options.degrees,
+            );
+        shape_path(path, mid_point, degrees, options.draw_mode)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        self.points.push(cursor);
+        if self.points.len() == self.poly_points {
+            self.mid_point = get_polyline_center(&self.points, self.center);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, other_point: bool) {
+        if let Some(index) = index {
+            self.points[index] = cursor;
+            self.recenter();
+            self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.pl_point);
+        } else if other_point {
+            let degrees = get_horizontal_angle_of_vector(self.mid_point, cursor);
+            self.transform(&Transform2D::rotate(self.mid_point, degrees-self.degrees));
+            self.pl_point = cursor;
+            self.degrees = degrees;
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let (point_index, point_dist) = nearest_point(self.points.iter().copied().enumerate(), cursor);
+        let mid_dist = self.mid_point.distance(cursor);
+        let pl_pt_dist = self.pl_point.distance(cursor);
+
+        if point_dist < mid_dist && point_dist < pl_pt_dist {
+            (Some(point_index), false, false)
+        } else if mid_dist < pl_pt_dist {
+            (None, true, false)
+        } else {
+            (None, false, true)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (self.points.clone(), Some(self.pl_point))
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        self.closed.then(|| self.outline())
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            poly_points: self.poly_points,
+            mid_point: ExportPoint::convert(&self.mid_point),
+            other_point: ExportPoint::convert(&self.pl_point),
+            rotation: self.degrees,
+            pl_center: Some(self.center),
+            pl_closed: self.closed,
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::PolyLine, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        PolyLine {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            poly_points: widget.poly_points,
+            mid_point: widget.get_mid_point(),
+            pl_point: widget.get_other_point(),
+            center: widget.pl_center.unwrap_or_default(),
+            closed: widget.pl_closed,
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            degrees: widget.rotation,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for Polygon {
+    shape_fields!(Polygon);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&self.points, self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.pg_point = transform.apply(self.pg_point);
+        self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.pg_point);
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, degrees, mid_point) = 
+            build_polygon_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_mid_point, 
+                options.edit_other_point, 
+                options.degrees,
+            );
+        shape_path(path, mid_point, degrees, options.draw_mode)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        if self.mid_point == Point::default() {
+            self.mid_point = cursor;
+            false
+        } else {
+            self.pg_point = cursor;
+            self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.pg_point);
+            true
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, _index: Option<usize>, other_point: bool) {
+        if other_point {
+            self.pg_point = cursor;
+            self.degrees = get_horizontal_angle_of_vector(self.mid_point, cursor);
+            self.points = 
+                build_polygon(
+                    self.mid_point, 
+                    self.pg_point, 
+                    self.poly_points,
+                    self.degrees,
+            );
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        if cursor.distance(self.mid_point) <= cursor.distance(self.pg_point) {
+            (None, true, false)
+        } else {
+            (None, false, true)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (vec![], Some(self.pg_point))
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        Some(self.points.clone())
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            poly_points: self.poly_points,
+            mid_point: ExportPoint::convert(&self.mid_point),
+            other_point: ExportPoint::convert(&self.pg_point),
+            rotation: self.degrees,
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::Polygon, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        Polygon {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            poly_points: widget.poly_points,
+            mid_point: widget.get_mid_point(),
+            pg_point: widget.get_other_point(),
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            degrees: widget.rotation,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for RightTriangle {
+    shape_fields!(RightTriangle);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&self.points, self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.tr_point = transform.apply(self.tr_point);
+        self.degrees = get_horizontal_angle_of_vector(self.mid_point, self.tr_point);
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, degrees, mid_point, _) = 
+            build_right_triangle_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point, 
+                options.edit_other_point, 
+                options.degrees,
+            );
+        shape_path(path, mid_point, degrees, options.draw_mode)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        self.points.push(cursor);
+        if self.points.len() == 3 {
+            self.points = square_right_triangle([self.points[0], self.points[1], cursor]).to_vec();
+            // close the triangle
+            self.points.push(self.points[0]);
+            self.mid_point = get_mid_geometry(&self.points, Widget::RightTriangle);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, other_point: bool) {
+        if let Some(index) = index {
+            self.set_leg_point(index, cursor);
+        } else if other_point {
+            let degrees = get_horizontal_angle_of_vector(self.mid_point, cursor);
+            self.transform(&Transform2D::rotate(self.mid_point, degrees-self.degrees));
+            self.tr_point = cursor;
+            self.degrees = degrees;
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let (point_index, point_dist) = nearest_point(self.points.iter().copied().enumerate(), cursor);
+        let mid_dist = self.mid_point.distance(cursor);
+        let tr_pt_dist = self.tr_point.distance(cursor);
+
+        if point_dist < mid_dist && point_dist < tr_pt_dist {
+            (Some(point_index), false, false)
+        } else if mid_dist < tr_pt_dist {
+            (None, true, false)
+        } else {
+            (None, false, true)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (self.points.clone(), Some(self.tr_point))
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        Some(self.points.clone())
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            poly_points: 3,
+            mid_point: ExportPoint::convert(&self.mid_point),
+            other_point: ExportPoint::convert(&self.tr_point),
+            rotation: self.degrees,
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::RightTriangle, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        RightTriangle {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            mid_point: widget.get_mid_point(),
+            tr_point: widget.get_other_point(),
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            degrees: widget.rotation,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for RoundedRect {
+    shape_fields!(RoundedRect);
+    hatch_field!();
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.clone()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&get_rounded_rect_points(self), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.mid_point
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        transform.apply_all(&mut self.points);
+        self.mid_point = transform.apply(self.mid_point);
+        self.radius *= transform.scale_factor();
+        self.degrees = get_horizontal_angle_of_vector(self.points[0], self.points[1]);
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (path, degrees, mid_point) = 
+            build_rounded_rect_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point, 
+                options.edit_other_point, 
+                options.degrees,
+            );
+        shape_path(path, mid_point, degrees, options.draw_mode)
+    }
+
+    // the radius while its handle is dragged
+    fn cursor_label(&self, options: &PathOptions) -> Option<String> {
+        let cursor = options.cursor?;
+        if options.draw_mode != DrawMode::Edit || !options.edit_other_point {
+            return None
+        }
+        let mut rr = self.clone();
+        rr.set_radius_point(cursor);
+        Some(format!("r {:.1}", rr.radius))
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        if self.points.is_empty() {
+            self.points.push(cursor);
+            false
+        } else {
+            self.set_corners(self.points[0], cursor);
+            true
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, other_point: bool) {
+        if let Some(index) = index {
+            self.set_corner(index, cursor);
+        } else if other_point {
+            self.set_radius_point(cursor);
+        }
+    }
+
+    // only the first and third corners are dragged
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let (point_index, point_dist) = nearest_point([0, 2].map(|idx| (idx, self.points[idx])), cursor);
+        let mid_dist = self.mid_point.distance(cursor);
+        let radius_dist = self.radius_point().distance(cursor);
+
+        if point_dist < mid_dist && point_dist < radius_dist {
+            (Some(point_index), false, false)
+        } else if mid_dist < radius_dist {
+            (None, true, false)
+        } else {
+            (None, false, true)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (vec![self.points[0], self.points[2]], Some(self.radius_point()))
+    }
+
+    fn closed_outline(&self) -> Option<Vec<Point>> {
+        Some(get_rounded_rect_points(self))
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&self.points),
+            poly_points: 4,
+            mid_point: ExportPoint::convert(&self.mid_point),
+            rotation: self.degrees,
+            radius: self.radius,
+            hatch: self.hatch,
+            ..ExportWidget::new(Widget::RoundedRect, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        RoundedRect {
+            id: WidgetId::unique(),
+            points: widget.get_points(),
+            mid_point: widget.get_mid_point(),
+            radius: widget.radius,
+            color: widget.get_color(),
+            width: widget.width,
+            hatch: widget.hatch,
+            degrees: widget.rotation,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl WidgetTrait for FreeHand {
+    shape_fields!(FreeHand);
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+        self.widths.iter_mut().for_each(|width| *width *= factor);
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.points.to_vec()
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        None
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(self.points.as_slice(), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.points.first().unwrap_or(Point::ORIGIN)
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        self.points = self.points.iter().map(|pt| transform.apply(pt)).collect();
+    }
+
+    fn rotate(&mut self, _step_degrees: f32) -> f32 {
+        0.0
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        WidgetPath::new(build_free_hand_path(self, options.draw_mode, options.cursor, options.edit_point_index))
+    }
+
+    // Each segment is drawn with the width of its end point.
+    fn draw(&self, frame: &mut Frame, vision: ColorVision) {
+        let color = simulate_color_vision(self.color, vision);
+        if self.points.len() < 2 || self.widths.len() != self.points.len() {
+            let path = self.build_path(&PathOptions::new(self.draw_mode)).path;
+            frame.stroke(&path, Stroke::default().with_width(self.width).with_color(color));
+            return
+        }
+        let points = self.points.as_slice();
+        for index in 0..points.len() - 1 {
+            let (start, end) = (points[index], points[index + 1]);
+            let segment = 
+                if self.smooth {
+                    let before = points[index.saturating_sub(1)];
+                    let after = points[(index + 2).min(points.len() - 1)];
+                    let (c1, c2) = get_catmull_rom_controls(before, start, end, after);
+                    Path::new(|p| {
+                        p.move_to(start);
+                        p.bezier_curve_to(c1, c2, end);
+                    })
+                } else {
+                    Path::line(start, end)
+                };
+            frame.stroke(
+                &segment,
+                Stroke::default()
+                .with_width(self.widths[index + 1])
+                .with_color(color)
+                .with_line_cap(LineCap::Round),
+            );
+        }
+    }
+
+    fn set_point(&mut self, cursor: Point, pressure_curve: &PressureCurve) -> bool {
+        self.add_point(cursor, pressure_curve);
+        self.completed
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, _other_point: bool) {
+        if let Some(index) = index {
+            self.points.set(index, cursor);
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        let (point_index, _) = nearest_point(self.points.iter().enumerate(), cursor);
+        (Some(point_index), false, false)
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(self.points.as_slice()),
+            widths: self.widths.clone(),
+            smooth: self.smooth,
+            ..ExportWidget::new(Widget::FreeHand, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        FreeHand {
+            id: WidgetId::unique(),
+            points: widget.get_points().into_iter().collect(),
+            color: widget.get_color(),
+            width: widget.width,
+            widths: widget.widths.clone(),
+            smooth: widget.smooth,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+            completed: true,
+        }
+    }
+}
+
+impl WidgetTrait for Text {
+    shape_fields!(Text);
+
+    fn width(&self) -> f32 {
+        1.0
+    }
+
+    // The text has no stroke, its size follows the transform.
+    fn scale_width(&mut self, _factor: f32) {}
+
+    fn points(&self) -> Vec<Point> {
+        vec![self.position]
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(self.degrees)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        let text_box = get_text_box(self);
+        // the text turns about its position
+        let (sin, cos) = to_radians(&self.degrees).sin_cos();
+        let corners: Vec<Point> = 
+            [(text_box.x, text_box.y), (text_box.x + text_box.width, text_box.y), 
+            (text_box.x, text_box.y + text_box.height), 
+            (text_box.x + text_box.width, text_box.y + text_box.height)].iter()
+                .map(|(x, y)| {
+                    let d = Point::new(*x, *y) - self.position;
+                    Point::new(self.position.x + d.x*cos - d.y*sin, self.position.y + d.x*sin + d.y*cos)
+                })
+                .collect();
+        get_points_bounds(&corners, 0.0)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.position
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        self.position = transform.apply(self.position);
+        self.size = Pixels(self.size.0 * transform.scale_factor());
+        self.degrees += transform.degrees();
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let (_, path) = build_text_path(self, options.draw_mode, false);
+        WidgetPath::new(path.unwrap_or_else(|| Path::new(|_| {})))
+    }
+
+    // The texts are drawn on their own cache by draw_text.
+    fn draw(&self, _frame: &mut Frame, _vision: ColorVision) {}
+
+    // The text is filled where it is or where the cursor moves it,
+    // turned by its degrees or the rotation not yet finished.
+    fn draw_pending(&self, frame: &mut Frame, options: &PathOptions, _theme: &Theme) -> WidgetPath {
+        let empty = WidgetPath::new(Path::new(|_| {}));
+        if options.draw_mode == DrawMode::New {
+            return empty
+        }
+        let position = 
+            match (options.edit_other_point, options.cursor) {
+                (true, Some(cursor)) => cursor,
+                _ => self.position,
+            };
+        let (text, path) = build_text_path(self, options.draw_mode, false);
+        frame.with_save(|frame| {
+            frame.translate(Vector::new(position.x, position.y));
+            frame.rotate(to_radians(&options.degrees.unwrap_or(self.degrees)));
+            frame.fill_text(text);
+            if let (Some(path), false) = (path, options.edit_other_point) {
+                frame.stroke(&path, Stroke::default().with_width(2.0).with_color(self.color));
+            }
+        });
+        empty
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        if self.position == Point::default() {
+            self.position = cursor;
+            false
+        } else {
+            self.status = DrawStatus::Completed;
+            self.draw_mode = DrawMode::DrawAll;
+            true
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, _index: Option<usize>, _other_point: bool) {
+        self.position = cursor;
+        self.draw_mode = DrawMode::DrawAll;
+    }
+
+    // just using the edit_other_point to indicate the position point
+    fn closest_point(&self, _cursor: Point) -> (Option<usize>, bool, bool) {
+        (None, false, true)
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (vec![], Some(self.position))
+    }
+
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            content: self.content.clone(),
+            other_point: ExportPoint::convert(&self.position),
+            rotation: self.degrees,
+            horizontal_alignment: convert_to_export_horizontal(self.horizontal_alignment),
+            vertical_alignment: convert_to_export_vertical(self.vertical_alignment),
+            text_style: Some(TextStyle::from_text(self)),
+            ..ExportWidget::new(Widget::Text, self.color, 0.0)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        let mut txt = Text {
+            id: WidgetId::unique(),
+            content: widget.content.clone(),
+            position: widget.get_other_point(),
+            color: widget.get_color(),
+            size: Pixels(16.0),
+            line_height: LineHeight::Relative(1.2),
+            font: Font::default(),
+            horizontal_alignment: convert_to_iced_horizontal(widget.horizontal_alignment),
+            vertical_alignment: convert_to_iced_vertical(widget.vertical_alignment),
+            shaping: Shaping::Basic,
+            degrees: widget.rotation,
+            cursor: TextCursor::default(),
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        };
+        widget.text_style.unwrap_or_default().apply(&mut txt);
+        txt
+    }
+}
+
+impl WidgetTrait for Gauge {
+    shape_fields!(Gauge);
+
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn scale_width(&mut self, factor: f32) {
+        self.width *= factor;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        bounding_points(self.center, self.radius)
+    }
+
+    fn degrees(&self) -> Option<f32> {
+        Some(0.0)
+    }
+
+    fn bounds(&self) -> Rectangle {
+        get_points_bounds(&bounding_points(self.center, self.radius), self.width)
+    }
+
+    fn mid_point(&self) -> Point {
+        self.center
+    }
+
+    fn transform(&mut self, transform: &Transform2D) {
+        self.center = transform.apply(self.center);
+        self.gauge_point = transform.apply(self.gauge_point);
+        self.radius *= transform.scale_factor();
+    }
+
+    // The scale stays upright.
+    fn rotate(&mut self, _step_degrees: f32) -> f32 {
+        0.0
+    }
+
+    fn build_path(&self, options: &PathOptions) -> WidgetPath {
+        let path = 
+            build_gauge_path(
+                self, 
+                options.draw_mode, 
+                options.cursor, 
+                options.edit_point_index, 
+                options.edit_mid_point,
+            );
+        WidgetPath::with_degrees(path, self.center, None)
+    }
+
+    fn set_point(&mut self, cursor: Point, _pressure_curve: &PressureCurve) -> bool {
+        if self.center == Point::default() {
+            self.center = cursor;
+            false
+        } else {
+            self.radius = self.center.distance(cursor);
+            self.gauge_point = cursor;
+            true
+        }
+    }
+
+    fn edit_point(&mut self, cursor: Point, index: Option<usize>, _other_point: bool) {
+        if index.is_some() {
+            self.gauge_point = cursor;
+            self.radius = self.center.distance(cursor);
+        }
+    }
+
+    fn closest_point(&self, cursor: Point) -> (Option<usize>, bool, bool) {
+        if cursor.distance(self.center) < cursor.distance(self.gauge_point) {
+            (None, true, false)
+        } else {
+            (Some(1), false, false)
+        }
+    }
+
+    fn edit_handles(&self) -> (Vec<Point>, Option<Point>) {
+        (vec![self.gauge_point], None)
+    }
+
+    fn handle_point(&self, index: Option<usize>, _other_point: bool) -> Option<Point> {
+        index.map(|_| self.gauge_point)
+    }
+
+    // the scale range is saved in the other_point
+    fn to_export(&self) -> ExportWidget {
+        ExportWidget {
+            points: export_points(&[self.gauge_point]),
+            mid_point: ExportPoint::convert(&self.center),
+            other_point: ExportPoint::convert(&Point::new(self.min, self.max)),
+            radius: self.radius,
+            value: self.value,
+            ..ExportWidget::new(Widget::Gauge, self.color, self.width)
+        }
+    }
+
+    fn from_export(widget: &ExportWidget) -> Self {
+        let range = widget.get_other_point();
+        Gauge {
+            id: WidgetId::unique(),
+            center: widget.get_mid_point(),
+            gauge_point: widget.get_points()[0],
+            radius: widget.radius,
+            min: range.x,
+            max: range.y,
+            value: widget.value,
+            color: widget.get_color(),
+            width: widget.width,
+            draw_mode: DrawMode::DrawAll,
+            status: DrawStatus::Completed,
+        }
+    }
+}
+
+impl HitTest for Arc {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&self.outline(), cursor)
+    }
+
+    // a chord or pie is a shape with an inside
+    fn contains(&self, point: Point) -> bool {
+        (self.closure != ArcClosure::Open && inside_polygon(&self.outline(), point)) || 
+            on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for Bezier {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&get_bezier_points(self), cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for Circle {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        (cursor.distance(self.center) - self.radius).abs()
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        point.distance(self.center) <= self.radius || on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for Ellipse {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&get_ellipse_points(self), cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        // the point turned into the axes of the ellipse
+        let (sin, cos) = self.rotation.0.sin_cos();
+        let d = point - self.center;
+        let x = (d.x*cos + d.y*sin) / self.radii.x;
+        let y = (d.y*cos - d.x*sin) / self.radii.y;
+        x*x + y*y <= 1.0 || on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for Line {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&self.points, cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for PolyLine {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&self.outline(), cursor)
+    }
+
+    // a closed polyline is a shape with an inside
+    fn contains(&self, point: Point) -> bool {
+        (self.closed && inside_polygon(&self.outline(), point)) || 
+            on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for Polygon {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&self.points, cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        inside_polygon(&self.points, point) || on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for RightTriangle {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&self.points, cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        inside_polygon(&self.points, point) || on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for RoundedRect {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(&get_rounded_rect_points(self), cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        inside_polygon(&get_rounded_rect_points(self), point) || on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl HitTest for FreeHand {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        distance_to_points(self.points.as_slice(), cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+// The text isn't measured, the position stands in for it.
+impl HitTest for Text {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        cursor.distance(self.position)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.distance_to(point) <= self.size.0 / 2.0
+    }
+}
+
+impl HitTest for Gauge {
+    fn distance_to(&self, cursor: Point) -> f32 {
+        get_distance_to_primitives(&get_gauge_primitives(self), cursor)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        point.distance(self.center) <= self.radius || on_stroke(self.distance_to(point), self.width)
+    }
+}
+
+impl CanvasWidget {
+    // None for CanvasWidget::None, the only arm that
+    // isn't a shape.
+    pub fn as_trait(&self) -> Option<&dyn WidgetTrait> {
+        match self {
+            CanvasWidget::None => None,
+            CanvasWidget::Arc(arc) => Some(arc),
+            CanvasWidget::Bezier(bz) => Some(bz),
+            CanvasWidget::Circle(cir) => Some(cir),
+            CanvasWidget::Ellipse(ell) => Some(ell),
+            CanvasWidget::Line(line) => Some(line),
+            CanvasWidget::PolyLine(pl) => Some(pl),
+            CanvasWidget::Polygon(pg) => Some(pg),
+            CanvasWidget::RightTriangle(tr) => Some(tr),
+            CanvasWidget::RoundedRect(rr) => Some(rr),
+            CanvasWidget::FreeHand(fh) => Some(fh),
+            CanvasWidget::Text(txt) => Some(txt),
+            CanvasWidget::Gauge(gauge) => Some(gauge),
+        }
+    }
+
+    pub fn as_trait_mut(&mut self) -> Option<&mut dyn WidgetTrait> {
+        match self {
+            CanvasWidget::None => None,
+            CanvasWidget::Arc(arc) => Some(arc),
+            CanvasWidget::Bezier(bz) => Some(bz),
+            CanvasWidget::Circle(cir) => Some(cir),
+            CanvasWidget::Ellipse(ell) => Some(ell),
+            CanvasWidget::Line(line) => Some(line),
+            CanvasWidget::PolyLine(pl) => Some(pl),
+            CanvasWidget::Polygon(pg) => Some(pg),
+            CanvasWidget::RightTriangle(tr) => Some(tr),
+            CanvasWidget::RoundedRect(rr) => Some(rr),
+            CanvasWidget::FreeHand(fh) => Some(fh),
+            CanvasWidget::Text(txt) => Some(txt),
+            CanvasWidget::Gauge(gauge) => Some(gauge),
+        }
+    }
+}
+
+// The center of the box around the widgets, the pivot
+// a selection turns about.  None without a shape.
+pub fn get_selection_center<'a>(widgets: impl IntoIterator<Item = &'a CanvasWidget>) -> Option<Point> {
+    widgets.into_iter()
+        .filter_map(CanvasWidget::as_trait)
+        .map(|wt| wt.bounds())
+        .reduce(|bounds, next| bounds.union(&next))
+        .map(|bounds| bounds.center())
+}
+
+// Turns the widgets together about the pivot, each
+// keeps its place relative to the others.
+pub fn rotate_widgets_about<'a>(widgets: impl IntoIterator<Item = &'a mut CanvasWidget>, pivot: Point, step_degrees: f32) {
+    let transform = Transform2D::rotate(pivot, step_degrees);
+    for wt in widgets.into_iter().filter_map(CanvasWidget::as_trait_mut) {
+        wt.transform(&transform);
+    }
+}
+
+
+#[test]
+fn test_widget_trait() {
+    use crate::test_widgets::circle;
+
+    let mut widget = CanvasWidget::Circle(circle(Point::new(10.0, 10.0), 5.0));
+    if let Some(cir) = widget.as_trait_mut() {
+        cir.translate(Vector::new(5.0, 0.0));
+        cir.scale(Point::new(15.0, 10.0), 2.0);
+        cir.set_mode_or_status(Some(DrawMode::Edit), None);
+    }
+    let cir = widget.as_trait().unwrap();
+    assert_eq!(Widget::Circle, cir.widget_type());
+    assert_eq!(vec![Point::new(5.0, 0.0), Point::new(25.0, 20.0)], cir.points());
+    assert_eq!((DrawMode::Edit, DrawStatus::Completed), cir.mode_and_status());
+    assert!(CanvasWidget::None.as_trait().is_none());
+}
+
+#[test]
+fn test_hit_test() {
+    use crate::test_widgets::polyline;
+
+    let id = WidgetId::unique();
+    let pg = Polygon {
+        id,
+        points: vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(10.0, 10.0), 
+                    Point::new(0.0, 10.0), Point::new(0.0, 0.0)],
+        poly_points: 4,
+        mid_point: Point::new(5.0, 5.0),
+        pg_point: Point::new(10.0, 5.0),
+        color: Color::BLACK,
+        width: 2.0,
+        hatch: None,
+        degrees: 0.0,
+        draw_mode: DrawMode::DrawAll,
+        status: DrawStatus::Completed,
+    };
+    assert!(pg.contains(Point::new(2.0, 3.0)));
+    assert_eq!(2.0, pg.distance_to(Point::new(2.0, 3.0)));
+    assert!(pg.contains(Point::new(12.0, 5.0)));
+    assert!(!pg.contains(Point::new(20.0, 5.0)));
+
+    let pl = PolyLine { id, ..polyline(pg.points[..4].to_vec(), false) };
+    // an open shape has no fill
+    assert!(!pl.contains(Point::new(5.0, 5.0)));
+    assert!(pl.contains(Point::new(5.0, 1.0)));
+    // closed, the side back to the first point and the inside count
+    assert_eq!(5.0, pl.distance_to(Point::new(1.0, 5.0)));
+    let pl = PolyLine { closed: true, ..pl };
+    assert_eq!(1.0, pl.distance_to(Point::new(1.0, 5.0)));
+    assert!(pl.contains(Point::new(5.0, 5.0)));
+}
+
+#[test]
+fn test_widget_bounds() {
+    use crate::test_widgets::line;
+
+    let ln = Line { width: 4.0, ..line(Point::new(10.0, 20.0), Point::new(30.0, 5.0)) };
+    assert_eq!(Rectangle::new(Point::new(8.0, 3.0), iced::Size::new(24.0, 19.0)), ln.bounds());
+}